@@ -2,10 +2,12 @@
 
 use std::{pin::Pin, sync::Arc, time::Duration};
 
-use futures::{Future, FutureExt as _, Stream};
+use futures::Stream;
 use juniper::{DefaultScalarValue, EmptyMutation, FieldError, RootNode};
-use juniper_subscriptions::Coordinator;
-use juniper_warp::{playground_filter, subscriptions::graphql_subscriptions};
+use juniper_warp::{
+    playground_filter,
+    subscriptions::{make_ws_filter, ConnectionConfig},
+};
 use warp::{http::Response, Filter};
 
 #[derive(Clone)]
@@ -156,29 +158,13 @@ async fn main() {
 
     log::info!("Listening on 127.0.0.1:8080");
 
-    let routes = (warp::path("subscriptions")
-        .and(warp::ws())
-        .and(sub_state.clone())
-        .and(warp::any().map(move || Arc::clone(&coordinator)))
-        .map(
-            |ws: warp::ws::Ws,
-             ctx: Context,
-             coordinator: Arc<Coordinator<'static, _, _, _, _, _>>| {
-                ws.on_upgrade(|websocket| -> Pin<Box<dyn Future<Output = ()> + Send>> {
-                    graphql_subscriptions(websocket, coordinator, ctx)
-                        .map(|r| {
-                            if let Err(e) = r {
-                                println!("Websocket error: {}", e);
-                            }
-                        })
-                        .boxed()
-                })
-            },
-        ))
-    .map(|reply| {
-        // TODO#584: remove this workaround
-        warp::reply::with_header(reply, "Sec-WebSocket-Protocol", "graphql-ws")
-    })
+    let routes = (warp::path("subscriptions").and(make_ws_filter(
+        coordinator,
+        sub_state.boxed(),
+        ConnectionConfig::new()
+            .with_max_operations(16)
+            .with_keep_alive_interval(Duration::from_secs(15)),
+    )))
     .or(warp::post()
         .and(warp::path("graphql"))
         .and(qm_graphql_filter))
@@ -0,0 +1,8 @@
+#[derive(juniper::GraphQLInputObject)]
+#[graphql(one_of)]
+enum Filter {
+    ById(juniper::ID),
+    Everything,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    test: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    fn id(&self, obj: ObjA) -> &str;
+}
+
+fn main() {}
@@ -8,7 +8,7 @@ enum Character {
     A(ObjA),
 }
 
-juniper::graphql_interface!(Character: () where Scalar = juniper::DefaultScalarValue |&self| {
+juniper::graphql_interface_legacy!(Character: () where Scalar = juniper::DefaultScalarValue |&self| {
     field id(__test: ObjA) -> &str {
         match *self {
             Character::A(_) => "funA",
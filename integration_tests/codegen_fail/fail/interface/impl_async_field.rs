@@ -0,0 +1,12 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    test: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    async fn id(&self) -> String;
+}
+
+fn main() {}
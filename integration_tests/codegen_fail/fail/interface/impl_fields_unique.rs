@@ -0,0 +1,15 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    test: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    fn id(&self) -> &str;
+
+    #[graphql(name = "id")]
+    fn id2(&self) -> &str;
+}
+
+fn main() {}
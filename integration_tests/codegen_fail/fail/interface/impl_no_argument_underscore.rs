@@ -0,0 +1,13 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    test: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    #[graphql(arguments(arg(name = "__arg")))]
+    fn id(&self, arg: String) -> String;
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[juniper::graphql_interface]
+trait Character {
+    fn id(&self) -> &str;
+}
+
+fn main() {}
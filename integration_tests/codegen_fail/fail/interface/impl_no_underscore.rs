@@ -0,0 +1,13 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    test: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    #[graphql(name = "__id")]
+    fn id(&self) -> &str;
+}
+
+fn main() {}
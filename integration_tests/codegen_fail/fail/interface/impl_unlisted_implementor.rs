@@ -0,0 +1,28 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(scalar = juniper::DefaultScalarValue)]
+pub struct ObjA {
+    id: String,
+}
+
+pub struct ObjB {
+    id: String,
+}
+
+#[juniper::graphql_interface(for = [ObjA])]
+trait Character {
+    fn id(&self) -> &str;
+}
+
+impl Character for ObjA {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Character for ObjB {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+fn main() {}
@@ -1,8 +1,9 @@
 use fnv::FnvHashMap;
 
 use juniper::{
-    self, DefaultScalarValue, FromInputValue, GraphQLInputObject, GraphQLType, InputValue,
-    ToInputValue,
+    self, graphql_value, meta::MetaType, parser::SourcePosition, DefaultScalarValue, EmptyMutation,
+    EmptySubscription, FromInputValue, GraphQLError, GraphQLInputObject, GraphQLType, InputValue,
+    RootNode, RuleError, ToInputValue, Variables,
 };
 
 #[derive(GraphQLInputObject, Debug, PartialEq)]
@@ -79,6 +80,7 @@ impl<'a> GraphQLType<DefaultScalarValue> for &'a Fake {
                 name: "fake".to_string(),
                 description: None,
                 deprecation_status: juniper::meta::DeprecationStatus::Current,
+                directives: vec![],
             }],
         );
         meta.into_meta()
@@ -164,3 +166,109 @@ fn test_doc_comment_override() {
     let meta = OverrideDocComment::meta(&(), &mut registry);
     assert_eq!(meta.description(), Some(&"obj override".to_string()));
 }
+
+/// How to find a user.
+#[derive(GraphQLInputObject, Debug, PartialEq)]
+#[graphql(one_of)]
+enum Filter {
+    ById(juniper::ID),
+    /// The name of the user.
+    ByName(String),
+    #[graphql(name = "olderThan")]
+    Age(i32),
+}
+
+struct Query;
+
+#[juniper::graphql_object]
+impl Query {
+    fn find(by: Filter) -> String {
+        format!("{:?}", by)
+    }
+}
+
+fn one_of_schema() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+#[test]
+fn test_one_of_input_object() {
+    let mut registry: juniper::Registry = juniper::Registry::new(FnvHashMap::default());
+    let meta = Filter::meta(&(), &mut registry);
+    assert_eq!(meta.name(), Some("Filter"));
+    assert_eq!(meta.description(), Some(&"How to find a user.".to_string()));
+    match meta {
+        MetaType::InputObject(ref meta) => {
+            assert!(meta.is_one_of);
+            assert_eq!(
+                meta.input_fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.arg_type.to_string()))
+                    .collect::<Vec<_>>(),
+                vec![("byId", "ID"), ("byName", "String"), ("olderThan", "Int")]
+                    .into_iter()
+                    .map(|(n, t)| (n, t.to_string()))
+                    .collect::<Vec<_>>(),
+            );
+            assert_eq!(
+                meta.input_fields[1].description,
+                Some("The name of the user.".to_string())
+            );
+        }
+        _ => panic!("Filter is not an input object"),
+    }
+
+    let input: InputValue =
+        serde_json::from_value(serde_json::json!({ "byName": "Luke" })).unwrap();
+    let output: Option<Filter> = FromInputValue::from_input_value(&input);
+    assert_eq!(output, Some(Filter::ByName("Luke".into())));
+    assert_eq!(Filter::ByName("Luke".into()).to_input_value(), input);
+
+    let input: InputValue =
+        serde_json::from_value(serde_json::json!({ "byName": "Luke", "olderThan": 18 })).unwrap();
+    let output: Option<Filter> = FromInputValue::from_input_value(&input);
+    assert_eq!(output, None);
+}
+
+#[test]
+fn test_one_of_input_object_validation() {
+    let schema = one_of_schema();
+    let run_one_of_query =
+        |query, vars: &Variables| juniper::execute_sync(query, None, &schema, vars, &());
+
+    assert_eq!(
+        run_one_of_query(r#"{ find(by: { olderThan: 18 }) }"#, &Variables::new()),
+        Ok((graphql_value!({ "find": "Age(18)" }), vec![])),
+    );
+    assert!(run_one_of_query(
+        r#"{ find(by: { byId: "1", byName: "Luke" }) }"#,
+        &Variables::new()
+    )
+    .is_err());
+    assert!(run_one_of_query(
+        r#"query($id: ID) { find(by: { byId: $id }) }"#,
+        &Variables::new()
+    )
+    .is_err());
+
+    let vars = vec![(
+        "by".to_owned(),
+        InputValue::object(
+            vec![
+                ("byId", InputValue::scalar("1")),
+                ("byName", InputValue::scalar("Luke")),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    )]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        run_one_of_query(r#"query($by: Filter!) { find(by: $by) }"#, &vars),
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Variable "$by" got invalid value. Expected exactly one non-null field of "Filter"."#,
+            &[SourcePosition::new(6, 0, 6)],
+        )])),
+    );
+}
@@ -0,0 +1,114 @@
+use juniper::{graphql_value, EmptyMutation, EmptySubscription, RootNode, Variables};
+
+#[juniper::graphql_interface(for = [Cat, Dog], enum = PetValue)]
+pub trait Pet {
+    fn name(&self) -> &str;
+}
+
+pub struct Cat {
+    name: String,
+    lives: i32,
+}
+
+impl Pet for Cat {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[juniper::graphql_object(interfaces = [&dyn Pet])]
+impl Cat {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn lives(&self) -> i32 {
+        self.lives
+    }
+}
+
+pub struct Dog {
+    name: String,
+}
+
+impl Pet for Dog {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[juniper::graphql_object(interfaces = [&dyn Pet])]
+impl Dog {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+pub struct Query;
+
+#[juniper::graphql_object]
+impl Query {
+    fn pets() -> Vec<PetValue> {
+        vec![
+            Cat {
+                name: "Garfield".to_owned(),
+                lives: 9,
+            }
+            .into(),
+            Dog {
+                name: "Odie".to_owned(),
+            }
+            .into(),
+        ]
+    }
+
+    fn favorite() -> Box<dyn Pet> {
+        Box::new(Dog {
+            name: "Odie".to_owned(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn resolves_interface_from_another_crate() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+    let (res, errs) = juniper::execute(
+        r#"
+        {
+            pets {
+                __typename
+                name
+                ... on Cat {
+                    lives
+                }
+            }
+            favorite {
+                __typename
+                name
+            }
+        }
+        "#,
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        res,
+        graphql_value!({
+            "pets": [
+                { "__typename": "Cat", "name": "Garfield", "lives": 9 },
+                { "__typename": "Dog", "name": "Odie" },
+            ],
+            "favorite": { "__typename": "Dog", "name": "Odie" },
+        })
+    );
+}
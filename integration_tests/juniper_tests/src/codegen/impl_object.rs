@@ -15,6 +15,29 @@ impl MyObject {
     }
 }
 
+#[derive(juniper::GraphQLObject)]
+pub struct Item {
+    #[graphql(complexity = 2)]
+    name: String,
+}
+
+pub struct ExpensiveQuery;
+
+#[juniper::graphql_object]
+impl ExpensiveQuery {
+    #[graphql(complexity = 10)]
+    fn report() -> String {
+        "report".to_owned()
+    }
+
+    #[graphql(list_size = 5)]
+    fn items() -> Vec<Item> {
+        vec![Item {
+            name: "item".to_owned(),
+        }]
+    }
+}
+
 #[tokio::test]
 async fn check_argument_rename() {
     let doc = format!(
@@ -49,6 +72,54 @@ async fn check_argument_rename() {
     .await;
 }
 
+#[tokio::test]
+async fn check_field_complexity() {
+    let schema = RootNode::new(
+        ExpensiveQuery,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .with_max_complexity(11);
+
+    // 1 + 5 * 2
+    let (result, errs) = execute("{ items { name } }", None, &schema, &Variables::new(), &())
+        .await
+        .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![(
+                "items",
+                Value::list(vec![Value::object(
+                    vec![("name", Value::scalar("item"))].into_iter().collect(),
+                )]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+    );
+
+    // 10 + 1 + 5 * 2
+    let res = execute(
+        "{ report items { name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .await;
+
+    match res {
+        Err(juniper::GraphQLError::ValidationError(errs)) => assert_eq!(
+            errs[0].message(),
+            "Operation has a complexity of 21, which exceeds the maximum complexity of 11"
+        ),
+        res => panic!("Unexpected result {:?}", res),
+    }
+}
+
 #[cfg(test)]
 async fn run_type_info_query<F>(doc: &str, f: F)
 where
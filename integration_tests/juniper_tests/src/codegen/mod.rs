@@ -3,7 +3,9 @@ mod derive_input_object;
 mod derive_object;
 mod derive_object_with_raw_idents;
 mod derive_union;
+mod impl_interface;
 mod impl_object;
 mod impl_scalar;
 mod impl_union;
+mod schema_directives;
 mod scalar_value_transparent;
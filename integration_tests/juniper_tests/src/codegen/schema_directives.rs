@@ -0,0 +1,121 @@
+#[cfg(test)]
+use juniper::{
+    self,
+    meta::{MetaType, SchemaDirective},
+    DirectiveLocation, DirectiveType, EmptyMutation, EmptySubscription, InputValue, RootNode,
+};
+
+#[derive(juniper::GraphQLObject)]
+#[graphql(directive(key(fields = "id"), shareable))]
+struct User {
+    id: i32,
+    #[graphql(directive(tag(name = "private")))]
+    email: String,
+}
+
+#[derive(juniper::GraphQLEnum)]
+#[graphql(directive(tag(name = "enum")))]
+enum Role {
+    Admin,
+    #[graphql(directive(inaccessible))]
+    Guest,
+}
+
+#[derive(juniper::GraphQLInputObject)]
+#[graphql(directive(tag(name = "input")))]
+struct UserFilter {
+    #[graphql(directive(tags(names = ["a", "b"])))]
+    role: Role,
+}
+
+struct Query;
+
+#[juniper::graphql_object(directive(tag(name = "root")))]
+impl Query {
+    #[graphql(
+        directive(cost(weight = 5, kind = COMPLEX)),
+        arguments(filter(directive(inaccessible))),
+    )]
+    fn users(filter: Option<UserFilter>) -> Vec<User> {
+        let _ = filter;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .with_schema_directive(|registry| {
+        DirectiveType::new(
+            "key",
+            &[DirectiveLocation::Object],
+            &[registry.arg::<String>("fields", &())],
+        )
+    })
+}
+
+#[test]
+fn attaches_directives_to_metadata() {
+    let schema = schema();
+
+    let user = match schema.schema.concrete_type_by_name("User") {
+        Some(MetaType::Object(ref meta)) => meta,
+        _ => panic!("User is not an object"),
+    };
+    assert_eq!(
+        user.directives,
+        vec![
+            SchemaDirective::new("key").argument("fields", InputValue::scalar("id")),
+            SchemaDirective::new("shareable"),
+        ]
+    );
+    assert_eq!(
+        user.fields[1].directives,
+        vec![SchemaDirective::new("tag").argument("name", InputValue::scalar("private"))]
+    );
+
+    let query = match schema.schema.concrete_type_by_name("Query") {
+        Some(MetaType::Object(ref meta)) => meta,
+        _ => panic!("Query is not an object"),
+    };
+    let users = &query.fields[0];
+    assert_eq!(
+        users.directives[0].argument_by_name("kind"),
+        Some(&InputValue::enum_value("COMPLEX"))
+    );
+    assert_eq!(
+        users.arguments.as_ref().unwrap()[0].directives,
+        vec![SchemaDirective::new("inaccessible")]
+    );
+
+    match schema.schema.concrete_type_by_name("Role") {
+        Some(MetaType::Enum(ref meta)) => {
+            assert!(meta.values[0].directives.is_empty());
+            assert_eq!(
+                meta.values[1].directives,
+                vec![SchemaDirective::new("inaccessible")]
+            );
+        }
+        _ => panic!("Role is not an enum"),
+    }
+}
+
+#[test]
+fn prints_directives_in_sdl() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("directive @key(fields: String!) on OBJECT"));
+    assert!(sdl.contains("type Query @tag(name: \"root\") {"));
+    assert!(sdl.contains(
+        "  users(filter: UserFilter @inaccessible): [User!]! @cost(weight: 5, kind: COMPLEX)"
+    ));
+    assert!(sdl.contains("type User @key(fields: \"id\") @shareable {"));
+    assert!(sdl.contains("  email: String! @tag(name: \"private\")"));
+    assert!(sdl.contains("enum Role @tag(name: \"enum\") {\n  ADMIN\n  GUEST @inaccessible\n}"));
+    assert!(sdl.contains("input UserFilter @tag(name: \"input\") {"));
+    assert!(sdl.contains("  role: Role! @tags(names: [\"a\", \"b\"])"));
+}
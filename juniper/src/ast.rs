@@ -45,23 +45,31 @@ pub enum InputValue<S = DefaultScalarValue> {
     Object(Vec<(Spanning<String>, Spanning<InputValue<S>>)>),
 }
 
+/// The definition of a variable of an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct VariableDefinition<'a, S> {
     pub var_type: Spanning<Type<'a>>,
     pub default_value: Option<Spanning<InputValue<S>>>,
 }
 
+/// The arguments of a field or directive
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Arguments<'a, S> {
     pub items: Vec<(Spanning<&'a str>, Spanning<InputValue<S>>)>,
 }
 
+/// The variables of an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct VariableDefinitions<'a, S> {
     pub items: Vec<(Spanning<&'a str>, VariableDefinition<'a, S>)>,
 }
 
+/// A field in a selection set
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Field<'a, S> {
     pub alias: Option<Spanning<&'a str>>,
     pub name: Spanning<&'a str>,
@@ -70,13 +78,17 @@ pub struct Field<'a, S> {
     pub selection_set: Option<Vec<Selection<'a, S>>>,
 }
 
+/// A spread of a named fragment, e.g. `...userFields`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct FragmentSpread<'a, S> {
     pub name: Spanning<&'a str>,
     pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
 }
 
+/// An inline fragment, e.g. `... on User { name }`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct InlineFragment<'a, S> {
     pub type_condition: Option<Spanning<&'a str>>,
     pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
@@ -106,20 +118,26 @@ pub enum Selection<'a, S = DefaultScalarValue> {
     InlineFragment(Spanning<InlineFragment<'a, S>>),
 }
 
+/// A directive on a part of a query, e.g. `@skip(if: true)`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Directive<'a, S> {
     pub name: Spanning<&'a str>,
     pub arguments: Option<Spanning<Arguments<'a, S>>>,
 }
 
+/// The type of an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub enum OperationType {
     Query,
     Mutation,
     Subscription,
 }
 
+/// A query, mutation or subscription operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Operation<'a, S> {
     pub operation_type: OperationType,
     pub name: Option<Spanning<&'a str>>,
@@ -128,7 +146,9 @@ pub struct Operation<'a, S> {
     pub selection_set: Vec<Selection<'a, S>>,
 }
 
+/// The definition of a named fragment
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Fragment<'a, S> {
     pub name: Spanning<&'a str>,
     pub type_condition: Spanning<&'a str>,
@@ -136,14 +156,103 @@ pub struct Fragment<'a, S> {
     pub selection_set: Vec<Selection<'a, S>>,
 }
 
+/// A definition in a query document
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub enum Definition<'a, S> {
     Operation(Spanning<Operation<'a, S>>),
     Fragment(Spanning<Fragment<'a, S>>),
 }
 
+/// A parsed query document
 pub type Document<'a, S> = Vec<Definition<'a, S>>;
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConstDirective<S> {
+    pub name: Spanning<String>,
+    pub arguments: Vec<(Spanning<String>, Spanning<InputValue<S>>)>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputValueDefinition<S> {
+    pub description: Option<String>,
+    pub name: Spanning<String>,
+    pub value_type: Spanning<Type<'static>>,
+    pub default_value: Option<Spanning<InputValue<S>>>,
+    pub directives: Vec<Spanning<ConstDirective<S>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FieldDefinition<S> {
+    pub description: Option<String>,
+    pub name: Spanning<String>,
+    pub arguments: Vec<InputValueDefinition<S>>,
+    pub field_type: Spanning<Type<'static>>,
+    pub directives: Vec<Spanning<ConstDirective<S>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnumValueDefinition<S> {
+    pub description: Option<String>,
+    pub name: Spanning<String>,
+    pub directives: Vec<Spanning<ConstDirective<S>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TypeDefinitionKind<S> {
+    Scalar,
+    Object {
+        interfaces: Vec<Spanning<String>>,
+        fields: Vec<FieldDefinition<S>>,
+    },
+    Interface {
+        fields: Vec<FieldDefinition<S>>,
+    },
+    Union {
+        members: Vec<Spanning<String>>,
+    },
+    Enum {
+        values: Vec<EnumValueDefinition<S>>,
+    },
+    InputObject {
+        fields: Vec<InputValueDefinition<S>>,
+    },
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TypeDefinition<S> {
+    pub description: Option<String>,
+    pub name: Spanning<String>,
+    pub directives: Vec<Spanning<ConstDirective<S>>>,
+    pub kind: TypeDefinitionKind<S>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SchemaDefinition<S> {
+    pub query: Option<Spanning<String>>,
+    pub mutation: Option<Spanning<String>>,
+    pub subscription: Option<Spanning<String>>,
+    pub directives: Vec<Spanning<ConstDirective<S>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DirectiveDefinition<S> {
+    pub description: Option<String>,
+    pub name: Spanning<String>,
+    pub arguments: Vec<InputValueDefinition<S>>,
+    pub locations: Vec<Spanning<String>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TypeSystemDefinition<S> {
+    Schema(Spanning<SchemaDefinition<S>>),
+    Type(Spanning<TypeDefinition<S>>),
+    TypeExtension(Spanning<TypeDefinition<S>>),
+    Directive(Spanning<DirectiveDefinition<S>>),
+}
+
+pub type SchemaDocument<S> = Vec<TypeSystemDefinition<S>>;
+
 /// Parse an unstructured input value into a Rust data type.
 ///
 /// The conversion _can_ fail, and must in that case return None. Implemented
@@ -189,6 +298,16 @@ impl<'a> Type<'a> {
             _ => false,
         }
     }
+
+    /// Converts the type literal into one that owns its names.
+    pub fn into_owned(self) -> Type<'static> {
+        match self {
+            Type::Named(n) => Type::Named(Cow::Owned(n.into_owned())),
+            Type::NonNullNamed(n) => Type::NonNullNamed(Cow::Owned(n.into_owned())),
+            Type::List(l) => Type::List(Box::new(l.into_owned())),
+            Type::NonNullList(l) => Type::NonNullList(Box::new(l.into_owned())),
+        }
+    }
 }
 
 impl<'a> fmt::Display for Type<'a> {
@@ -441,6 +560,15 @@ where
     }
 }
 
+impl<S> FromInputValue<S> for InputValue<S>
+where
+    S: Clone,
+{
+    fn from_input_value(v: &InputValue<S>) -> Option<InputValue<S>> {
+        Some(v.clone())
+    }
+}
+
 impl<S> fmt::Display for InputValue<S>
 where
     S: ScalarValue,
@@ -0,0 +1,623 @@
+//! Batching and caching of data loads in async resolvers
+//!
+//! Resolving a field of every item of a list usually loads the same kind of
+//! data once per item, like the author of every post. A
+//! [`DataLoader`](struct.DataLoader.html) collects the keys that are loaded by
+//! sibling fields and list items while the async executor resolves a
+//! selection set, and loads all of them with a single call of its
+//! [`BatchFn`](trait.BatchFn.html). Loaded values are cached, so keys are
+//! loaded at most once by the same loader.
+//!
+//! Loaders are usually part of the context, which is created for every
+//! request, so that the cache doesn't outlive the request:
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use juniper::{
+//!     dataloader::{BatchFn, DataLoader},
+//!     BoxFuture, FieldResult,
+//! };
+//!
+//! struct UserBatch;
+//!
+//! impl BatchFn<i32, String> for UserBatch {
+//!     type Error = String;
+//!
+//!     fn load(&self, keys: Vec<i32>) -> BoxFuture<'static, Result<HashMap<i32, String>, String>> {
+//!         // One query for all keys, like `SELECT * FROM users WHERE id IN (...)`.
+//!         Box::pin(async move {
+//!             Ok(keys.into_iter().map(|id| (id, format!("User {}", id))).collect())
+//!         })
+//!     }
+//! }
+//!
+//! struct Context {
+//!     users: DataLoader<i32, String, UserBatch>,
+//! }
+//!
+//! impl juniper::Context for Context {}
+//!
+//! struct Post {
+//!     author_id: i32,
+//! }
+//!
+//! #[juniper::graphql_object(Context = Context)]
+//! impl Post {
+//!     async fn author(&self, context: &Context) -> FieldResult<Option<String>> {
+//!         Ok(context.users.load(self.author_id).await?)
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let context = Context {
+//!     users: DataLoader::new(UserBatch),
+//! };
+//! # let _ = context;
+//! # }
+//! ```
+//!
+//! Batching happens in the async executor, which resolves sibling fields and
+//! list items concurrently. Loads wait until the executor has polled every
+//! field that can make progress, and the executor then dispatches the
+//! collected keys of every loader as one batch. Loads outside of the executor
+//! only wait for one round of polling of their task.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    future::Future,
+    hash::Hash,
+    mem,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use futures::{
+    future::{FutureExt as _, Shared},
+    task::{self, ArcWake},
+};
+
+use crate::BoxFuture;
+
+/// A function that loads the values of many keys at once
+pub trait BatchFn<K, V>: Send + Sync + 'static {
+    /// The error of a failed batch
+    ///
+    /// The error is returned by every load of the batch.
+    type Error: Clone + Send + Sync + 'static;
+
+    /// Load the values of the keys
+    ///
+    /// Keys without a value in the returned map are loaded as `None`.
+    fn load(&self, keys: Vec<K>) -> BoxFuture<'static, Result<HashMap<K, V>, Self::Error>>;
+}
+
+type Batch<K, V, E> = Shared<BoxFuture<'static, Result<Arc<HashMap<K, V>>, E>>>;
+
+struct State<K, V, E> {
+    max_batch_size: Option<usize>,
+    cache: HashMap<K, Option<V>>,
+    pending: Vec<K>,
+    in_flight: HashMap<K, (usize, Batch<K, V, E>)>,
+    batches: usize,
+    // Whether the loader is dispatched at the end of the executor's poll.
+    scheduled: bool,
+    // The loads that wait for their keys to be dispatched.
+    waiting: Vec<Waker>,
+}
+
+struct Inner<K, V, F>
+where
+    F: BatchFn<K, V>,
+{
+    batch_fn: Arc<F>,
+    state: Mutex<State<K, V, F::Error>>,
+}
+
+/// Batches and caches the loads of keys
+///
+/// See the [module documentation](index.html).
+pub struct DataLoader<K, V, F>
+where
+    F: BatchFn<K, V>,
+{
+    inner: Arc<Inner<K, V, F>>,
+}
+
+impl<K, V, F> fmt::Debug for DataLoader<K, V, F>
+where
+    F: BatchFn<K, V>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DataLoader")
+            .field(
+                "max_batch_size",
+                &self.inner.state.lock().unwrap().max_batch_size,
+            )
+            .finish()
+    }
+}
+
+impl<K, V, F> DataLoader<K, V, F>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    F: BatchFn<K, V>,
+{
+    /// Create a loader with an empty cache
+    pub fn new(batch_fn: F) -> Self {
+        DataLoader {
+            inner: Arc::new(Inner {
+                batch_fn: Arc::new(batch_fn),
+                state: Mutex::new(State {
+                    max_batch_size: None,
+                    cache: HashMap::new(),
+                    pending: Vec::new(),
+                    in_flight: HashMap::new(),
+                    batches: 0,
+                    scheduled: false,
+                    waiting: Vec::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Load at most `max_batch_size` keys with a single call of the batch
+    /// function
+    pub fn with_max_batch_size(self, max_batch_size: usize) -> Self {
+        self.inner.state.lock().unwrap().max_batch_size = Some(max_batch_size.max(1));
+        self
+    }
+
+    /// Load the value of a key
+    ///
+    /// The key is loaded in a batch with the keys of all other loads that
+    /// start before the batch is dispatched, unless it is already cached.
+    pub async fn load(&self, key: K) -> Result<Option<V>, F::Error> {
+        let lookup = match self.inner.enqueue(&key) {
+            Some(lookup) => lookup,
+            None if EXECUTOR_POLL.with(|s| s.borrow().is_some()) => {
+                WaitForDispatch {
+                    inner: &self.inner,
+                    key: &key,
+                }
+                .await
+            }
+            None => {
+                YieldOnce(false).await;
+                match self.inner.enqueue(&key) {
+                    Some(lookup) => lookup,
+                    None => {
+                        self.inner.dispatch();
+                        self.inner
+                            .enqueue(&key)
+                            .expect("The pending keys were dispatched")
+                    }
+                }
+            }
+        };
+
+        match lookup {
+            Lookup::Cached(value) => Ok(value),
+            Lookup::InFlight(batch) => self.inner.wait(&key, batch).await,
+        }
+    }
+
+    /// Load the values of many keys
+    ///
+    /// The values are in the order of the keys.
+    pub async fn load_many(&self, keys: Vec<K>) -> Result<Vec<Option<V>>, F::Error> {
+        futures::future::try_join_all(keys.into_iter().map(|key| self.load(key))).await
+    }
+
+    /// Put a value into the cache, unless the key is already cached
+    pub fn prime(&self, key: K, value: V) {
+        self.inner
+            .state
+            .lock()
+            .unwrap()
+            .cache
+            .entry(key)
+            .or_insert(Some(value));
+    }
+
+    /// Remove a key from the cache, so that it is loaded again
+    pub fn clear(&self, key: &K) {
+        self.inner.state.lock().unwrap().cache.remove(key);
+    }
+
+    /// Remove all keys from the cache
+    pub fn clear_all(&self) {
+        self.inner.state.lock().unwrap().cache.clear();
+    }
+}
+
+impl<K, V, E> State<K, V, E>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    // Look the key up in the cache and the running batches, or add it to the
+    // next batch.
+    fn enqueue(&mut self, key: &K) -> Option<Lookup<K, V, E>> {
+        if let Some(value) = self.cache.get(key) {
+            return Some(Lookup::Cached(value.clone()));
+        }
+        if let Some((_, batch)) = self.in_flight.get(key) {
+            return Some(Lookup::InFlight(batch.clone()));
+        }
+        if !self.pending.contains(key) {
+            self.pending.push(key.clone());
+        }
+        None
+    }
+}
+
+impl<K, V, F> Inner<K, V, F>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    F: BatchFn<K, V>,
+{
+    fn enqueue(&self, key: &K) -> Option<Lookup<K, V, F::Error>> {
+        self.state.lock().unwrap().enqueue(key)
+    }
+
+    // Start batches with all pending keys.
+    fn dispatch(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.scheduled = false;
+
+        let pending = mem::take(&mut state.pending);
+        let batch_size = state.max_batch_size.unwrap_or_else(|| pending.len().max(1));
+
+        for keys in pending.chunks(batch_size) {
+            let batch_fn = self.batch_fn.clone();
+            let batch_keys = keys.to_vec();
+            let batch = async move { batch_fn.load(batch_keys).await.map(Arc::new) }
+                .boxed()
+                .shared();
+
+            state.batches += 1;
+            let id = state.batches;
+            for key in keys {
+                state.in_flight.insert(key.clone(), (id, batch.clone()));
+            }
+        }
+
+        for waker in state.waiting.drain(..) {
+            waker.wake();
+        }
+    }
+
+    async fn wait(&self, key: &K, batch: Batch<K, V, F::Error>) -> Result<Option<V>, F::Error> {
+        let result = batch.await;
+        let mut state = self.state.lock().unwrap();
+
+        match result {
+            // The first load of a batch moves the values of all keys of the
+            // batch into the cache.
+            Ok(ref values) => {
+                if let Some((id, _)) = state.in_flight.remove(key) {
+                    let mut keys = state
+                        .in_flight
+                        .iter()
+                        .filter(|(_, (batch_id, _))| *batch_id == id)
+                        .map(|(k, _)| k.clone())
+                        .collect::<Vec<_>>();
+                    keys.push(key.clone());
+
+                    for k in keys {
+                        state.in_flight.remove(&k);
+                        let value = values.get(&k).cloned();
+                        state.cache.insert(k, value);
+                    }
+                }
+            }
+            // Errors are not cached. Every load of the failed batch gets the
+            // error, later loads of its keys start a new batch.
+            Err(_) => {
+                state.in_flight.remove(key);
+            }
+        }
+
+        result.map(|values| values.get(key).cloned())
+    }
+}
+
+enum Lookup<K, V, E> {
+    Cached(Option<V>),
+    InFlight(Batch<K, V, E>),
+}
+
+// Waits until the executor dispatches the pending keys of the loader.
+struct WaitForDispatch<'a, K, V, F>
+where
+    F: BatchFn<K, V>,
+{
+    inner: &'a Arc<Inner<K, V, F>>,
+    key: &'a K,
+}
+
+impl<'a, K, V, F> Future for WaitForDispatch<'a, K, V, F>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    F: BatchFn<K, V>,
+{
+    type Output = Lookup<K, V, F::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.inner.state.lock().unwrap();
+        if let Some(lookup) = state.enqueue(self.key) {
+            return Poll::Ready(lookup);
+        }
+
+        state.waiting.push(cx.waker().clone());
+        if !state.scheduled {
+            state.scheduled = true;
+            let inner = self.inner.clone() as Arc<dyn Dispatch>;
+            EXECUTOR_POLL.with(|s| {
+                if let Some(ref mut scheduled) = *s.borrow_mut() {
+                    scheduled.push(inner);
+                }
+            });
+        }
+        Poll::Pending
+    }
+}
+
+trait Dispatch: Send + Sync {
+    fn dispatch(&self);
+
+    // Called when the executor that scheduled the loader is dropped before
+    // dispatching it, so that the waiting loads schedule it again.
+    fn unschedule(&self);
+}
+
+impl<K, V, F> Dispatch for Inner<K, V, F>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    F: BatchFn<K, V>,
+{
+    fn dispatch(&self) {
+        Inner::dispatch(self)
+    }
+
+    fn unschedule(&self) {
+        // This also runs while unwinding, don't panic on a poisoned lock.
+        if let Ok(mut state) = self.state.lock() {
+            state.scheduled = false;
+            for waker in state.waiting.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+thread_local! {
+    // The loaders with keys that wait for the end of the executor's current
+    // poll, `None` outside of the executor.
+    static EXECUTOR_POLL: RefCell<Option<Vec<Arc<dyn Dispatch>>>> = RefCell::new(None);
+}
+
+// Dispatches the keys of all loads that were started while polling the
+// wrapped future, once it can't make progress anymore.
+pub(crate) struct Batched<F> {
+    future: Pin<Box<F>>,
+    scheduled: Vec<Arc<dyn Dispatch>>,
+}
+
+pub(crate) fn batched<F>(future: F) -> Batched<F>
+where
+    F: Future,
+{
+    Batched {
+        future: Box::pin(future),
+        scheduled: Vec::new(),
+    }
+}
+
+impl<F> Future for Batched<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<F::Output> {
+        let waker = Arc::new(PollWaker {
+            woken: AtomicBool::new(false),
+            waker: cx.waker().clone(),
+        });
+
+        let poll = ExecutorPoll::enter();
+        let result = self
+            .future
+            .as_mut()
+            .poll(&mut Context::from_waker(&task::waker(waker.clone())));
+        let scheduled = poll.scheduled();
+        self.scheduled.extend(scheduled);
+
+        // Futures that yield to let other tasks run wake the task while it is
+        // polled, the fields can still make progress in that case.
+        if result.is_pending() && !waker.woken.load(Ordering::SeqCst) {
+            for loader in mem::take(&mut self.scheduled) {
+                loader.dispatch();
+            }
+        }
+        result
+    }
+}
+
+impl<F> Drop for Batched<F> {
+    fn drop(&mut self) {
+        for loader in self.scheduled.drain(..) {
+            loader.unschedule();
+        }
+    }
+}
+
+struct PollWaker {
+    woken: AtomicBool,
+    waker: Waker,
+}
+
+impl ArcWake for PollWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+        arc_self.waker.wake_by_ref();
+    }
+}
+
+// Restores the loaders of an outer executor when dropped, even if polling
+// panics.
+struct ExecutorPoll {
+    outer: Option<Vec<Arc<dyn Dispatch>>>,
+}
+
+impl ExecutorPoll {
+    fn enter() -> Self {
+        ExecutorPoll {
+            outer: EXECUTOR_POLL.with(|s| s.borrow_mut().replace(Vec::new())),
+        }
+    }
+
+    fn scheduled(&self) -> Vec<Arc<dyn Dispatch>> {
+        EXECUTOR_POLL.with(|s| s.borrow_mut().as_mut().map(mem::take).unwrap_or_default())
+    }
+}
+
+impl Drop for ExecutorPoll {
+    fn drop(&mut self) {
+        let scheduled =
+            EXECUTOR_POLL.with(|s| mem::replace(&mut *s.borrow_mut(), self.outer.take()));
+
+        // Loaders are only left behind if polling panicked.
+        for loader in scheduled.into_iter().flatten() {
+            loader.unschedule();
+        }
+    }
+}
+
+// Returns `Pending` once, after scheduling itself to be polled again. The
+// other futures of the task are polled in the meantime.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::{BatchFn, DataLoader};
+
+    use crate::BoxFuture;
+
+    #[derive(Clone, Default)]
+    struct Doubler {
+        batches: Arc<Mutex<Vec<Vec<i32>>>>,
+    }
+
+    impl BatchFn<i32, i32> for Doubler {
+        type Error = String;
+
+        fn load(&self, keys: Vec<i32>) -> BoxFuture<'static, Result<HashMap<i32, i32>, String>> {
+            self.batches.lock().unwrap().push(keys.clone());
+            Box::pin(async move {
+                if keys.contains(&-1) {
+                    Err("Negative key".to_owned())
+                } else {
+                    Ok(keys
+                        .into_iter()
+                        .filter(|k| *k != 0)
+                        .map(|k| (k, k * 2))
+                        .collect())
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_concurrent_loads() {
+        let batch_fn = Doubler::default();
+        let loader = DataLoader::new(batch_fn.clone());
+
+        let values = futures::future::join3(loader.load(1), loader.load(2), loader.load(1)).await;
+
+        assert_eq!(values, (Ok(Some(2)), Ok(Some(4)), Ok(Some(2))));
+        assert_eq!(*batch_fn.batches.lock().unwrap(), vec![vec![1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn caches_loaded_values() {
+        let batch_fn = Doubler::default();
+        let loader = DataLoader::new(batch_fn.clone());
+
+        assert_eq!(loader.load_many(vec![1, 0]).await, Ok(vec![Some(2), None]));
+        assert_eq!(
+            loader.load_many(vec![0, 1, 3]).await,
+            Ok(vec![None, Some(2), Some(6)])
+        );
+        assert_eq!(*batch_fn.batches.lock().unwrap(), vec![vec![1, 0], vec![3]]);
+
+        loader.clear(&1);
+        loader.prime(4, 10);
+        assert_eq!(
+            loader.load_many(vec![1, 4]).await,
+            Ok(vec![Some(2), Some(10)])
+        );
+        assert_eq!(batch_fn.batches.lock().unwrap().last(), Some(&vec![1]));
+    }
+
+    #[tokio::test]
+    async fn splits_large_batches() {
+        let batch_fn = Doubler::default();
+        let loader = DataLoader::new(batch_fn.clone()).with_max_batch_size(2);
+
+        assert_eq!(
+            loader.load_many(vec![1, 2, 3]).await,
+            Ok(vec![Some(2), Some(4), Some(6)])
+        );
+        assert_eq!(batch_fn.batches.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_errors() {
+        let batch_fn = Doubler::default();
+        let loader = DataLoader::new(batch_fn.clone());
+
+        let values = futures::future::join(loader.load(-1), loader.load(1)).await;
+        assert_eq!(
+            values,
+            (
+                Err("Negative key".to_owned()),
+                Err("Negative key".to_owned())
+            )
+        );
+
+        assert_eq!(loader.load(1).await, Ok(Some(2)));
+        assert_eq!(batch_fn.batches.lock().unwrap().len(), 2);
+    }
+}
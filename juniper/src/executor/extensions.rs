@@ -0,0 +1,138 @@
+use std::{
+    mem,
+    sync::{Arc, RwLock},
+};
+
+#[cfg(feature = "multipart")]
+use crate::http::multipart::Files;
+use crate::{
+    ast::InputValue,
+    value::{DefaultScalarValue, Object, Value},
+};
+
+/// Extensions of a single GraphQL operation
+///
+/// Holds the `extensions` member sent along with the request, and collects
+/// the `extensions` entries that end up in the response. Resolvers and
+/// executable directives can reach it through [`Executor::extensions`], and
+/// use it to report things like tracing data, query cost or cache hints.
+///
+/// The response entries are shared with the executors that outlive the
+/// operation's executor, like the ones resolving the items of subscription
+/// streams, so entries they write after the operation has been executed are
+/// collected as well.
+///
+/// [`Executor::extensions`]: struct.Executor.html#method.extensions
+#[derive(Debug)]
+pub struct Extensions<S = DefaultScalarValue> {
+    request: Option<InputValue<S>>,
+    response: Arc<RwLock<Object<S>>>,
+    #[cfg(feature = "multipart")]
+    files: Files,
+}
+
+impl<S> Extensions<S> {
+    /// Construct a new set of extensions from the request `extensions`
+    pub fn new(request: Option<InputValue<S>>) -> Self {
+        Self {
+            request,
+            response: Arc::new(RwLock::new(Object::with_capacity(0))),
+            #[cfg(feature = "multipart")]
+            files: Files::default(),
+        }
+    }
+
+    // The files sent along with a multipart request.
+    #[cfg(feature = "multipart")]
+    pub(crate) fn with_files(self, files: Files) -> Self {
+        Self { files, ..self }
+    }
+
+    #[cfg(feature = "multipart")]
+    pub(crate) fn files(&self) -> &Files {
+        &self.files
+    }
+
+    /// The `extensions` member of the request, if any
+    pub fn request(&self) -> Option<&InputValue<S>> {
+        self.request.as_ref()
+    }
+
+    /// Look up a single entry of the request `extensions` by name
+    pub fn request_entry(&self, name: &str) -> Option<&InputValue<S>> {
+        match self.request {
+            Some(InputValue::Object(ref fields)) => fields
+                .iter()
+                .find(|(k, _)| k.item == name)
+                .map(|(_, v)| &v.item),
+            _ => None,
+        }
+    }
+
+    /// Set an entry of the response `extensions`
+    ///
+    /// Returns the previous value if an entry with the same name was already
+    /// present.
+    pub fn insert<K>(&self, name: K, value: Value<S>) -> Option<Value<S>>
+    where
+        K: Into<String>,
+        for<'a> &'a str: PartialEq<K>,
+    {
+        self.response.write().unwrap().add_field(name, value)
+    }
+
+    /// Update an entry of the response `extensions` in place
+    ///
+    /// The closure receives the current value of the entry, if any, and
+    /// returns the new one. This is useful for accumulating values, like the
+    /// total cost of a query, from several resolvers.
+    pub fn update<F>(&self, name: &str, f: F)
+    where
+        F: FnOnce(Option<Value<S>>) -> Value<S>,
+    {
+        let mut response = self.response.write().unwrap();
+        let previous = response.iter_mut().find(|(k, _)| k == name);
+        if let Some((_, value)) = previous {
+            let previous = mem::replace(value, Value::Null);
+            *value = f(Some(previous));
+        } else {
+            response.add_field(name, f(None));
+        }
+    }
+}
+
+impl<S> Extensions<S>
+where
+    S: Clone,
+{
+    /// A copy of the response `extensions` collected so far
+    pub fn response(&self) -> Object<S> {
+        self.response.read().unwrap().clone()
+    }
+
+    /// Consume the extensions and return the collected response entries
+    pub fn into_response(self) -> Object<S> {
+        match Arc::try_unwrap(self.response) {
+            Ok(response) => response.into_inner().unwrap(),
+            // Still shared with an executor, like one of a subscription stream.
+            Err(response) => response.read().unwrap().clone(),
+        }
+    }
+
+    // A handle with the same request and response entries, for executors
+    // that outlive the borrowed extensions.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            request: self.request.clone(),
+            response: Arc::clone(&self.response),
+            #[cfg(feature = "multipart")]
+            files: self.files.clone(),
+        }
+    }
+}
+
+impl<S> Default for Extensions<S> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
@@ -22,21 +22,27 @@ use crate::{
         },
         model::{RootNode, SchemaType, TypeType},
     },
-    types::{base::GraphQLType, name::Name},
+    types::{base::GraphQLType, directives::FieldDirectives, name::Name},
     value::{DefaultScalarValue, ParseScalarValue, ScalarValue, Value},
     GraphQLError,
 };
 
 pub use self::{
+    extensions::Extensions,
     look_ahead::{
         Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadMethods,
         LookAheadSelection, LookAheadValue,
     },
     owned_executor::OwnedExecutor,
+    tracer::{TracedField, Tracer},
 };
 
+pub(crate) use self::tracer::Tracers;
+
+mod extensions;
 mod look_ahead;
 mod owned_executor;
+mod tracer;
 
 /// A type registry used to build schemas
 ///
@@ -48,9 +54,12 @@ pub struct Registry<'r, S = DefaultScalarValue> {
     pub types: FnvHashMap<Name, MetaType<'r, S>>,
 }
 
-#[derive(Clone)]
+/// The path from the root of an operation to the field being resolved
+#[derive(Clone, Debug)]
 pub enum FieldPath<'a> {
+    /// The root of the operation, at the position of its definition
     Root(SourcePosition),
+    /// A field, by its response name and position, and the path to its parent
     Field(&'a str, SourcePosition, Arc<FieldPath<'a>>),
 }
 
@@ -71,7 +80,10 @@ where
     schema: &'a SchemaType<'a, S>,
     context: &'a CtxT,
     errors: &'r RwLock<Vec<ExecutionError<S>>>,
+    extensions: &'r Extensions<S>,
     field_path: Arc<FieldPath<'a>>,
+    directives: Option<Arc<dyn FieldDirectives<S> + 'a>>,
+    tracers: &'a Tracers<S>,
 }
 
 /// Error type for errors that occur during query execution
@@ -499,7 +511,10 @@ where
             schema: self.schema,
             context: ctx,
             errors: self.errors,
+            extensions: self.extensions,
             field_path: self.field_path.clone(),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 
@@ -527,11 +542,14 @@ where
             schema: self.schema,
             context: self.context,
             errors: self.errors,
+            extensions: self.extensions,
             field_path: Arc::new(FieldPath::Field(
                 field_alias,
                 location,
                 Arc::clone(&self.field_path),
             )),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 
@@ -553,7 +571,10 @@ where
             schema: self.schema,
             context: self.context,
             errors: self.errors,
+            extensions: self.extensions,
             field_path: self.field_path.clone(),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 
@@ -570,11 +591,31 @@ where
         self.context
     }
 
+    /// Access the extensions of the current operation
+    ///
+    /// Gives access to the `extensions` member of the request, and allows
+    /// adding entries to the `extensions` member of the response.
+    pub fn extensions(&self) -> &'r Extensions<S> {
+        self.extensions
+    }
+
     /// The currently executing schema
     pub fn schema(&self) -> &'a SchemaType<S> {
         self.schema
     }
 
+    pub(crate) fn executable_directives(&self) -> Option<&(dyn FieldDirectives<S> + 'a)> {
+        self.directives.as_deref()
+    }
+
+    pub(crate) fn tracers(&self) -> &'a Tracers<S> {
+        self.tracers
+    }
+
+    pub(crate) fn field_path(&self) -> &FieldPath<'a> {
+        &self.field_path
+    }
+
     #[doc(hidden)]
     pub fn current_type(&self) -> &TypeType<'a, S> {
         &self.current_type
@@ -699,12 +740,22 @@ where
             schema: self.schema,
             context: self.context,
             errors: RwLock::new(vec![]),
+            extensions: Arc::new(self.extensions.share()),
             field_path: Arc::clone(&self.field_path),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 }
 
 impl<'a> FieldPath<'a> {
+    /// The response names of the fields from the root down to this one
+    pub fn to_vec(&self) -> Vec<String> {
+        let mut path = Vec::new();
+        self.construct_path(&mut path);
+        path
+    }
+
     fn construct_path(&self, acc: &mut Vec<String>) {
         match self {
             FieldPath::Root(_) => (),
@@ -715,7 +766,8 @@ impl<'a> FieldPath<'a> {
         }
     }
 
-    fn location(&self) -> &SourcePosition {
+    /// The position of the field, or of the operation for the root
+    pub fn location(&self) -> &SourcePosition {
         match *self {
             FieldPath::Root(ref pos) | FieldPath::Field(_, ref pos, _) => pos,
         }
@@ -756,6 +808,7 @@ pub fn execute_validated_query<'a, 'b, QueryT, MutationT, SubscriptionT, CtxT, S
     root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &CtxT,
+    extensions: &Extensions<S>,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
 where
     S: ScalarValue,
@@ -787,6 +840,7 @@ where
     });
 
     let errors = RwLock::new(Vec::new());
+    let started;
     let value;
 
     {
@@ -824,9 +878,15 @@ where
             schema: &root_node.schema,
             context,
             errors: &errors,
+            extensions,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            directives: root_node.directives.bind(context),
+            tracers: &root_node.tracers,
         };
 
+        started = root_node
+            .tracers
+            .execution_start(extensions, &operation.item);
         value = match operation.item.operation_type {
             OperationType::Query => executor.resolve_into_value(&root_node.query_info, &root_node),
             OperationType::Mutation => {
@@ -838,6 +898,9 @@ where
 
     let mut errors = errors.into_inner().unwrap();
     errors.sort();
+    root_node
+        .tracers
+        .execution_end(extensions, started, &errors);
 
     Ok((value, errors))
 }
@@ -850,6 +913,7 @@ pub async fn execute_validated_query_async<'a, 'b, QueryT, MutationT, Subscripti
     root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &CtxT,
+    extensions: &Extensions<S>,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
 where
     S: ScalarValue + Send + Sync,
@@ -885,6 +949,7 @@ where
     });
 
     let errors = RwLock::new(Vec::new());
+    let started;
     let value;
 
     {
@@ -922,26 +987,44 @@ where
             schema: &root_node.schema,
             context,
             errors: &errors,
+            extensions,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            directives: root_node.directives.bind(context),
+            tracers: &root_node.tracers,
         };
 
-        value = match operation.item.operation_type {
-            OperationType::Query => {
-                executor
-                    .resolve_into_value_async(&root_node.query_info, &root_node)
-                    .await
-            }
-            OperationType::Mutation => {
-                executor
-                    .resolve_into_value_async(&root_node.mutation_info, &root_node.mutation_type)
-                    .await
+        started = root_node
+            .tracers
+            .execution_start(extensions, &operation.item);
+
+        // The keys of `DataLoader`s are dispatched whenever the executor can't
+        // make progress without them.
+        value = crate::dataloader::batched(async {
+            match operation.item.operation_type {
+                OperationType::Query => {
+                    executor
+                        .resolve_into_value_async(&root_node.query_info, &root_node)
+                        .await
+                }
+                OperationType::Mutation => {
+                    executor
+                        .resolve_into_value_async(
+                            &root_node.mutation_info,
+                            &root_node.mutation_type,
+                        )
+                        .await
+                }
+                OperationType::Subscription => unreachable!(),
             }
-            OperationType::Subscription => unreachable!(),
-        };
+        })
+        .await;
     }
 
     let mut errors = errors.into_inner().unwrap();
     errors.sort();
+    root_node
+        .tracers
+        .execution_end(extensions, started, &errors);
 
     Ok((value, errors))
 }
@@ -994,6 +1077,7 @@ pub async fn resolve_validated_subscription<
     root_node: &'r RootNode<'r, QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &'r CtxT,
+    extensions: &Extensions<S>,
 ) -> Result<(Value<ValuesStream<'r, S>>, Vec<ExecutionError<S>>), GraphQLError<'r>>
 where
     'r: 'exec_ref,
@@ -1069,7 +1153,10 @@ where
             schema: &root_node.schema,
             context,
             errors: &errors,
+            extensions,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            directives: root_node.directives.bind(context),
+            tracers: &root_node.tracers,
         };
 
         value = match operation.item.operation_type {
@@ -1132,6 +1219,9 @@ where
             arguments: None,
             field_type: self.get_type::<T>(info),
             deprecation_status: DeprecationStatus::Current,
+            complexity: 1,
+            list_size: 1,
+            directives: vec![],
         }
     }
 
@@ -1150,6 +1240,9 @@ where
             arguments: None,
             field_type: self.get_type::<I>(info),
             deprecation_status: DeprecationStatus::Current,
+            complexity: 1,
+            list_size: 1,
+            directives: vec![],
         }
     }
 
@@ -5,9 +5,10 @@ use std::{
 
 use crate::{
     ast::Fragment,
-    executor::FieldPath,
+    executor::{Extensions, FieldPath, Tracers},
     parser::SourcePosition,
     schema::model::{SchemaType, TypeType},
+    types::directives::FieldDirectives,
     ExecutionError, Executor, Selection, Variables,
 };
 
@@ -22,7 +23,10 @@ pub struct OwnedExecutor<'a, CtxT, S> {
     pub(super) schema: &'a SchemaType<'a, S>,
     pub(super) context: &'a CtxT,
     pub(super) errors: RwLock<Vec<ExecutionError<S>>>,
+    pub(super) extensions: Arc<Extensions<S>>,
     pub(super) field_path: Arc<FieldPath<'a>>,
+    pub(super) directives: Option<Arc<dyn FieldDirectives<S> + 'a>>,
+    pub(super) tracers: &'a Tracers<S>,
 }
 
 impl<'a, CtxT, S> Clone for OwnedExecutor<'a, CtxT, S>
@@ -39,7 +43,10 @@ where
             schema: self.schema,
             context: self.context,
             errors: RwLock::new(vec![]),
+            extensions: Arc::clone(&self.extensions),
             field_path: self.field_path.clone(),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 }
@@ -66,7 +73,10 @@ where
             schema: self.schema,
             context: self.context,
             errors: RwLock::new(vec![]),
+            extensions: Arc::clone(&self.extensions),
             field_path: self.field_path.clone(),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 
@@ -99,11 +109,14 @@ where
             schema: self.schema,
             context: self.context,
             errors: RwLock::new(vec![]),
+            extensions: Arc::clone(&self.extensions),
             field_path: Arc::new(FieldPath::Field(
                 field_alias,
                 location,
                 Arc::clone(&self.field_path),
             )),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 
@@ -126,7 +139,10 @@ where
             schema: self.schema,
             context: self.context,
             errors: &self.errors,
+            extensions: &self.extensions,
             field_path: Arc::clone(&self.field_path),
+            directives: self.directives.clone(),
+            tracers: self.tracers,
         }
     }
 }
@@ -0,0 +1,238 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ast::{Operation, Type},
+    executor::{ExecutionError, ExecutionResult, Extensions, FieldPath},
+    value::{DefaultScalarValue, ScalarValue},
+    GraphQLError,
+};
+
+/// A field about to be, or just, resolved by the executor
+#[derive(Debug)]
+pub struct TracedField<'a> {
+    /// The path of the field in the response
+    pub path: &'a FieldPath<'a>,
+    /// The name of the type the field is defined on
+    pub parent_type: &'a str,
+    /// The name of the field in the schema, regardless of its alias
+    pub field_name: &'a str,
+    /// The type of the field
+    pub return_type: &'a Type<'a>,
+}
+
+/// Callbacks invoked around each phase of the execution of an operation
+///
+/// Tracers are meant for instrumentation, like emitting spans, recording
+/// latency metrics or reporting tracing data in the response `extensions`,
+/// without having to wrap every resolver by hand. Each callback receives the
+/// [`Extensions`] of the current operation, and every callback ending a phase
+/// receives the time the phase took.
+///
+/// All callbacks do nothing by default. Parsing and validation callbacks are
+/// skipped for documents served from the document cache of the schema.
+///
+/// Register tracers with
+/// [`RootNode::with_tracer`](struct.RootNode.html#method.with_tracer). Field
+/// callbacks are invoked by both the synchronous and the asynchronous
+/// executor.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use juniper::{ExecutionResult, Extensions, TracedField, Tracer};
+///
+/// struct SlowFields;
+///
+/// impl Tracer for SlowFields {
+///     fn field_end(
+///         &self,
+///         _: &Extensions,
+///         field: &TracedField,
+///         duration: Duration,
+///         _: &ExecutionResult,
+///     ) {
+///         if duration > Duration::from_millis(100) {
+///             println!("{}.{} took {:?}", field.parent_type, field.field_name, duration);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Extensions`]: struct.Extensions.html
+pub trait Tracer<S = DefaultScalarValue>: Send + Sync
+where
+    S: ScalarValue,
+{
+    /// Called before the query is parsed
+    fn parse_start(&self, _extensions: &Extensions<S>, _query: &str) {}
+
+    /// Called after the query is parsed, with the error if parsing failed
+    fn parse_end(
+        &self,
+        _extensions: &Extensions<S>,
+        _duration: Duration,
+        _error: Option<&GraphQLError>,
+    ) {
+    }
+
+    /// Called before the document is validated
+    fn validation_start(&self, _extensions: &Extensions<S>) {}
+
+    /// Called after the document is validated, with the error if validation
+    /// failed
+    fn validation_end(
+        &self,
+        _extensions: &Extensions<S>,
+        _duration: Duration,
+        _error: Option<&GraphQLError>,
+    ) {
+    }
+
+    /// Called before the operation is executed
+    fn execution_start(&self, _extensions: &Extensions<S>, _operation: &Operation<S>) {}
+
+    /// Called after the operation is executed, with the field errors that
+    /// occurred
+    fn execution_end(
+        &self,
+        _extensions: &Extensions<S>,
+        _duration: Duration,
+        _errors: &[ExecutionError<S>],
+    ) {
+    }
+
+    /// Called before a field is resolved
+    fn field_start(&self, _extensions: &Extensions<S>, _field: &TracedField) {}
+
+    /// Called after a field is resolved, with the result of resolving it
+    ///
+    /// For fields of object types, the result includes all of their
+    /// sub-fields.
+    fn field_end(
+        &self,
+        _extensions: &Extensions<S>,
+        _field: &TracedField,
+        _duration: Duration,
+        _result: &ExecutionResult<S>,
+    ) {
+    }
+}
+
+/// The tracers registered on a schema
+///
+/// Each `*_start` method returns the time the phase started, if there are any
+/// tracers, to be passed to the matching `*_end` method.
+pub(crate) struct Tracers<S>(Vec<Box<dyn Tracer<S>>>);
+
+impl<S> Default for Tracers<S> {
+    fn default() -> Self {
+        Tracers(Vec::new())
+    }
+}
+
+impl<S> fmt::Debug for Tracers<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Tracers({})", self.0.len())
+    }
+}
+
+impl<S> Tracers<S>
+where
+    S: ScalarValue,
+{
+    pub(crate) fn push<T>(&mut self, tracer: T)
+    where
+        T: Tracer<S> + 'static,
+    {
+        self.0.push(Box::new(tracer));
+    }
+
+    fn start<F>(&self, f: F) -> Option<Instant>
+    where
+        F: Fn(&dyn Tracer<S>),
+    {
+        if self.0.is_empty() {
+            return None;
+        }
+        for tracer in &self.0 {
+            f(&**tracer);
+        }
+        Some(Instant::now())
+    }
+
+    fn end<F>(&self, started: Option<Instant>, f: F)
+    where
+        F: Fn(&dyn Tracer<S>, Duration),
+    {
+        if let Some(started) = started {
+            let duration = started.elapsed();
+            for tracer in &self.0 {
+                f(&**tracer, duration);
+            }
+        }
+    }
+
+    pub(crate) fn parse_start(&self, extensions: &Extensions<S>, query: &str) -> Option<Instant> {
+        self.start(|t| t.parse_start(extensions, query))
+    }
+
+    pub(crate) fn parse_end(
+        &self,
+        extensions: &Extensions<S>,
+        started: Option<Instant>,
+        error: Option<&GraphQLError>,
+    ) {
+        self.end(started, |t, d| t.parse_end(extensions, d, error))
+    }
+
+    pub(crate) fn validation_start(&self, extensions: &Extensions<S>) -> Option<Instant> {
+        self.start(|t| t.validation_start(extensions))
+    }
+
+    pub(crate) fn validation_end(
+        &self,
+        extensions: &Extensions<S>,
+        started: Option<Instant>,
+        error: Option<&GraphQLError>,
+    ) {
+        self.end(started, |t, d| t.validation_end(extensions, d, error))
+    }
+
+    pub(crate) fn execution_start(
+        &self,
+        extensions: &Extensions<S>,
+        operation: &Operation<S>,
+    ) -> Option<Instant> {
+        self.start(|t| t.execution_start(extensions, operation))
+    }
+
+    pub(crate) fn execution_end(
+        &self,
+        extensions: &Extensions<S>,
+        started: Option<Instant>,
+        errors: &[ExecutionError<S>],
+    ) {
+        self.end(started, |t, d| t.execution_end(extensions, d, errors))
+    }
+
+    pub(crate) fn field_start(
+        &self,
+        extensions: &Extensions<S>,
+        field: &TracedField,
+    ) -> Option<Instant> {
+        self.start(|t| t.field_start(extensions, field))
+    }
+
+    pub(crate) fn field_end(
+        &self,
+        extensions: &Extensions<S>,
+        field: &TracedField,
+        started: Option<Instant>,
+        result: &ExecutionResult<S>,
+    ) {
+        self.end(started, |t, d| t.field_end(extensions, field, d, result))
+    }
+}
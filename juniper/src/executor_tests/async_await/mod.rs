@@ -107,3 +107,33 @@ async fn async_simple() {
         }),
     );
 }
+
+#[tokio::test]
+async fn async_typename_keeps_selection_order() {
+    let schema = RootNode::new(Query, EmptyMutation::new(), crate::EmptySubscription::new());
+    let doc = r#"
+        query {
+            user(id: "user1") {
+                name
+                __typename
+                kind
+            }
+        }
+    "#;
+
+    let vars = Default::default();
+    let (res, errs) = crate::execute(doc, None, &schema, &vars, &())
+        .await
+        .unwrap();
+
+    assert!(errs.is_empty());
+
+    let user = res
+        .as_object_value()
+        .and_then(|res| res.get_field_value("user"))
+        .and_then(|user| user.as_object_value())
+        .unwrap();
+    let fields = user.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(fields, ["name", "__typename", "kind"]);
+}
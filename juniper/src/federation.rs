@@ -0,0 +1,656 @@
+//! Support for Apollo Federation subgraphs
+//!
+//! A subgraph is a schema that is composed with other subgraphs into a single
+//! graph by a federation gateway. On top of its own types, a subgraph exposes
+//! the `_service { sdl }` field, which the gateway uses to fetch its schema,
+//! and the `_entities(representations:)` field, which resolves references to
+//! the subgraph's entities from other subgraphs.
+//!
+//! Entities are object types with a `@key` directive that implement
+//! [`Entity`](trait.Entity.html). They are registered with
+//! [`Entities`](struct.Entities.html) when the schema is built with
+//! [`RootNode::new_subgraph`](../struct.RootNode.html#method.new_subgraph):
+//!
+//! ```rust
+//! use juniper::{
+//!     federation::{Entities, Entity, Representation},
+//!     EmptyMutation, EmptySubscription, FieldResult, RootNode,
+//! };
+//!
+//! #[derive(juniper::GraphQLObject)]
+//! #[graphql(directive(key(fields = "id")))]
+//! struct User {
+//!     id: String,
+//!     name: String,
+//! }
+//!
+//! impl Entity for User {
+//!     fn resolve_reference(representation: &Representation, _: &()) -> FieldResult<Option<User>> {
+//!         Ok(representation.get::<String>("id").map(|id| User {
+//!             name: format!("User {}", id),
+//!             id,
+//!         }))
+//!     }
+//! }
+//!
+//! struct Query;
+//!
+//! #[juniper::graphql_object]
+//! impl Query {
+//!     fn me() -> User {
+//!         User { id: "1".to_owned(), name: "Me".to_owned() }
+//!     }
+//! }
+//!
+//! let schema = RootNode::new_subgraph(
+//!     Query,
+//!     EmptyMutation::<()>::new(),
+//!     EmptySubscription::<()>::new(),
+//!     Entities::new().entity::<User>(),
+//! );
+//!
+//! assert!(schema.as_sdl().contains("type User @key(fields: \"id\") {"));
+//! ```
+
+use std::marker::PhantomData;
+
+use juniper_codegen::GraphQLObjectInternal as GraphQLObject;
+
+use crate::{
+    ast::{FromInputValue, InputValue, Type},
+    executor::{ExecutionResult, Executor, FieldError, FieldResult, Registry},
+    parser::{parse_scalar_literal, ScalarToken},
+    schema::{
+        meta::MetaType,
+        model::{DirectiveLocation, DirectiveType, RootNode},
+        printer::{print_schema_excluding, Excluded},
+    },
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+    },
+    value::{DefaultScalarValue, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+// The types, directives and query fields added to a subgraph, which are not
+// part of the SDL returned by `_service`.
+const FEDERATION_EXCLUDED: Excluded<'static> = Excluded {
+    types: &["_Any", "_Entity", "_Service"],
+    directives: &["extends", "external", "key", "provides", "requires"],
+    query_fields: &["_entities", "_service"],
+};
+
+/// A reference to an entity, passed to the `_entities` field as an `_Any`
+///
+/// Representations are objects with the `__typename` of the entity and the
+/// fields of one of its keys, like `{ __typename: "User", id: "1" }`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Representation<S = DefaultScalarValue> {
+    typename: String,
+    value: InputValue<S>,
+}
+
+impl<S> Representation<S>
+where
+    S: ScalarValue,
+{
+    /// The name of the entity's type
+    pub fn typename(&self) -> &str {
+        &self.typename
+    }
+
+    /// Convert a field of the representation, if it is present and has the
+    /// right type
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromInputValue<S>,
+    {
+        self.value
+            .to_object_value()?
+            .get(key)
+            .and_then(|v| T::from_input_value(v))
+    }
+
+    /// The whole representation, including the `__typename`
+    pub fn value(&self) -> &InputValue<S> {
+        &self.value
+    }
+}
+
+impl<S> FromInputValue<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    fn from_input_value(v: &InputValue<S>) -> Option<Representation<S>> {
+        let typename = v.to_object_value()?.get("__typename")?.as_string_value()?;
+
+        Some(Representation {
+            typename: typename.to_owned(),
+            value: v.clone(),
+        })
+    }
+}
+
+impl<S> ParseScalarValue<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    fn from_str(value: ScalarToken<'_>) -> ParseScalarResult<'_, S> {
+        parse_scalar_literal(value)
+    }
+}
+
+impl<S> GraphQLType<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("_Any")
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        registry.build_scalar_type::<Self>(info).into_meta()
+    }
+}
+
+/// An object type that can be resolved from a reference by other subgraphs
+///
+/// The type should have a `@key` directive, which tells the gateway which
+/// fields make up the representations of the entity.
+///
+/// Implement `resolve_reference` for entities that can be looked up
+/// synchronously, or `resolve_reference_async` for those that need to await a
+/// datastore. The asynchronous executor uses the latter, which falls back to
+/// the former by default, while the synchronous executor only uses the former.
+pub trait Entity<S = DefaultScalarValue>: GraphQLTypeAsync<S, TypeInfo = ()> + 'static
+where
+    Self::Context: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    /// Resolve the entity that the representation refers to
+    ///
+    /// Returning `None` resolves the entity to `null`. The default
+    /// implementation fails, as the entity can only be resolved
+    /// asynchronously.
+    fn resolve_reference(
+        _representation: &Representation<S>,
+        _context: &Self::Context,
+    ) -> FieldResult<Option<Self>, S> {
+        Err(FieldError::new(
+            format!(
+                "Entity \"{}\" can only be resolved asynchronously",
+                Self::name(&()).unwrap_or_default(),
+            ),
+            Value::null(),
+        ))
+    }
+
+    /// Resolve the entity that the representation refers to asynchronously
+    ///
+    /// Returning `None` resolves the entity to `null`. The default
+    /// implementation calls `resolve_reference`.
+    fn resolve_reference_async<'a>(
+        representation: &'a Representation<S>,
+        context: &'a Self::Context,
+    ) -> BoxFuture<'a, FieldResult<Option<Self>, S>> {
+        Box::pin(futures::future::ready(Self::resolve_reference(
+            representation,
+            context,
+        )))
+    }
+}
+
+/// The entities of a subgraph, which make up its `_Entity` union
+pub struct Entities<CtxT, S = DefaultScalarValue> {
+    types: Vec<Box<dyn EntityType<CtxT, S>>>,
+}
+
+impl<CtxT, S> Default for Entities<CtxT, S> {
+    fn default() -> Self {
+        Entities { types: Vec::new() }
+    }
+}
+
+impl<CtxT, S> Entities<CtxT, S>
+where
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    /// Create an empty set of entities
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entity type
+    pub fn entity<T>(mut self) -> Self
+    where
+        T: Entity<S, Context = CtxT>,
+    {
+        self.types.push(Box::new(EntityMarker::<T>(PhantomData)));
+        self
+    }
+}
+
+trait EntityType<CtxT, S>: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn meta<'r>(&self, registry: &mut Registry<'r, S>) -> Type<'r>
+    where
+        S: 'r;
+
+    fn resolve_reference(
+        &self,
+        representation: &Representation<S>,
+        context: &CtxT,
+    ) -> FieldResult<Option<BoxedEntity<CtxT, S>>, S>;
+
+    fn resolve_reference_async<'a>(
+        &self,
+        representation: &'a Representation<S>,
+        context: &'a CtxT,
+    ) -> BoxFuture<'a, FieldResult<Option<BoxedEntity<CtxT, S>>, S>>;
+}
+
+struct EntityMarker<T>(PhantomData<fn() -> T>);
+
+impl<T, CtxT, S> EntityType<CtxT, S> for EntityMarker<T>
+where
+    T: Entity<S, Context = CtxT>,
+    CtxT: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn name(&self) -> &str {
+        T::name(&()).expect("Entity types must be named")
+    }
+
+    fn meta<'r>(&self, registry: &mut Registry<'r, S>) -> Type<'r>
+    where
+        S: 'r,
+    {
+        registry.get_type::<T>(&())
+    }
+
+    fn resolve_reference(
+        &self,
+        representation: &Representation<S>,
+        context: &CtxT,
+    ) -> FieldResult<Option<BoxedEntity<CtxT, S>>, S> {
+        Ok(T::resolve_reference(representation, context)?
+            .map(|entity| Box::new(entity) as BoxedEntity<CtxT, S>))
+    }
+
+    fn resolve_reference_async<'a>(
+        &self,
+        representation: &'a Representation<S>,
+        context: &'a CtxT,
+    ) -> BoxFuture<'a, FieldResult<Option<BoxedEntity<CtxT, S>>, S>> {
+        Box::pin(async move {
+            Ok(T::resolve_reference_async(representation, context)
+                .await?
+                .map(|entity| Box::new(entity) as BoxedEntity<CtxT, S>))
+        })
+    }
+}
+
+type BoxedEntity<CtxT, S> = Box<dyn ResolveEntity<CtxT, S>>;
+
+trait ResolveEntity<CtxT, S>: Send + Sync {
+    fn resolve(&self, executor: &Executor<CtxT, S>) -> ExecutionResult<S>;
+
+    fn resolve_async<'a>(
+        &'a self,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>>;
+}
+
+impl<T, CtxT, S> ResolveEntity<CtxT, S> for T
+where
+    T: GraphQLTypeAsync<S, Context = CtxT, TypeInfo = ()>,
+    CtxT: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve(&self, executor: &Executor<CtxT, S>) -> ExecutionResult<S> {
+        executor.resolve(&(), self)
+    }
+
+    fn resolve_async<'a>(
+        &'a self,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        Box::pin(executor.resolve_async(&(), self))
+    }
+}
+
+/// A member of the `_Entity` union
+struct EntityValue<CtxT, S> {
+    typename: String,
+    entity: BoxedEntity<CtxT, S>,
+}
+
+impl<CtxT, S> GraphQLType<S> for EntityValue<CtxT, S>
+where
+    S: ScalarValue,
+{
+    type Context = CtxT;
+    type TypeInfo = Entities<CtxT, S>;
+
+    fn name(_: &Entities<CtxT, S>) -> Option<&str> {
+        Some("_Entity")
+    }
+
+    fn meta<'r>(info: &Entities<CtxT, S>, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let types = info
+            .types
+            .iter()
+            .map(|t| t.meta(registry))
+            .collect::<Vec<_>>();
+
+        registry.build_union_type::<Self>(info, &types).into_meta()
+    }
+
+    fn concrete_type_name(&self, _: &CtxT, _: &Entities<CtxT, S>) -> String {
+        self.typename.clone()
+    }
+
+    fn resolve_into_type(
+        &self,
+        _: &Entities<CtxT, S>,
+        type_name: &str,
+        _: Option<&[crate::ast::Selection<S>]>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S> {
+        if type_name == self.typename {
+            self.entity.resolve(executor)
+        } else {
+            Ok(Value::null())
+        }
+    }
+}
+
+impl<CtxT, S> GraphQLTypeAsync<S> for EntityValue<CtxT, S>
+where
+    CtxT: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_into_type_async<'a>(
+        &'a self,
+        _: &'a Entities<CtxT, S>,
+        type_name: &str,
+        _: Option<&'a [crate::ast::Selection<'a, S>]>,
+        executor: &'a Executor<'a, 'a, CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        if type_name == self.typename {
+            self.entity.resolve_async(executor)
+        } else {
+            Box::pin(futures::future::ready(Ok(Value::null())))
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+#[graphql(name = "_Service")]
+struct Service {
+    sdl: String,
+}
+
+/// The query type of a subgraph
+///
+/// Wraps the query type of the subgraph's own schema, and adds the `_service`
+/// and `_entities` fields to it. The `_entities` field is only added if the
+/// subgraph has entities.
+pub struct Subgraph<QueryT> {
+    query: QueryT,
+}
+
+impl<QueryT> Subgraph<QueryT> {
+    /// Wrap a query type
+    pub fn new(query: QueryT) -> Self {
+        Subgraph { query }
+    }
+
+    /// The wrapped query type
+    pub fn query(&self) -> &QueryT {
+        &self.query
+    }
+}
+
+fn representations<S>(arguments: &Arguments<S>) -> Vec<Representation<S>>
+where
+    S: ScalarValue,
+{
+    arguments
+        .get::<Vec<Representation<S>>>("representations")
+        .unwrap_or_default()
+}
+
+fn entity_type<'e, CtxT, S>(
+    info: &'e Entities<CtxT, S>,
+    representation: &Representation<S>,
+) -> FieldResult<&'e dyn EntityType<CtxT, S>, S>
+where
+    S: ScalarValue,
+{
+    info.types
+        .iter()
+        .find(|t| t.name() == representation.typename())
+        .map(|t| &**t)
+        .ok_or_else(|| {
+            FieldError::new(
+                format!("Unknown entity type \"{}\"", representation.typename()),
+                Value::null(),
+            )
+        })
+}
+
+fn resolve_entities<CtxT, S>(
+    info: &Entities<CtxT, S>,
+    arguments: &Arguments<S>,
+    context: &CtxT,
+) -> FieldResult<Vec<Option<EntityValue<CtxT, S>>>, S>
+where
+    S: ScalarValue,
+{
+    representations(arguments)
+        .iter()
+        .map(|representation| {
+            Ok(entity_type(info, representation)?
+                .resolve_reference(representation, context)?
+                .map(|entity| EntityValue {
+                    typename: representation.typename().to_owned(),
+                    entity,
+                }))
+        })
+        .collect()
+}
+
+// Looks up the entities concurrently.
+async fn resolve_entities_async<CtxT, S>(
+    info: &Entities<CtxT, S>,
+    arguments: &Arguments<'_, S>,
+    context: &CtxT,
+) -> FieldResult<Vec<Option<EntityValue<CtxT, S>>>, S>
+where
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    let representations = representations(arguments);
+    let entities = representations.iter().map(|representation| async move {
+        Ok(entity_type(info, representation)?
+            .resolve_reference_async(representation, context)
+            .await?
+            .map(|entity| EntityValue {
+                typename: representation.typename().to_owned(),
+                entity,
+            }))
+    });
+
+    futures::future::try_join_all(entities).await
+}
+
+impl<QueryT, S> GraphQLType<S> for Subgraph<QueryT>
+where
+    QueryT: GraphQLType<S, TypeInfo = ()>,
+    S: ScalarValue,
+{
+    type Context = QueryT::Context;
+    type TypeInfo = Entities<QueryT::Context, S>;
+
+    fn name(_: &Entities<QueryT::Context, S>) -> Option<&str> {
+        QueryT::name(&())
+    }
+
+    fn meta<'r>(
+        info: &Entities<QueryT::Context, S>,
+        registry: &mut Registry<'r, S>,
+    ) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let mut fields = vec![registry.field::<Service>("_service", &())];
+        if !info.types.is_empty() {
+            let representations = registry.arg::<Vec<Representation<S>>>("representations", &());
+            fields.push(
+                registry
+                    .field::<Vec<Option<EntityValue<QueryT::Context, S>>>>("_entities", info)
+                    .argument(representations),
+            );
+        }
+
+        match QueryT::meta(&(), registry) {
+            MetaType::Object(mut meta) => {
+                meta.fields.extend(fields);
+                meta.into_meta()
+            }
+            _ => panic!("The query type of a subgraph must be an object"),
+        }
+    }
+
+    fn resolve_field(
+        &self,
+        info: &Entities<QueryT::Context, S>,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<QueryT::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "_service" => executor.resolve_with_ctx(
+                &(),
+                &Service {
+                    sdl: print_schema_excluding(executor.schema(), &FEDERATION_EXCLUDED),
+                },
+            ),
+            "_entities" => {
+                let entities = resolve_entities(info, arguments, executor.context())?;
+                executor.resolve(info, &entities)
+            }
+            _ => self
+                .query
+                .resolve_field(&(), field_name, arguments, executor),
+        }
+    }
+}
+
+impl<QueryT, S> GraphQLTypeAsync<S> for Subgraph<QueryT>
+where
+    QueryT: GraphQLTypeAsync<S, TypeInfo = ()>,
+    QueryT::Context: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a Entities<QueryT::Context, S>,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<QueryT::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        match field_name {
+            "_service" => Box::pin(futures::future::ready(
+                self.resolve_field(info, field_name, arguments, executor),
+            )),
+            "_entities" => Box::pin(async move {
+                let entities = resolve_entities_async(info, arguments, executor.context()).await?;
+                executor.resolve_async(info, &entities).await
+            }),
+            _ => self
+                .query
+                .resolve_field_async(&(), field_name, arguments, executor),
+        }
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S>
+    RootNode<'a, Subgraph<QueryT>, MutationT, SubscriptionT, S>
+where
+    S: ScalarValue + 'a,
+    QueryT: GraphQLType<S, TypeInfo = ()>,
+    MutationT: GraphQLType<S, TypeInfo = ()>,
+    SubscriptionT: GraphQLType<S, TypeInfo = ()>,
+{
+    /// Construct the root node of an Apollo Federation subgraph
+    ///
+    /// The `_service` and `_entities` fields are added to the query type, and
+    /// the `@key`, `@external`, `@requires`, `@provides` and `@extends`
+    /// directives to the schema. Attach the directives to types and fields
+    /// with the `directive` attribute of the codegen macros.
+    pub fn new_subgraph(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+        entities: Entities<QueryT::Context, S>,
+    ) -> Self {
+        RootNode::new_with_info(
+            Subgraph::new(query_obj),
+            mutation_obj,
+            subscription_obj,
+            entities,
+            (),
+            (),
+        )
+        .with_schema_directive(|registry| {
+            field_set_directive(
+                registry,
+                "key",
+                &[DirectiveLocation::Object, DirectiveLocation::Interface],
+            )
+        })
+        .with_schema_directive(|registry| {
+            field_set_directive(registry, "requires", &[DirectiveLocation::FieldDefinition])
+        })
+        .with_schema_directive(|registry| {
+            field_set_directive(registry, "provides", &[DirectiveLocation::FieldDefinition])
+        })
+        .with_schema_directive(|_| {
+            DirectiveType::new("external", &[DirectiveLocation::FieldDefinition], &[])
+        })
+        .with_schema_directive(|_| {
+            DirectiveType::new(
+                "extends",
+                &[DirectiveLocation::Object, DirectiveLocation::Interface],
+                &[],
+            )
+        })
+    }
+}
+
+// A directive with a `fields` argument, which selects fields of the type with
+// the syntax of a selection set, like `"id organization { id }"`.
+fn field_set_directive<'r, S>(
+    registry: &mut Registry<'r, S>,
+    name: &str,
+    locations: &[DirectiveLocation],
+) -> DirectiveType<'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    DirectiveType::new(name, locations, &[registry.arg::<String>("fields", &())])
+}
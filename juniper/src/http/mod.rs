@@ -1,6 +1,9 @@
 //! Utilities for building HTTP endpoints in a library-agnostic manner
 
 pub mod graphiql;
+#[cfg(feature = "multipart")]
+pub mod multipart;
+pub mod persisted_queries;
 pub mod playground;
 
 use serde::{
@@ -11,8 +14,9 @@ use serde::{
 
 use crate::{
     ast::InputValue,
-    executor::{ExecutionError, ValuesStream},
-    value::{DefaultScalarValue, ScalarValue},
+    executor::{ExecutionError, Extensions, ValuesStream},
+    http::persisted_queries::QuerySource,
+    value::{DefaultScalarValue, Object, ScalarValue},
     FieldError, GraphQLError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, RootNode,
     Value, Variables,
 };
@@ -23,17 +27,29 @@ use crate::{
 /// into this struct - it derives Deserialize for exactly this reason.
 ///
 /// For GET, you will need to parse the query string and extract "query",
-/// "operationName", and "variables" manually.
+/// "operationName", "variables" and "extensions" manually.
+///
+/// The "query" may be left out of requests referring to a persisted query,
+/// see [`persisted_queries`](persisted_queries/index.html).
 #[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
 pub struct GraphQLRequest<S = DefaultScalarValue>
 where
     S: ScalarValue,
 {
+    #[serde(default)]
     query: String,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
     #[serde(bound(deserialize = "InputValue<S>: Deserialize<'de> + Serialize"))]
     variables: Option<InputValue<S>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        bound(deserialize = "InputValue<S>: Deserialize<'de> + Serialize")
+    )]
+    extensions: Option<InputValue<S>>,
+    #[cfg(feature = "multipart")]
+    #[serde(skip)]
+    files: multipart::Files,
 }
 
 impl<S> GraphQLRequest<S>
@@ -58,6 +74,11 @@ where
             .unwrap_or_default()
     }
 
+    /// Returns the `extensions` associated with this request.
+    pub fn extensions(&self) -> Option<&InputValue<S>> {
+        self.extensions.as_ref()
+    }
+
     /// Construct a new GraphQL request from parts
     pub fn new(
         query: String,
@@ -68,13 +89,32 @@ where
             query,
             operation_name,
             variables,
+            extensions: None,
+            #[cfg(feature = "multipart")]
+            files: multipart::Files::default(),
         }
     }
 
+    /// Set the `extensions` of this request
+    pub fn with_extensions(mut self, extensions: Option<InputValue<S>>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// The extensions of an operation executing this request
+    fn operation_extensions(&self) -> Extensions<S> {
+        let extensions = Extensions::new(self.extensions.clone());
+        #[cfg(feature = "multipart")]
+        let extensions = extensions.with_files(self.files.clone());
+        extensions
+    }
+
     /// Execute a GraphQL request synchronously using the specified schema and context
     ///
-    /// This is a simple wrapper around the `execute_sync` function exposed at the
-    /// top level of this crate.
+    /// This is a simple wrapper around the `execute_sync_with_extensions`
+    /// function exposed at the top level of this crate. Response `extensions`
+    /// added during execution are included in the returned response, and
+    /// persisted queries are resolved if enabled on the schema.
     pub fn execute_sync<'a, CtxT, QueryT, MutationT, SubscriptionT>(
         &'a self,
         root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
@@ -86,19 +126,47 @@ where
         MutationT: GraphQLType<S, Context = CtxT>,
         SubscriptionT: GraphQLType<S, Context = CtxT>,
     {
-        GraphQLResponse(crate::execute_sync(
-            &self.query,
-            self.operation_name(),
-            root_node,
-            &self.variables(),
-            context,
-        ))
+        let persisted_queries = &root_node.persisted_queries;
+        let source = match persisted_queries.query_source(&self.query, self.extensions.as_ref()) {
+            Ok(source) => source,
+            Err(e) => return GraphQLResponse::error(e),
+        };
+
+        let op = self.operation_name();
+        let vars = &self.variables();
+        let extensions = self.operation_extensions();
+        let res = match source {
+            QuerySource::Provided(hash) => {
+                let res = crate::execute_sync_with_extensions(
+                    &self.query,
+                    op,
+                    root_node,
+                    vars,
+                    context,
+                    &extensions,
+                );
+                persisted_queries.register(hash, &self.query, &res);
+                res
+            }
+            QuerySource::Stored(query) => crate::execute_sync_with_extensions(
+                &query,
+                op,
+                root_node,
+                vars,
+                context,
+                &extensions,
+            )
+            .map_err(GraphQLError::into_static),
+        };
+        GraphQLResponse::from_result(res).with_extensions(extensions.into_response())
     }
 
     /// Execute a GraphQL request using the specified schema and context
     ///
-    /// This is a simple wrapper around the `execute` function exposed at the
-    /// top level of this crate.
+    /// This is a simple wrapper around the `execute_with_extensions` function
+    /// exposed at the top level of this crate. Response `extensions` added
+    /// during execution are included in the returned response, and persisted
+    /// queries are resolved if enabled on the schema.
     pub async fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
         &'a self,
         root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
@@ -114,17 +182,44 @@ where
         SubscriptionT::TypeInfo: Send + Sync,
         CtxT: Send + Sync,
     {
+        let persisted_queries = &root_node.persisted_queries;
+        let source = match persisted_queries.query_source(&self.query, self.extensions.as_ref()) {
+            Ok(source) => source,
+            Err(e) => return GraphQLResponse::error(e),
+        };
+
         let op = self.operation_name();
         let vars = &self.variables();
-        let res = crate::execute(&self.query, op, root_node, vars, context).await;
-        GraphQLResponse(res)
+        let extensions = self.operation_extensions();
+        let res = match source {
+            QuerySource::Provided(hash) => {
+                let res = crate::execute_with_extensions(
+                    &self.query,
+                    op,
+                    root_node,
+                    vars,
+                    context,
+                    &extensions,
+                )
+                .await;
+                persisted_queries.register(hash, &self.query, &res);
+                res
+            }
+            QuerySource::Stored(query) => {
+                crate::execute_with_extensions(&query, op, root_node, vars, context, &extensions)
+                    .await
+                    .map_err(GraphQLError::into_static)
+            }
+        };
+        GraphQLResponse::from_result(res).with_extensions(extensions.into_response())
     }
 }
 
 /// Resolve a GraphQL subscription into `Value<ValuesStream<S>` using the
 /// specified schema and context.
-/// This is a wrapper around the `resolve_into_stream` function exposed at the top
-/// level of this crate.
+/// This is a wrapper around the `resolve_into_stream_with_extensions` function
+/// exposed at the top level of this crate, which passes on the request
+/// `extensions`. Stream items are sent without response `extensions`.
 pub async fn resolve_into_stream<'req, 'rn, 'ctx, 'a, CtxT, QueryT, MutationT, SubscriptionT, S>(
     req: &'req GraphQLRequest<S>,
     root_node: &'rn RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
@@ -146,7 +241,17 @@ where
     let op = req.operation_name();
     let vars = req.variables();
 
-    crate::resolve_into_stream(&req.query, op, root_node, &vars, context).await
+    let extensions = req.operation_extensions();
+
+    crate::resolve_into_stream_with_extensions(
+        &req.query,
+        op,
+        root_node,
+        &vars,
+        context,
+        &extensions,
+    )
+    .await
 }
 
 /// Simple wrapper around the result from executing a GraphQL query
@@ -155,9 +260,10 @@ where
 /// to JSON and send it over the wire. Use the `is_ok` method to determine
 /// whether to send a 200 or 400 HTTP status code.
 #[derive(Debug)]
-pub struct GraphQLResponse<'a, S = DefaultScalarValue>(
-    Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
-);
+pub struct GraphQLResponse<'a, S = DefaultScalarValue> {
+    result: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
+    extensions: Object<S>,
+}
 
 impl<'a, S> GraphQLResponse<'a, S>
 where
@@ -165,12 +271,29 @@ where
 {
     /// Constructs new `GraphQLResponse` using the given result
     pub fn from_result(r: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>) -> Self {
-        Self(r)
+        Self {
+            result: r,
+            extensions: Object::with_capacity(0),
+        }
     }
 
     /// Constructs an error response outside of the normal execution flow
     pub fn error(error: FieldError<S>) -> Self {
-        GraphQLResponse(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+        Self::from_result(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+    }
+
+    /// Attaches the given `extensions` to this response
+    ///
+    /// They are serialized as the `extensions` member of the response, unless
+    /// empty.
+    pub fn with_extensions(mut self, extensions: Object<S>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// The `extensions` of this response
+    pub fn extensions(&self) -> &Object<S> {
+        &self.extensions
     }
 
     /// Was the request successful or not?
@@ -178,7 +301,7 @@ where
     /// Note that there still might be errors in the response even though it's
     /// considered OK. This is by design in GraphQL.
     pub fn is_ok(&self) -> bool {
-        self.0.is_ok()
+        self.result.is_ok()
     }
 }
 
@@ -193,10 +316,10 @@ where
     where
         S: ser::Serializer,
     {
-        match self.0 {
-            Ok((ref res, ref err)) => {
-                let mut map = serializer.serialize_map(None)?;
+        let mut map = serializer.serialize_map(None)?;
 
+        match self.result {
+            Ok((ref res, ref err)) => {
                 map.serialize_key("data")?;
                 map.serialize_value(res)?;
 
@@ -204,16 +327,19 @@ where
                     map.serialize_key("errors")?;
                     map.serialize_value(err)?;
                 }
-
-                map.end()
             }
             Err(ref err) => {
-                let mut map = serializer.serialize_map(Some(1))?;
                 map.serialize_key("errors")?;
                 map.serialize_value(err)?;
-                map.end()
             }
         }
+
+        if self.extensions.field_count() > 0 {
+            map.serialize_key("extensions")?;
+            map.serialize_value(&self.extensions)?;
+        }
+
+        map.end()
     }
 }
 
@@ -404,6 +530,12 @@ pub mod tests {
 
         println!("  - test_duplicate_keys");
         test_duplicate_keys(integration);
+
+        println!("  - test_persisted_query_get");
+        test_persisted_query_get(integration);
+
+        println!("  - test_persisted_query_post");
+        test_persisted_query_post(integration);
     }
 
     fn unwrap_json_response(response: &TestResponse) -> Json {
@@ -554,4 +686,32 @@ pub mod tests {
         );
         assert_eq!(response.status_code, 400);
     }
+
+    fn assert_persisted_query_not_supported(response: &TestResponse) {
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.content_type, "application/json");
+
+        assert_eq!(
+            unwrap_json_response(response)["errors"][0]["extensions"],
+            serde_json::from_str::<Json>(r#"{"code": "PERSISTED_QUERY_NOT_SUPPORTED"}"#)
+                .expect("Invalid JSON constant in test")
+        );
+    }
+
+    // The schemas of the integration tests don't enable persisted queries, but
+    // the `extensions` of the request have to be passed on to juniper to
+    // detect that.
+    fn test_persisted_query_get<T: HTTPIntegration>(integration: &T) {
+        // extensions = { "persistedQuery": { "version": 1, "sha256Hash": hash("{hero{name}}") } }
+        let response = integration.get("/?extensions=%7B%22persistedQuery%22%3A%7B%22version%22%3A1%2C%22sha256Hash%22%3A%22993f8cd4f05bd4830617ad3e781cec9d68ac28b92a8a35eb38485702e2ca9348%22%7D%7D");
+        assert_persisted_query_not_supported(&response);
+    }
+
+    fn test_persisted_query_post<T: HTTPIntegration>(integration: &T) {
+        let response = integration.post(
+            "/",
+            r#"{"extensions": {"persistedQuery": {"version": 1, "sha256Hash": "993f8cd4f05bd4830617ad3e781cec9d68ac28b92a8a35eb38485702e2ca9348"}}}"#,
+        );
+        assert_persisted_query_not_supported(&response);
+    }
 }
@@ -0,0 +1,832 @@
+//! Support for file uploads
+//!
+//! Implements the [GraphQL multipart request specification]. A client sends
+//! a `multipart/form-data` body made of:
+//!
+//! - an `operations` part, holding the JSON encoded request or batch of
+//!   requests, with `null` in place of every file,
+//! - a `map` part, a JSON object mapping the name of every file part to the
+//!   paths of the variables it fills, e.g. `{ "0": ["variables.avatar"] }`,
+//! - the file parts themselves.
+//!
+//! [`parse`] turns such a body into a [`GraphQLBatchRequest`]. The files are
+//! kept alongside the requests, and every variable they fill refers to one of
+//! them. Resolvers receive the files as arguments of the [`Upload`] scalar,
+//! and read their content through the executor:
+//!
+//! ```
+//! # use juniper::{http::multipart::Upload, Executor, FieldResult};
+//! struct Mutation;
+//!
+//! #[juniper::graphql_object]
+//! impl Mutation {
+//!     fn upload_avatar(executor: &Executor, avatar: Upload) -> FieldResult<i32> {
+//!         Ok(avatar.content(executor)?.len() as i32)
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Every integration crate accepts `multipart/form-data` POST requests. The
+//! body is buffered in memory as a whole, so its size, the number of files it
+//! carries and their size are bounded by [`MultipartOptions`].
+//!
+//! [GraphQL multipart request specification]: https://github.com/jaydenseric/graphql-multipart-request-spec
+//! [`parse`]: fn.parse.html
+//! [`MultipartOptions`]: struct.MultipartOptions.html
+//! [`GraphQLBatchRequest`]: ../enum.GraphQLBatchRequest.html
+//! [`Upload`]: struct.Upload.html
+
+use std::{collections::HashMap, convert::TryFrom, error::Error, fmt, str, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{
+    ast::InputValue,
+    executor::{Executor, FieldResult},
+    http::{GraphQLBatchRequest, GraphQLRequest},
+    parser::{ParseError, Token},
+    value::{ParseScalarResult, ScalarValue},
+    Value,
+};
+
+/// A file uploaded with a multipart request
+///
+/// Within the variables of a request, an upload is represented by an object
+/// holding the `filename` and the `contentType` of the file, and the `index`
+/// of its content among the files sent along with the request. The content
+/// itself is kept out of the variables, and looked up with
+/// [`content`](#method.content).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Upload {
+    filename: Option<String>,
+    content_type: Option<String>,
+    index: usize,
+}
+
+impl Upload {
+    /// The name of the file, as sent by the client
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    /// The media type of the file, as sent by the client
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The content of the file, from the request being executed
+    ///
+    /// Fails if the request wasn't sent with the file, like a JSON request
+    /// mimicking the representation of an upload.
+    pub fn content<'r, CtxT, S>(
+        &self,
+        executor: &Executor<'r, '_, CtxT, S>,
+    ) -> FieldResult<&'r [u8], S>
+    where
+        S: ScalarValue,
+    {
+        executor
+            .extensions()
+            .files()
+            .get(self.index)
+            .ok_or_else(|| "File not found in the request".into())
+    }
+
+    fn to_input_value<S>(&self) -> InputValue<S>
+    where
+        S: ScalarValue,
+    {
+        let optional = |value: &Option<String>| {
+            value
+                .as_ref()
+                .map_or_else(InputValue::null, |v| InputValue::scalar(v.clone()))
+        };
+
+        let mut object = IndexMap::new();
+        object.insert("filename", optional(&self.filename));
+        object.insert("contentType", optional(&self.content_type));
+        object.insert("index", InputValue::scalar(self.index as i32));
+        InputValue::object(object)
+    }
+}
+
+#[crate::graphql_scalar_internal(
+    name = "Upload",
+    description = "A file uploaded with a multipart request"
+)]
+impl<S> GraphQLScalar for Upload
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        self.filename
+            .as_ref()
+            .map_or_else(Value::null, |filename| Value::scalar(filename.clone()))
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<Upload> {
+        let object = v.to_object_value()?;
+        let optional = |name| match object.get(name) {
+            None => Some(None),
+            Some(value) if value.is_null() => Some(None),
+            Some(value) => value.as_string_value().map(|v| Some(v.to_owned())),
+        };
+
+        let index = object.get("index")?.as_int_value()?;
+        Some(Upload {
+            filename: optional("filename")?,
+            content_type: optional("contentType")?,
+            index: usize::try_from(index).ok()?,
+        })
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        // Files can only be sent as variables.
+        Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+    }
+}
+
+/// The content of the files sent along with a multipart request, shared by
+/// the requests of a batch
+#[derive(Clone, Default, PartialEq)]
+pub(crate) struct Files(Arc<Vec<Vec<u8>>>);
+
+impl Files {
+    fn get(&self, index: usize) -> Option<&[u8]> {
+        self.0.get(index).map(Vec::as_slice)
+    }
+}
+
+impl fmt::Debug for Files {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Files({})", self.0.len())
+    }
+}
+
+/// Limits applied when parsing a multipart request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MultipartOptions {
+    max_body_size: u64,
+    max_files: usize,
+    max_file_size: Option<u64>,
+}
+
+impl MultipartOptions {
+    /// Construct the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject bodies larger than `size` bytes, 10 MiB by default
+    pub fn with_max_body_size(mut self, size: u64) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Reject requests carrying more than `max` files, 10 by default
+    ///
+    /// Besides the files, a body may only hold the `operations` and `map`
+    /// parts.
+    pub fn with_max_files(mut self, max: usize) -> Self {
+        self.max_files = max;
+        self
+    }
+
+    /// Reject files larger than `size` bytes, which are only bounded by the
+    /// size of the body by default
+    pub fn with_max_file_size(mut self, size: u64) -> Self {
+        self.max_file_size = Some(size);
+        self
+    }
+
+    /// The largest body accepted, in bytes
+    pub fn max_body_size(&self) -> u64 {
+        self.max_body_size
+    }
+
+    /// The largest number of files accepted
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+
+    /// The largest file accepted, in bytes, if files are limited besides the
+    /// body
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.max_file_size
+    }
+}
+
+impl Default for MultipartOptions {
+    fn default() -> Self {
+        MultipartOptions {
+            max_body_size: 10 * 1024 * 1024,
+            max_files: 10,
+            max_file_size: None,
+        }
+    }
+}
+
+/// An error raised when parsing a multipart request
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultipartError {
+    /// The content type is not `multipart/form-data` or lacks the boundary
+    InvalidContentType,
+    /// The body is not a valid `multipart/form-data` body
+    InvalidBody,
+    /// The `operations` part is missing or invalid
+    InvalidOperations(String),
+    /// The `map` part is missing or invalid
+    InvalidMap(String),
+    /// A file referenced by the `map` part is missing
+    MissingFile(String),
+    /// A path of the `map` part does not point into the variables of a request
+    InvalidPath(String),
+    /// The body is larger than allowed
+    BodyTooLarge,
+    /// The body holds more parts or files than allowed
+    TooManyFiles,
+    /// A file is larger than allowed
+    FileTooLarge(String),
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultipartError::InvalidContentType => {
+                write!(
+                    f,
+                    "Expected a multipart/form-data content type with a boundary"
+                )
+            }
+            MultipartError::InvalidBody => write!(f, "Invalid multipart body"),
+            MultipartError::InvalidOperations(e) => write!(f, "Invalid operations: {}", e),
+            MultipartError::InvalidMap(e) => write!(f, "Invalid map: {}", e),
+            MultipartError::MissingFile(name) => write!(f, "Missing file \"{}\"", name),
+            MultipartError::InvalidPath(path) => write!(f, "Invalid variable path \"{}\"", path),
+            MultipartError::BodyTooLarge => write!(f, "Body too large"),
+            MultipartError::TooManyFiles => write!(f, "Too many files"),
+            MultipartError::FileTooLarge(name) => write!(f, "File \"{}\" too large", name),
+        }
+    }
+}
+
+impl Error for MultipartError {}
+
+/// Whether a `Content-Type` header denotes a multipart request
+pub fn is_multipart(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default();
+    mime.trim().eq_ignore_ascii_case("multipart/form-data")
+}
+
+/// Parse a multipart request from the value of its `Content-Type` header and
+/// its body, within the default [`MultipartOptions`]
+///
+/// [`MultipartOptions`]: struct.MultipartOptions.html
+pub fn parse<S>(content_type: &str, body: &[u8]) -> Result<GraphQLBatchRequest<S>, MultipartError>
+where
+    S: ScalarValue,
+{
+    parse_with_options(content_type, body, &MultipartOptions::default())
+}
+
+/// Parse a multipart request from the value of its `Content-Type` header and
+/// its body, within the limits of `options`
+pub fn parse_with_options<S>(
+    content_type: &str,
+    body: &[u8],
+    options: &MultipartOptions,
+) -> Result<GraphQLBatchRequest<S>, MultipartError>
+where
+    S: ScalarValue,
+{
+    if !is_multipart(content_type) {
+        return Err(MultipartError::InvalidContentType);
+    }
+    if body.len() as u64 > options.max_body_size {
+        return Err(MultipartError::BodyTooLarge);
+    }
+    let boundary = boundary(content_type).ok_or(MultipartError::InvalidContentType)?;
+    // Besides the files, only the `operations` and `map` parts are expected.
+    let mut parts = parse_parts(
+        boundary.as_bytes(),
+        body,
+        options.max_files.saturating_add(2),
+    )?;
+
+    let operations = take_part(&mut parts, "operations")
+        .ok_or_else(|| MultipartError::InvalidOperations("missing part".to_owned()))?;
+    let mut request: GraphQLBatchRequest<S> = serde_json::from_slice(operations.content)
+        .map_err(|e| MultipartError::InvalidOperations(e.to_string()))?;
+
+    let map = take_part(&mut parts, "map")
+        .ok_or_else(|| MultipartError::InvalidMap("missing part".to_owned()))?;
+    let map: HashMap<String, Vec<String>> = serde_json::from_slice(map.content)
+        .map_err(|e| MultipartError::InvalidMap(e.to_string()))?;
+    if map.len() > options.max_files {
+        return Err(MultipartError::TooManyFiles);
+    }
+
+    let mut files = Vec::with_capacity(map.len());
+    for (name, paths) in map {
+        let file = take_part(&mut parts, &name).ok_or(MultipartError::MissingFile(name))?;
+        if options
+            .max_file_size
+            .map_or(false, |max| file.content.len() as u64 > max)
+        {
+            return Err(MultipartError::FileTooLarge(file.name));
+        }
+        let upload = Upload {
+            filename: file.filename,
+            content_type: file.content_type,
+            index: files.len(),
+        };
+        files.push(file.content.to_vec());
+
+        for path in paths {
+            let target =
+                variable_at_path(&mut request, &path).ok_or(MultipartError::InvalidPath(path))?;
+            *target = upload.to_input_value();
+        }
+    }
+
+    let files = Files(Arc::new(files));
+    match &mut request {
+        GraphQLBatchRequest::Single(request) => request.files = files,
+        GraphQLBatchRequest::Batch(requests) => {
+            for request in requests {
+                request.files = files.clone();
+            }
+        }
+    }
+    Ok(request)
+}
+
+/// A single part of a multipart body
+struct Part<'a> {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    content: &'a [u8],
+}
+
+fn take_part<'a>(parts: &mut Vec<Part<'a>>, name: &str) -> Option<Part<'a>> {
+    let index = parts.iter().position(|p| p.name == name)?;
+    Some(parts.remove(index))
+}
+
+fn boundary(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = split_param(param)?;
+        if key.eq_ignore_ascii_case("boundary") && !value.is_empty() {
+            Some(value)
+        } else {
+            None
+        }
+    })
+}
+
+/// Split a `key=value` header parameter, unquoting the value
+fn split_param(param: &str) -> Option<(&str, String)> {
+    let mut split = param.splitn(2, '=');
+    let key = split.next()?.trim();
+    let value = split.next()?.trim();
+
+    let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace("\\\"", "\"")
+    } else {
+        value.to_owned()
+    };
+    Some((key, value))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn parse_parts<'a>(
+    boundary: &[u8],
+    body: &'a [u8],
+    max_parts: usize,
+) -> Result<Vec<Part<'a>>, MultipartError> {
+    let mut delimiter = b"\r\n--".to_vec();
+    delimiter.extend_from_slice(boundary);
+
+    // The first delimiter may be at the very start of the body, without the
+    // preceding line break.
+    let start = if body.starts_with(&delimiter[2..]) {
+        delimiter.len() - 2
+    } else {
+        find(body, &delimiter).ok_or(MultipartError::InvalidBody)? + delimiter.len()
+    };
+
+    let mut parts = Vec::new();
+    let mut rest = &body[start..];
+    loop {
+        if rest.starts_with(b"--") {
+            return Ok(parts);
+        }
+        if !rest.starts_with(b"\r\n") {
+            return Err(MultipartError::InvalidBody);
+        }
+        rest = &rest[2..];
+        if parts.len() == max_parts {
+            return Err(MultipartError::TooManyFiles);
+        }
+
+        let end = find(rest, &delimiter).ok_or(MultipartError::InvalidBody)?;
+        parts.push(parse_part(&rest[..end])?);
+        rest = &rest[end + delimiter.len()..];
+    }
+}
+
+fn parse_part<'a>(part: &'a [u8]) -> Result<Part<'a>, MultipartError> {
+    let (headers, content) = match find(part, b"\r\n\r\n") {
+        Some(end) => (&part[..end], &part[end + 4..]),
+        // A part without headers starts with an empty line.
+        None if part.starts_with(b"\r\n") => (&part[..0], &part[2..]),
+        None => return Err(MultipartError::InvalidBody),
+    };
+    let headers = str::from_utf8(headers).map_err(|_| MultipartError::InvalidBody)?;
+
+    let mut name = None;
+    let mut filename = None;
+    let mut content_type = None;
+    for header in headers.split("\r\n").filter(|h| !h.is_empty()) {
+        let mut split = header.splitn(2, ':');
+        let key = split.next().unwrap_or_default().trim();
+        let value = split.next().ok_or(MultipartError::InvalidBody)?.trim();
+
+        if key.eq_ignore_ascii_case("content-disposition") {
+            for (key, value) in value.split(';').skip(1).filter_map(split_param) {
+                if key.eq_ignore_ascii_case("name") {
+                    name = Some(value);
+                } else if key.eq_ignore_ascii_case("filename") {
+                    filename = Some(value);
+                }
+            }
+        } else if key.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_owned());
+        }
+    }
+
+    Ok(Part {
+        name: name.ok_or(MultipartError::InvalidBody)?,
+        filename,
+        content_type,
+        content,
+    })
+}
+
+/// Look up the value at a path like `variables.files.0`, or `0.variables.file`
+/// for batches
+fn variable_at_path<'a, S>(
+    request: &'a mut GraphQLBatchRequest<S>,
+    path: &str,
+) -> Option<&'a mut InputValue<S>>
+where
+    S: ScalarValue,
+{
+    let mut segments = path.split('.');
+    let request: &mut GraphQLRequest<S> = match request {
+        GraphQLBatchRequest::Single(request) => request,
+        GraphQLBatchRequest::Batch(requests) => {
+            requests.get_mut(segments.next()?.parse::<usize>().ok()?)?
+        }
+    };
+    if segments.next()? != "variables" {
+        return None;
+    }
+
+    let mut value = request.variables.as_mut()?;
+    for segment in segments {
+        value = match value {
+            InputValue::Object(fields) => fields
+                .iter_mut()
+                .find(|(key, _)| key.item == segment)
+                .map(|(_, value)| &mut value.item)?,
+            InputValue::List(items) => &mut items.get_mut(segment.parse::<usize>().ok()?)?.item,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_multipart, parse, parse_with_options, MultipartError, MultipartOptions, Upload,
+    };
+    use crate::{
+        http::{GraphQLBatchRequest, GraphQLRequest},
+        value::DefaultScalarValue,
+        EmptySubscription, FieldResult, FromInputValue, RootNode,
+    };
+
+    const CONTENT_TYPE: &str = "multipart/form-data; boundary=\"xyz\"";
+
+    fn body(parts: &[(&str, Option<&str>, &str)]) -> Vec<u8> {
+        let mut body = String::new();
+        for (name, filename, content) in parts {
+            body.push_str("--xyz\r\n");
+            match filename {
+                Some(filename) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: text/plain\r\n",
+                    name, filename,
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n",
+                    name
+                )),
+            }
+            body.push_str("\r\n");
+            body.push_str(content);
+            body.push_str("\r\n");
+        }
+        body.push_str("--xyz--\r\n");
+        body.into_bytes()
+    }
+
+    // The filename, content type and content of an upload.
+    type File = (Option<String>, Option<String>, String);
+
+    fn upload(filename: &str, content: &str) -> File {
+        (
+            Some(filename.to_owned()),
+            Some("text/plain".to_owned()),
+            content.to_owned(),
+        )
+    }
+
+    fn parse_variable(request: &GraphQLBatchRequest, path: &[&str]) -> Option<File> {
+        let request = match request {
+            GraphQLBatchRequest::Single(request) => request,
+            GraphQLBatchRequest::Batch(requests) => &requests[0],
+        };
+        let mut value = request.variables.as_ref()?;
+        for segment in path {
+            value = match segment.parse::<usize>() {
+                Ok(index) => value.to_list_value()?[index],
+                Err(_) => value.to_object_value()?[segment],
+            };
+        }
+        let upload = Upload::from_input_value(value)?;
+        let content = request.files.get(upload.index)?;
+        Some((
+            upload.filename,
+            upload.content_type,
+            String::from_utf8(content.to_vec()).ok()?,
+        ))
+    }
+
+    #[test]
+    fn detects_multipart_content_types() {
+        assert!(is_multipart("multipart/form-data; boundary=xyz"));
+        assert!(is_multipart("Multipart/Form-Data"));
+        assert!(!is_multipart("application/json"));
+    }
+
+    #[test]
+    fn maps_files_into_variables() {
+        let body = body(&[
+            (
+                "operations",
+                None,
+                r#"{"query": "mutation ($file: Upload!, $files: [Upload!]!) { a }",
+                    "variables": {"file": null, "files": [null, null]}}"#,
+            ),
+            (
+                "map",
+                None,
+                r#"{"0": ["variables.file"], "1": ["variables.files.0", "variables.files.1"]}"#,
+            ),
+            ("0", Some("a.txt"), "Alpha"),
+            ("1", Some("b.txt"), "Beta\r\n--xy"),
+        ]);
+
+        let request = parse::<DefaultScalarValue>(CONTENT_TYPE, &body).unwrap();
+
+        assert_eq!(
+            parse_variable(&request, &["file"]),
+            Some(upload("a.txt", "Alpha")),
+        );
+        assert_eq!(
+            parse_variable(&request, &["files", "0"]),
+            Some(upload("b.txt", "Beta\r\n--xy")),
+        );
+        assert_eq!(
+            parse_variable(&request, &["files", "1"]),
+            Some(upload("b.txt", "Beta\r\n--xy")),
+        );
+    }
+
+    #[test]
+    fn maps_files_into_batches() {
+        let body = body(&[
+            (
+                "operations",
+                None,
+                r#"[{"query": "{ a }", "variables": {"file": null}}]"#,
+            ),
+            ("map", None, r#"{"0": ["0.variables.file"]}"#),
+            ("0", Some("a.txt"), "Alpha"),
+        ]);
+
+        let request = parse::<DefaultScalarValue>(CONTENT_TYPE, &body).unwrap();
+
+        assert_eq!(
+            parse_variable(&request, &["file"]),
+            Some(upload("a.txt", "Alpha")),
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let operations = r#"{"query": "{ a }", "variables": {"file": null}}"#;
+
+        assert_eq!(
+            parse::<DefaultScalarValue>("application/json", b"{}"),
+            Err(MultipartError::InvalidContentType),
+        );
+        assert_eq!(
+            parse::<DefaultScalarValue>("multipart/form-data", b""),
+            Err(MultipartError::InvalidContentType),
+        );
+        assert_eq!(
+            parse::<DefaultScalarValue>(CONTENT_TYPE, b"--xyz\r\nno headers"),
+            Err(MultipartError::InvalidBody),
+        );
+        assert_eq!(
+            parse::<DefaultScalarValue>(
+                CONTENT_TYPE,
+                &body(&[
+                    ("operations", None, operations),
+                    ("map", None, r#"{"0": ["variables.file"]}"#)
+                ]),
+            ),
+            Err(MultipartError::MissingFile("0".to_owned())),
+        );
+        assert_eq!(
+            parse::<DefaultScalarValue>(
+                CONTENT_TYPE,
+                &body(&[
+                    ("operations", None, operations),
+                    ("map", None, r#"{"0": ["variables.other"]}"#),
+                    ("0", Some("a.txt"), "Alpha"),
+                ]),
+            ),
+            Err(MultipartError::InvalidPath("variables.other".to_owned())),
+        );
+        assert!(matches!(
+            parse::<DefaultScalarValue>(CONTENT_TYPE, &body(&[("operations", None, operations)])),
+            Err(MultipartError::InvalidMap(_)),
+        ));
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let body = body(&[
+            (
+                "operations",
+                None,
+                r#"{"query": "{ a }", "variables": {"a": null, "b": null}}"#,
+            ),
+            (
+                "map",
+                None,
+                r#"{"0": ["variables.a"], "1": ["variables.b"]}"#,
+            ),
+            ("0", Some("a.txt"), "Alpha"),
+            ("1", Some("b.txt"), "Beta"),
+        ]);
+        let parse = |options: MultipartOptions| {
+            parse_with_options::<DefaultScalarValue>(CONTENT_TYPE, &body, &options)
+        };
+
+        assert!(parse(MultipartOptions::new()).is_ok());
+        assert_eq!(
+            parse(MultipartOptions::new().with_max_body_size(body.len() as u64 - 1)),
+            Err(MultipartError::BodyTooLarge),
+        );
+        assert_eq!(
+            parse(MultipartOptions::new().with_max_files(1)),
+            Err(MultipartError::TooManyFiles),
+        );
+        assert_eq!(
+            parse(MultipartOptions::new().with_max_file_size(4)),
+            Err(MultipartError::FileTooLarge("0".to_owned())),
+        );
+
+        let unmapped = self::body(&[
+            ("operations", None, r#"{"query": "{ a }"}"#),
+            ("map", None, "{}"),
+            ("0", Some("a.txt"), "Alpha"),
+            ("1", Some("b.txt"), "Beta"),
+        ]);
+        assert_eq!(
+            parse_with_options::<DefaultScalarValue>(
+                CONTENT_TYPE,
+                &unmapped,
+                &MultipartOptions::new().with_max_files(1),
+            ),
+            Err(MultipartError::TooManyFiles),
+        );
+    }
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn upload(executor: &Executor, file: Upload) -> FieldResult<String> {
+            Ok(format!(
+                "{} ({}): {}",
+                file.filename().unwrap_or_default(),
+                file.content_type().unwrap_or_default(),
+                String::from_utf8_lossy(file.content(executor)?),
+            ))
+        }
+    }
+
+    struct Mutation;
+
+    #[crate::graphql_object_internal]
+    impl Mutation {
+        fn size(executor: &Executor, files: Vec<Upload>) -> FieldResult<i32> {
+            let mut size = 0;
+            for file in files {
+                size += file.content(executor)?.len() as i32;
+            }
+            Ok(size)
+        }
+    }
+
+    #[test]
+    fn passes_uploads_to_resolvers() {
+        let schema = RootNode::new(Query, Mutation, EmptySubscription::<()>::new());
+        let body = body(&[
+            (
+                "operations",
+                None,
+                r#"{"query": "mutation ($files: [Upload!]!) { size(files: $files) }",
+                    "variables": {"files": [null, null]}}"#,
+            ),
+            (
+                "map",
+                None,
+                r#"{"0": ["variables.files.0"], "1": ["variables.files.1"]}"#,
+            ),
+            ("0", Some("a.txt"), "Alpha"),
+            ("1", Some("b.txt"), "Beta"),
+        ]);
+
+        let request = parse::<DefaultScalarValue>(CONTENT_TYPE, &body).unwrap();
+        let response = request.execute_sync(&schema, &());
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({ "data": { "size": 9 } }),
+        );
+
+        let body = self::body(&[
+            (
+                "operations",
+                None,
+                r#"{"query": "query ($file: Upload!) { upload(file: $file) }",
+                    "variables": {"file": null}}"#,
+            ),
+            ("map", None, r#"{"0": ["variables.file"]}"#),
+            ("0", Some("a.txt"), "Alpha"),
+        ]);
+        let request = parse::<DefaultScalarValue>(CONTENT_TYPE, &body).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(request.execute_sync(&schema, &())).unwrap(),
+            serde_json::json!({ "data": { "upload": "a.txt (text/plain): Alpha" } }),
+        );
+    }
+
+    #[test]
+    fn rejects_uploads_without_files() {
+        let schema = RootNode::new(Query, Mutation, EmptySubscription::<()>::new());
+        let request: GraphQLRequest = serde_json::from_value(serde_json::json!({
+            "query": "query ($file: Upload!) { upload(file: $file) }",
+            "variables": { "file": { "filename": "a.txt", "index": 0 } },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(request.execute_sync(&schema, &())).unwrap(),
+            serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": "File not found in the request",
+                    "locations": [{ "line": 1, "column": 26 }],
+                    "path": ["upload"],
+                }],
+            }),
+        );
+    }
+}
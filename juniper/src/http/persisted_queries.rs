@@ -0,0 +1,229 @@
+//! Support for automatic persisted queries
+//!
+//! Implements the [automatic persisted queries] protocol of Apollo. Instead of
+//! the full query, a client sends the SHA-256 hash of it in the
+//! `extensions.persistedQuery.sha256Hash` member of the request. If the hash
+//! is unknown, the server answers with a `PersistedQueryNotFound` error, and
+//! the client retries with both the query and the hash, which registers the
+//! query for subsequent requests.
+//!
+//! Persisted queries require the `persisted-queries` feature, and are enabled
+//! by adding a store to the schema with [`RootNode::with_persisted_queries`].
+//! They are then supported by
+//! [`GraphQLRequest::execute`] and [`GraphQLRequest::execute_sync`], and with
+//! them by every integration crate, for both GET and POST requests. Without a
+//! store, requests referring to a persisted query are answered with a
+//! `PersistedQueryNotSupported` error, which makes clients fall back to
+//! sending full queries.
+//!
+//! [automatic persisted queries]: https://www.apollographql.com/docs/apollo-server/performance/apq/
+//! [`RootNode::with_persisted_queries`]: ../../struct.RootNode.html#method.with_persisted_queries
+//! [`GraphQLRequest::execute`]: ../struct.GraphQLRequest.html#method.execute
+//! [`GraphQLRequest::execute_sync`]: ../struct.GraphQLRequest.html#method.execute_sync
+
+use std::fmt;
+#[cfg(feature = "persisted-queries")]
+use std::{collections::HashMap, sync::RwLock};
+
+#[cfg(feature = "persisted-queries")]
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ast::InputValue,
+    executor::{ExecutionError, FieldError},
+    value::{ScalarValue, Value},
+    GraphQLError,
+};
+
+/// Storage for persisted queries, keyed by the hex encoded SHA-256 hash of
+/// their source
+///
+/// Implement this to share persisted queries between several servers, e.g.
+/// through a cache like Redis or Memcached.
+#[cfg(feature = "persisted-queries")]
+pub trait PersistedQueryStore: Send + Sync {
+    /// Look up the query registered under the given hash
+    fn get(&self, hash: &str) -> Option<String>;
+
+    /// Register a query under its hash
+    fn insert(&self, hash: String, query: String);
+}
+
+/// A [`PersistedQueryStore`] keeping the queries in memory
+///
+/// Queries are never evicted, as the number of distinct queries sent by the
+/// clients of a schema is usually small.
+///
+/// [`PersistedQueryStore`]: trait.PersistedQueryStore.html
+#[cfg(feature = "persisted-queries")]
+#[derive(Debug, Default)]
+pub struct InMemoryPersistedQueryStore {
+    queries: RwLock<HashMap<String, String>>,
+}
+
+#[cfg(feature = "persisted-queries")]
+impl InMemoryPersistedQueryStore {
+    /// Construct a new, empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "persisted-queries")]
+impl PersistedQueryStore for InMemoryPersistedQueryStore {
+    fn get(&self, hash: &str) -> Option<String> {
+        self.queries.read().unwrap().get(hash).cloned()
+    }
+
+    fn insert(&self, hash: String, query: String) {
+        self.queries.write().unwrap().insert(hash, query);
+    }
+}
+
+/// The persisted query store added to a schema, if any
+#[derive(Default)]
+pub(crate) struct PersistedQueries(
+    #[cfg(feature = "persisted-queries")] pub(crate) Option<Box<dyn PersistedQueryStore>>,
+);
+
+impl PersistedQueries {
+    /// Find the source of the query of a request, given the `query` and
+    /// `extensions` members of it
+    ///
+    /// Without a store, requests referring to a persisted query are rejected.
+    #[cfg_attr(not(feature = "persisted-queries"), allow(unused_variables))]
+    pub(crate) fn query_source<S>(
+        &self,
+        query: &str,
+        extensions: Option<&InputValue<S>>,
+    ) -> Result<QuerySource, FieldError<S>>
+    where
+        S: ScalarValue,
+    {
+        let persisted = match extensions
+            .and_then(|e| e.to_object_value())
+            .and_then(|e| e.get("persistedQuery").copied())
+        {
+            Some(persisted) => persisted,
+            None => return Ok(QuerySource::Provided(None)),
+        };
+
+        #[cfg(feature = "persisted-queries")]
+        {
+            if let Some(store) = self.0.as_deref() {
+                return stored_query_source(query, persisted, store);
+            }
+        }
+
+        Err(FieldError::new(
+            "PersistedQueryNotSupported",
+            graphql_value!({ "code": "PERSISTED_QUERY_NOT_SUPPORTED" }),
+        ))
+    }
+
+    /// Register a query sent along with its persisted query hash, once it
+    /// has been parsed and validated successfully.
+    #[cfg_attr(not(feature = "persisted-queries"), allow(unused_variables))]
+    pub(crate) fn register<S>(
+        &self,
+        hash: Option<String>,
+        query: &str,
+        res: &Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>,
+    ) {
+        #[cfg(feature = "persisted-queries")]
+        {
+            if let (Some(store), Some(hash), Ok(_)) = (self.0.as_deref(), hash, res) {
+                store.insert(hash, query.to_owned());
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PersistedQueries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "persisted-queries")]
+        {
+            if self.0.is_some() {
+                return write!(f, "PersistedQueries(enabled)");
+            }
+        }
+        write!(f, "PersistedQueries(disabled)")
+    }
+}
+
+/// The hex encoded SHA-256 hash of a query, as used by persisted queries
+#[cfg(feature = "persisted-queries")]
+pub fn query_hash(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Where the source of a request's query comes from
+#[cfg_attr(not(feature = "persisted-queries"), allow(dead_code))]
+pub(crate) enum QuerySource {
+    /// The query sent along with the request, with the hash to register it
+    /// under, if any
+    Provided(Option<String>),
+    /// A query looked up from the store
+    Stored(String),
+}
+
+/// Find the source of the query of a request referring to a persisted query,
+/// given the `query` and `extensions.persistedQuery` members of it
+#[cfg(feature = "persisted-queries")]
+fn stored_query_source<S>(
+    query: &str,
+    persisted: &InputValue<S>,
+    store: &dyn PersistedQueryStore,
+) -> Result<QuerySource, FieldError<S>>
+where
+    S: ScalarValue,
+{
+    let persisted = persisted.to_object_value().unwrap_or_default();
+    if persisted.get("version").and_then(|v| v.as_int_value()) != Some(1) {
+        return Err(FieldError::new(
+            "Unsupported persisted query version",
+            Value::null(),
+        ));
+    }
+    let hash = match persisted
+        .get("sha256Hash")
+        .and_then(|h| h.as_string_value())
+    {
+        Some(hash) => hash.to_lowercase(),
+        None => {
+            return Err(FieldError::new(
+                "Missing persisted query hash",
+                Value::null(),
+            ))
+        }
+    };
+
+    if query.is_empty() {
+        store.get(&hash).map(QuerySource::Stored).ok_or_else(|| {
+            FieldError::new(
+                "PersistedQueryNotFound",
+                graphql_value!({ "code": "PERSISTED_QUERY_NOT_FOUND" }),
+            )
+        })
+    } else if query_hash(query) == hash {
+        Ok(QuerySource::Provided(Some(hash)))
+    } else {
+        Err(FieldError::new(
+            "provided sha does not match query",
+            graphql_value!({ "code": "PERSISTED_QUERY_HASH_MISMATCH" }),
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "persisted-queries"))]
+mod tests {
+    use super::query_hash;
+
+    #[test]
+    fn hashes_queries() {
+        assert_eq!(
+            query_hash("{ hello }"),
+            "001c3174e099bd72b729d0c0a529ba9f5a740c446e2a6e1d71b283cb84ec3065",
+        );
+    }
+}
@@ -14,6 +14,10 @@ pub mod url;
 /// GraphQL support for [uuid](https://doc.rust-lang.org/uuid/uuid/struct.Uuid.html) types.
 pub mod uuid;
 
+#[cfg(feature = "serde_json")]
+/// GraphQL support for [serde_json](https://github.com/serde-rs/json) values.
+pub mod serde_json;
+
 #[cfg(feature = "bson")]
 /// GraphQL support for [bson](https://github.com/mongodb/bson-rust) types.
 pub mod bson;
@@ -0,0 +1,266 @@
+use std::convert::TryFrom;
+
+use futures::future;
+use serde_json::{Map, Number, Value as Json};
+
+use crate::{
+    ast::{FromInputValue, InputValue, Selection, ToInputValue},
+    executor::{ExecutionResult, Executor, Registry},
+    parser::{parse_scalar_literal, ScalarToken},
+    schema::meta::MetaType,
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::GraphQLType,
+        marker::{IsInputType, IsOutputType},
+    },
+    value::{Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+impl<S> GraphQLType<S> for Json
+where
+    S: ScalarValue,
+{
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("JSON")
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        // Numbers nested in object and list literals are parsed by inferring
+        // their type, which needs `Int` and `Float` to be part of the schema.
+        registry.get_type::<i32>(&());
+        registry.get_type::<f64>(&());
+
+        registry
+            .build_scalar_type::<Self>(info)
+            .description("An arbitrary JSON value, including objects and lists")
+            .into_meta()
+    }
+
+    fn resolve(
+        &self,
+        _: &(),
+        _: Option<&[Selection<S>]>,
+        _: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        Ok(json_to_value(self))
+    }
+}
+
+impl<S> GraphQLTypeAsync<S> for Json
+where
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        selection_set: Option<&'a [Selection<S>]>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        future::FutureExt::boxed(future::ready(self.resolve(info, selection_set, executor)))
+    }
+}
+
+impl<S> IsInputType<S> for Json where S: ScalarValue {}
+
+impl<S> IsOutputType<S> for Json where S: ScalarValue {}
+
+impl<S> ToInputValue<S> for Json
+where
+    S: ScalarValue,
+{
+    fn to_input_value(&self) -> InputValue<S> {
+        json_to_value(self).to_input_value()
+    }
+}
+
+impl<S> FromInputValue<S> for Json
+where
+    S: ScalarValue,
+{
+    fn from_input_value(v: &InputValue<S>) -> Option<Json> {
+        input_value_to_json(v)
+    }
+}
+
+impl<S> ParseScalarValue<S> for Json
+where
+    S: ScalarValue,
+{
+    fn from_str(value: ScalarToken<'_>) -> ParseScalarResult<'_, S> {
+        parse_scalar_literal(value)
+    }
+}
+
+// Integers that don't fit into an `Int` are resolved as a `Float`.
+fn json_to_value<S>(json: &Json) -> Value<S>
+where
+    S: ScalarValue,
+{
+    match *json {
+        Json::Null => Value::null(),
+        Json::Bool(b) => Value::scalar(b),
+        Json::Number(ref n) => n
+            .as_i64()
+            .and_then(|i| i32::try_from(i).ok())
+            .map(Value::scalar)
+            .or_else(|| n.as_f64().map(Value::scalar))
+            .unwrap_or_else(Value::null),
+        Json::String(ref s) => Value::scalar(s.clone()),
+        Json::Array(ref l) => Value::list(l.iter().map(json_to_value).collect()),
+        Json::Object(ref o) => Value::object(
+            o.iter()
+                .map(|(k, v)| (k.as_str(), json_to_value(v)))
+                .collect::<Object<S>>(),
+        ),
+    }
+}
+
+// Enum values aren't valid JSON, and variables have already been substituted
+// by the time an input value is converted.
+fn input_value_to_json<S>(v: &InputValue<S>) -> Option<Json>
+where
+    S: ScalarValue,
+{
+    match *v {
+        InputValue::Null => Some(Json::Null),
+        InputValue::Scalar(ref s) => {
+            if let Some(i) = s.as_int() {
+                Some(Json::Number(i.into()))
+            } else if let Some(f) = s.as_float() {
+                Number::from_f64(f).map(Json::Number)
+            } else if let Some(s) = s.as_str() {
+                Some(Json::String(s.to_owned()))
+            } else {
+                s.as_boolean().map(Json::Bool)
+            }
+        }
+        InputValue::List(ref l) => l
+            .iter()
+            .map(|v| input_value_to_json(&v.item))
+            .collect::<Option<Vec<_>>>()
+            .map(Json::Array),
+        InputValue::Object(ref o) => o
+            .iter()
+            .map(|(k, v)| input_value_to_json(&v.item).map(|v| (k.item.clone(), v)))
+            .collect::<Option<Map<_, _>>>()
+            .map(Json::Object),
+        InputValue::Enum(_) | InputValue::Variable(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value as Json};
+
+    use crate::{
+        EmptyMutation, EmptySubscription, FromInputValue, InputValue, RootNode, ToInputValue,
+        Value, Variables,
+    };
+
+    #[test]
+    fn json_from_input_value() {
+        let input: InputValue = InputValue::parsed_object(vec![]);
+        assert_eq!(Json::from_input_value(&input), Some(json!({})));
+
+        let input: InputValue = InputValue::list(vec![
+            InputValue::scalar(1),
+            InputValue::scalar(1.5),
+            InputValue::scalar("a"),
+            InputValue::scalar(true),
+            InputValue::null(),
+        ]);
+        assert_eq!(
+            Json::from_input_value(&input),
+            Some(json!([1, 1.5, "a", true, null])),
+        );
+
+        let input: InputValue = InputValue::enum_value("DARK");
+        assert_eq!(Json::from_input_value(&input), None);
+    }
+
+    #[test]
+    fn json_to_input_value() {
+        let json = json!({ "sizes": [1, 2147483648u64], "theme": "dark" });
+
+        let input: InputValue = json.to_input_value();
+        assert_eq!(
+            Json::from_input_value(&input),
+            Some(json!({ "sizes": [1, 2147483648.0], "theme": "dark" })),
+        );
+    }
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn settings() -> Json {
+            json!({ "theme": "dark", "columns": [3, 2.5], "beta": null })
+        }
+
+        fn echo(value: Json) -> Json {
+            value
+        }
+    }
+
+    fn run_query(query: &str, vars: &Variables) -> Value {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let (result, errs) =
+            crate::execute_sync(query, None, &schema, vars, &()).expect("Execution failed");
+
+        assert_eq!(errs, []);
+        result
+    }
+
+    #[test]
+    fn json_resolves_structurally() {
+        assert_eq!(
+            run_query("{ settings }", &Variables::new()),
+            graphql_value!({
+                "settings": { "beta": None, "columns": [3, 2.5], "theme": "dark" },
+            }),
+        );
+    }
+
+    #[test]
+    fn json_accepts_object_and_list_literals() {
+        assert_eq!(
+            run_query(
+                r#"{ echo(value: { theme: "dark", columns: [3, 2.5], nested: { beta: true } }) }"#,
+                &Variables::new(),
+            ),
+            graphql_value!({
+                "echo": {
+                    "columns": [3, 2.5],
+                    "nested": { "beta": true },
+                    "theme": "dark",
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn json_accepts_variables() {
+        let mut vars = Variables::new();
+        vars.insert(
+            "value".to_owned(),
+            InputValue::list(vec![InputValue::scalar("a"), InputValue::scalar(1)]),
+        );
+
+        assert_eq!(
+            run_query("query($value: JSON!) { echo(value: $value) }", &vars),
+            graphql_value!({ "echo": ["a", 1] }),
+        );
+    }
+}
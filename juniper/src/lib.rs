@@ -41,7 +41,7 @@ see the [rocket][rocket_examples] and [iron][iron_examples] examples folders.
 Juniper supports the full GraphQL query language according to the
 [specification][graphql_spec], including interfaces, unions, schema
 introspection, and validations.
-It does not, however, support the schema language.
+Schemas can be printed in the GraphQL schema language with `RootNode::as_sdl`.
 
 As an exception to other GraphQL libraries for other languages, Juniper builds
 non-null types by default. A field of type `Vec<Episode>` will be converted into
@@ -96,7 +96,7 @@ Juniper has not reached 1.0 yet, thus some API instability should be expected.
 #[doc(hidden)]
 pub extern crate serde;
 
-#[cfg(any(test, feature = "expose-test-schema"))]
+#[cfg(any(test, feature = "serde_json"))]
 extern crate serde_json;
 
 #[cfg(any(test, feature = "chrono"))]
@@ -115,16 +115,16 @@ extern crate bson;
 // This allows users to just depend on juniper and get the derive
 // functionality automatically.
 pub use juniper_codegen::{
-    graphql_object, graphql_scalar, graphql_subscription, graphql_union, GraphQLEnum,
-    GraphQLInputObject, GraphQLObject, GraphQLScalarValue, GraphQLUnion,
+    graphql_interface, graphql_object, graphql_scalar, graphql_subscription, graphql_union,
+    GraphQLEnum, GraphQLInputObject, GraphQLObject, GraphQLScalarValue, GraphQLUnion,
 };
 // Internal macros are not exported,
 // but declared at the root to make them easier to use.
 #[allow(unused_imports)]
 use juniper_codegen::{
-    graphql_object_internal, graphql_scalar_internal, graphql_subscription_internal,
-    graphql_union_internal, GraphQLEnumInternal, GraphQLInputObjectInternal,
-    GraphQLScalarValueInternal,
+    graphql_interface_internal, graphql_object_internal, graphql_scalar_internal,
+    graphql_subscription_internal, graphql_union_internal, GraphQLEnumInternal,
+    GraphQLInputObjectInternal, GraphQLScalarValueInternal,
 };
 
 #[macro_use]
@@ -132,9 +132,12 @@ mod value;
 #[macro_use]
 mod macros;
 mod ast;
+pub mod dataloader;
 mod executor;
+pub mod federation;
 mod introspection;
 pub mod parser;
+pub mod relay;
 pub(crate) mod schema;
 mod types;
 mod util;
@@ -159,31 +162,44 @@ use crate::{
     executor::{execute_validated_query, get_operation},
     introspection::{INTROSPECTION_QUERY, INTROSPECTION_QUERY_WITHOUT_DESCRIPTIONS},
     parser::{parse_document_source, ParseError, Spanning},
-    validation::{validate_input_values, visit_all_rules, ValidatorContext},
+    schema::document_cache::CachedDocument,
+    validation::{validate_input_values, visit_all_rules, visit_custom_rules, visit_limit_rules},
 };
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 pub use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue, Type},
+    ast::{
+        Definition, Directive, Document, Field, Fragment, FragmentSpread, FromInputValue,
+        InlineFragment, InputValue, Operation, OperationType, Selection, ToInputValue, Type,
+        VariableDefinition,
+    },
     executor::{
-        Applies, Context, ExecutionError, ExecutionResult, Executor, FieldError, FieldResult,
-        FromContext, IntoFieldError, IntoResolvable, LookAheadArgument, LookAheadMethods,
-        LookAheadSelection, LookAheadValue, OwnedExecutor, Registry, ValuesStream, Variables,
+        Applies, Context, ExecutionError, ExecutionResult, Executor, Extensions, FieldError,
+        FieldPath, FieldResult, FromContext, IntoFieldError, IntoResolvable, LookAheadArgument,
+        LookAheadMethods, LookAheadSelection, LookAheadValue, OwnedExecutor, Registry, TracedField,
+        Tracer, ValuesStream, Variables,
     },
     introspection::IntrospectionFormat,
-    macros::subscription_helpers::{ExtractTypeFromStream, IntoFieldResult},
+    macros::{
+        interface_helpers::AsAny,
+        subscription_helpers::{ExtractTypeFromStream, IntoFieldResult},
+    },
     schema::{
         meta,
-        model::{RootNode, SchemaType},
+        model::{DirectiveLocation, DirectiveType, RootNode, SchemaType},
+        sdl::{SchemaBuildError, SchemaBuilder},
     },
     types::{
         async_await::GraphQLTypeAsync,
         base::{Arguments, GraphQLType, TypeKind},
+        directives::ExecutableDirective,
+        dynamic::{Dynamic, DynamicRootNode, DynamicTypeInfo, DynamicValue},
+        guards::{check_guard, resolve_guarded, Guard},
         marker,
         scalars::{EmptyMutation, EmptySubscription, ID},
         subscriptions::{GraphQLSubscriptionType, SubscriptionConnection, SubscriptionCoordinator},
     },
-    validation::RuleError,
+    validation::{RuleError, ValidationRule, ValidatorContext, Visitor},
     value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
 };
 
@@ -224,6 +240,128 @@ impl<'a> fmt::Display for GraphQLError<'a> {
 
 impl<'a> std::error::Error for GraphQLError<'a> {}
 
+impl<'a> GraphQLError<'a> {
+    /// Detach the error from the text of the query it may borrow from
+    ///
+    /// Parse errors are turned into validation errors with the same message
+    /// and location, and thus the same representation in a response.
+    pub(crate) fn into_static(self) -> GraphQLError<'static> {
+        match self {
+            GraphQLError::ParseError(e) => {
+                GraphQLError::ValidationError(vec![RuleError::new(&e.item.to_string(), &[e.start])])
+            }
+            GraphQLError::ValidationError(errors) => GraphQLError::ValidationError(errors),
+            GraphQLError::NoOperationProvided => GraphQLError::NoOperationProvided,
+            GraphQLError::MultipleOperationsProvided => GraphQLError::MultipleOperationsProvided,
+            GraphQLError::UnknownOperationName => GraphQLError::UnknownOperationName,
+            GraphQLError::IsSubscription => GraphQLError::IsSubscription,
+            GraphQLError::NotSubscription => GraphQLError::NotSubscription,
+        }
+    }
+}
+
+/// A document that passed validation, either parsed for a single request or
+/// shared through the document cache of the schema
+enum ValidDocument<'a, S> {
+    Parsed(Document<'a, S>),
+    Cached(Arc<CachedDocument<S>>),
+}
+
+impl<'a, S> ValidDocument<'a, S> {
+    fn get<'s>(&'s self) -> &'s Document<'s, S> {
+        match self {
+            ValidDocument::Parsed(document) => document,
+            ValidDocument::Cached(cached) => cached.document(),
+        }
+    }
+}
+
+fn parse<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    extensions: &Extensions<S>,
+) -> Result<Document<'a, S>, GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
+    let started = root_node.tracers.parse_start(extensions, document_source);
+    let result =
+        parse_document_source(document_source, &root_node.schema).map_err(GraphQLError::from);
+    root_node
+        .tracers
+        .parse_end(extensions, started, result.as_ref().err());
+    result
+}
+
+fn validate_document<'a, S, QueryT, MutationT, SubscriptionT>(
+    document: &Document<'a, S>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    extensions: &Extensions<S>,
+) -> Result<(), GraphQLError<'static>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
+    let started = root_node.tracers.validation_start(extensions);
+
+    let mut ctx = ValidatorContext::new(&root_node.schema, document);
+    visit_all_rules(&mut ctx, document);
+    visit_limit_rules(&mut ctx, document, &root_node.limits);
+    visit_custom_rules(&mut ctx, document, &root_node.validation_rules);
+
+    let errors = ctx.into_errors();
+    let result = if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(GraphQLError::ValidationError(errors))
+    };
+    root_node
+        .tracers
+        .validation_end(extensions, started, result.as_ref().err());
+    result
+}
+
+/// Parse and validate a query, going through the document cache of the
+/// schema, if any
+fn parse_and_validate<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    extensions: &Extensions<S>,
+) -> Result<ValidDocument<'a, S>, GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
+    let cache = match root_node.document_cache.as_ref() {
+        Some(cache) => cache,
+        None => {
+            let document = parse(document_source, root_node, extensions)?;
+            validate_document(&document, root_node, extensions)?;
+            return Ok(ValidDocument::Parsed(document));
+        }
+    };
+    if let Some(cached) = cache.get(document_source) {
+        return Ok(ValidDocument::Cached(cached));
+    }
+
+    // The query is parsed from a copy of it, which the cached document keeps.
+    let cached = CachedDocument::parse(document_source, |source| {
+        parse(source, root_node, extensions).map_err(GraphQLError::into_static)
+    })?;
+    validate_document(cached.document(), root_node, extensions)?;
+
+    let cached = Arc::new(cached);
+    cache.insert(Arc::clone(&cached));
+    Ok(ValidDocument::Cached(cached))
+}
+
 /// Execute a query synchronously in a provided schema
 pub fn execute_sync<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
     document_source: &'a str,
@@ -238,19 +376,39 @@ where
     MutationT: GraphQLType<S, Context = CtxT>,
     SubscriptionT: GraphQLType<S, Context = CtxT>,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
-
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
-
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    execute_sync_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &Extensions::default(),
+    )
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Execute a query synchronously in a provided schema, with the given
+/// request and response `extensions`
+///
+/// Entries added to `extensions` during execution can be retrieved with
+/// [`Extensions::into_response`](struct.Extensions.html#method.into_response)
+/// afterwards.
+pub fn execute_sync_with_extensions<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S, Context = CtxT>,
+    MutationT: GraphQLType<S, Context = CtxT>,
+    SubscriptionT: GraphQLType<S, Context = CtxT>,
+{
+    let document = parse_and_validate(document_source, root_node, extensions)?;
+    let document = document.get();
+    let operation = get_operation(document, operation_name)?;
 
     {
         let errors = validate_input_values(variables, operation, &root_node.schema);
@@ -260,7 +418,9 @@ where
         }
     }
 
-    execute_validated_query(&document, operation, root_node, variables, context)
+    execute_validated_query(
+        document, operation, root_node, variables, context, extensions,
+    )
 }
 
 /// Execute a query in a provided schema
@@ -281,19 +441,44 @@ where
     SubscriptionT::TypeInfo: Send + Sync,
     CtxT: Send + Sync,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
-
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
-
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    execute_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &Extensions::default(),
+    )
+    .await
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Execute a query in a provided schema, with the given request and response
+/// `extensions`
+///
+/// Entries added to `extensions` during execution can be retrieved with
+/// [`Extensions::into_response`](struct.Extensions.html#method.into_response)
+/// afterwards.
+pub async fn execute_with_extensions<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    let document = parse_and_validate(document_source, root_node, extensions)?;
+    let document = document.get();
+    let operation = get_operation(document, operation_name)?;
 
     {
         let errors = validate_input_values(variables, operation, &root_node.schema);
@@ -303,8 +488,12 @@ where
         }
     }
 
-    executor::execute_validated_query_async(&document, operation, root_node, variables, context)
-        .await
+    executor::execute_validated_query_async(
+        document, operation, root_node, variables, context, extensions,
+    )
+    .await
+    // Execution errors don't borrow from the document, which may be cached.
+    .map_err(GraphQLError::into_static)
 }
 
 /// Resolve subscription into `ValuesStream`
@@ -325,18 +514,46 @@ where
     SubscriptionT::TypeInfo: Send + Sync,
     CtxT: Send + Sync,
 {
-    let document: crate::ast::Document<'a, S> =
-        parse_document_source(document_source, &root_node.schema)?;
-
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+    resolve_into_stream_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &Extensions::default(),
+    )
+    .await
+}
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+/// Resolve subscription into `ValuesStream`, with the request and response
+/// `extensions`
+///
+/// The items of the streams are resolved after this function returns, the
+/// entries they add to `extensions` can be retrieved with
+/// [`Extensions::response`](struct.Extensions.html#method.response) once the
+/// streams have produced them.
+pub async fn resolve_into_stream_with_extensions<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &'a CtxT,
+    extensions: &Extensions<S>,
+) -> Result<(Value<ValuesStream<'a, S>>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    // The streams borrow from the text of the query for as long as they live,
+    // so subscriptions bypass the document cache.
+    let document = parse(document_source, root_node, extensions)?;
+    validate_document(&document, root_node, extensions)?;
 
     let operation = get_operation(&document, operation_name)?;
 
@@ -348,8 +565,10 @@ where
         }
     }
 
-    executor::resolve_validated_subscription(&document, operation, root_node, variables, context)
-        .await
+    executor::resolve_validated_subscription(
+        &document, operation, root_node, variables, context, extensions,
+    )
+    .await
 }
 
 /// Execute the reference introspection query in the provided schema
@@ -1,6 +1,11 @@
 /**
 Expose GraphQL interfaces
 
+This is the declarative predecessor of the
+[`#[graphql_interface]`](attr.graphql_interface.html) attribute, which should
+be preferred for new code. As the attribute takes the name of
+`graphql_interface!`, this macro is exported as `graphql_interface_legacy!`.
+
 Mapping interfaces to GraphQL can be tricky: there is no direct counterpart to
 GraphQL interfaces in Rust, and downcasting is not possible in the general case.
 Many other GraphQL implementations in other languages use instance checks and
@@ -13,7 +18,7 @@ type name as well as downcast the general type to the actual concrete type.
 ## Syntax
 
 See the documentation for [`graphql_object!`][1] on the general item and type
-syntax. `graphql_interface!` requires an additional `instance_resolvers` item,
+syntax. `graphql_interface_legacy!` requires an additional `instance_resolvers` item,
 and does _not_ support the `interfaces` item.
 
 `instance_resolvers` is a match like structure used to resolve the concrete
@@ -75,7 +80,7 @@ impl Droid {
 }
 
 // You can introduce lifetimes or generic parameters by < > before the name.
-juniper::graphql_interface!(<'a> &'a dyn Character: Database as "Character" |&self| {
+juniper::graphql_interface_legacy!(<'a> &'a dyn Character: Database as "Character" |&self| {
     field id() -> &str { self.id() }
 
     instance_resolvers: |&context| {
@@ -91,7 +96,7 @@ juniper::graphql_interface!(<'a> &'a dyn Character: Database as "Character" |&se
 
 */
 #[macro_export]
-macro_rules! graphql_interface {
+macro_rules! graphql_interface_legacy {
 
     (
         @generate,
@@ -263,7 +268,7 @@ macro_rules! graphql_interface {
         rest = $($rest:tt)*
     ) => {
         $crate::__juniper_parse_field_list!(
-            success_callback = graphql_interface,
+            success_callback = graphql_interface_legacy,
             additional_parser = {
                 callback = __juniper_parse_instance_resolver,
                 header = {},
@@ -275,17 +280,26 @@ macro_rules! graphql_interface {
     };
 
     (@$($stuff:tt)*) => {
-        compile_error!("Invalid syntax for `graphql_interface!`");
+        compile_error!("Invalid syntax for `graphql_interface_legacy!`");
     };
 
     (
         $($rest:tt)*
     ) => {
         $crate::__juniper_parse_object_header!(
-            callback = graphql_interface,
+            callback = graphql_interface_legacy,
             rest = $($rest)*
         );
     }
 
 
 }
+
+// `#[graphql_interface]` takes the name of this macro at the root of the crate,
+// so it is only declared under its old name within the crate.
+#[allow(unused_macros)]
+macro_rules! graphql_interface {
+    ($($tokens:tt)*) => {
+        $crate::graphql_interface_legacy!($($tokens)*);
+    };
+}
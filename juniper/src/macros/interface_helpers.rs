@@ -0,0 +1,26 @@
+//! Helper traits for the `#[juniper::graphql_interface]` macro.
+//!
+//! Values of an interface are trait objects, which are downcast to the
+//! implementing object types to resolve them.
+
+use std::any::Any;
+
+/// Trait for accessing a trait object as `&dyn Any`, to downcast it to the
+/// concrete type implementing the trait.
+///
+/// `#[graphql_interface]` adds this trait to the supertraits of the
+/// interface's trait. It is implemented for every `'static` type that is
+/// `Send` and `Sync`, so it never needs to be implemented manually.
+pub trait AsAny: Any + Send + Sync {
+    /// Access this value as `&dyn Any`
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> AsAny for T
+where
+    T: Any + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,5 +1,6 @@
 // Wrapper macros which allows built-in macros to be recognized as "crate-local"
-// and helper traits for #[juniper::graphql_subscription] macro.
+// and helper traits for #[juniper::graphql_subscription] and
+// #[juniper::graphql_interface] macros.
 
 #[macro_use]
 mod common;
@@ -9,4 +10,5 @@ mod interface;
 #[cfg(test)]
 mod tests;
 
+pub mod interface_helpers;
 pub mod subscription_helpers;
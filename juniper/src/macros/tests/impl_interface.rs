@@ -0,0 +1,445 @@
+use crate::{EmptyMutation, EmptySubscription, RootNode, Value, Variables};
+
+struct Context {
+    greeting: String,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            greeting: "Hello".to_owned(),
+        }
+    }
+}
+
+impl crate::Context for Context {}
+
+/// A character in the story.
+#[crate::graphql_interface_internal(for = [Human, Droid], enum = CharacterValue, Context = Context)]
+trait Character {
+    /// The id of the character
+    fn id(&self) -> &str;
+
+    #[graphql(arguments(name(description = "The name to greet")))]
+    fn greet(&self, ctx: &Context, name: String) -> String;
+
+    #[graphql(skip)]
+    fn is_droid(&self) -> bool {
+        false
+    }
+}
+
+#[crate::graphql_interface_internal(
+    name = "Named",
+    description = "Something with an id",
+    for = [Human],
+    Context = Context,
+)]
+trait WithId {
+    fn id(&self) -> &str;
+}
+
+struct Human {
+    id: String,
+    home_planet: String,
+}
+
+impl Character for Human {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn greet(&self, ctx: &Context, name: String) -> String {
+        format!("{}, {}!", ctx.greeting, name)
+    }
+}
+
+impl WithId for Human {
+    fn id(&self) -> &str {
+        Character::id(self)
+    }
+}
+
+#[crate::graphql_object_internal(Context = Context, interfaces = [&dyn Character, &dyn WithId])]
+impl Human {
+    fn id(&self) -> &str {
+        WithId::id(self)
+    }
+
+    fn greet(&self, ctx: &Context, name: String) -> String {
+        Character::greet(self, ctx, name)
+    }
+
+    fn home_planet(&self) -> &str {
+        &self.home_planet
+    }
+}
+
+struct Droid {
+    id: String,
+    primary_function: String,
+}
+
+impl Character for Droid {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn greet(&self, ctx: &Context, name: String) -> String {
+        format!("{}, {}! Beep.", ctx.greeting, name)
+    }
+
+    fn is_droid(&self) -> bool {
+        true
+    }
+}
+
+#[crate::graphql_object_internal(Context = Context, interfaces = [&dyn Character])]
+impl Droid {
+    async fn id(&self) -> &str {
+        Character::id(self)
+    }
+
+    async fn greet(&self, ctx: &Context, name: String) -> String {
+        Character::greet(self, ctx, name)
+    }
+
+    async fn primary_function(&self) -> &str {
+        &self.primary_function
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Context)]
+impl Query {
+    fn human() -> Box<dyn Character> {
+        Box::new(Human {
+            id: "1000".to_owned(),
+            home_planet: "Tatooine".to_owned(),
+        })
+    }
+
+    fn droid() -> Box<dyn Character> {
+        Box::new(Droid {
+            id: "2001".to_owned(),
+            primary_function: "Astromech".to_owned(),
+        })
+    }
+
+    fn characters() -> Vec<Box<dyn Character>> {
+        characters()
+    }
+
+    fn droids() -> Vec<Box<dyn Character>> {
+        characters().into_iter().filter(|c| c.is_droid()).collect()
+    }
+
+    fn heroes() -> Vec<CharacterValue> {
+        vec![
+            Droid {
+                id: "2000".to_owned(),
+                primary_function: "Protocol".to_owned(),
+            }
+            .into(),
+            Human {
+                id: "1003".to_owned(),
+                home_planet: "Alderaan".to_owned(),
+            }
+            .into(),
+        ]
+    }
+
+    fn named() -> Box<dyn WithId> {
+        Box::new(Human {
+            id: "1001".to_owned(),
+            home_planet: "Alderaan".to_owned(),
+        })
+    }
+}
+
+fn characters() -> Vec<Box<dyn Character>> {
+    vec![
+        Box::new(Human {
+            id: "1000".to_owned(),
+            home_planet: "Tatooine".to_owned(),
+        }),
+        Box::new(Droid {
+            id: "2001".to_owned(),
+            primary_function: "Astromech".to_owned(),
+        }),
+    ]
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Context>, EmptySubscription<Context>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Context>::new(),
+        EmptySubscription::<Context>::new(),
+    )
+}
+
+async fn run_query(query: &str) -> Value {
+    let (result, errs) = crate::execute(
+        query,
+        None,
+        &schema(),
+        &Variables::new(),
+        &Context::default(),
+    )
+    .await
+    .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    result
+}
+
+#[tokio::test]
+async fn interface_introspect() {
+    let res = run_query(
+        r#"
+        {
+            __type(name: "Character") {
+                name
+                kind
+                description
+                fields {
+                    name
+                    description
+                    args {
+                        name
+                        description
+                    }
+                }
+            }
+        }
+        "#,
+    )
+    .await;
+
+    assert_eq!(
+        res,
+        graphql_value!({
+            "__type": {
+                "name": "Character",
+                "kind": "INTERFACE",
+                "description": "A character in the story.",
+                "fields": [
+                    {
+                        "name": "id",
+                        "description": "The id of the character",
+                        "args": [],
+                    },
+                    {
+                        "name": "greet",
+                        "description": None,
+                        "args": [
+                            {
+                                "name": "name",
+                                "description": "The name to greet",
+                            },
+                        ],
+                    },
+                ],
+            },
+        })
+    );
+}
+
+#[tokio::test]
+async fn interface_introspect_implementors() {
+    let res = run_query(
+        r#"
+        {
+            __type(name: "Character") {
+                possibleTypes {
+                    name
+                }
+            }
+        }
+        "#,
+    )
+    .await;
+
+    let mut possible_types = res
+        .as_object_value()
+        .and_then(|res| res.get_field_value("__type"))
+        .and_then(|ty| ty.as_object_value())
+        .and_then(|ty| ty.get_field_value("possibleTypes"))
+        .and_then(|types| types.as_list_value())
+        .expect("possibleTypes missing")
+        .iter()
+        .map(|ty| {
+            ty.as_object_value()
+                .and_then(|ty| ty.get_field_value("name"))
+                .and_then(|name| name.as_scalar_value::<String>())
+                .expect("name missing")
+                .clone()
+        })
+        .collect::<Vec<_>>();
+    possible_types.sort();
+
+    assert_eq!(possible_types, vec!["Droid", "Human"]);
+}
+
+#[tokio::test]
+async fn interface_introspect_custom_name() {
+    let res = run_query(
+        r#"
+        {
+            __type(name: "Named") {
+                name
+                description
+                fields {
+                    name
+                }
+                possibleTypes {
+                    name
+                }
+            }
+        }
+        "#,
+    )
+    .await;
+
+    assert_eq!(
+        res,
+        graphql_value!({
+            "__type": {
+                "name": "Named",
+                "description": "Something with an id",
+                "fields": [
+                    { "name": "id" },
+                ],
+                "possibleTypes": [
+                    { "name": "Human" },
+                ],
+            },
+        })
+    );
+}
+
+#[tokio::test]
+async fn interface_resolve_async() {
+    let res = run_query(
+        r#"
+        {
+            characters {
+                __typename
+                id
+                greet(name: "Leia")
+                ... on Human {
+                    homePlanet
+                }
+                ... on Droid {
+                    primaryFunction
+                }
+            }
+            droids {
+                id
+            }
+            named {
+                id
+            }
+        }
+        "#,
+    )
+    .await;
+
+    assert_eq!(
+        res,
+        graphql_value!({
+            "characters": [
+                {
+                    "__typename": "Human",
+                    "id": "1000",
+                    "greet": "Hello, Leia!",
+                    "homePlanet": "Tatooine",
+                },
+                {
+                    "__typename": "Droid",
+                    "id": "2001",
+                    "greet": "Hello, Leia! Beep.",
+                    "primaryFunction": "Astromech",
+                },
+            ],
+            "droids": [
+                { "id": "2001" },
+            ],
+            "named": { "id": "1001" },
+        })
+    );
+}
+
+#[tokio::test]
+async fn interface_resolve_enum() {
+    let res = run_query(
+        r#"
+        {
+            heroes {
+                __typename
+                id
+                ... on Human {
+                    homePlanet
+                }
+                ... on Droid {
+                    primaryFunction
+                }
+            }
+        }
+        "#,
+    )
+    .await;
+
+    assert_eq!(
+        res,
+        graphql_value!({
+            "heroes": [
+                {
+                    "__typename": "Droid",
+                    "id": "2000",
+                    "primaryFunction": "Protocol",
+                },
+                {
+                    "__typename": "Human",
+                    "id": "1003",
+                    "homePlanet": "Alderaan",
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn interface_resolve_sync() {
+    let (res, errs) = crate::execute_sync(
+        r#"
+        {
+            human {
+                __typename
+                id
+                ... on Human {
+                    homePlanet
+                }
+            }
+        }
+        "#,
+        None,
+        &schema(),
+        &Variables::new(),
+        &Context::default(),
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        res,
+        graphql_value!({
+            "human": {
+                "__typename": "Human",
+                "id": "1000",
+                "homePlanet": "Tatooine",
+            },
+        })
+    );
+}
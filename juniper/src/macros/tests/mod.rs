@@ -1,5 +1,6 @@
 mod args;
 mod field;
+mod impl_interface;
 mod impl_object;
 mod impl_subscription;
 mod interface;
@@ -24,6 +24,8 @@ pub struct Lexer<'a> {
 #[allow(missing_docs)]
 pub enum ScalarToken<'a> {
     String(&'a str),
+    /// The raw content of a block string, between its triple quotes
+    BlockString(&'a str),
     Float(&'a str),
     Int(&'a str),
 }
@@ -46,6 +48,7 @@ pub enum Token<'a> {
     Colon,
     Equals,
     At,
+    Amp,
     Pipe,
     EndOfFile,
 }
@@ -69,7 +72,7 @@ pub enum LexerError {
     ///
     /// Apart from forgetting the ending `"`, terminating a string within a
     /// Unicode escape sequence or having a line break in the string also
-    /// causes this error.
+    /// causes this error, unless the string is a block string.
     UnterminatedString,
 
     /// An unknown character in a string literal was found
@@ -224,6 +227,9 @@ impl<'a> Lexer<'a> {
                 LexerError::UnterminatedString,
             ));
         }
+        if self.source[start_idx..].starts_with("\"\"\"") {
+            return self.scan_block_string(start_pos, start_idx);
+        }
 
         let mut escaped = false;
         let mut old_pos = self.position;
@@ -273,6 +279,54 @@ impl<'a> Lexer<'a> {
         ))
     }
 
+    /// Scan a block string starting at `start_idx`, whose first quote has
+    /// already been consumed
+    ///
+    /// Only `\"""` is an escape sequence in block strings, and line breaks
+    /// are allowed. The block string value is computed from the raw content
+    /// when the token is parsed into a scalar.
+    fn scan_block_string(
+        &mut self,
+        start_pos: SourcePosition,
+        start_idx: usize,
+    ) -> LexerResult<'a> {
+        self.next_char();
+        self.next_char();
+
+        let mut old_pos = self.position;
+        while let Some((idx, ch)) = self.next_char() {
+            match ch {
+                '\\' if self.source[idx..].starts_with("\\\"\"\"") => {
+                    for _ in 0..3 {
+                        self.next_char();
+                    }
+                }
+                '"' if self.source[idx..].starts_with("\"\"\"") => {
+                    self.next_char();
+                    self.next_char();
+                    return Ok(Spanning::start_end(
+                        &start_pos,
+                        &self.position,
+                        Token::Scalar(ScalarToken::BlockString(&self.source[start_idx + 3..idx])),
+                    ));
+                }
+                c if !is_source_char(c) => {
+                    return Err(Spanning::zero_width(
+                        &old_pos,
+                        LexerError::UnknownCharacterInString(ch),
+                    ));
+                }
+                _ => {}
+            }
+            old_pos = self.position;
+        }
+
+        Err(Spanning::zero_width(
+            &self.position,
+            LexerError::UnterminatedString,
+        ))
+    }
+
     fn scan_escaped_unicode(
         &mut self,
         start_pos: &SourcePosition,
@@ -457,6 +511,7 @@ impl<'a> Iterator for Lexer<'a> {
             Some(':') => Ok(self.emit_single_char(Token::Colon)),
             Some('=') => Ok(self.emit_single_char(Token::Equals)),
             Some('@') => Ok(self.emit_single_char(Token::At)),
+            Some('&') => Ok(self.emit_single_char(Token::Amp)),
             Some('|') => Ok(self.emit_single_char(Token::Pipe)),
             Some('.') => self.scan_ellipsis(),
             Some('"') => self.scan_string(),
@@ -490,6 +545,7 @@ impl<'a> fmt::Display for Token<'a> {
             Token::Scalar(ScalarToken::String(s)) => {
                 write!(f, "\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
             }
+            Token::Scalar(ScalarToken::BlockString(s)) => write!(f, "\"\"\"{}\"\"\"", s),
             Token::ExclamationMark => write!(f, "!"),
             Token::Dollar => write!(f, "$"),
             Token::ParenOpen => write!(f, "("),
@@ -502,6 +558,7 @@ impl<'a> fmt::Display for Token<'a> {
             Token::Colon => write!(f, ":"),
             Token::Equals => write!(f, "="),
             Token::At => write!(f, "@"),
+            Token::Amp => write!(f, "&"),
             Token::Pipe => write!(f, "|"),
             Token::EndOfFile => write!(f, "End of file"),
         }
@@ -4,13 +4,16 @@
 mod document;
 mod lexer;
 mod parser;
+mod schema;
 mod utils;
 mod value;
 
 #[cfg(test)]
 mod tests;
 
-pub use self::document::parse_document_source;
+pub use self::{document::parse_document_source, schema::parse_schema_source};
+
+pub(crate) use self::schema::parse_scalar_literal;
 
 pub use self::{
     lexer::{Lexer, LexerError, ScalarToken, Token},
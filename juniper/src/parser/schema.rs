@@ -0,0 +1,529 @@
+use crate::{
+    ast::{
+        ConstDirective, DirectiveDefinition, EnumValueDefinition, FieldDefinition, InputValue,
+        InputValueDefinition, SchemaDefinition, SchemaDocument, Type, TypeDefinition,
+        TypeDefinitionKind, TypeSystemDefinition,
+    },
+    parser::{
+        document::parse_type, Lexer, ParseError, ParseResult, Parser, ScalarToken, SourcePosition,
+        Spanning, Token, UnlocatedParseResult,
+    },
+    value::{ParseScalarValue, ScalarValue},
+};
+
+/// Parse a document in the GraphQL schema definition language
+///
+/// Unlike executable documents, type system documents are parsed without a
+/// schema, so scalar literals are interpreted by their syntax alone.
+#[doc(hidden)]
+pub fn parse_schema_source<'a, S>(s: &'a str) -> UnlocatedParseResult<'a, SchemaDocument<S>>
+where
+    S: ScalarValue,
+{
+    let mut lexer = Lexer::new(s);
+    let mut parser = Parser::new(&mut lexer).map_err(|s| s.map(ParseError::LexerError))?;
+    parse_schema_document(&mut parser)
+}
+
+fn parse_schema_document<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, SchemaDocument<S>>
+where
+    S: ScalarValue,
+{
+    let mut defs = Vec::new();
+
+    loop {
+        defs.push(parse_type_system_definition(parser)?);
+
+        if parser.peek().item == Token::EndOfFile {
+            return Ok(defs);
+        }
+    }
+}
+
+fn parse_type_system_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, TypeSystemDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description::<S>(parser)?;
+
+    match parser.peek().item {
+        Token::Name("schema") => Ok(TypeSystemDefinition::Schema(parse_schema_definition(
+            parser,
+        )?)),
+        Token::Name("scalar")
+        | Token::Name("type")
+        | Token::Name("interface")
+        | Token::Name("union")
+        | Token::Name("enum")
+        | Token::Name("input") => Ok(TypeSystemDefinition::Type(parse_type_definition(
+            parser,
+            description,
+        )?)),
+        Token::Name("directive") => Ok(TypeSystemDefinition::Directive(
+            parse_directive_definition(parser, description)?,
+        )),
+        Token::Name("extend") if description.is_none() => {
+            let Spanning {
+                start: start_pos, ..
+            } = parser.next_token()?;
+            let extension = parse_type_definition(parser, None)?;
+
+            Ok(TypeSystemDefinition::TypeExtension(Spanning::start_end(
+                &start_pos,
+                &extension.end,
+                extension.item,
+            )))
+        }
+        _ => Err(parser.next_token()?.map(ParseError::UnexpectedToken)),
+    }
+}
+
+fn parse_description<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, Option<Spanning<String>>>
+where
+    S: ScalarValue,
+{
+    match parser.peek().item {
+        Token::Scalar(ScalarToken::String(_)) | Token::Scalar(ScalarToken::BlockString(_)) => {
+            if let Spanning {
+                item: Token::Scalar(token),
+                start,
+                end,
+            } = parser.next_token()?
+            {
+                <String as ParseScalarValue<S>>::from_str(token)
+                    .map(|s| Some(Spanning::start_end(&start, &end, s.as_string().unwrap())))
+                    .map_err(|e| Spanning::start_end(&start, &end, e))
+            } else {
+                unreachable!()
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn parse_schema_definition<'a, S>(parser: &mut Parser<'a>) -> ParseResult<'a, SchemaDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::Name("schema"))?;
+    let directives = parse_const_directives(parser)?;
+    let operation_types = parser.unlocated_delimited_nonempty_list(
+        &Token::CurlyOpen,
+        parse_operation_type_definition,
+        &Token::CurlyClose,
+    )?;
+
+    let mut definition = SchemaDefinition {
+        query: None,
+        mutation: None,
+        subscription: None,
+        directives,
+    };
+
+    for (operation, type_name) in operation_types.item {
+        let slot = match operation.item {
+            "query" => &mut definition.query,
+            "mutation" => &mut definition.mutation,
+            "subscription" => &mut definition.subscription,
+            _ => return Err(operation.map(|o| ParseError::UnexpectedToken(Token::Name(o)))),
+        };
+
+        if slot.is_some() {
+            return Err(operation.map(|o| ParseError::UnexpectedToken(Token::Name(o))));
+        }
+        *slot = Some(type_name);
+    }
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &operation_types.end,
+        definition,
+    ))
+}
+
+fn parse_operation_type_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, (Spanning<&'a str>, Spanning<String>)> {
+    let operation = parser.expect_name()?;
+    parser.expect(&Token::Colon)?;
+    let type_name = parser.expect_name()?.map(str::to_owned);
+
+    Ok((operation, type_name))
+}
+
+fn parse_type_definition<'a, S>(
+    parser: &mut Parser<'a>,
+    description: Option<Spanning<String>>,
+) -> ParseResult<'a, TypeDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let keyword = parser.next_token()?;
+    let name = parser.expect_name()?.map(str::to_owned);
+    let mut end_pos = name.end;
+
+    let interfaces = match keyword.item {
+        Token::Name("type") => parse_implements_interfaces(parser, &mut end_pos)?,
+        _ => Vec::new(),
+    };
+
+    let directives = parse_const_directives(parser)?;
+    if let Some(last) = directives.last() {
+        end_pos = last.end;
+    }
+
+    let kind = match keyword.item {
+        Token::Name("scalar") => TypeDefinitionKind::Scalar,
+        Token::Name("type") => TypeDefinitionKind::Object {
+            interfaces,
+            fields: parse_optional_block(parser, parse_field_definition, &mut end_pos)?,
+        },
+        Token::Name("interface") => TypeDefinitionKind::Interface {
+            fields: parse_optional_block(parser, parse_field_definition, &mut end_pos)?,
+        },
+        Token::Name("union") => TypeDefinitionKind::Union {
+            members: parse_union_members(parser, &mut end_pos)?,
+        },
+        Token::Name("enum") => TypeDefinitionKind::Enum {
+            values: parse_optional_block(parser, parse_enum_value_definition, &mut end_pos)?,
+        },
+        Token::Name("input") => TypeDefinitionKind::InputObject {
+            fields: parse_optional_block(parser, parse_input_value_definition, &mut end_pos)?,
+        },
+        _ => return Err(keyword.map(ParseError::UnexpectedToken)),
+    };
+
+    Ok(Spanning::start_end(
+        &description
+            .as_ref()
+            .map_or(&keyword.start, |d| &d.start)
+            .clone(),
+        &end_pos,
+        TypeDefinition {
+            description: description.map(|d| d.item),
+            name,
+            directives,
+            kind,
+        },
+    ))
+}
+
+fn parse_implements_interfaces<'a>(
+    parser: &mut Parser<'a>,
+    end_pos: &mut SourcePosition,
+) -> UnlocatedParseResult<'a, Vec<Spanning<String>>> {
+    let mut interfaces = Vec::new();
+
+    if parser.peek().item != Token::Name("implements") {
+        return Ok(interfaces);
+    }
+    parser.next_token()?;
+
+    if parser.peek().item == Token::Amp {
+        parser.next_token()?;
+    }
+
+    loop {
+        let interface = parser.expect_name()?.map(str::to_owned);
+        *end_pos = interface.end;
+        interfaces.push(interface);
+
+        if parser.peek().item == Token::Amp {
+            parser.next_token()?;
+        } else {
+            return Ok(interfaces);
+        }
+    }
+}
+
+fn parse_union_members<'a>(
+    parser: &mut Parser<'a>,
+    end_pos: &mut SourcePosition,
+) -> UnlocatedParseResult<'a, Vec<Spanning<String>>> {
+    let mut members = Vec::new();
+
+    if parser.peek().item != Token::Equals {
+        return Ok(members);
+    }
+    parser.next_token()?;
+
+    if parser.peek().item == Token::Pipe {
+        parser.next_token()?;
+    }
+
+    loop {
+        let member = parser.expect_name()?.map(str::to_owned);
+        *end_pos = member.end;
+        members.push(member);
+
+        if parser.peek().item == Token::Pipe {
+            parser.next_token()?;
+        } else {
+            return Ok(members);
+        }
+    }
+}
+
+fn parse_optional_block<'a, T, F>(
+    parser: &mut Parser<'a>,
+    item_parser: F,
+    end_pos: &mut SourcePosition,
+) -> UnlocatedParseResult<'a, Vec<T>>
+where
+    T: std::fmt::Debug,
+    F: Fn(&mut Parser<'a>) -> UnlocatedParseResult<'a, T>,
+{
+    if parser.peek().item != Token::CurlyOpen {
+        return Ok(Vec::new());
+    }
+
+    let items = parser.unlocated_delimited_nonempty_list(
+        &Token::CurlyOpen,
+        item_parser,
+        &Token::CurlyClose,
+    )?;
+    *end_pos = items.end;
+
+    Ok(items.item)
+}
+
+fn parse_field_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, FieldDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description::<S>(parser)?;
+    let name = parser.expect_name()?.map(str::to_owned);
+    let arguments = parse_arguments_definition(parser)?;
+    parser.expect(&Token::Colon)?;
+    let field_type = parse_type(parser)?.map(Type::into_owned);
+    let directives = parse_const_directives(parser)?;
+
+    Ok(FieldDefinition {
+        description: description.map(|d| d.item),
+        name,
+        arguments,
+        field_type,
+        directives,
+    })
+}
+
+fn parse_arguments_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, Vec<InputValueDefinition<S>>>
+where
+    S: ScalarValue,
+{
+    if parser.peek().item != Token::ParenOpen {
+        Ok(Vec::new())
+    } else {
+        Ok(parser
+            .unlocated_delimited_nonempty_list(
+                &Token::ParenOpen,
+                parse_input_value_definition,
+                &Token::ParenClose,
+            )?
+            .item)
+    }
+}
+
+fn parse_input_value_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, InputValueDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description::<S>(parser)?;
+    let name = parser.expect_name()?.map(str::to_owned);
+    parser.expect(&Token::Colon)?;
+    let value_type = parse_type(parser)?.map(Type::into_owned);
+
+    let default_value = if parser.peek().item == Token::Equals {
+        parser.next_token()?;
+        Some(parse_const_value(parser)?)
+    } else {
+        None
+    };
+
+    let directives = parse_const_directives(parser)?;
+
+    Ok(InputValueDefinition {
+        description: description.map(|d| d.item),
+        name,
+        value_type,
+        default_value,
+        directives,
+    })
+}
+
+fn parse_enum_value_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, EnumValueDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description::<S>(parser)?;
+    let name = parser.expect_name()?.map(str::to_owned);
+    let directives = parse_const_directives(parser)?;
+
+    Ok(EnumValueDefinition {
+        description: description.map(|d| d.item),
+        name,
+        directives,
+    })
+}
+
+fn parse_directive_definition<'a, S>(
+    parser: &mut Parser<'a>,
+    description: Option<Spanning<String>>,
+) -> ParseResult<'a, DirectiveDefinition<S>>
+where
+    S: ScalarValue,
+{
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::Name("directive"))?;
+    parser.expect(&Token::At)?;
+    let name = parser.expect_name()?.map(str::to_owned);
+    let arguments = parse_arguments_definition(parser)?;
+
+    if parser.peek().item == Token::Name("repeatable") {
+        parser.next_token()?;
+    }
+
+    parser.expect(&Token::Name("on"))?;
+    if parser.peek().item == Token::Pipe {
+        parser.next_token()?;
+    }
+
+    let mut locations = vec![parser.expect_name()?.map(str::to_owned)];
+    while parser.peek().item == Token::Pipe {
+        parser.next_token()?;
+        locations.push(parser.expect_name()?.map(str::to_owned));
+    }
+
+    Ok(Spanning::start_end(
+        &description
+            .as_ref()
+            .map_or(&start_pos, |d| &d.start)
+            .clone(),
+        &locations.last().unwrap().end.clone(),
+        DirectiveDefinition {
+            description: description.map(|d| d.item),
+            name,
+            arguments,
+            locations,
+        },
+    ))
+}
+
+fn parse_const_directives<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, Vec<Spanning<ConstDirective<S>>>>
+where
+    S: ScalarValue,
+{
+    let mut directives = Vec::new();
+
+    while parser.peek().item == Token::At {
+        let Spanning {
+            start: start_pos, ..
+        } = parser.next_token()?;
+        let name = parser.expect_name()?.map(str::to_owned);
+
+        let (arguments, end_pos) = if parser.peek().item == Token::ParenOpen {
+            let arguments = parser.delimited_nonempty_list(
+                &Token::ParenOpen,
+                parse_const_argument,
+                &Token::ParenClose,
+            )?;
+            (
+                arguments.item.into_iter().map(|a| a.item).collect(),
+                arguments.end,
+            )
+        } else {
+            (Vec::new(), name.end)
+        };
+
+        directives.push(Spanning::start_end(
+            &start_pos,
+            &end_pos,
+            ConstDirective { name, arguments },
+        ));
+    }
+
+    Ok(directives)
+}
+
+fn parse_const_argument<'a, S>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<'a, (Spanning<String>, Spanning<InputValue<S>>)>
+where
+    S: ScalarValue,
+{
+    let name = parser.expect_name()?.map(str::to_owned);
+    parser.expect(&Token::Colon)?;
+    let value = parse_const_value(parser)?;
+
+    Ok(Spanning::start_end(
+        &name.start.clone(),
+        &value.end.clone(),
+        (name, value),
+    ))
+}
+
+fn parse_const_value<'a, S>(parser: &mut Parser<'a>) -> ParseResult<'a, InputValue<S>>
+where
+    S: ScalarValue,
+{
+    match parser.peek().item {
+        Token::BracketOpen => Ok(parser
+            .delimited_list(&Token::BracketOpen, parse_const_value, &Token::BracketClose)?
+            .map(InputValue::parsed_list)),
+        Token::CurlyOpen => Ok(parser
+            .delimited_list(&Token::CurlyOpen, parse_const_argument, &Token::CurlyClose)?
+            .map(|items| InputValue::parsed_object(items.into_iter().map(|s| s.item).collect()))),
+        Token::Scalar(_) => {
+            if let Spanning {
+                item: Token::Scalar(token),
+                start,
+                end,
+            } = parser.next_token()?
+            {
+                parse_scalar_literal(token)
+                    .map(|s| Spanning::start_end(&start, &end, InputValue::Scalar(s)))
+                    .map_err(|e| Spanning::start_end(&start, &end, e))
+            } else {
+                unreachable!()
+            }
+        }
+        Token::Name("true") => Ok(parser.next_token()?.map(|_| InputValue::scalar(true))),
+        Token::Name("false") => Ok(parser.next_token()?.map(|_| InputValue::scalar(false))),
+        Token::Name("null") => Ok(parser.next_token()?.map(|_| InputValue::null())),
+        Token::Name(name) => Ok(parser.next_token()?.map(|_| InputValue::enum_value(name))),
+        _ => Err(parser.next_token()?.map(ParseError::UnexpectedToken)),
+    }
+}
+
+pub(crate) fn parse_scalar_literal<'a, S>(token: ScalarToken<'a>) -> Result<S, ParseError<'a>>
+where
+    S: ScalarValue,
+{
+    match token {
+        ScalarToken::String(_) | ScalarToken::BlockString(_) => {
+            <String as ParseScalarValue<S>>::from_str(token)
+        }
+        ScalarToken::Int(_) => <i32 as ParseScalarValue<S>>::from_str(token)
+            .or_else(|_| <f64 as ParseScalarValue<S>>::from_str(token)),
+        ScalarToken::Float(_) => <f64 as ParseScalarValue<S>>::from_str(token),
+    }
+}
@@ -201,6 +201,91 @@ fn strings() {
     );
 }
 
+#[test]
+fn block_strings() {
+    assert_eq!(
+        tokenize_single(r#""""simple""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(12, 0, 12),
+            Token::Scalar(ScalarToken::BlockString("simple"))
+        )
+    );
+
+    assert_eq!(
+        tokenize_single("\"\"\"\n  multi\n  line\n\"\"\""),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(22, 3, 3),
+            Token::Scalar(ScalarToken::BlockString("\n  multi\n  line\n"))
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""unescaped \n and escaped \""" quotes""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(42, 0, 42),
+            Token::Scalar(ScalarToken::BlockString(
+                r#"unescaped \n and escaped \""" quotes"#
+            ))
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(6, 0, 6),
+            Token::Scalar(ScalarToken::BlockString(""))
+        )
+    );
+
+    assert_eq!(
+        tokenize_to_vec(r#""" """#),
+        vec![
+            Spanning::start_end(
+                &SourcePosition::new(0, 0, 0),
+                &SourcePosition::new(2, 0, 2),
+                Token::Scalar(ScalarToken::String(""))
+            ),
+            Spanning::start_end(
+                &SourcePosition::new(3, 0, 3),
+                &SourcePosition::new(5, 0, 5),
+                Token::Scalar(ScalarToken::String(""))
+            ),
+            Spanning::zero_width(&SourcePosition::new(5, 0, 5), Token::EndOfFile),
+        ]
+    );
+}
+
+#[test]
+fn block_string_errors() {
+    assert_eq!(
+        tokenize_error(r#""""no end quotes""#),
+        Spanning::zero_width(
+            &SourcePosition::new(17, 0, 17),
+            LexerError::UnterminatedString
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""""escaped end \""""#),
+        Spanning::zero_width(
+            &SourcePosition::new(19, 0, 19),
+            LexerError::UnterminatedString
+        )
+    );
+
+    assert_eq!(
+        tokenize_error("\"\"\"contains unescaped \u{0007} control char\"\"\""),
+        Spanning::zero_width(
+            &SourcePosition::new(22, 0, 22),
+            LexerError::UnknownCharacterInString('\u{0007}')
+        )
+    );
+}
+
 #[test]
 fn string_errors() {
     assert_eq!(
@@ -599,6 +684,11 @@ fn punctuation() {
         Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::At)
     );
 
+    assert_eq!(
+        tokenize_single("&"),
+        Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::Amp)
+    );
+
     assert_eq!(
         tokenize_single("["),
         Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::BracketOpen)
@@ -684,6 +774,14 @@ fn display() {
         "\"string with \\\\ escape and \\\" quote\""
     );
 
+    assert_eq!(
+        format!(
+            "{}",
+            Token::Scalar(ScalarToken::BlockString("block\nstring"))
+        ),
+        "\"\"\"block\nstring\"\"\""
+    );
+
     assert_eq!(format!("{}", Token::ExclamationMark), "!");
     assert_eq!(format!("{}", Token::Dollar), "$");
     assert_eq!(format!("{}", Token::ParenOpen), "(");
@@ -696,5 +794,6 @@ fn display() {
     assert_eq!(format!("{}", Token::Colon), ":");
     assert_eq!(format!("{}", Token::Equals), "=");
     assert_eq!(format!("{}", Token::At), "@");
+    assert_eq!(format!("{}", Token::Amp), "&");
     assert_eq!(format!("{}", Token::Pipe), "|");
 }
@@ -0,0 +1,221 @@
+use crate::{
+    ast::{InputValue, SchemaDocument, Type, TypeDefinitionKind, TypeSystemDefinition},
+    parser::{parse_schema_source, ParseError, Spanning, Token},
+    value::DefaultScalarValue,
+};
+
+fn parse_schema(s: &str) -> SchemaDocument<DefaultScalarValue> {
+    parse_schema_source(s).expect(&format!("Parse error on input {:#?}", s))
+}
+
+fn parse_schema_error(s: &str) -> Spanning<ParseError> {
+    match parse_schema_source::<DefaultScalarValue>(s) {
+        Ok(doc) => panic!("*No* parse error on input {:#?} =>\n{:#?}", s, doc),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn object_type_definition() {
+    let doc = parse_schema(
+        r#"
+        "A user"
+        type User implements Node & Named @key(fields: "id") {
+            id: ID!
+            "The friends of the user"
+            friends(first: Int = 10, after: String): [User!]! @deprecated
+        }
+        "#,
+    );
+
+    let definition = match doc.as_slice() {
+        [TypeSystemDefinition::Type(definition)] => &definition.item,
+        _ => panic!("Unexpected document {:#?}", doc),
+    };
+
+    assert_eq!(definition.description, Some("A user".to_owned()));
+    assert_eq!(definition.name.item, "User");
+    assert_eq!(definition.directives.len(), 1);
+    assert_eq!(definition.directives[0].item.name.item, "key");
+    assert_eq!(
+        definition.directives[0].item.arguments[0].1.item,
+        InputValue::scalar("id")
+    );
+
+    let (interfaces, fields) = match definition.kind {
+        TypeDefinitionKind::Object {
+            ref interfaces,
+            ref fields,
+        } => (interfaces, fields),
+        _ => panic!("Unexpected kind {:#?}", definition.kind),
+    };
+
+    assert_eq!(
+        interfaces.iter().map(|i| &i.item[..]).collect::<Vec<_>>(),
+        vec!["Node", "Named"]
+    );
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].field_type.item, Type::NonNullNamed("ID".into()));
+    assert_eq!(
+        fields[1].description,
+        Some("The friends of the user".to_owned())
+    );
+    assert_eq!(
+        fields[1].field_type.item,
+        Type::NonNullList(Box::new(Type::NonNullNamed("User".into())))
+    );
+    assert_eq!(fields[1].arguments.len(), 2);
+    assert_eq!(
+        fields[1].arguments[0]
+            .default_value
+            .as_ref()
+            .map(|v| &v.item),
+        Some(&InputValue::scalar(10))
+    );
+    assert_eq!(fields[1].directives[0].item.name.item, "deprecated");
+}
+
+#[test]
+fn block_string_descriptions() {
+    let doc = parse_schema(
+        r#"
+        """
+        A user
+
+        Identified by its `id`
+        """
+        type User {
+            """The "id" of the user"""
+            id: ID!
+        }
+        "#,
+    );
+
+    let definition = match doc.as_slice() {
+        [TypeSystemDefinition::Type(definition)] => &definition.item,
+        _ => panic!("Unexpected document {:#?}", doc),
+    };
+
+    assert_eq!(
+        definition.description,
+        Some("A user\n\nIdentified by its `id`".to_owned())
+    );
+    match definition.kind {
+        TypeDefinitionKind::Object { ref fields, .. } => assert_eq!(
+            fields[0].description,
+            Some("The \"id\" of the user".to_owned())
+        ),
+        _ => panic!("Unexpected kind {:#?}", definition.kind),
+    }
+}
+
+#[test]
+fn other_type_definitions() {
+    let doc = parse_schema(
+        r#"
+        schema { query: Root mutation: Mutations }
+        scalar Date
+        interface Node { id: ID! }
+        union SearchResult = | User | Post
+        enum Color { RED GREEN @deprecated(reason: "Too green") }
+        input Filter { color: Color = RED, tags: [String] }
+        extend type User { age: Int }
+        directive @auth(role: String!) repeatable on FIELD_DEFINITION | OBJECT
+        "#,
+    );
+
+    assert_eq!(doc.len(), 8);
+
+    match doc[0] {
+        TypeSystemDefinition::Schema(ref schema) => {
+            assert_eq!(schema.item.query.as_ref().unwrap().item, "Root");
+            assert_eq!(schema.item.mutation.as_ref().unwrap().item, "Mutations");
+            assert!(schema.item.subscription.is_none());
+        }
+        ref d => panic!("Unexpected definition {:#?}", d),
+    }
+
+    let kinds = doc[1..7]
+        .iter()
+        .map(|d| match *d {
+            TypeSystemDefinition::Type(ref t) | TypeSystemDefinition::TypeExtension(ref t) => {
+                &t.item.kind
+            }
+            ref d => panic!("Unexpected definition {:#?}", d),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(kinds[0], &TypeDefinitionKind::Scalar);
+    match kinds[1] {
+        TypeDefinitionKind::Interface { fields } => assert_eq!(fields[0].name.item, "id"),
+        k => panic!("Unexpected kind {:#?}", k),
+    }
+    match kinds[2] {
+        TypeDefinitionKind::Union { members } => assert_eq!(
+            members.iter().map(|m| &m.item[..]).collect::<Vec<_>>(),
+            vec!["User", "Post"]
+        ),
+        k => panic!("Unexpected kind {:#?}", k),
+    }
+    match kinds[3] {
+        TypeDefinitionKind::Enum { values } => {
+            assert_eq!(values.len(), 2);
+            assert_eq!(values[1].name.item, "GREEN");
+            assert_eq!(values[1].directives[0].item.name.item, "deprecated");
+        }
+        k => panic!("Unexpected kind {:#?}", k),
+    }
+    match kinds[4] {
+        TypeDefinitionKind::InputObject { fields } => {
+            assert_eq!(
+                fields[0].default_value.as_ref().map(|v| &v.item),
+                Some(&InputValue::enum_value("RED"))
+            );
+            assert_eq!(
+                fields[1].value_type.item,
+                Type::List(Box::new(Type::Named("String".into())))
+            );
+        }
+        k => panic!("Unexpected kind {:#?}", k),
+    }
+    assert!(match doc[6] {
+        TypeSystemDefinition::TypeExtension(ref t) => t.item.name.item == "User",
+        _ => false,
+    });
+
+    match doc[7] {
+        TypeSystemDefinition::Directive(ref d) => {
+            assert_eq!(d.item.name.item, "auth");
+            assert_eq!(d.item.arguments[0].name.item, "role");
+            assert_eq!(
+                d.item
+                    .locations
+                    .iter()
+                    .map(|l| &l.item[..])
+                    .collect::<Vec<_>>(),
+                vec!["FIELD_DEFINITION", "OBJECT"]
+            );
+        }
+        ref d => panic!("Unexpected definition {:#?}", d),
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse_schema_error("type Query { id: $var }").item,
+        ParseError::UnexpectedToken(Token::Dollar)
+    );
+    assert_eq!(
+        parse_schema_error("type Query { id: ID! }\nquery { id }").item,
+        ParseError::UnexpectedToken(Token::Name("query"))
+    );
+    assert_eq!(
+        parse_schema_error("type Query {").item,
+        ParseError::UnexpectedEndOfFile
+    );
+    assert_eq!(
+        parse_schema_error("union U = ").item,
+        ParseError::UnexpectedEndOfFile
+    );
+}
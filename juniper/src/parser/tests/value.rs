@@ -123,6 +123,17 @@ fn input_value_literals() {
             InputValue::scalar("test")
         )
     );
+    assert_eq!(
+        parse_value::<DefaultScalarValue>(
+            "\"\"\"\n    block\n      string\n\"\"\"",
+            &scalar_meta::<String>("String")
+        ),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(30, 3, 3),
+            InputValue::scalar("block\n  string")
+        )
+    );
     let values = &[EnumValue::new("enum_value")];
     let e: EnumMeta<DefaultScalarValue> = EnumMeta::new::<Enum>("TestEnum".into(), values);
 
@@ -26,12 +26,21 @@ where
             },
             _,
         ) => parse_list_literal(parser, is_const, schema, tpe),
+        // Custom scalars can accept objects, like the `_Any` scalar of Apollo
+        // Federation.
         (
             &Spanning {
                 item: Token::CurlyOpen,
                 ..
             },
             None,
+        )
+        | (
+            &Spanning {
+                item: Token::CurlyOpen,
+                ..
+            },
+            Some(&MetaType::Scalar(_)),
         ) => parse_object_literal(parser, is_const, schema, None),
         (
             &Spanning {
@@ -211,7 +220,7 @@ where
     S: ScalarValue,
 {
     let result = match token {
-        ScalarToken::String(_) => {
+        ScalarToken::String(_) | ScalarToken::BlockString(_) => {
             if let Some(&MetaType::Scalar(ref s)) = schema.concrete_type_by_name("String") {
                 (s.parse_fn)(token).map(InputValue::Scalar)
             } else {
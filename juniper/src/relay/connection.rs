@@ -0,0 +1,531 @@
+use std::future::Future;
+
+use juniper_codegen::GraphQLObjectInternal as GraphQLObject;
+
+use crate::{
+    executor::{ExecutionResult, Executor, FieldError, FieldResult, Registry},
+    relay::{base64_decode, derived_type_name},
+    schema::meta::MetaType,
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+    },
+    value::{ScalarValue, Value},
+    BoxFuture,
+};
+
+const CURSOR_PREFIX: &str = "arrayconnection:";
+
+/// The cursor of the item at `offset` of a list
+///
+/// These are the cursors of the connections built by
+/// [`Connection::from_slice`](struct.Connection.html#method.from_slice) and
+/// [`Connection::from_source`](struct.Connection.html#method.from_source).
+pub fn offset_to_cursor(offset: usize) -> String {
+    base64::encode(format!("{}{}", CURSOR_PREFIX, offset))
+}
+
+/// The offset of the item a cursor points at, if the cursor is valid
+pub fn cursor_to_offset(cursor: &str) -> Option<usize> {
+    base64_decode(cursor)?
+        .strip_prefix(CURSOR_PREFIX)?
+        .parse()
+        .ok()
+}
+
+/// Information about the current page of a connection
+#[derive(Clone, Debug, Default, PartialEq, GraphQLObject)]
+pub struct PageInfo {
+    /// Whether there are items before the current page
+    pub has_previous_page: bool,
+    /// Whether there are items after the current page
+    pub has_next_page: bool,
+    /// The cursor of the first item of the current page
+    pub start_cursor: Option<String>,
+    /// The cursor of the last item of the current page
+    pub end_cursor: Option<String>,
+}
+
+/// The pagination arguments of a connection field
+///
+/// `first` and `after` select the items after a cursor, `last` and `before`
+/// the items before a cursor. They can be combined, in which case `first` is
+/// applied before `last`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionArgs {
+    /// The maximum number of items from the start of the selected range
+    pub first: Option<i32>,
+    /// Only select items after this cursor
+    pub after: Option<String>,
+    /// The maximum number of items from the end of the selected range
+    pub last: Option<i32>,
+    /// Only select items before this cursor
+    pub before: Option<String>,
+}
+
+impl ConnectionArgs {
+    /// The range of offsets that these arguments select from a list of
+    /// `count` items
+    fn range<S>(&self, count: usize) -> FieldResult<(usize, usize), S>
+    where
+        S: ScalarValue,
+    {
+        let mut start = 0;
+        let mut end = count;
+
+        if let Some(ref after) = self.after {
+            start = parse_cursor(after)?.saturating_add(1).min(count);
+        }
+        if let Some(ref before) = self.before {
+            end = parse_cursor(before)?.min(count).max(start);
+        }
+        if let Some(first) = self.first {
+            end = end.min(start.saturating_add(parse_limit("first", first)?));
+        }
+        if let Some(last) = self.last {
+            start = start.max(end.saturating_sub(parse_limit("last", last)?));
+        }
+
+        Ok((start, end))
+    }
+}
+
+fn parse_cursor<S>(cursor: &str) -> FieldResult<usize, S>
+where
+    S: ScalarValue,
+{
+    cursor_to_offset(cursor)
+        .ok_or_else(|| FieldError::new(format!("Invalid cursor \"{}\"", cursor), Value::null()))
+}
+
+fn parse_limit<S>(name: &str, limit: i32) -> FieldResult<usize, S>
+where
+    S: ScalarValue,
+{
+    if limit < 0 {
+        Err(FieldError::new(
+            format!("Argument \"{}\" must not be negative", name),
+            Value::null(),
+        ))
+    } else {
+        Ok(limit as usize)
+    }
+}
+
+/// An item of a connection, along with its cursor
+///
+/// The GraphQL name of `Edge<T>` is the name of `T` followed by `Edge`, like
+/// `UserEdge`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge<T> {
+    /// The item
+    pub node: T,
+    /// The cursor of the item, which can be passed as `after` or `before`
+    pub cursor: String,
+}
+
+impl<T> Edge<T> {
+    /// Create an edge
+    pub fn new(node: T, cursor: String) -> Self {
+        Edge { node, cursor }
+    }
+}
+
+/// A page of a paginated list, following the Relay connection specification
+///
+/// The GraphQL name of `Connection<T>` is the name of `T` followed by
+/// `Connection`, like `UserConnection`. `T` has to be a named type, such as
+/// an object or an interface.
+///
+/// ```rust
+/// use juniper::{
+///     relay::{Connection, ConnectionArgs},
+///     FieldResult,
+/// };
+///
+/// #[derive(Clone, juniper::GraphQLObject)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// struct Query;
+///
+/// #[juniper::graphql_object]
+/// impl Query {
+///     fn users(
+///         first: Option<i32>,
+///         after: Option<String>,
+///         last: Option<i32>,
+///         before: Option<String>,
+///     ) -> FieldResult<Connection<User>> {
+///         let users = vec![User { name: "Alice".to_owned() }, User { name: "Bob".to_owned() }];
+///
+///         Connection::from_slice(&users, &ConnectionArgs { first, after, last, before })
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection<T> {
+    /// The items of the current page
+    pub edges: Vec<Edge<T>>,
+    /// Information about the current page
+    pub page_info: PageInfo,
+}
+
+impl<T> Connection<T> {
+    /// Create a connection
+    pub fn new(edges: Vec<Edge<T>>, page_info: PageInfo) -> Self {
+        Connection { edges, page_info }
+    }
+
+    /// Select a page of a list of items
+    ///
+    /// The cursors of the items are based on their offsets in `items`.
+    pub fn from_slice<S>(items: &[T], args: &ConnectionArgs) -> FieldResult<Self, S>
+    where
+        T: Clone,
+        S: ScalarValue,
+    {
+        let (start, end) = args.range(items.len())?;
+
+        Ok(Self::from_offset(
+            start,
+            items.len(),
+            items[start..end].to_vec(),
+        ))
+    }
+
+    /// Select a page of a list of `count` items that are loaded on demand
+    ///
+    /// `fetch` is called with the offset of the first item of the page and
+    /// the number of items to load, like `OFFSET` and `LIMIT` of a SQL query.
+    /// The cursors of the items are based on their offsets.
+    pub async fn from_source<F, Fut, S>(
+        args: &ConnectionArgs,
+        count: usize,
+        fetch: F,
+    ) -> FieldResult<Self, S>
+    where
+        F: FnOnce(usize, usize) -> Fut,
+        Fut: Future<Output = FieldResult<Vec<T>, S>>,
+        S: ScalarValue,
+    {
+        let (start, end) = args.range(count)?;
+        let nodes = if start < end {
+            fetch(start, end - start).await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self::from_offset(start, count, nodes))
+    }
+
+    fn from_offset(start: usize, count: usize, nodes: Vec<T>) -> Self {
+        let end = start + nodes.len();
+        let edges = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| Edge::new(node, offset_to_cursor(start + i)))
+            .collect::<Vec<_>>();
+
+        let page_info = PageInfo {
+            has_previous_page: start > 0,
+            has_next_page: end < count,
+            start_cursor: edges.first().map(|e| e.cursor.clone()),
+            end_cursor: edges.last().map(|e| e.cursor.clone()),
+        };
+
+        Connection::new(edges, page_info)
+    }
+}
+
+impl<T, S> GraphQLType<S> for Edge<T>
+where
+    T: GraphQLType<S>,
+    S: ScalarValue,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn name(info: &T::TypeInfo) -> Option<&str> {
+        T::name(info).map(|name| derived_type_name(name, "Edge"))
+    }
+
+    fn meta<'r>(info: &T::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry.field::<T>("node", info).description("The item"),
+            registry
+                .field::<String>("cursor", &())
+                .description("The cursor of the item"),
+        ];
+
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .description("An item of a connection, along with its cursor")
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &T::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "node" => executor.resolve(info, &self.node),
+            "cursor" => Ok(Value::scalar(self.cursor.clone())),
+            _ => panic!(
+                "Field {} not found on type {:?}",
+                field_name,
+                Self::name(info)
+            ),
+        }
+    }
+
+    fn concrete_type_name(&self, _: &T::Context, info: &T::TypeInfo) -> String {
+        Self::name(info).unwrap().to_owned()
+    }
+}
+
+impl<T, S> GraphQLTypeAsync<S> for Edge<T>
+where
+    T: GraphQLTypeAsync<S>,
+    T::Context: Send + Sync,
+    T::TypeInfo: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a T::TypeInfo,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<T::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        match field_name {
+            "node" => Box::pin(executor.resolve_async(info, &self.node)),
+            _ => Box::pin(futures::future::ready(
+                self.resolve_field(info, field_name, arguments, executor),
+            )),
+        }
+    }
+}
+
+impl<T, S> GraphQLType<S> for Connection<T>
+where
+    T: GraphQLType<S>,
+    S: ScalarValue,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn name(info: &T::TypeInfo) -> Option<&str> {
+        T::name(info).map(|name| derived_type_name(name, "Connection"))
+    }
+
+    fn meta<'r>(info: &T::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry
+                .field::<Vec<Edge<T>>>("edges", info)
+                .description("The items of the current page"),
+            registry
+                .field::<PageInfo>("pageInfo", &())
+                .description("Information about the current page"),
+        ];
+
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .description("A page of a paginated list")
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &T::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "edges" => executor.resolve(info, &self.edges),
+            "pageInfo" => executor.resolve_with_ctx(&(), &self.page_info),
+            _ => panic!(
+                "Field {} not found on type {:?}",
+                field_name,
+                Self::name(info)
+            ),
+        }
+    }
+
+    fn concrete_type_name(&self, _: &T::Context, info: &T::TypeInfo) -> String {
+        Self::name(info).unwrap().to_owned()
+    }
+}
+
+impl<T, S> GraphQLTypeAsync<S> for Connection<T>
+where
+    T: GraphQLTypeAsync<S>,
+    T::Context: Send + Sync,
+    T::TypeInfo: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a T::TypeInfo,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<T::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        match field_name {
+            "edges" => Box::pin(executor.resolve_async(info, &self.edges)),
+            _ => Box::pin(futures::future::ready(
+                self.resolve_field(info, field_name, arguments, executor),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cursor_to_offset, offset_to_cursor, Connection, ConnectionArgs, PageInfo};
+
+    use crate::{executor::FieldResult, value::DefaultScalarValue};
+
+    fn page(args: ConnectionArgs) -> FieldResult<(Vec<i32>, PageInfo), DefaultScalarValue> {
+        let items = (0..10).collect::<Vec<_>>();
+        let connection = Connection::from_slice(&items, &args)?;
+
+        Ok((
+            connection.edges.into_iter().map(|e| e.node).collect(),
+            connection.page_info,
+        ))
+    }
+
+    fn page_info(
+        has_previous_page: bool,
+        has_next_page: bool,
+        start: usize,
+        end: usize,
+    ) -> PageInfo {
+        PageInfo {
+            has_previous_page,
+            has_next_page,
+            start_cursor: Some(offset_to_cursor(start)),
+            end_cursor: Some(offset_to_cursor(end)),
+        }
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        assert_eq!(cursor_to_offset(&offset_to_cursor(42)), Some(42));
+        assert_eq!(cursor_to_offset("not a cursor"), None);
+        assert_eq!(cursor_to_offset(&base64::encode("42")), None);
+        assert_eq!(
+            cursor_to_offset(&base64::encode("arrayconnection:arrayconnection:42")),
+            None
+        );
+    }
+
+    #[test]
+    fn selects_everything_without_arguments() {
+        assert_eq!(
+            page(ConnectionArgs::default()),
+            Ok(((0..10).collect(), page_info(false, false, 0, 9)))
+        );
+    }
+
+    #[test]
+    fn selects_first_items_after_cursor() {
+        assert_eq!(
+            page(ConnectionArgs {
+                first: Some(3),
+                after: Some(offset_to_cursor(1)),
+                ..ConnectionArgs::default()
+            }),
+            Ok((vec![2, 3, 4], page_info(true, true, 2, 4)))
+        );
+    }
+
+    #[test]
+    fn selects_last_items_before_cursor() {
+        assert_eq!(
+            page(ConnectionArgs {
+                last: Some(2),
+                before: Some(offset_to_cursor(8)),
+                ..ConnectionArgs::default()
+            }),
+            Ok((vec![6, 7], page_info(true, true, 6, 7)))
+        );
+    }
+
+    #[test]
+    fn applies_first_before_last() {
+        assert_eq!(
+            page(ConnectionArgs {
+                first: Some(5),
+                last: Some(2),
+                ..ConnectionArgs::default()
+            }),
+            Ok((vec![3, 4], page_info(true, true, 3, 4)))
+        );
+    }
+
+    #[test]
+    fn selects_nothing_past_the_end() {
+        assert_eq!(
+            page(ConnectionArgs {
+                after: Some(offset_to_cursor(9)),
+                ..ConnectionArgs::default()
+            }),
+            Ok((
+                vec![],
+                PageInfo {
+                    has_previous_page: true,
+                    ..PageInfo::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(page(ConnectionArgs {
+            first: Some(-1),
+            ..ConnectionArgs::default()
+        })
+        .is_err());
+        assert!(page(ConnectionArgs {
+            after: Some("invalid".to_owned()),
+            ..ConnectionArgs::default()
+        })
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn loads_pages_from_source() {
+        let connection = Connection::<i32>::from_source::<_, _, DefaultScalarValue>(
+            &ConnectionArgs {
+                first: Some(2),
+                after: Some(offset_to_cursor(4)),
+                ..ConnectionArgs::default()
+            },
+            100,
+            |offset, limit| async move {
+                assert_eq!((offset, limit), (5, 2));
+                Ok(vec![5, 6])
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(connection.page_info, page_info(true, true, 5, 6));
+        assert_eq!(connection.edges[1].node, 6);
+        assert_eq!(connection.edges[1].cursor, offset_to_cursor(6));
+    }
+}
@@ -0,0 +1,82 @@
+//! Building blocks for schemas that follow the Relay conventions
+//!
+//! See the [GraphQL Cursor Connections
+//! Specification](https://relay.dev/graphql/connections.htm) for the
+//! connection types and pagination arguments, and the [GraphQL Global Object
+//! Identification Specification](https://relay.dev/graphql/objectidentification.htm)
+//! for the `Node` interface.
+
+mod connection;
+mod node;
+
+use std::{cell::RefCell, collections::HashMap, sync::Mutex};
+
+pub use self::{
+    connection::{cursor_to_offset, offset_to_cursor, Connection, ConnectionArgs, Edge, PageInfo},
+    node::{GlobalId, Node, NodeValue, Nodes},
+};
+
+// Generic types, like `Connection<T>`, have names that are derived from the
+// names of their type parameters, but `GraphQLType::name` can only return
+// borrowed names. Derived names are leaked once and then cached per thread,
+// so that resolving them doesn't take a lock.
+static TYPE_NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CACHED_TYPE_NAMES: RefCell<HashMap<&'static str, HashMap<String, &'static str>>> =
+        RefCell::new(HashMap::new());
+}
+
+pub(crate) fn derived_type_name(name: &str, suffix: &'static str) -> &'static str {
+    CACHED_TYPE_NAMES.with(|cached| {
+        let mut cached = cached.borrow_mut();
+        let cached = cached.entry(suffix).or_insert_with(HashMap::new);
+
+        if let Some(&n) = cached.get(name) {
+            return n;
+        }
+
+        let n = static_type_name(format!("{}{}", name, suffix));
+        cached.insert(name.to_owned(), n);
+        n
+    })
+}
+
+fn static_type_name(name: String) -> &'static str {
+    let mut names = TYPE_NAMES.lock().unwrap();
+
+    match names.iter().find(|&&n| n == name) {
+        Some(n) => n,
+        None => {
+            let n: &'static str = Box::leak(name.into_boxed_str());
+            names.push(n);
+            n
+        }
+    }
+}
+
+// Cursors and global IDs are opaque to clients, by convention they are base64
+// encoded.
+pub(crate) fn base64_decode(input: &str) -> Option<String> {
+    base64::decode(input)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::derived_type_name;
+
+    #[test]
+    fn reuses_type_names() {
+        let name = derived_type_name("User", "Connection");
+
+        assert_eq!(name, "UserConnection");
+        assert!(std::ptr::eq(name, derived_type_name("User", "Connection")));
+
+        let other_thread = std::thread::spawn(|| derived_type_name("User", "Connection"))
+            .join()
+            .unwrap();
+        assert!(std::ptr::eq(name, other_thread));
+    }
+}
@@ -0,0 +1,549 @@
+use std::{fmt, marker::PhantomData, mem};
+
+use crate::{
+    ast::Selection,
+    executor::{ExecutionResult, Executor, FieldError, FieldResult, Registry},
+    relay::base64_decode,
+    schema::{meta::MetaType, model::RootNode},
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+        scalars::ID,
+    },
+    value::{DefaultScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+/// A globally unique ID, made of the name of an object's type and its ID
+/// within that type
+///
+/// Global IDs are encoded as the base64 representation of `Type:localId`, so
+/// that clients treat them as opaque strings.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GlobalId {
+    type_name: String,
+    id: String,
+}
+
+impl GlobalId {
+    /// Create a global ID
+    pub fn new<T, I>(type_name: T, id: I) -> Self
+    where
+        T: Into<String>,
+        I: Into<String>,
+    {
+        GlobalId {
+            type_name: type_name.into(),
+            id: id.into(),
+        }
+    }
+
+    /// Decode a global ID, if it is valid
+    pub fn decode(id: &ID) -> Option<Self> {
+        let decoded = base64_decode(id)?;
+        let mut parts = decoded.splitn(2, ':');
+
+        match (parts.next(), parts.next()) {
+            (Some(type_name), Some(id)) if !type_name.is_empty() => {
+                Some(GlobalId::new(type_name, id))
+            }
+            _ => None,
+        }
+    }
+
+    /// Encode the global ID
+    pub fn encode(&self) -> ID {
+        ID::new(base64::encode(self.to_string()))
+    }
+
+    /// The name of the object's type
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The ID of the object within its type
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl fmt::Display for GlobalId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.type_name, self.id)
+    }
+}
+
+impl From<GlobalId> for ID {
+    fn from(id: GlobalId) -> ID {
+        id.encode()
+    }
+}
+
+/// An object type that implements the `Node` interface
+///
+/// The type opts into the interface with the `node` attribute of
+/// `#[derive(GraphQLObject)]` or `#[graphql_object]`, and must have an
+/// `id: ID!` field that returns its global ID, see
+/// [`Node::global_id`](#method.global_id).
+///
+/// Objects are loaded with `resolve_node`, or `resolve_node_async` for those
+/// that need to await a datastore. The latter is used by
+/// [`Nodes::resolve_async`](struct.Nodes.html#method.resolve_async), and falls
+/// back to the former by default.
+pub trait Node<S = DefaultScalarValue>: GraphQLTypeAsync<S, TypeInfo = ()> + 'static
+where
+    Self::Context: Send + Sync,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    /// Load the object with the given ID within this type
+    ///
+    /// Returning `None` resolves the node to `null`. The default
+    /// implementation fails, as the object can only be loaded asynchronously.
+    fn resolve_node(_id: &str, _context: &Self::Context) -> FieldResult<Option<Self>, S> {
+        Err(FieldError::new(
+            format!(
+                "Node \"{}\" can only be resolved asynchronously",
+                Self::name(&()).unwrap_or_default(),
+            ),
+            Value::null(),
+        ))
+    }
+
+    /// Load the object with the given ID within this type asynchronously
+    ///
+    /// Returning `None` resolves the node to `null`. The default
+    /// implementation calls `resolve_node`.
+    fn resolve_node_async<'a>(
+        id: &'a str,
+        context: &'a Self::Context,
+    ) -> BoxFuture<'a, FieldResult<Option<Self>, S>> {
+        Box::pin(futures::future::ready(Self::resolve_node(id, context)))
+    }
+
+    /// The global ID of the object with the given ID within this type
+    fn global_id(id: &str) -> ID {
+        GlobalId::new(Self::name(&()).expect("Node types must be named"), id).encode()
+    }
+}
+
+/// The types that implement the `Node` interface, which can be refetched by
+/// their global ID
+///
+/// The root fields of the interface are implemented with
+/// [`Nodes::resolve`](#method.resolve) and
+/// [`Nodes::resolve_many`](#method.resolve_many), or their asynchronous
+/// counterparts [`Nodes::resolve_async`](#method.resolve_async) and
+/// [`Nodes::resolve_many_async`](#method.resolve_many_async):
+///
+/// ```rust
+/// use juniper::{
+///     relay::{Node, NodeValue, Nodes},
+///     FieldResult, ID,
+/// };
+///
+/// #[derive(juniper::GraphQLObject)]
+/// #[graphql(node)]
+/// struct User {
+///     id: ID,
+///     name: String,
+/// }
+///
+/// impl Node for User {
+///     fn resolve_node(id: &str, _: &()) -> FieldResult<Option<User>> {
+///         Ok(Some(User {
+///             id: User::global_id(id),
+///             name: format!("User {}", id),
+///         }))
+///     }
+/// }
+///
+/// fn nodes() -> Nodes<()> {
+///     Nodes::new().node::<User>()
+/// }
+///
+/// struct Query;
+///
+/// # fn main() {
+/// let schema = juniper::RootNode::new(
+///     Query,
+///     juniper::EmptyMutation::<()>::new(),
+///     juniper::EmptySubscription::<()>::new(),
+/// )
+/// .with_nodes(&nodes());
+///
+/// assert!(schema.as_sdl().contains("type User implements Node {"));
+/// # }
+///
+/// #[juniper::graphql_object]
+/// impl Query {
+///     fn node(id: ID) -> FieldResult<Option<NodeValue<()>>> {
+///         nodes().resolve(&id, &())
+///     }
+///
+///     fn nodes(ids: Vec<ID>) -> FieldResult<Vec<Option<NodeValue<()>>>> {
+///         nodes().resolve_many(&ids, &())
+///     }
+/// }
+/// ```
+///
+/// Node types that no field returns directly are only part of the schema if
+/// they are added with [`RootNode::with_nodes`](../struct.RootNode.html#method.with_nodes).
+pub struct Nodes<CtxT, S = DefaultScalarValue> {
+    types: Vec<Box<dyn NodeType<CtxT, S>>>,
+}
+
+impl<CtxT, S> Default for Nodes<CtxT, S> {
+    fn default() -> Self {
+        Nodes { types: Vec::new() }
+    }
+}
+
+impl<CtxT, S> Nodes<CtxT, S>
+where
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    /// Create an empty set of node types
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node type
+    pub fn node<T>(mut self) -> Self
+    where
+        T: Node<S, Context = CtxT>,
+    {
+        self.types.push(Box::new(NodeMarker::<T>(PhantomData)));
+        self
+    }
+
+    /// Load the object with the given global ID
+    ///
+    /// Returns `None` if the ID belongs to a type that is not part of this
+    /// set, and an error if it is not a valid global ID.
+    pub fn resolve(&self, id: &ID, context: &CtxT) -> FieldResult<Option<NodeValue<CtxT, S>>, S> {
+        let global_id = decode(id)?;
+
+        match self.node_type(&global_id) {
+            Some(node_type) => node_type.resolve_node(global_id.id(), context),
+            None => Ok(None),
+        }
+    }
+
+    /// Load the objects with the given global IDs
+    pub fn resolve_many(
+        &self,
+        ids: &[ID],
+        context: &CtxT,
+    ) -> FieldResult<Vec<Option<NodeValue<CtxT, S>>>, S> {
+        ids.iter().map(|id| self.resolve(id, context)).collect()
+    }
+
+    /// Load the object with the given global ID asynchronously
+    ///
+    /// Returns `None` if the ID belongs to a type that is not part of this
+    /// set, and an error if it is not a valid global ID.
+    pub async fn resolve_async(
+        &self,
+        id: &ID,
+        context: &CtxT,
+    ) -> FieldResult<Option<NodeValue<CtxT, S>>, S> {
+        let global_id = decode(id)?;
+
+        match self.node_type(&global_id) {
+            Some(node_type) => node_type.resolve_node_async(global_id.id(), context).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Load the objects with the given global IDs asynchronously, all at once
+    pub async fn resolve_many_async(
+        &self,
+        ids: &[ID],
+        context: &CtxT,
+    ) -> FieldResult<Vec<Option<NodeValue<CtxT, S>>>, S> {
+        futures::future::try_join_all(ids.iter().map(|id| self.resolve_async(id, context))).await
+    }
+
+    fn node_type(&self, global_id: &GlobalId) -> Option<&dyn NodeType<CtxT, S>> {
+        self.types
+            .iter()
+            .find(|t| t.name() == global_id.type_name())
+            .map(|t| &**t)
+    }
+}
+
+fn decode<S>(id: &ID) -> FieldResult<GlobalId, S>
+where
+    S: ScalarValue,
+{
+    GlobalId::decode(id)
+        .ok_or_else(|| FieldError::new(format!("Invalid ID \"{}\"", &**id), Value::null()))
+}
+
+trait NodeType<CtxT, S>: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn meta<'r>(&self, registry: &mut Registry<'r, S>)
+    where
+        S: 'r;
+
+    fn resolve_node(&self, id: &str, context: &CtxT) -> FieldResult<Option<NodeValue<CtxT, S>>, S>;
+
+    fn resolve_node_async<'a>(
+        &self,
+        id: &'a str,
+        context: &'a CtxT,
+    ) -> BoxFuture<'a, FieldResult<Option<NodeValue<CtxT, S>>, S>>;
+}
+
+struct NodeMarker<T>(PhantomData<fn() -> T>);
+
+impl<T, CtxT, S> NodeType<CtxT, S> for NodeMarker<T>
+where
+    T: Node<S, Context = CtxT>,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    fn name(&self) -> &str {
+        T::name(&()).expect("Node types must be named")
+    }
+
+    fn meta<'r>(&self, registry: &mut Registry<'r, S>)
+    where
+        S: 'r,
+    {
+        registry.get_type::<T>(&());
+    }
+
+    fn resolve_node(&self, id: &str, context: &CtxT) -> FieldResult<Option<NodeValue<CtxT, S>>, S> {
+        Ok(T::resolve_node(id, context)?.map(NodeValue::new))
+    }
+
+    fn resolve_node_async<'a>(
+        &self,
+        id: &'a str,
+        context: &'a CtxT,
+    ) -> BoxFuture<'a, FieldResult<Option<NodeValue<CtxT, S>>, S>> {
+        Box::pin(async move {
+            Ok(T::resolve_node_async(id, context)
+                .await?
+                .map(NodeValue::new))
+        })
+    }
+}
+
+trait ResolveNode<CtxT, S>: Send + Sync {
+    fn resolve(&self, executor: &Executor<CtxT, S>) -> ExecutionResult<S>;
+
+    fn resolve_async<'a>(
+        &'a self,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>>;
+
+    fn resolve_field(
+        &self,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S>;
+
+    fn resolve_field_async<'a>(
+        &'a self,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>>;
+}
+
+impl<T, CtxT, S> ResolveNode<CtxT, S> for T
+where
+    T: GraphQLTypeAsync<S, Context = CtxT, TypeInfo = ()>,
+    CtxT: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve(&self, executor: &Executor<CtxT, S>) -> ExecutionResult<S> {
+        executor.resolve(&(), self)
+    }
+
+    fn resolve_async<'a>(
+        &'a self,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        Box::pin(executor.resolve_async(&(), self))
+    }
+
+    fn resolve_field(
+        &self,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S> {
+        GraphQLType::resolve_field(self, &(), field_name, arguments, executor)
+    }
+
+    fn resolve_field_async<'a>(
+        &'a self,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        GraphQLTypeAsync::resolve_field_async(self, &(), field_name, arguments, executor)
+    }
+}
+
+/// A value of the `Node` interface
+///
+/// The fields of the interface are resolved by the object's own fields.
+pub struct NodeValue<CtxT, S = DefaultScalarValue> {
+    type_name: &'static str,
+    node: Box<dyn ResolveNode<CtxT, S>>,
+}
+
+impl<CtxT, S> NodeValue<CtxT, S>
+where
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    /// Wrap an object that implements the `Node` interface
+    pub fn new<T>(node: T) -> Self
+    where
+        T: Node<S, Context = CtxT>,
+    {
+        NodeValue {
+            type_name: T::name(&()).expect("Node types must be named"),
+            node: Box::new(node),
+        }
+    }
+}
+
+impl<CtxT, S> GraphQLType<S> for NodeValue<CtxT, S>
+where
+    S: ScalarValue,
+{
+    type Context = CtxT;
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("Node")
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [registry
+            .field::<ID>("id", &())
+            .description("The globally unique ID of the object")];
+
+        registry
+            .build_interface_type::<Self>(info, &fields)
+            .description("An object with a globally unique ID")
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        _: &(),
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S> {
+        self.node.resolve_field(field_name, arguments, executor)
+    }
+
+    fn concrete_type_name(&self, _: &CtxT, _: &()) -> String {
+        self.type_name.to_owned()
+    }
+
+    fn resolve_into_type(
+        &self,
+        _: &(),
+        type_name: &str,
+        _: Option<&[Selection<S>]>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S> {
+        if type_name == self.type_name {
+            self.node.resolve(executor)
+        } else {
+            Ok(Value::null())
+        }
+    }
+}
+
+impl<CtxT, S> GraphQLTypeAsync<S> for NodeValue<CtxT, S>
+where
+    CtxT: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        _: &'a (),
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        self.node
+            .resolve_field_async(field_name, arguments, executor)
+    }
+
+    fn resolve_into_type_async<'a>(
+        &'a self,
+        _: &'a (),
+        type_name: &str,
+        _: Option<&'a [Selection<'a, S>]>,
+        executor: &'a Executor<'a, 'a, CtxT, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        if type_name == self.type_name {
+            self.node.resolve_async(executor)
+        } else {
+            Box::pin(futures::future::ready(Ok(Value::null())))
+        }
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    QueryT::Context: Send + Sync + 'static,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    /// Add the `Node` interface and the types that implement it to the schema
+    ///
+    /// Types are otherwise only part of the schema if a field returns them,
+    /// which is not the case for types that are only fetched through the
+    /// `node` field.
+    pub fn with_nodes(mut self, nodes: &Nodes<QueryT::Context, S>) -> Self {
+        let mut registry = Registry::new(mem::take(&mut self.schema.types));
+        registry.get_type::<NodeValue<QueryT::Context, S>>(&());
+        for node_type in &nodes.types {
+            node_type.meta(&mut registry);
+        }
+        self.schema.types = registry.types;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlobalId;
+
+    use crate::types::scalars::ID;
+
+    #[test]
+    fn global_id_round_trip() {
+        let id = GlobalId::new("User", "1:2").encode();
+
+        assert_eq!(&*id, "VXNlcjoxOjI=");
+        assert_eq!(GlobalId::decode(&id), Some(GlobalId::new("User", "1:2")));
+    }
+
+    #[test]
+    fn rejects_invalid_global_ids() {
+        assert_eq!(GlobalId::decode(&ID::new("not base64!")), None);
+        assert_eq!(GlobalId::decode(&ID::new("VXNlcg==")), None);
+        assert_eq!(GlobalId::decode(&ID::new("OjE=")), None);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    mem::ManuallyDrop,
+    sync::{Arc, Mutex},
+};
+
+use crate::ast::Document;
+
+/// A parsed document, owning the query text it borrows from
+pub(crate) struct CachedDocument<S> {
+    // Borrows from `source`. The `'static` lifetime never leaves this struct,
+    // see `document`, and it's dropped before `source`, see `drop`.
+    document: ManuallyDrop<Document<'static, S>>,
+    source: Arc<str>,
+}
+
+impl<S> CachedDocument<S> {
+    /// Parse a copy of `source` with `parse`, keeping the copy alive for as
+    /// long as the document
+    pub(crate) fn parse<E, F>(source: &str, parse: F) -> Result<Self, E>
+    where
+        F: FnOnce(&str) -> Result<Document<S>, E>,
+    {
+        let source: Arc<str> = source.into();
+        // SAFETY: The text is a heap allocation owned by `source` and only
+        // ever read through shared references. Moving an `Arc`, unlike a
+        // `Box`, doesn't move its text or assert unique access to it, and the
+        // text is freed only after `document` is dropped. The document is only
+        // handed out with the lifetime of a borrow of `self`, so no reference
+        // to the text outlives `source`.
+        let text: &'static str = unsafe { &*(&*source as *const str) };
+        let document = parse(text)?;
+        Ok(CachedDocument {
+            document: ManuallyDrop::new(document),
+            source,
+        })
+    }
+
+    /// The parsed document, borrowing from this entry
+    pub(crate) fn document<'a>(&'a self) -> &'a Document<'a, S> {
+        &self.document
+    }
+}
+
+impl<S> Drop for CachedDocument<S> {
+    fn drop(&mut self) {
+        // SAFETY: `document` isn't used again, and it's dropped here while
+        // `source`, which is only dropped after this, still holds its text.
+        unsafe { ManuallyDrop::drop(&mut self.document) }
+    }
+}
+
+struct Entry<S> {
+    document: Arc<CachedDocument<S>>,
+    last_used: u64,
+}
+
+struct Entries<S> {
+    map: HashMap<String, Entry<S>>,
+    clock: u64,
+}
+
+/// A bounded cache of parsed and validated documents, keyed by their text
+///
+/// When full, the least recently used document is evicted.
+pub(crate) struct DocumentCache<S> {
+    capacity: usize,
+    entries: Mutex<Entries<S>>,
+}
+
+impl<S> DocumentCache<S> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        DocumentCache {
+            capacity,
+            entries: Mutex::new(Entries {
+                map: HashMap::new(),
+                clock: 0,
+            }),
+        }
+    }
+
+    /// Look up the document parsed from `source`
+    pub(crate) fn get(&self, source: &str) -> Option<Arc<CachedDocument<S>>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let clock = entries.clock;
+
+        entries.map.get_mut(source).map(|entry| {
+            entry.last_used = clock;
+            Arc::clone(&entry.document)
+        })
+    }
+
+    /// Add a document to the cache, keyed by the text it was parsed from
+    ///
+    /// The document must have been validated already.
+    pub(crate) fn insert(&self, document: Arc<CachedDocument<S>>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let source = &*document.source;
+        let mut entries = self.entries.lock().unwrap();
+        if entries.map.len() >= self.capacity && !entries.map.contains_key(source) {
+            let oldest = entries
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(source, _)| source.clone());
+            if let Some(oldest) = oldest {
+                entries.map.remove(&oldest);
+            }
+        }
+
+        entries.clock += 1;
+        let last_used = entries.clock;
+        entries.map.insert(
+            source.to_owned(),
+            Entry {
+                document,
+                last_used,
+            },
+        );
+    }
+}
+
+impl<S> fmt::Debug for DocumentCache<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DocumentCache({}/{} documents)",
+            self.entries.lock().unwrap().map.len(),
+            self.capacity,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{CachedDocument, DocumentCache};
+    use crate::{
+        parser::parse_document_source,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::DefaultScalarValue,
+    };
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn hello() -> &'static str {
+            "world"
+        }
+    }
+
+    type Document = CachedDocument<DefaultScalarValue>;
+
+    fn root_node() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+        RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    fn parse(source: &str) -> Option<Arc<Document>> {
+        let root_node = root_node();
+        Document::parse(source, |s| {
+            parse_document_source(s, &root_node.schema).map_err(drop)
+        })
+        .ok()
+        .map(Arc::new)
+    }
+
+    #[test]
+    fn documents_outlive_the_source() {
+        let cache = DocumentCache::new(2);
+
+        let source = String::from("{ hello }");
+        cache.insert(parse(&source).unwrap());
+        drop(source);
+
+        let cached = cache.get("{ hello }").unwrap();
+        assert_eq!(cached.document().len(), 1);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = DocumentCache::new(2);
+
+        cache.insert(parse("{ a: hello }").unwrap());
+        cache.insert(parse("{ b: hello }").unwrap());
+        assert!(cache.get("{ a: hello }").is_some());
+        cache.insert(parse("{ c: hello }").unwrap());
+
+        assert!(cache.get("{ a: hello }").is_some());
+        assert!(cache.get("{ b: hello }").is_none());
+        assert!(cache.get("{ c: hello }").is_some());
+    }
+
+    #[test]
+    fn skips_invalid_documents() {
+        assert!(parse("{ hello").is_none());
+
+        let cache = DocumentCache::new(0);
+        cache.insert(parse("{ hello }").unwrap());
+        assert!(cache.get("{ hello }").is_none());
+    }
+}
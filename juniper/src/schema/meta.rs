@@ -75,6 +75,8 @@ pub struct ObjectMeta<'a, S> {
     pub fields: Vec<Field<'a, S>>,
     #[doc(hidden)]
     pub interface_names: Vec<String>,
+    #[doc(hidden)]
+    pub directives: Vec<SchemaDirective>,
 }
 
 /// Enum type metadata
@@ -85,6 +87,8 @@ pub struct EnumMeta<'a, S> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub values: Vec<EnumValue>,
+    #[doc(hidden)]
+    pub directives: Vec<SchemaDirective>,
     pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> bool,
 }
 
@@ -118,6 +122,10 @@ pub struct InputObjectMeta<'a, S> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub input_fields: Vec<Argument<'a, S>>,
+    #[doc(hidden)]
+    pub directives: Vec<SchemaDirective>,
+    #[doc(hidden)]
+    pub is_one_of: bool,
     pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> bool,
 }
 
@@ -167,6 +175,12 @@ pub struct Field<'a, S> {
     pub field_type: Type<'a>,
     #[doc(hidden)]
     pub deprecation_status: DeprecationStatus,
+    #[doc(hidden)]
+    pub complexity: usize,
+    #[doc(hidden)]
+    pub list_size: usize,
+    #[doc(hidden)]
+    pub directives: Vec<SchemaDirective>,
 }
 
 /// Metadata for an argument to a field
@@ -180,6 +194,8 @@ pub struct Argument<'a, S> {
     pub arg_type: Type<'a>,
     #[doc(hidden)]
     pub default_value: Option<InputValue<S>>,
+    #[doc(hidden)]
+    pub directives: Vec<SchemaDirective>,
 }
 
 /// Metadata for a single value in an enum
@@ -196,6 +212,21 @@ pub struct EnumValue {
     pub description: Option<String>,
     /// Whether the field is deprecated or not, with an optional reason.
     pub deprecation_status: DeprecationStatus,
+    /// The schema directives applied to the enum value
+    pub directives: Vec<SchemaDirective>,
+}
+
+/// A directive applied to a part of the schema, like `@key(fields: "id")`
+///
+/// Schema directives have no effect on execution. They are printed in the
+/// schema definition language and can be read from the metadata of the
+/// schema, e.g. by gateways or to implement authorization policies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaDirective {
+    /// The name of the directive, without the `@`
+    pub name: String,
+    /// The arguments of the directive, in the order they were given
+    pub arguments: Vec<(String, InputValue)>,
 }
 
 impl<'a, S> MetaType<'a, S> {
@@ -453,6 +484,7 @@ where
             description: None,
             fields: fields.to_vec(),
             interface_names: vec![],
+            directives: vec![],
         }
     }
 
@@ -476,6 +508,12 @@ where
         self
     }
 
+    /// Apply a schema directive to the object
+    pub fn directive(mut self, directive: SchemaDirective) -> ObjectMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
     /// Wrap this object type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::Object(self)
@@ -495,6 +533,7 @@ where
             name,
             description: None,
             values: values.to_vec(),
+            directives: vec![],
             try_parse_fn: try_parse_fn::<S, T>,
         }
     }
@@ -507,6 +546,12 @@ where
         self
     }
 
+    /// Apply a schema directive to the enum
+    pub fn directive(mut self, directive: SchemaDirective) -> EnumMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
     /// Wrap this enum type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::Enum(self)
@@ -578,6 +623,8 @@ where {
             name,
             description: None,
             input_fields: input_fields.to_vec(),
+            directives: vec![],
+            is_one_of: false,
             try_parse_fn: try_parse_fn::<S, T>,
         }
     }
@@ -590,6 +637,21 @@ where {
         self
     }
 
+    /// Apply a schema directive to the input object
+    pub fn directive(mut self, directive: SchemaDirective) -> InputObjectMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Mark the input object as a `@oneOf` input object
+    ///
+    /// Exactly one of its fields must be given a non-null value. The input
+    /// fields are expected to be nullable and have no default value.
+    pub fn one_of(mut self) -> InputObjectMeta<'a, S> {
+        self.is_one_of = true;
+        self
+    }
+
     /// Wrap this union type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::InputObject(self)
@@ -651,6 +713,30 @@ impl<'a, S> Field<'a, S> {
         self.deprecation_status = DeprecationStatus::Deprecated(reason.map(ToOwned::to_owned));
         self
     }
+
+    /// Set the cost of resolving the field, used to compute the complexity
+    /// of a query
+    ///
+    /// The default cost is 1.
+    pub fn complexity(mut self, complexity: usize) -> Self {
+        self.complexity = complexity;
+        self
+    }
+
+    /// Set the expected number of items returned by the field
+    ///
+    /// The complexity of the selection set of the field is multiplied by this
+    /// number, which defaults to 1.
+    pub fn list_size(mut self, list_size: usize) -> Self {
+        self.list_size = list_size;
+        self
+    }
+
+    /// Apply a schema directive to the field
+    pub fn directive(mut self, directive: SchemaDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
 }
 
 impl<'a, S> Argument<'a, S> {
@@ -661,6 +747,7 @@ impl<'a, S> Argument<'a, S> {
             description: None,
             arg_type,
             default_value: None,
+            directives: vec![],
         }
     }
 
@@ -700,6 +787,12 @@ impl<'a, S> Argument<'a, S> {
         self.default_value = Some(default_value);
         self
     }
+
+    /// Apply a schema directive to the argument
+    pub fn directive(mut self, directive: SchemaDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
 }
 
 impl EnumValue {
@@ -709,6 +802,7 @@ impl EnumValue {
             name: name.to_owned(),
             description: None,
             deprecation_status: DeprecationStatus::Current,
+            directives: vec![],
         }
     }
 
@@ -727,6 +821,36 @@ impl EnumValue {
         self.deprecation_status = DeprecationStatus::Deprecated(reason.map(ToOwned::to_owned));
         self
     }
+
+    /// Apply a schema directive to the enum value
+    pub fn directive(mut self, directive: SchemaDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
+}
+
+impl SchemaDirective {
+    /// Construct a new directive without arguments
+    pub fn new(name: &str) -> SchemaDirective {
+        SchemaDirective {
+            name: name.to_owned(),
+            arguments: vec![],
+        }
+    }
+
+    /// Add an argument to the directive
+    pub fn argument(mut self, name: &str, value: InputValue) -> SchemaDirective {
+        self.arguments.push((name.to_owned(), value));
+        self
+    }
+
+    /// Access an argument's value given its name
+    pub fn argument_by_name(&self, name: &str) -> Option<&InputValue> {
+        self.arguments
+            .iter()
+            .find(|&&(ref n, _)| n == name)
+            .map(|&(_, ref v)| v)
+    }
 }
 
 impl<'a, S: fmt::Debug> fmt::Debug for ScalarMeta<'a, S> {
@@ -744,6 +868,7 @@ impl<'a, S: fmt::Debug> fmt::Debug for EnumMeta<'a, S> {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("values", &self.values)
+            .field("directives", &self.directives)
             .finish()
     }
 }
@@ -754,6 +879,8 @@ impl<'a, S: fmt::Debug> fmt::Debug for InputObjectMeta<'a, S> {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("input_fields", &self.input_fields)
+            .field("directives", &self.directives)
+            .field("is_one_of", &self.is_one_of)
             .finish()
     }
 }
@@ -1,5 +1,8 @@
 #![allow(clippy::module_inception)]
 
+pub(crate) mod document_cache;
 pub mod meta;
 pub mod model;
+pub(crate) mod printer;
 pub mod schema;
+pub mod sdl;
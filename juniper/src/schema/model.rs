@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, mem};
 
 use fnv::FnvHashMap;
 
@@ -6,9 +6,19 @@ use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
 
 use crate::{
     ast::Type,
-    executor::{Context, Registry},
-    schema::meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
-    types::{base::GraphQLType, name::Name},
+    executor::{Context, Registry, Tracer, Tracers},
+    http::persisted_queries::PersistedQueries,
+    schema::{
+        document_cache::DocumentCache,
+        meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
+        printer,
+    },
+    types::{
+        base::GraphQLType,
+        directives::{ExecutableDirective, ExecutableDirectives},
+        name::Name,
+    },
+    validation::{CustomRules, OperationLimits, ValidationRule},
     value::{DefaultScalarValue, ScalarValue},
 };
 
@@ -40,6 +50,12 @@ pub struct RootNode<
     pub subscription_info: SubscriptionT::TypeInfo,
     #[doc(hidden)]
     pub schema: SchemaType<'a, S>,
+    pub(crate) limits: OperationLimits,
+    pub(crate) validation_rules: CustomRules<S>,
+    pub(crate) directives: ExecutableDirectives<'a, QueryT::Context, S>,
+    pub(crate) persisted_queries: PersistedQueries,
+    pub(crate) document_cache: Option<DocumentCache<S>>,
+    pub(crate) tracers: Tracers<S>,
 }
 
 /// Metadata for a schema
@@ -61,7 +77,9 @@ pub enum TypeType<'a, S: 'a> {
     List(Box<TypeType<'a, S>>),
 }
 
+/// Metadata for a directive
 #[derive(Debug)]
+#[allow(missing_docs)]
 pub struct DirectiveType<'a, S> {
     pub name: String,
     pub description: Option<String>,
@@ -71,6 +89,7 @@ pub struct DirectiveType<'a, S> {
 
 #[derive(Clone, PartialEq, Eq, Debug, GraphQLEnum)]
 #[graphql(name = "__DirectiveLocation")]
+#[allow(missing_docs)]
 pub enum DirectiveLocation {
     Query,
     Mutation,
@@ -82,6 +101,22 @@ pub enum DirectiveLocation {
     FragmentSpread,
     #[graphql(name = "INLINE_FRAGMENT")]
     InlineFragment,
+    Schema,
+    Scalar,
+    Object,
+    #[graphql(name = "FIELD_DEFINITION")]
+    FieldDefinition,
+    #[graphql(name = "ARGUMENT_DEFINITION")]
+    ArgumentDefinition,
+    Interface,
+    Union,
+    Enum,
+    #[graphql(name = "ENUM_VALUE")]
+    EnumValue,
+    #[graphql(name = "INPUT_OBJECT")]
+    InputObject,
+    #[graphql(name = "INPUT_FIELD_DEFINITION")]
+    InputFieldDefinition,
 }
 
 impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
@@ -134,8 +169,144 @@ where
             query_info,
             mutation_info,
             subscription_info,
+            limits: OperationLimits::default(),
+            validation_rules: CustomRules::default(),
+            directives: ExecutableDirectives::default(),
+            persisted_queries: PersistedQueries::default(),
+            document_cache: None,
+            tracers: Tracers::default(),
         }
     }
+
+    /// Add a custom executable directive to the schema
+    ///
+    /// The directive's definition is added to the schema, so that it passes
+    /// validation and shows up in introspection, and its hooks run around
+    /// every field it is attached to, including fields resolved with another
+    /// context. Directives registered under the name of an existing directive
+    /// replace it.
+    pub fn with_directive<D>(mut self, directive: D) -> Self
+    where
+        D: ExecutableDirective<QueryT::Context, S> + 'static,
+        QueryT::Context: Sync,
+        S: Send + Sync,
+    {
+        let mut registry = Registry::new(mem::take(&mut self.schema.types));
+        let meta = directive.meta(&mut registry);
+        self.schema.types = registry.types;
+
+        self.directives.insert(&meta, directive);
+        self.schema.add_directive(meta);
+        self
+    }
+
+    /// Add the definition of a type system directive to the schema
+    ///
+    /// Type system directives, like `@key` on `OBJECT`, are attached to types,
+    /// fields, arguments and enum values with the `directive` attribute of the
+    /// codegen macros, or with the `directive` methods of the metadata types.
+    /// They have no runtime behavior, but can be read from the schema's
+    /// metadata and are part of the printed schema.
+    pub fn with_schema_directive<F>(mut self, meta: F) -> Self
+    where
+        F: FnOnce(&mut Registry<'a, S>) -> DirectiveType<'a, S>,
+    {
+        let mut registry = Registry::new(mem::take(&mut self.schema.types));
+        let meta = meta(&mut registry);
+        self.schema.types = registry.types;
+
+        self.schema.add_directive(meta);
+        self
+    }
+
+    /// Check every document executed against the schema with an additional
+    /// validation rule
+    ///
+    /// Custom rules run after the built-in ones, in the order they were added.
+    /// Their errors are returned as `GraphQLError::ValidationError`s.
+    pub fn with_validation_rule<R>(mut self, rule: R) -> Self
+    where
+        R: ValidationRule<S> + 'static,
+    {
+        self.validation_rules.0.push(Box::new(rule));
+        self
+    }
+
+    /// Reject operations whose selections are nested deeper than `max_depth`
+    ///
+    /// `__typename` doesn't count, but selections of `__schema` and `__type`
+    /// do, so the limit also applies to introspection queries.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.limits.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Reject operations that contain more than `max_aliases` aliased fields
+    ///
+    /// Aliases in fragments count once for every time the fragment is spread.
+    pub fn with_max_aliases(mut self, max_aliases: usize) -> Self {
+        self.limits.max_aliases = Some(max_aliases);
+        self
+    }
+
+    /// Reject operations whose complexity exceeds `max_complexity`
+    ///
+    /// The complexity of a field is its cost, plus the complexity of its
+    /// selection set multiplied by its list size. Both are 1 unless set with
+    /// [`Field::complexity`](meta/struct.Field.html#method.complexity) and
+    /// [`Field::list_size`](meta/struct.Field.html#method.list_size), or the
+    /// `complexity` and `list_size` attributes of the codegen macros.
+    /// `__typename` is free.
+    pub fn with_max_complexity(mut self, max_complexity: usize) -> Self {
+        self.limits.max_complexity = Some(max_complexity);
+        self
+    }
+
+    /// Support automatic persisted queries, keeping them in `store`
+    ///
+    /// Applies to requests executed through `juniper::http`, see
+    /// [`juniper::http::persisted_queries`](http/persisted_queries/index.html).
+    #[cfg(feature = "persisted-queries")]
+    pub fn with_persisted_queries<T>(mut self, store: T) -> Self
+    where
+        T: crate::http::persisted_queries::PersistedQueryStore + 'static,
+    {
+        self.persisted_queries = PersistedQueries(Some(Box::new(store)));
+        self
+    }
+
+    /// Cache up to `capacity` parsed and validated documents, keyed by the
+    /// text of their query
+    ///
+    /// Queries found in the cache skip parsing and validation, only their
+    /// variables are still checked. When the cache is full, the least
+    /// recently used document is evicted. Subscriptions bypass the cache, as
+    /// their streams borrow from the text of the query. Queries that fail to
+    /// parse are reported as validation errors, with the same message and
+    /// location.
+    pub fn with_document_cache(mut self, capacity: usize) -> Self {
+        self.document_cache = Some(DocumentCache::new(capacity));
+        self
+    }
+
+    /// Invoke the callbacks of `tracer` around the parsing, validation and
+    /// execution of every operation, and the resolution of every field
+    ///
+    /// Tracers are invoked in the order they were added.
+    pub fn with_tracer<T>(mut self, tracer: T) -> Self
+    where
+        T: Tracer<S> + 'static,
+    {
+        self.tracers.push(tracer);
+        self
+    }
+
+    /// The schema in the GraphQL schema definition language (SDL).
+    ///
+    /// See [`SchemaType::as_schema_language`](struct.SchemaType.html#method.as_schema_language).
+    pub fn as_sdl(&self) -> String {
+        self.schema.as_schema_language()
+    }
 }
 
 impl<'a, S> SchemaType<'a, S> {
@@ -219,6 +390,19 @@ impl<'a, S> SchemaType<'a, S> {
         }
     }
 
+    /// Print the schema in the GraphQL schema definition language (SDL).
+    ///
+    /// The output contains every type, interface, union, enum, input object
+    /// and custom directive of the schema, including descriptions and
+    /// deprecations. Types are sorted by name, so the result is stable and
+    /// suitable for committing to a repository or diffing in CI.
+    pub fn as_schema_language(&self) -> String
+    where
+        S: ScalarValue,
+    {
+        printer::print_schema(self)
+    }
+
     /// Add a directive like `skip` or `include`.
     pub fn add_directive(&mut self, directive: DirectiveType<'a, S>) {
         self.directives.insert(directive.name.clone(), directive);
@@ -462,6 +646,7 @@ impl<'a, S> DirectiveType<'a, S>
 where
     S: ScalarValue + 'a,
 {
+    /// Build a new directive that can be used at `locations`
     pub fn new(
         name: &str,
         locations: &[DirectiveLocation],
@@ -505,6 +690,7 @@ where
         )
     }
 
+    /// Set the description of the directive
     pub fn description(mut self, description: &str) -> DirectiveType<'a, S> {
         self.description = Some(description.to_owned());
         self
@@ -521,6 +707,17 @@ impl fmt::Display for DirectiveLocation {
             DirectiveLocation::FragmentDefinition => "fragment definition",
             DirectiveLocation::FragmentSpread => "fragment spread",
             DirectiveLocation::InlineFragment => "inline fragment",
+            DirectiveLocation::Schema => "schema",
+            DirectiveLocation::Scalar => "scalar",
+            DirectiveLocation::Object => "object",
+            DirectiveLocation::FieldDefinition => "field definition",
+            DirectiveLocation::ArgumentDefinition => "argument definition",
+            DirectiveLocation::Interface => "interface",
+            DirectiveLocation::Union => "union",
+            DirectiveLocation::Enum => "enum",
+            DirectiveLocation::EnumValue => "enum value",
+            DirectiveLocation::InputObject => "input object",
+            DirectiveLocation::InputFieldDefinition => "input field definition",
         })
     }
 }
@@ -0,0 +1,478 @@
+//! Printing of a schema in the GraphQL schema definition language (SDL)
+
+use std::fmt::Write;
+
+use crate::{
+    ast::InputValue,
+    schema::{
+        meta::{
+            Argument, DeprecationStatus, EnumMeta, EnumValue, Field, InputObjectMeta,
+            InterfaceMeta, MetaType, ObjectMeta, ScalarMeta, SchemaDirective, UnionMeta,
+        },
+        model::{DirectiveLocation, DirectiveType, SchemaType},
+    },
+    value::ScalarValue,
+};
+
+const BUILTIN_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
+const BUILTIN_DIRECTIVES: &[&str] = &["deprecated", "include", "skip"];
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+const INDENT: &str = "  ";
+
+/// Print the whole schema as a GraphQL SDL document.
+///
+/// Types and directives are printed sorted by name, so the output is stable
+/// across runs and can be committed and diffed.
+pub(crate) fn print_schema<S>(schema: &SchemaType<S>) -> String
+where
+    S: ScalarValue,
+{
+    print_schema_excluding(schema, &Excluded::default())
+}
+
+/// The parts of a schema left out by `print_schema_excluding`
+///
+/// This is used to leave out the parts of the schema that are added by Apollo
+/// Federation, which are not part of a subgraph's own schema.
+#[derive(Default)]
+pub(crate) struct Excluded<'a> {
+    /// The names of the excluded types
+    pub(crate) types: &'a [&'a str],
+    /// The names of the excluded directive definitions
+    pub(crate) directives: &'a [&'a str],
+    /// The names of the excluded fields of the query type
+    pub(crate) query_fields: &'a [&'a str],
+}
+
+/// Print the schema without the types, directive definitions and query type
+/// fields in `excluded`.
+pub(crate) fn print_schema_excluding<S>(schema: &SchemaType<S>, excluded: &Excluded) -> String
+where
+    S: ScalarValue,
+{
+    let mut definitions = Vec::new();
+
+    if let Some(schema_definition) = print_schema_definition(schema) {
+        definitions.push(schema_definition);
+    }
+
+    let mut directives = schema
+        .directive_list()
+        .into_iter()
+        .filter(|d| !BUILTIN_DIRECTIVES.contains(&d.name.as_str()))
+        .filter(|d| !excluded.directives.contains(&d.name.as_str()))
+        .collect::<Vec<_>>();
+    directives.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions.extend(directives.into_iter().map(|d| print_directive(schema, d)));
+
+    let query_name = schema.concrete_query_type().name();
+    let mut types = schema
+        .concrete_type_list()
+        .into_iter()
+        .filter(|t| t.name().map(is_printable_type_name).unwrap_or(false))
+        .filter(|t| {
+            t.name()
+                .map(|n| !excluded.types.contains(&n))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    types.sort_by(|a, b| a.name().cmp(&b.name()));
+    definitions.extend(types.into_iter().filter_map(|t| {
+        let excluded_fields = if t.name() == query_name {
+            excluded.query_fields
+        } else {
+            &[]
+        };
+        print_type(schema, t, excluded_fields)
+    }));
+
+    let mut out = definitions.join("\n\n");
+    out.push('\n');
+    out
+}
+
+fn is_printable_type_name(name: &str) -> bool {
+    !name.starts_with("__")
+        && !BUILTIN_SCALARS.contains(&name)
+        && name != "_EmptyMutation"
+        && name != "_EmptySubscription"
+}
+
+fn print_schema_definition<S>(schema: &SchemaType<S>) -> Option<String> {
+    let query = schema.concrete_query_type().name()?;
+    let mutation = schema.concrete_mutation_type().and_then(MetaType::name);
+    let subscription = schema.concrete_subscription_type().and_then(MetaType::name);
+
+    // The schema definition can be omitted if the root types use the
+    // conventional names.
+    if query == "Query"
+        && mutation.map(|n| n == "Mutation").unwrap_or(true)
+        && subscription.map(|n| n == "Subscription").unwrap_or(true)
+    {
+        return None;
+    }
+
+    let mut out = String::from("schema {\n");
+    writeln!(out, "{}query: {}", INDENT, query).unwrap();
+    if let Some(mutation) = mutation {
+        writeln!(out, "{}mutation: {}", INDENT, mutation).unwrap();
+    }
+    if let Some(subscription) = subscription {
+        writeln!(out, "{}subscription: {}", INDENT, subscription).unwrap();
+    }
+    out.push('}');
+    Some(out)
+}
+
+fn print_directive<S>(schema: &SchemaType<S>, directive: &DirectiveType<S>) -> String
+where
+    S: ScalarValue,
+{
+    let mut out = String::new();
+    print_description(&mut out, directive.description.as_ref(), "");
+    write!(out, "directive @{}", directive.name).unwrap();
+    print_arguments(&mut out, schema, &directive.arguments, "");
+    out.push_str(" on ");
+    let locations = directive
+        .locations
+        .iter()
+        .map(location_name)
+        .collect::<Vec<_>>();
+    out.push_str(&locations.join(" | "));
+    out
+}
+
+fn location_name(location: &DirectiveLocation) -> &'static str {
+    match *location {
+        DirectiveLocation::Query => "QUERY",
+        DirectiveLocation::Mutation => "MUTATION",
+        DirectiveLocation::Subscription => "SUBSCRIPTION",
+        DirectiveLocation::Field => "FIELD",
+        DirectiveLocation::FragmentDefinition => "FRAGMENT_DEFINITION",
+        DirectiveLocation::FragmentSpread => "FRAGMENT_SPREAD",
+        DirectiveLocation::InlineFragment => "INLINE_FRAGMENT",
+        DirectiveLocation::Schema => "SCHEMA",
+        DirectiveLocation::Scalar => "SCALAR",
+        DirectiveLocation::Object => "OBJECT",
+        DirectiveLocation::FieldDefinition => "FIELD_DEFINITION",
+        DirectiveLocation::ArgumentDefinition => "ARGUMENT_DEFINITION",
+        DirectiveLocation::Interface => "INTERFACE",
+        DirectiveLocation::Union => "UNION",
+        DirectiveLocation::Enum => "ENUM",
+        DirectiveLocation::EnumValue => "ENUM_VALUE",
+        DirectiveLocation::InputObject => "INPUT_OBJECT",
+        DirectiveLocation::InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+    }
+}
+
+fn print_type<S>(
+    schema: &SchemaType<S>,
+    meta_type: &MetaType<S>,
+    excluded_fields: &[&str],
+) -> Option<String>
+where
+    S: ScalarValue,
+{
+    let mut out = String::new();
+    print_description(&mut out, meta_type.description(), "");
+
+    match *meta_type {
+        MetaType::Scalar(ScalarMeta { ref name, .. }) => {
+            write!(out, "scalar {}", name).unwrap();
+        }
+        MetaType::Object(ObjectMeta {
+            ref name,
+            ref fields,
+            ref interface_names,
+            ref directives,
+            ..
+        }) => {
+            write!(out, "type {}", name).unwrap();
+            if !interface_names.is_empty() {
+                write!(out, " implements {}", interface_names.join(" & ")).unwrap();
+            }
+            print_applied_directives(&mut out, schema, directives);
+            print_fields(&mut out, schema, fields, excluded_fields);
+        }
+        MetaType::Interface(InterfaceMeta {
+            ref name,
+            ref fields,
+            ..
+        }) => {
+            write!(out, "interface {}", name).unwrap();
+            print_fields(&mut out, schema, fields, excluded_fields);
+        }
+        MetaType::Union(UnionMeta {
+            ref name,
+            ref of_type_names,
+            ..
+        }) => {
+            write!(out, "union {} = {}", name, of_type_names.join(" | ")).unwrap();
+        }
+        MetaType::Enum(EnumMeta {
+            ref name,
+            ref values,
+            ref directives,
+            ..
+        }) => {
+            write!(out, "enum {}", name).unwrap();
+            print_applied_directives(&mut out, schema, directives);
+            print_enum_values(&mut out, schema, values);
+        }
+        MetaType::InputObject(InputObjectMeta {
+            ref name,
+            ref input_fields,
+            ref directives,
+            is_one_of,
+            ..
+        }) => {
+            write!(out, "input {}", name).unwrap();
+            if is_one_of {
+                out.push_str(" @oneOf");
+            }
+            print_applied_directives(&mut out, schema, directives);
+            print_input_fields(&mut out, schema, input_fields);
+        }
+        MetaType::List(_) | MetaType::Nullable(_) | MetaType::Placeholder(_) => return None,
+    }
+
+    Some(out)
+}
+
+fn print_fields<S>(
+    out: &mut String,
+    schema: &SchemaType<S>,
+    fields: &[Field<S>],
+    excluded_fields: &[&str],
+) where
+    S: ScalarValue,
+{
+    out.push_str(" {\n");
+    for field in fields
+        .iter()
+        .filter(|f| !f.name.starts_with("__") && !excluded_fields.contains(&f.name.as_str()))
+    {
+        print_description(out, field.description.as_ref(), INDENT);
+        write!(out, "{}{}", INDENT, field.name).unwrap();
+        if let Some(ref arguments) = field.arguments {
+            print_arguments(out, schema, arguments, INDENT);
+        }
+        write!(out, ": {}", field.field_type).unwrap();
+        print_deprecation(out, &field.deprecation_status);
+        print_applied_directives(out, schema, &field.directives);
+        out.push('\n');
+    }
+    out.push('}');
+}
+
+fn print_arguments<S>(
+    out: &mut String,
+    schema: &SchemaType<S>,
+    arguments: &[Argument<S>],
+    indent: &str,
+) where
+    S: ScalarValue,
+{
+    if arguments.is_empty() {
+        return;
+    }
+
+    if arguments.iter().all(|a| a.description.is_none()) {
+        let arguments = arguments
+            .iter()
+            .map(|a| {
+                let mut out = String::new();
+                print_input_value(&mut out, schema, a);
+                out
+            })
+            .collect::<Vec<_>>();
+        write!(out, "({})", arguments.join(", ")).unwrap();
+        return;
+    }
+
+    let inner_indent = format!("{}{}", indent, INDENT);
+    out.push_str("(\n");
+    for argument in arguments {
+        print_description(out, argument.description.as_ref(), &inner_indent);
+        out.push_str(&inner_indent);
+        print_input_value(out, schema, argument);
+        out.push('\n');
+    }
+    write!(out, "{})", indent).unwrap();
+}
+
+fn print_input_fields<S>(out: &mut String, schema: &SchemaType<S>, input_fields: &[Argument<S>])
+where
+    S: ScalarValue,
+{
+    out.push_str(" {\n");
+    for input_field in input_fields {
+        print_description(out, input_field.description.as_ref(), INDENT);
+        out.push_str(INDENT);
+        print_input_value(out, schema, input_field);
+        out.push('\n');
+    }
+    out.push('}');
+}
+
+fn print_input_value<S>(out: &mut String, schema: &SchemaType<S>, argument: &Argument<S>)
+where
+    S: ScalarValue,
+{
+    write!(out, "{}: {}", argument.name, argument.arg_type).unwrap();
+    if let Some(ref default_value) = argument.default_value {
+        out.push_str(" = ");
+        print_value(
+            out,
+            schema,
+            default_value,
+            schema.lookup_type(&argument.arg_type),
+        );
+    }
+    print_applied_directives(out, schema, &argument.directives);
+}
+
+fn print_enum_values<S>(out: &mut String, schema: &SchemaType<S>, values: &[EnumValue])
+where
+    S: ScalarValue,
+{
+    out.push_str(" {\n");
+    for value in values {
+        print_description(out, value.description.as_ref(), INDENT);
+        write!(out, "{}{}", INDENT, value.name).unwrap();
+        print_deprecation(out, &value.deprecation_status);
+        print_applied_directives(out, schema, &value.directives);
+        out.push('\n');
+    }
+    out.push('}');
+}
+
+fn print_deprecation(out: &mut String, status: &DeprecationStatus) {
+    match status.reason() {
+        _ if !status.is_deprecated() => {}
+        Some(reason) if reason != DEFAULT_DEPRECATION_REASON => {
+            out.push_str(" @deprecated(reason: ");
+            print_string(out, reason);
+            out.push(')');
+        }
+        _ => out.push_str(" @deprecated"),
+    }
+}
+
+fn print_applied_directives<S>(
+    out: &mut String,
+    schema: &SchemaType<S>,
+    directives: &[SchemaDirective],
+) where
+    S: ScalarValue,
+{
+    for directive in directives {
+        write!(out, " @{}", directive.name).unwrap();
+        if directive.arguments.is_empty() {
+            continue;
+        }
+
+        let definition = schema.directive_by_name(&directive.name);
+        out.push('(');
+        for (i, &(ref name, ref value)) in directive.arguments.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            write!(out, "{}: ", name).unwrap();
+            let arg_type = definition
+                .and_then(|d| d.arguments.iter().find(|a| &a.name == name))
+                .and_then(|a| schema.lookup_type(&a.arg_type));
+            print_value(out, schema, value, arg_type);
+        }
+        out.push(')');
+    }
+}
+
+fn print_description(out: &mut String, description: Option<&String>, indent: &str) {
+    let description = match description {
+        Some(d) => d,
+        None => return,
+    };
+
+    if !description.contains('\n') && description.len() <= 70 {
+        out.push_str(indent);
+        print_string(out, description);
+        out.push('\n');
+        return;
+    }
+
+    writeln!(out, "{}\"\"\"", indent).unwrap();
+    for line in description.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            writeln!(out, "{}{}", indent, line.replace("\"\"\"", "\\\"\"\"")).unwrap();
+        }
+    }
+    writeln!(out, "{}\"\"\"", indent).unwrap();
+}
+
+// Enum values are converted to string scalars by `ToInputValue`, so the
+// expected type is needed to print them as enum literals again.
+fn print_value<S, T>(
+    out: &mut String,
+    schema: &SchemaType<S>,
+    value: &InputValue<T>,
+    meta_type: Option<&MetaType<S>>,
+) where
+    S: ScalarValue,
+    T: ScalarValue,
+{
+    match *value {
+        InputValue::Null => out.push_str("null"),
+        InputValue::Scalar(ref s) => match (s.as_str(), meta_type) {
+            (Some(s), Some(&MetaType::Enum(_))) => out.push_str(s),
+            (Some(s), _) => print_string(out, s),
+            (None, _) => write!(out, "{}", s).unwrap(),
+        },
+        InputValue::Enum(ref e) => out.push_str(e),
+        InputValue::Variable(ref v) => write!(out, "${}", v).unwrap(),
+        InputValue::List(ref items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                print_value(out, schema, &item.item, meta_type);
+            }
+            out.push(']');
+        }
+        InputValue::Object(ref fields) => {
+            out.push('{');
+            for (i, &(ref key, ref value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write!(out, "{}: ", key.item).unwrap();
+                let field_type = meta_type
+                    .and_then(|t| t.input_field_by_name(&key.item))
+                    .and_then(|f| schema.lookup_type(&f.arg_type));
+                print_value(out, schema, &value.item, field_type);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn print_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0008}' => out.push_str("\\b"),
+            '\u{000c}' => out.push_str("\\f"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -216,6 +216,15 @@ where
         }
     }
 
+    fn is_one_of(&self) -> Option<bool> {
+        match *self {
+            TypeType::Concrete(&MetaType::InputObject(InputObjectMeta { is_one_of, .. })) => {
+                Some(is_one_of)
+            }
+            _ => None,
+        }
+    }
+
     fn interfaces(&self, schema: &SchemaType<'a, S>) -> Option<Vec<TypeType<S>>> {
         match *self {
             TypeType::Concrete(&MetaType::Object(ObjectMeta {
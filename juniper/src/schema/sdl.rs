@@ -0,0 +1,560 @@
+//! Building schemas from documents in the GraphQL schema definition language
+
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use fnv::FnvHashMap;
+
+use crate::{
+    ast::{
+        DirectiveDefinition, InputValueDefinition, SchemaDefinition, SchemaDocument, Type,
+        TypeDefinition, TypeDefinitionKind, TypeSystemDefinition,
+    },
+    executor::{Executor, FieldResult},
+    parser::{parse_schema_source, ParseError, Spanning},
+    schema::{
+        meta::Argument,
+        model::{DirectiveLocation, DirectiveType, RootNode},
+    },
+    types::{
+        base::Arguments,
+        dynamic::{
+            Dynamic, DynamicRootNode, DynamicSchema, DynamicTypeInfo, DynamicValue, FieldResolver,
+        },
+    },
+    value::{DefaultScalarValue, ScalarValue},
+};
+
+const BUILTIN_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
+
+/// Builds a schema from an SDL document, with resolvers registered at runtime
+///
+/// Fields without a registered resolver are resolved from the entries of
+/// their parent value, if it's an object `Value`.
+///
+/// ```rust
+/// # use juniper::{graphql_value, SchemaBuilder, Variables};
+/// let schema = SchemaBuilder::<()>::from_sdl(r#"
+///     type Query {
+///         user(id: ID!): User
+///     }
+///
+///     type User {
+///         id: ID!
+///         name: String!
+///     }
+/// "#)
+/// .unwrap()
+/// .resolver("Query", "user", |_, args, _| {
+///     let id = args.get::<String>("id").unwrap();
+///     Ok(graphql_value!({ "id": id, "name": "Alice" }).into())
+/// })
+/// .build()
+/// .unwrap();
+///
+/// let (res, _errors) = juniper::execute_sync(
+///     r#"{ user(id: "1") { name } }"#,
+///     None,
+///     &schema,
+///     &Variables::new(),
+///     &(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(res, graphql_value!({ "user": { "name": "Alice" } }));
+/// ```
+pub struct SchemaBuilder<CtxT, S = DefaultScalarValue> {
+    document: SchemaDocument<S>,
+    resolvers: FnvHashMap<String, FnvHashMap<String, FieldResolver<CtxT, S>>>,
+}
+
+/// An error that prevented a schema from being built from an SDL document
+#[derive(Debug, PartialEq)]
+pub enum SchemaBuildError {
+    /// A type was defined more than once
+    DuplicateType(String),
+    /// The document contains more than one `schema` definition
+    DuplicateSchemaDefinition,
+    /// A type is referenced, but never defined
+    UnknownType(String),
+    /// A type is used where its kind of type is not allowed, e.g. an input
+    /// object as the type of a field
+    InvalidTypeUsage {
+        /// The type that is used
+        type_name: String,
+        /// Where it is used
+        usage: String,
+    },
+    /// The document defines no query root type
+    MissingQueryType,
+    /// An object type lacks a field of one of its interfaces
+    MissingInterfaceField {
+        /// The object type
+        type_name: String,
+        /// The interface it implements
+        interface: String,
+        /// The field it lacks
+        field_name: String,
+    },
+    /// A resolver was registered for a field that is not defined
+    UnknownField {
+        /// The type of the resolver
+        type_name: String,
+        /// The field of the resolver
+        field_name: String,
+    },
+}
+
+impl<CtxT, S> SchemaBuilder<CtxT, S>
+where
+    S: ScalarValue,
+{
+    /// Parse an SDL document
+    pub fn from_sdl(sdl: &str) -> Result<Self, Spanning<ParseError<'_>>> {
+        Ok(SchemaBuilder {
+            document: parse_schema_source(sdl)?,
+            resolvers: FnvHashMap::default(),
+        })
+    }
+
+    /// Register the resolver of the field `field_name` on type `type_name`
+    ///
+    /// Resolvers get the value of the parent object, the arguments of the
+    /// field and the executor, which provides the context. Resolvers
+    /// registered on an interface are used for all the object types
+    /// implementing it which don't have a resolver for the field of their own.
+    ///
+    /// Enum arguments are passed as enum literals, or as strings if they were
+    /// given as variables, so resolvers should read them as `InputValue`s.
+    pub fn resolver<F>(mut self, type_name: &str, field_name: &str, resolver: F) -> Self
+    where
+        F: Fn(
+                &DynamicValue<S>,
+                &Arguments<S>,
+                &Executor<CtxT, S>,
+            ) -> FieldResult<DynamicValue<S>, S>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.resolvers
+            .entry(type_name.to_owned())
+            .or_default()
+            .insert(field_name.to_owned(), Box::new(resolver));
+        self
+    }
+
+    /// Check the document and the resolvers, and build the schema
+    pub fn build<'a>(self) -> Result<DynamicRootNode<'a, CtxT, S>, SchemaBuildError>
+    where
+        S: 'a,
+    {
+        let mut schema_definition = None;
+        let mut types = FnvHashMap::<String, TypeDefinition<S>>::default();
+        let mut extensions = Vec::new();
+        let mut directives = Vec::new();
+
+        for definition in self.document {
+            match definition {
+                TypeSystemDefinition::Schema(definition) => {
+                    if schema_definition.replace(definition.item).is_some() {
+                        return Err(SchemaBuildError::DuplicateSchemaDefinition);
+                    }
+                }
+                TypeSystemDefinition::Type(definition) => {
+                    let name = definition.item.name.item.clone();
+                    if BUILTIN_SCALARS.contains(&name.as_str()) || types.contains_key(&name) {
+                        return Err(SchemaBuildError::DuplicateType(name));
+                    }
+                    types.insert(name, definition.item);
+                }
+                TypeSystemDefinition::TypeExtension(extension) => extensions.push(extension.item),
+                TypeSystemDefinition::Directive(definition) => directives.push(definition.item),
+            }
+        }
+
+        for extension in extensions {
+            extend_type(&mut types, extension)?;
+        }
+
+        let (query, mutation, subscription) = root_type_names(schema_definition, &types)?;
+
+        validate_types(&types)?;
+        for name in vec![Some(&query), mutation.as_ref(), subscription.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            expect_kind(&types, name, is_object, "a root operation type")?;
+        }
+        for definition in &directives {
+            validate_input_values(&types, &definition.arguments, &definition.name.item)?;
+        }
+
+        for (type_name, fields) in &self.resolvers {
+            for field_name in fields.keys() {
+                let is_defined = match types.get(type_name).map(|t| &t.kind) {
+                    Some(TypeDefinitionKind::Object { fields, .. })
+                    | Some(TypeDefinitionKind::Interface { fields }) => {
+                        fields.iter().any(|f| &f.name.item == field_name)
+                    }
+                    _ => false,
+                };
+
+                if !is_defined {
+                    return Err(SchemaBuildError::UnknownField {
+                        type_name: type_name.clone(),
+                        field_name: field_name.clone(),
+                    });
+                }
+            }
+        }
+
+        let schema = Arc::new(DynamicSchema {
+            types,
+            query_type: query.clone(),
+            resolvers: self.resolvers,
+        });
+        let info = |name: Option<String>, empty: &str| {
+            DynamicTypeInfo::new(
+                schema.clone(),
+                Type::NonNullNamed(Cow::Owned(name.unwrap_or_else(|| empty.to_owned()))),
+            )
+        };
+
+        let mut root_node = RootNode::new_with_info(
+            Dynamic::root(),
+            Dynamic::root(),
+            Dynamic::root(),
+            info(Some(query), ""),
+            info(mutation, "_EmptyMutation"),
+            info(subscription, "_EmptySubscription"),
+        );
+
+        for definition in &directives {
+            if let Some(directive) = build_directive(definition) {
+                root_node.schema.add_directive(directive);
+            }
+        }
+
+        Ok(root_node)
+    }
+}
+
+impl<CtxT, S> fmt::Debug for SchemaBuilder<CtxT, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SchemaBuilder")
+            .field("document", &self.document)
+            .finish()
+    }
+}
+
+fn extend_type<S>(
+    types: &mut FnvHashMap<String, TypeDefinition<S>>,
+    extension: TypeDefinition<S>,
+) -> Result<(), SchemaBuildError> {
+    let name = extension.name.item;
+    let definition = types
+        .get_mut(&name)
+        .ok_or_else(|| SchemaBuildError::UnknownType(name.clone()))?;
+
+    definition.directives.extend(extension.directives);
+
+    match (&mut definition.kind, extension.kind) {
+        (TypeDefinitionKind::Scalar, TypeDefinitionKind::Scalar) => {}
+        (
+            TypeDefinitionKind::Object { interfaces, fields },
+            TypeDefinitionKind::Object {
+                interfaces: new_interfaces,
+                fields: new_fields,
+            },
+        ) => {
+            interfaces.extend(new_interfaces);
+            fields.extend(new_fields);
+        }
+        (
+            TypeDefinitionKind::Interface { fields },
+            TypeDefinitionKind::Interface { fields: new_fields },
+        ) => fields.extend(new_fields),
+        (
+            TypeDefinitionKind::Union { members },
+            TypeDefinitionKind::Union {
+                members: new_members,
+            },
+        ) => members.extend(new_members),
+        (TypeDefinitionKind::Enum { values }, TypeDefinitionKind::Enum { values: new_values }) => {
+            values.extend(new_values)
+        }
+        (
+            TypeDefinitionKind::InputObject { fields },
+            TypeDefinitionKind::InputObject { fields: new_fields },
+        ) => fields.extend(new_fields),
+        _ => {
+            return Err(SchemaBuildError::InvalidTypeUsage {
+                type_name: name,
+                usage: "an extension of another kind of type".to_owned(),
+            })
+        }
+    }
+
+    Ok(())
+}
+
+fn root_type_names<S>(
+    definition: Option<SchemaDefinition<S>>,
+    types: &FnvHashMap<String, TypeDefinition<S>>,
+) -> Result<(String, Option<String>, Option<String>), SchemaBuildError> {
+    match definition {
+        Some(definition) => Ok((
+            definition
+                .query
+                .map(|q| q.item)
+                .ok_or(SchemaBuildError::MissingQueryType)?,
+            definition.mutation.map(|m| m.item),
+            definition.subscription.map(|s| s.item),
+        )),
+        None => {
+            let default_name = |name: &str| {
+                if types.contains_key(name) {
+                    Some(name.to_owned())
+                } else {
+                    None
+                }
+            };
+
+            Ok((
+                default_name("Query").ok_or(SchemaBuildError::MissingQueryType)?,
+                default_name("Mutation"),
+                default_name("Subscription"),
+            ))
+        }
+    }
+}
+
+fn validate_types<S>(
+    types: &FnvHashMap<String, TypeDefinition<S>>,
+) -> Result<(), SchemaBuildError> {
+    for (name, definition) in types {
+        match definition.kind {
+            TypeDefinitionKind::Scalar | TypeDefinitionKind::Enum { .. } => {}
+            TypeDefinitionKind::Object {
+                ref interfaces,
+                ref fields,
+            } => {
+                for interface in interfaces {
+                    let usage = format!("an interface of \"{}\"", name);
+                    expect_kind(types, &interface.item, is_interface, &usage)?;
+
+                    if let Some(TypeDefinitionKind::Interface {
+                        fields: ref interface_fields,
+                    }) = types.get(&interface.item).map(|t| &t.kind)
+                    {
+                        for interface_field in interface_fields {
+                            if !fields
+                                .iter()
+                                .any(|f| f.name.item == interface_field.name.item)
+                            {
+                                return Err(SchemaBuildError::MissingInterfaceField {
+                                    type_name: name.clone(),
+                                    interface: interface.item.clone(),
+                                    field_name: interface_field.name.item.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+
+                validate_fields(types, name, fields)?;
+            }
+            TypeDefinitionKind::Interface { ref fields } => validate_fields(types, name, fields)?,
+            TypeDefinitionKind::Union { ref members } => {
+                for member in members {
+                    let usage = format!("a member of union \"{}\"", name);
+                    expect_kind(types, &member.item, is_object, &usage)?;
+                }
+            }
+            TypeDefinitionKind::InputObject { ref fields } => {
+                validate_input_values(types, fields, name)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_fields<S>(
+    types: &FnvHashMap<String, TypeDefinition<S>>,
+    type_name: &str,
+    fields: &[crate::ast::FieldDefinition<S>],
+) -> Result<(), SchemaBuildError> {
+    for field in fields {
+        let owner = format!("{}.{}", type_name, field.name.item);
+        let usage = format!("the type of field \"{}\"", owner);
+        expect_kind(
+            types,
+            field.field_type.item.innermost_name(),
+            is_output,
+            &usage,
+        )?;
+        validate_input_values(types, &field.arguments, &owner)?;
+    }
+
+    Ok(())
+}
+
+fn validate_input_values<S>(
+    types: &FnvHashMap<String, TypeDefinition<S>>,
+    values: &[InputValueDefinition<S>],
+    owner: &str,
+) -> Result<(), SchemaBuildError> {
+    for value in values {
+        let usage = format!("the type of \"{}.{}\"", owner, value.name.item);
+        expect_kind(
+            types,
+            value.value_type.item.innermost_name(),
+            is_input,
+            &usage,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn expect_kind<S>(
+    types: &FnvHashMap<String, TypeDefinition<S>>,
+    type_name: &str,
+    is_expected_kind: fn(Option<&TypeDefinitionKind<S>>) -> bool,
+    usage: &str,
+) -> Result<(), SchemaBuildError> {
+    let kind = match types.get(type_name) {
+        Some(definition) => Some(&definition.kind),
+        None if BUILTIN_SCALARS.contains(&type_name) => None,
+        None => return Err(SchemaBuildError::UnknownType(type_name.to_owned())),
+    };
+
+    if is_expected_kind(kind) {
+        Ok(())
+    } else {
+        Err(SchemaBuildError::InvalidTypeUsage {
+            type_name: type_name.to_owned(),
+            usage: usage.to_owned(),
+        })
+    }
+}
+
+// The kind of builtin scalars is `None`.
+
+fn is_object<S>(kind: Option<&TypeDefinitionKind<S>>) -> bool {
+    matches!(kind, Some(TypeDefinitionKind::Object { .. }))
+}
+
+fn is_interface<S>(kind: Option<&TypeDefinitionKind<S>>) -> bool {
+    matches!(kind, Some(TypeDefinitionKind::Interface { .. }))
+}
+
+fn is_output<S>(kind: Option<&TypeDefinitionKind<S>>) -> bool {
+    !matches!(kind, Some(TypeDefinitionKind::InputObject { .. }))
+}
+
+fn is_input<S>(kind: Option<&TypeDefinitionKind<S>>) -> bool {
+    matches!(
+        kind,
+        None | Some(TypeDefinitionKind::Scalar)
+            | Some(TypeDefinitionKind::Enum { .. })
+            | Some(TypeDefinitionKind::InputObject { .. })
+    )
+}
+
+// Definitions of directives with locations that are not known to juniper, such
+// as `VARIABLE_DEFINITION`, are skipped.
+fn build_directive<'a, S>(definition: &DirectiveDefinition<S>) -> Option<DirectiveType<'a, S>>
+where
+    S: ScalarValue + 'a,
+{
+    let locations = definition
+        .locations
+        .iter()
+        .map(|l| match l.item.as_str() {
+            "QUERY" => Some(DirectiveLocation::Query),
+            "MUTATION" => Some(DirectiveLocation::Mutation),
+            "SUBSCRIPTION" => Some(DirectiveLocation::Subscription),
+            "FIELD" => Some(DirectiveLocation::Field),
+            "FRAGMENT_DEFINITION" => Some(DirectiveLocation::FragmentDefinition),
+            "FRAGMENT_SPREAD" => Some(DirectiveLocation::FragmentSpread),
+            "INLINE_FRAGMENT" => Some(DirectiveLocation::InlineFragment),
+            "SCHEMA" => Some(DirectiveLocation::Schema),
+            "SCALAR" => Some(DirectiveLocation::Scalar),
+            "OBJECT" => Some(DirectiveLocation::Object),
+            "FIELD_DEFINITION" => Some(DirectiveLocation::FieldDefinition),
+            "ARGUMENT_DEFINITION" => Some(DirectiveLocation::ArgumentDefinition),
+            "INTERFACE" => Some(DirectiveLocation::Interface),
+            "UNION" => Some(DirectiveLocation::Union),
+            "ENUM" => Some(DirectiveLocation::Enum),
+            "ENUM_VALUE" => Some(DirectiveLocation::EnumValue),
+            "INPUT_OBJECT" => Some(DirectiveLocation::InputObject),
+            "INPUT_FIELD_DEFINITION" => Some(DirectiveLocation::InputFieldDefinition),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let arguments = definition
+        .arguments
+        .iter()
+        .map(|a| {
+            let argument = Argument::new(&a.name.item, a.value_type.item.clone());
+            let argument = match a.description {
+                Some(ref d) => argument.description(d),
+                None => argument,
+            };
+            match a.default_value {
+                Some(ref v) => argument.default_value(v.item.clone()),
+                None => argument,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let directive = DirectiveType::new(&definition.name.item, &locations, &arguments);
+    Some(match definition.description {
+        Some(ref d) => directive.description(d),
+        None => directive,
+    })
+}
+
+impl fmt::Display for SchemaBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SchemaBuildError::DuplicateType(ref name) => {
+                write!(f, "Type \"{}\" is defined more than once", name)
+            }
+            SchemaBuildError::DuplicateSchemaDefinition => {
+                write!(f, "The schema is defined more than once")
+            }
+            SchemaBuildError::UnknownType(ref name) => write!(f, "Unknown type \"{}\"", name),
+            SchemaBuildError::InvalidTypeUsage {
+                ref type_name,
+                ref usage,
+            } => write!(f, "Type \"{}\" cannot be used as {}", type_name, usage),
+            SchemaBuildError::MissingQueryType => write!(f, "The schema has no query type"),
+            SchemaBuildError::MissingInterfaceField {
+                ref type_name,
+                ref interface,
+                ref field_name,
+            } => write!(
+                f,
+                "Type \"{}\" lacks the field \"{}\" of interface \"{}\"",
+                type_name, field_name, interface
+            ),
+            SchemaBuildError::UnknownField {
+                ref type_name,
+                ref field_name,
+            } => write!(
+                f,
+                "A resolver was registered for unknown field \"{}.{}\"",
+                type_name, field_name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaBuildError {}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    ast::{Document, Field, Operation},
+    executor::Variables,
+    parser::{SourcePosition, Spanning},
+    schema::model::RootNode,
+    tests::{model::Database, schema::Query},
+    types::scalars::{EmptyMutation, EmptySubscription},
+    validation::{RuleError, ValidationRule, ValidatorContext, Visitor},
+    value::ScalarValue,
+    GraphQLError,
+};
+
+#[derive(Clone)]
+struct NoAnonymousOperations;
+
+impl<'a, S> Visitor<'a, S> for NoAnonymousOperations
+where
+    S: ScalarValue,
+{
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        if op.item.name.is_none() {
+            ctx.report_error("Operations must be named", &[op.start]);
+        }
+    }
+}
+
+struct NoIntrospection;
+
+struct NoIntrospectionVisitor<'a> {
+    fields: Vec<Spanning<&'a str>>,
+}
+
+impl<S> ValidationRule<S> for NoIntrospection
+where
+    S: ScalarValue,
+{
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a,
+    {
+        Box::new(NoIntrospectionVisitor { fields: Vec::new() })
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for NoIntrospectionVisitor<'a>
+where
+    S: ScalarValue,
+{
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        let is_root = ctx
+            .parent_type()
+            .and_then(|t| t.name())
+            .map(|n| Some(n) == ctx.schema.concrete_query_type().name())
+            .unwrap_or(false);
+
+        if is_root && (field.item.name.item == "__schema" || field.item.name.item == "__type") {
+            self.fields.push(field.item.name.clone());
+        }
+    }
+
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {
+        for field in &self.fields {
+            ctx.report_error(
+                &format!("Introspection with \"{}\" is disabled", field.item),
+                &[field.start],
+            );
+        }
+    }
+}
+
+// Records whether the built-in rules reported errors before it ran.
+#[derive(Clone, Default)]
+struct SeesPriorErrors(Arc<Mutex<Vec<bool>>>);
+
+impl<'a, S> Visitor<'a, S> for SeesPriorErrors
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {
+        self.0.lock().unwrap().push(ctx.has_errors());
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<Database>, EmptySubscription<Database>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+    .with_validation_rule(NoAnonymousOperations)
+    .with_validation_rule(NoIntrospection)
+}
+
+fn validation_errors(query: &str) -> Vec<RuleError> {
+    let schema = schema();
+    let database = Database::new();
+
+    match crate::execute_sync(query, None, &schema, &Variables::new(), &database) {
+        Ok(_) => Vec::new(),
+        Err(GraphQLError::ValidationError(errors)) => errors,
+        Err(e) => panic!("Unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn passes_custom_rules() {
+    assert_eq!(
+        validation_errors("query Hero { hero { name __typename } }"),
+        vec![]
+    );
+}
+
+#[test]
+fn reports_errors_of_custom_rules() {
+    assert_eq!(
+        validation_errors("{ hero { name } __schema { types { name } } }"),
+        vec![
+            RuleError::new("Operations must be named", &[SourcePosition::new(0, 0, 0)]),
+            RuleError::new(
+                "Introspection with \"__schema\" is disabled",
+                &[SourcePosition::new(16, 0, 16)],
+            ),
+        ]
+    );
+}
+
+#[test]
+fn reports_errors_of_built_in_and_custom_rules() {
+    assert_eq!(
+        validation_errors("{ unknown }"),
+        vec![
+            RuleError::new("Operations must be named", &[SourcePosition::new(0, 0, 0)]),
+            RuleError::new(
+                "Unknown field \"unknown\" on type \"Query\"",
+                &[SourcePosition::new(2, 0, 2)],
+            ),
+        ]
+    );
+}
+
+#[test]
+fn custom_rules_run_after_built_in_rules() {
+    let seen = SeesPriorErrors::default();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+    .with_validation_rule(seen.clone());
+    let database = Database::new();
+
+    for query in &["query Hero { hero { name } }", "{ unknown }"] {
+        let _ = crate::execute_sync(query, None, &schema, &Variables::new(), &database);
+    }
+
+    assert_eq!(*seen.0.lock().unwrap(), vec![false, true]);
+}
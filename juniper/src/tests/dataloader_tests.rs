@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::FutureExt;
+
+use crate::{
+    dataloader::{BatchFn, DataLoader},
+    executor::{FieldResult, Variables},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::Value,
+    BoxFuture,
+};
+
+#[derive(Clone, Default)]
+struct UserBatch {
+    batches: Arc<Mutex<Vec<Vec<i32>>>>,
+}
+
+impl BatchFn<i32, String> for UserBatch {
+    type Error = String;
+
+    fn load(&self, mut keys: Vec<i32>) -> BoxFuture<'static, Result<HashMap<i32, String>, String>> {
+        keys.sort();
+        self.batches.lock().unwrap().push(keys.clone());
+
+        Box::pin(async move {
+            Ok(keys
+                .into_iter()
+                .map(|id| (id, format!("User {}", id)))
+                .collect())
+        })
+    }
+}
+
+struct Context {
+    users: DataLoader<i32, String, UserBatch>,
+}
+
+impl crate::Context for Context {}
+
+struct Post {
+    id: i32,
+    author_id: i32,
+}
+
+#[crate::graphql_object_internal(Context = Context)]
+impl Post {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    async fn author(&self, context: &Context) -> FieldResult<Option<String>> {
+        Ok(context.users.load(self.author_id).await?)
+    }
+
+    async fn reviewers(&self, context: &Context) -> FieldResult<Vec<Option<String>>> {
+        Ok(context
+            .users
+            .load_many(vec![self.author_id + 10, self.author_id + 20])
+            .await?)
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Context)]
+impl Query {
+    fn posts() -> Vec<Post> {
+        (1..=4)
+            .map(|id| Post {
+                id,
+                author_id: id % 3,
+            })
+            .collect()
+    }
+
+    fn all_posts() -> Vec<Post> {
+        (1..=100).map(|id| Post { id, author_id: id }).collect()
+    }
+
+    async fn admin(context: &Context) -> FieldResult<Option<String>> {
+        Ok(context.users.load(0).await?)
+    }
+
+    async fn busy() -> bool {
+        let () = tokio::task::yield_now().await;
+        true
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<Context>, EmptySubscription<Context>> {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+async fn run(query: &str) -> (Value, Vec<Vec<i32>>) {
+    let batch_fn = UserBatch::default();
+    let context = Context {
+        users: DataLoader::new(batch_fn.clone()),
+    };
+
+    let (result, errs) = crate::execute(query, None, &schema(), &Variables::new(), &context)
+        .await
+        .expect("Execution failed");
+    assert_eq!(errs, vec![]);
+
+    let batches = batch_fn.batches.lock().unwrap().clone();
+    (result, batches)
+}
+
+#[tokio::test]
+async fn batches_loads_of_list_items() {
+    let (result, batches) = run("{ posts { id author } }").await;
+
+    assert_eq!(batches, vec![vec![0, 1, 2]]);
+    assert_eq!(
+        result
+            .as_object_value()
+            .and_then(|o| o.get_field_value("posts"))
+            .and_then(|p| p.as_list_value())
+            .map(|posts| posts
+                .iter()
+                .map(|p| p
+                    .as_object_value()
+                    .and_then(|p| p.get_field_value("author"))
+                    .and_then(|a| a.as_scalar_value::<String>())
+                    .cloned()
+                    .unwrap())
+                .collect::<Vec<_>>()),
+        Some(vec![
+            "User 1".to_owned(),
+            "User 2".to_owned(),
+            "User 0".to_owned(),
+            "User 1".to_owned(),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn batches_loads_of_sibling_fields() {
+    let (_, batches) = run("{ admin posts { author reviewers } }").await;
+
+    assert_eq!(batches, vec![vec![0, 1, 2, 10, 11, 12, 20, 21, 22]]);
+}
+
+#[tokio::test]
+async fn loads_cached_keys_once() {
+    let (_, batches) = run("{ admin posts { author } more: posts { author } }").await;
+
+    assert_eq!(batches, vec![vec![0, 1, 2]]);
+}
+
+#[tokio::test]
+async fn batches_loads_of_long_lists() {
+    let (_, batches) = run("{ allPosts { author reviewers } }").await;
+
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0], (1..=120).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn dispatches_after_a_dropped_request() {
+    let batch_fn = UserBatch::default();
+    let context = Context {
+        users: DataLoader::new(batch_fn.clone()),
+    };
+    let schema = schema();
+
+    // `busy` keeps the executor from dispatching the key of `admin` during
+    // the first poll, after which the request is dropped.
+    let dropped =
+        crate::execute("{ admin busy }", None, &schema, &Variables::new(), &context).now_or_never();
+    assert!(dropped.is_none());
+    assert_eq!(*batch_fn.batches.lock().unwrap(), Vec::<Vec<i32>>::new());
+
+    let (result, errs) = tokio::time::timeout(
+        Duration::from_secs(5),
+        crate::execute("{ admin }", None, &schema, &Variables::new(), &context),
+    )
+    .await
+    .expect("The load was never dispatched")
+    .expect("Execution failed");
+
+    assert_eq!(errs, vec![]);
+    assert_eq!(result, graphql_value!({ "admin": "User 0" }));
+    assert_eq!(*batch_fn.batches.lock().unwrap(), vec![vec![0]]);
+}
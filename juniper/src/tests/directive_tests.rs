@@ -0,0 +1,289 @@
+use crate::{
+    executor::{
+        ExecutionError, ExecutionResult, Executor, FieldError, FieldResult, Registry, Variables,
+    },
+    parser::SourcePosition,
+    schema::model::{DirectiveLocation, DirectiveType, RootNode},
+    types::{
+        base::Arguments,
+        directives::ExecutableDirective,
+        scalars::{EmptyMutation, EmptySubscription},
+    },
+    validation::RuleError,
+    value::{DefaultScalarValue, Object, Value},
+    GraphQLError,
+};
+
+struct Context {
+    role: Option<String>,
+}
+
+impl crate::Context for Context {}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Context)]
+impl Query {
+    fn greeting() -> &'static str {
+        "hello"
+    }
+
+    fn secret() -> Option<&'static str> {
+        Some("s3cr3t")
+    }
+
+    fn role(context: &Context) -> Option<&str> {
+        context.role.as_deref()
+    }
+
+    fn planet() -> Planet {
+        Planet
+    }
+}
+
+// Objects without a context are resolved with a replaced context.
+struct Planet;
+
+#[crate::graphql_object_internal]
+impl Planet {
+    fn name() -> &'static str {
+        "Tatooine"
+    }
+
+    fn coordinates() -> Option<&'static str> {
+        Some("R5-D4")
+    }
+}
+
+struct Uppercase;
+
+impl ExecutableDirective<Context> for Uppercase {
+    fn meta<'r>(&self, _: &mut Registry<'r>) -> DirectiveType<'r, DefaultScalarValue> {
+        DirectiveType::new("uppercase", &[DirectiveLocation::Field], &[])
+            .description("Converts a string to upper case")
+    }
+
+    fn after_field(&self, _: &Arguments, _: &Executor<Context>, value: Value) -> ExecutionResult {
+        Ok(match value.as_string_value() {
+            Some(s) => Value::scalar(s.to_uppercase()),
+            None => value,
+        })
+    }
+}
+
+struct Auth;
+
+impl ExecutableDirective<Context> for Auth {
+    fn meta<'r>(&self, registry: &mut Registry<'r>) -> DirectiveType<'r, DefaultScalarValue> {
+        DirectiveType::new(
+            "auth",
+            &[DirectiveLocation::Field],
+            &[registry.arg_with_default("role", &"admin".to_owned(), &())],
+        )
+    }
+
+    fn before_field(&self, args: &Arguments, executor: &Executor<Context>) -> FieldResult<()> {
+        let role = args.get::<String>("role").unwrap();
+
+        if executor.context().role.as_ref() == Some(&role) {
+            Ok(())
+        } else {
+            Err(FieldError::new(
+                format!("Requires the {} role", role),
+                Value::null(),
+            ))
+        }
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<Context>, EmptySubscription<Context>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Context>::new(),
+        EmptySubscription::<Context>::new(),
+    )
+    .with_directive(Uppercase)
+    .with_directive(Auth)
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::object(fields.into_iter().collect::<Object<_>>())
+}
+
+fn run_query(
+    query: &str,
+    vars: &Variables,
+    role: Option<&str>,
+) -> (Value, Vec<ExecutionError<DefaultScalarValue>>) {
+    let schema = schema();
+    let context = Context {
+        role: role.map(|r| r.to_owned()),
+    };
+
+    crate::execute_sync(query, None, &schema, vars, &context).expect("Execution failed")
+}
+
+#[test]
+fn transforms_field_values() {
+    assert_eq!(
+        run_query(
+            "{ greeting loud: greeting @uppercase }",
+            &Variables::new(),
+            None
+        ),
+        (
+            object(vec![
+                ("greeting", Value::scalar("hello")),
+                ("loud", Value::scalar("HELLO")),
+            ]),
+            vec![],
+        )
+    );
+}
+
+#[test]
+fn guards_field_resolution() {
+    let (result, errs) = run_query("{ secret @auth }", &Variables::new(), Some("user"));
+
+    assert_eq!(result, object(vec![("secret", Value::null())]));
+    assert_eq!(
+        errs,
+        vec![ExecutionError::new(
+            SourcePosition::new(2, 0, 2),
+            &["secret"],
+            FieldError::new("Requires the admin role", Value::null()),
+        )]
+    );
+
+    assert_eq!(
+        run_query("{ secret @auth }", &Variables::new(), Some("admin")),
+        (object(vec![("secret", Value::scalar("s3cr3t"))]), vec![])
+    );
+}
+
+#[test]
+fn resolves_directive_arguments_from_variables() {
+    let mut vars = Variables::new();
+    vars.insert("role".to_owned(), crate::InputValue::scalar("user"));
+
+    assert_eq!(
+        run_query(
+            "query ($role: String) { role @auth(role: $role) @uppercase }",
+            &vars,
+            Some("user"),
+        ),
+        (object(vec![("role", Value::scalar("USER"))]), vec![])
+    );
+}
+
+#[test]
+fn applies_below_fields_with_another_context() {
+    let (result, errs) = run_query(
+        "{ planet { name @uppercase coordinates @auth } }",
+        &Variables::new(),
+        Some("user"),
+    );
+
+    assert_eq!(
+        result,
+        object(vec![(
+            "planet",
+            object(vec![
+                ("name", Value::scalar("TATOOINE")),
+                ("coordinates", Value::null()),
+            ]),
+        )])
+    );
+    assert_eq!(
+        errs,
+        vec![ExecutionError::new(
+            SourcePosition::new(27, 0, 27),
+            &["planet", "coordinates"],
+            FieldError::new("Requires the admin role", Value::null()),
+        )]
+    );
+
+    assert_eq!(
+        run_query(
+            "{ planet { coordinates @auth } }",
+            &Variables::new(),
+            Some("admin"),
+        ),
+        (
+            object(vec![(
+                "planet",
+                object(vec![("coordinates", Value::scalar("R5-D4"))]),
+            )]),
+            vec![],
+        )
+    );
+}
+
+#[tokio::test]
+async fn applies_in_async_executor() {
+    let schema = schema();
+    let context = Context { role: None };
+
+    assert_eq!(
+        crate::execute(
+            "{ loud: greeting @uppercase secret @auth }",
+            None,
+            &schema,
+            &Variables::new(),
+            &context,
+        )
+        .await,
+        Ok((
+            object(vec![
+                ("loud", Value::scalar("HELLO")),
+                ("secret", Value::null()),
+            ]),
+            vec![ExecutionError::new(
+                SourcePosition::new(28, 0, 28),
+                &["secret"],
+                FieldError::new("Requires the admin role", Value::null()),
+            )],
+        ))
+    );
+}
+
+#[test]
+fn known_directives_accepts_registered_directives() {
+    let schema = schema();
+    let context = Context { role: None };
+
+    assert_eq!(
+        crate::execute_sync(
+            "{ greeting @lowercase }",
+            None,
+            &schema,
+            &Variables::new(),
+            &context,
+        ),
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Unknown directive \"lowercase\"",
+            &[SourcePosition::new(11, 0, 11)],
+        )]))
+    );
+    assert_eq!(
+        crate::execute_sync(
+            "query @uppercase { greeting }",
+            None,
+            &schema,
+            &Variables::new(),
+            &context,
+        ),
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Directive \"uppercase\" may not be used on query",
+            &[SourcePosition::new(6, 0, 6)],
+        )]))
+    );
+}
+
+#[test]
+fn directives_are_part_of_the_schema() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("\"Converts a string to upper case\"\ndirective @uppercase on FIELD"));
+    assert!(sdl.contains("directive @auth(role: String = \"admin\") on FIELD"));
+}
@@ -0,0 +1,150 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    ast::{Document, InputValue},
+    executor::Variables,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    validation::{ValidatorContext, Visitor},
+    value::{ScalarValue, Value},
+    GraphQLError,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn hello() -> &'static str {
+        "world"
+    }
+
+    fn echo(value: i32) -> i32 {
+        value
+    }
+}
+
+#[derive(Clone, Default)]
+struct CountValidations(Arc<AtomicUsize>);
+
+impl<'a, S> Visitor<'a, S> for CountValidations
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>>;
+
+fn schema(capacity: usize) -> (Schema, Arc<AtomicUsize>) {
+    let counter = CountValidations::default();
+    let validations = Arc::clone(&counter.0);
+    let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+        .with_validation_rule(counter)
+        .with_document_cache(capacity);
+    (schema, validations)
+}
+
+fn hello() -> Value {
+    Value::object(
+        vec![("hello", Value::scalar("world"))]
+            .into_iter()
+            .collect(),
+    )
+}
+
+#[test]
+fn repeated_queries_are_validated_once() {
+    let (schema, validations) = schema(10);
+
+    for _ in 0..3 {
+        let (res, errs) =
+            crate::execute_sync("{ hello }", None, &schema, &Variables::new(), &()).unwrap();
+        assert!(errs.is_empty());
+        assert_eq!(res, hello());
+    }
+
+    assert_eq!(validations.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn repeated_queries_are_validated_once_asynchronously() {
+    let (schema, validations) = schema(10);
+
+    for _ in 0..3 {
+        let (res, errs) = crate::execute("{ hello }", None, &schema, &Variables::new(), &())
+            .await
+            .unwrap();
+        assert!(errs.is_empty());
+        assert_eq!(res, hello());
+    }
+
+    assert_eq!(validations.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn queries_are_validated_every_time_without_cache() {
+    let (schema, validations) = schema(0);
+
+    for _ in 0..3 {
+        crate::execute_sync("{ hello }", None, &schema, &Variables::new(), &()).unwrap();
+    }
+
+    assert_eq!(validations.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn variables_are_validated_on_cache_hits() {
+    let (schema, _) = schema(10);
+    let query = "query Q($value: Int!) { echo(value: $value) }";
+
+    let mut vars = Variables::new();
+    vars.insert("value".to_owned(), InputValue::scalar(42));
+    let (res, _) = crate::execute_sync(query, None, &schema, &vars, &()).unwrap();
+    assert_eq!(
+        res,
+        Value::object(vec![("echo", Value::scalar(42))].into_iter().collect()),
+    );
+
+    let mut vars = Variables::new();
+    vars.insert("value".to_owned(), InputValue::scalar("42"));
+    assert!(matches!(
+        crate::execute_sync(query, None, &schema, &vars, &()),
+        Err(GraphQLError::ValidationError(_)),
+    ));
+}
+
+#[test]
+fn invalid_queries_are_not_cached() {
+    let (schema, validations) = schema(10);
+
+    for _ in 0..2 {
+        assert!(matches!(
+            crate::execute_sync("{ goodbye }", None, &schema, &Variables::new(), &()),
+            Err(GraphQLError::ValidationError(_)),
+        ));
+    }
+
+    assert_eq!(validations.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    let (schema, validations) = schema(10);
+
+    for _ in 0..2 {
+        match crate::execute_sync("{ hello", None, &schema, &Variables::new(), &()) {
+            Err(GraphQLError::ValidationError(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].message(), "Unexpected end of input");
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    assert_eq!(validations.load(Ordering::SeqCst), 0);
+}
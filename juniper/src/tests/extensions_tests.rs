@@ -0,0 +1,252 @@
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt};
+use serde_json::{self, json};
+
+use crate::{
+    executor::{ExecutionResult, Executor, Extensions, Registry, Variables},
+    http::{GraphQLRequest, GraphQLResponse},
+    schema::model::{DirectiveLocation, DirectiveType, RootNode},
+    types::{base::Arguments, directives::ExecutableDirective, scalars::EmptyMutation},
+    value::{DefaultScalarValue, Value},
+    InputValue,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn hello(executor: &Executor) -> &str {
+        executor
+            .extensions()
+            .insert("cacheControl", Value::scalar("public"));
+        "world"
+    }
+
+    fn client(executor: &Executor) -> Option<String> {
+        executor
+            .extensions()
+            .request_entry("client")
+            .and_then(|v| v.as_string_value())
+            .map(|s| s.to_owned())
+    }
+
+    fn expensive(executor: &Executor) -> i32 {
+        executor.extensions().update("cost", |cost| {
+            let cost = cost.as_ref().and_then(|c| c.as_scalar_value::<i32>());
+            Value::scalar(cost.copied().unwrap_or(0) + 10)
+        });
+        42
+    }
+}
+
+struct Traced;
+
+impl ExecutableDirective<()> for Traced {
+    fn meta<'r>(&self, _: &mut Registry<'r>) -> DirectiveType<'r, DefaultScalarValue> {
+        DirectiveType::new("traced", &[DirectiveLocation::Field], &[])
+    }
+
+    fn after_field(&self, _: &Arguments, executor: &Executor<()>, value: Value) -> ExecutionResult {
+        executor.extensions().update("traced", |traced| {
+            let mut list = match traced {
+                Some(Value::List(list)) => list,
+                _ => vec![],
+            };
+            list.push(value.clone());
+            Value::list(list)
+        });
+        Ok(value)
+    }
+}
+
+struct Subscription;
+
+type QueryStream = Pin<Box<dyn Stream<Item = Query> + Send>>;
+
+#[crate::graphql_subscription_internal]
+impl Subscription {
+    async fn queries(executor: &Executor) -> QueryStream {
+        let client = executor
+            .extensions()
+            .request_entry("client")
+            .and_then(|v| v.as_string_value())
+            .map(|s| s.to_owned());
+
+        Box::pin(futures::stream::iter(client.map(|_| Query)))
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<()>, Subscription> {
+    RootNode::new(Query, EmptyMutation::new(), Subscription).with_directive(Traced)
+}
+
+#[test]
+fn request_extensions_are_deserialized() {
+    let request: GraphQLRequest = serde_json::from_value(json!({
+        "query": "{ client }",
+        "extensions": { "client": "ios" },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        request.extensions(),
+        Some(&InputValue::object(
+            vec![("client", InputValue::scalar("ios"))]
+                .into_iter()
+                .collect()
+        )),
+    );
+
+    let without: GraphQLRequest = serde_json::from_value(json!({ "query": "{ client }" })).unwrap();
+    assert_eq!(without.extensions(), None);
+    assert_eq!(
+        serde_json::to_value(&without).unwrap(),
+        json!({ "query": "{ client }", "operationName": null, "variables": null }),
+    );
+}
+
+#[test]
+fn request_extensions_are_available_to_resolvers() {
+    let schema = schema();
+    let request = GraphQLRequest::<DefaultScalarValue>::new("{ client }".into(), None, None)
+        .with_extensions(Some(InputValue::object(
+            vec![("client", InputValue::scalar("ios"))]
+                .into_iter()
+                .collect(),
+        )));
+
+    let response = request.execute_sync(&schema, &());
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({ "data": { "client": "ios" } }),
+    );
+}
+
+#[test]
+fn resolvers_write_response_extensions() {
+    let schema = schema();
+    let request = GraphQLRequest::new("{ hello a: expensive b: expensive }".into(), None, None);
+
+    let response = request.execute_sync(&schema, &());
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({
+            "data": { "hello": "world", "a": 42, "b": 42 },
+            "extensions": { "cacheControl": "public", "cost": 20 },
+        }),
+    );
+}
+
+#[tokio::test]
+async fn directives_write_response_extensions() {
+    let schema = schema();
+    let request = GraphQLRequest::new("{ hello @traced client @traced }".into(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({
+            "data": { "hello": "world", "client": null },
+            "extensions": { "cacheControl": "public", "traced": ["world", null] },
+        }),
+    );
+}
+
+#[test]
+fn extensions_are_serialized_with_errors() {
+    let response = GraphQLResponse::<DefaultScalarValue>::from_result(Err(
+        crate::GraphQLError::UnknownOperationName,
+    ))
+    .with_extensions(vec![("cost", Value::scalar(1))].into_iter().collect());
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({
+            "errors": [{ "message": "Unknown operation" }],
+            "extensions": { "cost": 1 },
+        }),
+    );
+}
+
+#[test]
+fn top_level_execution_collects_extensions() {
+    let schema = schema();
+    let extensions = Extensions::default();
+
+    let (res, errs) = crate::execute_sync_with_extensions(
+        "{ hello }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+        &extensions,
+    )
+    .unwrap();
+
+    assert!(errs.is_empty());
+    assert_eq!(
+        res,
+        Value::object(
+            vec![("hello", Value::scalar("world"))]
+                .into_iter()
+                .collect()
+        ),
+    );
+    assert_eq!(
+        extensions.into_response(),
+        vec![("cacheControl", Value::scalar("public"))]
+            .into_iter()
+            .collect(),
+    );
+}
+
+#[tokio::test]
+async fn subscriptions_share_extensions() {
+    let schema = schema();
+    let extensions = Extensions::new(Some(InputValue::object(
+        vec![("client", InputValue::scalar("ios"))]
+            .into_iter()
+            .collect(),
+    )));
+
+    let (res, errs) = crate::resolve_into_stream_with_extensions(
+        "subscription { queries { client expensive } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+        &extensions,
+    )
+    .await
+    .unwrap();
+    assert!(errs.is_empty());
+
+    let items = match res {
+        Value::Object(o) => o.into_iter().next().unwrap().1,
+        _ => panic!("Expected an object"),
+    };
+    let items = match items {
+        Value::Scalar(stream) => stream.collect::<Vec<_>>().await,
+        _ => panic!("Expected a stream"),
+    };
+
+    assert_eq!(
+        items,
+        vec![Ok(Value::object(
+            vec![
+                ("client", Value::scalar("ios")),
+                ("expensive", Value::scalar(42)),
+            ]
+            .into_iter()
+            .collect(),
+        ))],
+    );
+    assert_eq!(
+        extensions.into_response(),
+        vec![("cost", Value::scalar(10))].into_iter().collect(),
+    );
+}
@@ -0,0 +1,440 @@
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::{ExecutionError, FieldError, FieldResult, Variables},
+    federation::{Entities, Entity, Representation},
+    parser::SourcePosition,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::Value,
+    BoxFuture, InputValue,
+};
+
+struct Context;
+
+impl crate::Context for Context {}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Context, directive(key(fields = "id")))]
+struct User {
+    id: String,
+    name: String,
+}
+
+impl Entity for User {
+    fn resolve_reference(
+        representation: &Representation,
+        _: &Context,
+    ) -> FieldResult<Option<User>> {
+        let id = representation
+            .get::<String>("id")
+            .ok_or_else(|| FieldError::new("Missing id", Value::null()))?;
+
+        Ok(if id == "0" {
+            None
+        } else {
+            Some(User {
+                name: format!("User {}", id),
+                id,
+            })
+        })
+    }
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Context, directive(key(fields = "upc"), extends))]
+struct Product {
+    #[graphql(directive(external))]
+    upc: String,
+    #[graphql(directive(requires(fields = "upc")))]
+    in_stock: bool,
+}
+
+impl Entity for Product {
+    fn resolve_reference(
+        representation: &Representation,
+        _: &Context,
+    ) -> FieldResult<Option<Product>> {
+        Ok(representation.get::<String>("upc").map(|upc| Product {
+            upc,
+            in_stock: true,
+        }))
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Context)]
+impl Query {
+    fn me() -> User {
+        User {
+            id: "1".to_owned(),
+            name: "Me".to_owned(),
+        }
+    }
+}
+
+fn schema<'a>() -> RootNode<
+    'a,
+    crate::federation::Subgraph<Query>,
+    EmptyMutation<Context>,
+    EmptySubscription<Context>,
+> {
+    RootNode::new_subgraph(
+        Query,
+        EmptyMutation::new(),
+        EmptySubscription::new(),
+        Entities::new().entity::<User>().entity::<Product>(),
+    )
+}
+
+fn representations(representations: Vec<Vec<(&str, &str)>>) -> Variables {
+    let mut vars = Variables::new();
+    vars.insert(
+        "representations".to_owned(),
+        InputValue::list(
+            representations
+                .into_iter()
+                .map(|fields| {
+                    InputValue::object(
+                        fields
+                            .into_iter()
+                            .map(|(k, v)| (k, InputValue::scalar(v)))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        ),
+    );
+    vars
+}
+
+const ENTITIES_QUERY: &str = r#"
+    query ($representations: [_Any!]!) {
+        _entities(representations: $representations) {
+            __typename
+            ... on User { id name }
+            ... on Product { upc inStock }
+        }
+    }
+"#;
+
+fn user(id: &str) -> Value {
+    Value::object(
+        vec![
+            ("__typename", Value::scalar("User")),
+            ("id", Value::scalar(id)),
+            ("name", Value::scalar(format!("User {}", id))),
+        ]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn product(upc: &str) -> Value {
+    Value::object(
+        vec![
+            ("__typename", Value::scalar("Product")),
+            ("upc", Value::scalar(upc)),
+            ("inStock", Value::scalar(true)),
+        ]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn entities(entities: Vec<Value>) -> Value {
+    Value::object(
+        vec![("_entities", Value::list(entities))]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn service_sdl(result: &Value) -> &str {
+    result
+        .as_object_value()
+        .and_then(|o| o.get_field_value("_service"))
+        .and_then(|s| s.as_object_value())
+        .and_then(|s| s.get_field_value("sdl"))
+        .and_then(|s| s.as_scalar_value::<String>())
+        .expect("sdl is a string")
+}
+
+#[test]
+fn service_sdl_excludes_federation_types() {
+    let schema = schema();
+    let (result, errs) = crate::execute_sync(
+        "{ _service { sdl } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &Context,
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errs, vec![]);
+    assert_eq!(
+        service_sdl(&result),
+        r#"type Product @key(fields: "upc") @extends {
+  upc: String! @external
+  inStock: Boolean! @requires(fields: "upc")
+}
+
+type Query {
+  me: User!
+}
+
+type User @key(fields: "id") {
+  id: String!
+  name: String!
+}
+"#
+    );
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Context)]
+struct Setting {
+    key: String,
+    provides: String,
+}
+
+struct SettingsQuery;
+
+#[crate::graphql_object_internal(name = "Query", Context = Context)]
+impl SettingsQuery {
+    fn setting() -> Setting {
+        Setting {
+            key: "theme".to_owned(),
+            provides: "dark".to_owned(),
+        }
+    }
+
+    fn key() -> String {
+        "theme".to_owned()
+    }
+}
+
+#[test]
+fn service_sdl_keeps_fields_named_like_federation_directives() {
+    let schema = RootNode::new_subgraph(
+        SettingsQuery,
+        EmptyMutation::<Context>::new(),
+        EmptySubscription::<Context>::new(),
+        Entities::new(),
+    );
+    let (result, errs) = crate::execute_sync(
+        "{ _service { sdl } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &Context,
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errs, vec![]);
+    assert_eq!(
+        service_sdl(&result),
+        r#"type Query {
+  setting: Setting!
+  key: String!
+}
+
+type Setting {
+  key: String!
+  provides: String!
+}
+"#
+    );
+}
+
+#[test]
+fn schema_contains_federation_types() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("directive @key(fields: String!) on OBJECT | INTERFACE"));
+    assert!(sdl.contains("scalar _Any"));
+    assert!(sdl.contains("union _Entity = User | Product"));
+    assert!(
+        sdl.contains("  _service: _Service!\n  _entities(representations: [_Any!]!): [_Entity]!")
+    );
+}
+
+#[test]
+fn resolves_entities() {
+    assert_eq!(
+        crate::execute_sync(
+            ENTITIES_QUERY,
+            None,
+            &schema(),
+            &representations(vec![
+                vec![("__typename", "Product"), ("upc", "p-1")],
+                vec![("__typename", "User"), ("id", "2")],
+                vec![("__typename", "User"), ("id", "0")],
+            ]),
+            &Context,
+        ),
+        Ok((
+            entities(vec![product("p-1"), user("2"), Value::null()]),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn resolves_entities_from_literals() {
+    assert_eq!(
+        crate::execute_sync(
+            r#"{
+                _entities(representations: [{ __typename: "User", id: "3" }]) {
+                    ... on User { __typename id name }
+                }
+            }"#,
+            None,
+            &schema(),
+            &Variables::new(),
+            &Context,
+        ),
+        Ok((entities(vec![user("3")]), vec![]))
+    );
+}
+
+#[test]
+fn reports_unknown_entity_types() {
+    assert_eq!(
+        crate::execute_sync(
+            ENTITIES_QUERY,
+            None,
+            &schema(),
+            &representations(vec![vec![("__typename", "Review"), ("id", "1")]]),
+            &Context,
+        ),
+        Ok((
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(50, 2, 8),
+                &["_entities"],
+                FieldError::new("Unknown entity type \"Review\"", Value::null()),
+            )]
+        ))
+    );
+}
+
+#[tokio::test]
+async fn resolves_entities_in_async_executor() {
+    assert_eq!(
+        crate::execute(
+            ENTITIES_QUERY,
+            None,
+            &schema(),
+            &representations(vec![
+                vec![("__typename", "User"), ("id", "4")],
+                vec![("__typename", "Product"), ("upc", "p-2")],
+            ]),
+            &Context,
+        )
+        .await,
+        Ok((entities(vec![user("4"), product("p-2")]), vec![]))
+    );
+}
+
+#[test]
+fn rejects_representations_without_typename() {
+    assert!(crate::execute_sync(
+        ENTITIES_QUERY,
+        None,
+        &schema(),
+        &representations(vec![vec![("id", "1")]]),
+        &Context,
+    )
+    .is_err());
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Context, directive(key(fields = "id")))]
+struct Warehouse {
+    id: String,
+}
+
+impl Entity for Warehouse {
+    fn resolve_reference_async<'a>(
+        representation: &'a Representation,
+        _: &'a Context,
+    ) -> BoxFuture<'a, FieldResult<Option<Warehouse>>> {
+        Box::pin(async move {
+            let id = futures::future::ready(representation.get::<String>("id")).await;
+            Ok(id.map(|id| Warehouse { id }))
+        })
+    }
+}
+
+fn warehouse_schema<'a>() -> RootNode<
+    'a,
+    crate::federation::Subgraph<Query>,
+    EmptyMutation<Context>,
+    EmptySubscription<Context>,
+> {
+    RootNode::new_subgraph(
+        Query,
+        EmptyMutation::new(),
+        EmptySubscription::new(),
+        Entities::new().entity::<User>().entity::<Warehouse>(),
+    )
+}
+
+const WAREHOUSES_QUERY: &str = r#"
+    query ($representations: [_Any!]!) {
+        _entities(representations: $representations) {
+            ... on User { id }
+            ... on Warehouse { id }
+        }
+    }
+"#;
+
+fn id(id: &str) -> Value {
+    Value::object(vec![("id", Value::scalar(id))].into_iter().collect())
+}
+
+#[tokio::test]
+async fn resolves_entities_asynchronously() {
+    assert_eq!(
+        crate::execute(
+            WAREHOUSES_QUERY,
+            None,
+            &warehouse_schema(),
+            &representations(vec![
+                vec![("__typename", "Warehouse"), ("id", "w-1")],
+                vec![("__typename", "User"), ("id", "5")],
+                vec![("__typename", "Warehouse"), ("id", "w-2")],
+            ]),
+            &Context,
+        )
+        .await,
+        Ok((entities(vec![id("w-1"), id("5"), id("w-2")]), vec![]))
+    );
+}
+
+#[test]
+fn reports_async_entities_in_sync_executor() {
+    assert_eq!(
+        crate::execute_sync(
+            WAREHOUSES_QUERY,
+            None,
+            &warehouse_schema(),
+            &representations(vec![vec![("__typename", "Warehouse"), ("id", "w-1")]]),
+            &Context,
+        ),
+        Ok((
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(50, 2, 8),
+                &["_entities"],
+                FieldError::new(
+                    "Entity \"Warehouse\" can only be resolved asynchronously",
+                    Value::null(),
+                ),
+            )]
+        ))
+    );
+}
@@ -0,0 +1,248 @@
+use std::pin::Pin;
+
+use futures::{stream, Stream};
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::{Context, ExecutionResult, Executor, FieldError, FieldResult, Registry, Variables},
+    schema::{meta::MetaType, model::RootNode},
+    types::{
+        base::{Arguments, GraphQLType},
+        guards::{resolve_guarded, Guard},
+        scalars::EmptyMutation,
+    },
+    value::{DefaultScalarValue, ScalarValue, Value},
+    GraphQLError,
+};
+
+struct Viewer {
+    role: &'static str,
+}
+
+impl Context for Viewer {}
+
+// Generic over the scalar, as derived objects are.
+fn admin_only<S: ScalarValue>(viewer: &Viewer, _: &Arguments<S>) -> FieldResult<bool, S> {
+    if viewer.role == "admin" {
+        Ok(true)
+    } else {
+        Err(FieldError::new("Forbidden", Value::null()))
+    }
+}
+
+struct Role(&'static str);
+
+impl Guard<Viewer> for Role {
+    fn check(&self, viewer: &Viewer, _: &Arguments) -> FieldResult<bool> {
+        // Other roles get null instead of an error.
+        Ok(viewer.role == self.0)
+    }
+}
+
+fn own_account(viewer: &Viewer, arguments: &Arguments) -> FieldResult<bool> {
+    Ok(arguments.get::<String>("owner").as_deref() == Some(viewer.role))
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(context = Viewer)]
+struct Account {
+    name: String,
+    #[graphql(guard = admin_only)]
+    balance: Option<i32>,
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(context = Viewer)]
+impl Query {
+    fn account() -> Account {
+        Account {
+            name: "savings".into(),
+            balance: Some(100),
+        }
+    }
+
+    #[graphql(guard = admin_only)]
+    fn secret() -> i32 {
+        42
+    }
+
+    #[graphql(guard = Role("auditor"))]
+    async fn audit_log() -> Option<Vec<String>> {
+        Some(vec!["login".into()])
+    }
+
+    #[graphql(guard = own_account)]
+    fn statement(owner: String) -> Option<String> {
+        Some(format!("statement of {}", owner))
+    }
+}
+
+struct Subscription;
+
+type Strings = Pin<Box<dyn Stream<Item = String> + Send>>;
+
+#[crate::graphql_subscription_internal(context = Viewer)]
+impl Subscription {
+    #[graphql(guard = admin_only)]
+    async fn alerts() -> Strings {
+        Box::pin(stream::once(async { "alert".to_owned() }))
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation<Viewer>, Subscription>;
+
+fn schema() -> Schema {
+    RootNode::new(Query, EmptyMutation::new(), Subscription)
+}
+
+fn execute(query: &str, role: &'static str) -> (Value, Vec<String>) {
+    let (res, errs) =
+        crate::execute_sync(query, None, &schema(), &Variables::new(), &Viewer { role }).unwrap();
+    let errs = errs
+        .iter()
+        .map(|e| e.error().message().to_owned())
+        .collect();
+    (res, errs)
+}
+
+#[test]
+fn guards_allow_fields() {
+    assert_eq!(
+        execute("{ secret account { balance } }", "admin"),
+        (
+            graphql_value!({ "secret": 42, "account": { "balance": 100 } }),
+            vec![],
+        ),
+    );
+}
+
+#[test]
+fn guards_reject_fields_with_errors() {
+    let (res, errs) = execute("{ account { name balance } }", "user");
+
+    assert_eq!(
+        res,
+        graphql_value!({ "account": { "name": "savings", "balance": None } }),
+    );
+    assert_eq!(errs, vec!["Forbidden"]);
+}
+
+#[tokio::test]
+async fn guards_reject_async_fields_with_null() {
+    let schema = schema();
+
+    let (res, errs) = crate::execute(
+        "{ auditLog }",
+        None,
+        &schema,
+        &Variables::new(),
+        &Viewer { role: "user" },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(res, graphql_value!({ "auditLog": None }));
+    assert!(errs.is_empty());
+
+    let (res, _) = crate::execute(
+        "{ auditLog }",
+        None,
+        &schema,
+        &Variables::new(),
+        &Viewer { role: "auditor" },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(res, graphql_value!({ "auditLog": ["login"] }));
+}
+
+#[test]
+fn guards_receive_arguments() {
+    assert_eq!(
+        execute(r#"{ statement(owner: "alice") }"#, "alice"),
+        (
+            graphql_value!({ "statement": "statement of alice" }),
+            vec![]
+        ),
+    );
+    assert_eq!(
+        execute(r#"{ statement(owner: "alice") }"#, "bob"),
+        (graphql_value!({ "statement": None }), vec![]),
+    );
+}
+
+#[tokio::test]
+async fn guards_reject_subscriptions() {
+    let schema = schema();
+    let viewer = Viewer { role: "user" };
+
+    let (_, errs) = crate::resolve_into_stream(
+        "subscription { alerts }",
+        None,
+        &schema,
+        &Variables::new(),
+        &viewer,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].error().message(), "Forbidden");
+}
+
+struct Vault;
+
+impl GraphQLType for Vault {
+    type Context = Viewer;
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("Vault")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let fields = &[registry.field::<Option<i32>>("gold", &())];
+        registry.build_object_type::<Vault>(&(), fields).into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        _: &(),
+        _: &str,
+        arguments: &Arguments,
+        executor: &Executor<Viewer>,
+    ) -> ExecutionResult {
+        resolve_guarded(&admin_only, arguments, executor, || Ok(Value::scalar(1000)))
+    }
+}
+
+#[test]
+fn guards_apply_to_hand_written_types() {
+    let schema = RootNode::new(
+        Vault,
+        EmptyMutation::<Viewer>::new(),
+        crate::EmptySubscription::new(),
+    );
+    let execute = |role| {
+        crate::execute_sync(
+            "{ gold }",
+            None,
+            &schema,
+            &Variables::new(),
+            &Viewer { role },
+        )
+        .map_err(|e: GraphQLError| e.to_string())
+        .unwrap()
+    };
+
+    assert_eq!(execute("admin").0, graphql_value!({ "gold": 1000 }));
+
+    let (res, errs) = execute("user");
+    assert_eq!(res, graphql_value!({ "gold": None }));
+    assert_eq!(errs[0].error().message(), "Forbidden");
+}
@@ -1,14 +1,38 @@
 //! Library tests and fixtures
 
+#[cfg(test)]
+mod custom_validation_tests;
+#[cfg(test)]
+mod dataloader_tests;
+#[cfg(test)]
+mod directive_tests;
+#[cfg(test)]
+mod document_cache_tests;
+#[cfg(test)]
+mod extensions_tests;
+#[cfg(test)]
+mod federation_tests;
+#[cfg(test)]
+mod guard_tests;
 #[cfg(test)]
 mod introspection_tests;
 pub mod model;
+#[cfg(all(test, feature = "persisted-queries"))]
+mod persisted_queries_tests;
 #[cfg(test)]
 mod query_tests;
+#[cfg(test)]
+mod relay_tests;
 pub mod schema;
 #[cfg(test)]
 mod schema_introspection;
 #[cfg(test)]
+mod schema_language_tests;
+#[cfg(test)]
+mod sdl_schema_tests;
+#[cfg(test)]
 mod subscriptions;
 #[cfg(test)]
+mod tracer_tests;
+#[cfg(test)]
 mod type_info_tests;
@@ -0,0 +1,172 @@
+use serde_json::{self, json, Value as Json};
+
+use crate::{
+    http::{
+        persisted_queries::{query_hash, InMemoryPersistedQueryStore},
+        GraphQLBatchRequest, GraphQLRequest,
+    },
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn hello() -> &'static str {
+        "world"
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>>;
+
+fn schema() -> Schema {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+        .with_persisted_queries(InMemoryPersistedQueryStore::new())
+}
+
+fn request(body: Json) -> GraphQLRequest {
+    serde_json::from_value(body).unwrap()
+}
+
+fn persisted(hash: &str) -> Json {
+    json!({ "persistedQuery": { "version": 1, "sha256Hash": hash } })
+}
+
+fn execute(schema: &Schema, body: Json) -> Json {
+    let request = request(body);
+    let response = request.execute_sync(schema, &());
+    serde_json::to_value(&response).unwrap()
+}
+
+#[test]
+fn unknown_hash_is_not_found() {
+    let schema = schema();
+
+    assert_eq!(
+        execute(
+            &schema,
+            json!({ "extensions": persisted(&query_hash("{ hello }")) }),
+        ),
+        json!({
+            "data": null,
+            "errors": [{
+                "message": "PersistedQueryNotFound",
+                "locations": [{ "line": 1, "column": 1 }],
+                "path": [],
+                "extensions": { "code": "PERSISTED_QUERY_NOT_FOUND" },
+            }],
+        }),
+    );
+}
+
+#[test]
+fn queries_are_registered_and_resolved_by_hash() {
+    let schema = schema();
+    let hash = query_hash("{ hello }");
+
+    assert_eq!(
+        execute(
+            &schema,
+            json!({ "query": "{ hello }", "extensions": persisted(&hash) }),
+        ),
+        json!({ "data": { "hello": "world" } }),
+    );
+    assert_eq!(
+        execute(&schema, json!({ "extensions": persisted(&hash) })),
+        json!({ "data": { "hello": "world" } }),
+    );
+}
+
+#[tokio::test]
+async fn queries_are_resolved_by_hash_asynchronously() {
+    let schema = schema();
+    let hash = query_hash("{ hello }");
+
+    let register = request(json!({ "query": "{ hello }", "extensions": persisted(&hash) }));
+    assert!(register.execute(&schema, &()).await.is_ok());
+
+    let batch: GraphQLBatchRequest = serde_json::from_value(json!([
+        { "extensions": persisted(&hash) },
+        { "extensions": persisted(&hash) },
+    ]))
+    .unwrap();
+    let response = batch.execute(&schema, &()).await;
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!([{ "data": { "hello": "world" } }, { "data": { "hello": "world" } }]),
+    );
+}
+
+#[test]
+fn invalid_queries_are_not_registered() {
+    let schema = schema();
+    let hash = query_hash("{ goodbye }");
+
+    let response = execute(
+        &schema,
+        json!({ "query": "{ goodbye }", "extensions": persisted(&hash) }),
+    );
+    assert_eq!(
+        response["errors"][0]["message"],
+        json!("Unknown field \"goodbye\" on type \"Query\""),
+    );
+
+    let response = execute(&schema, json!({ "extensions": persisted(&hash) }));
+    assert_eq!(
+        response["errors"][0]["message"],
+        json!("PersistedQueryNotFound"),
+    );
+}
+
+#[test]
+fn mismatching_hash_is_rejected() {
+    let schema = schema();
+
+    let response = execute(
+        &schema,
+        json!({ "query": "{ hello }", "extensions": persisted(&query_hash("{ __typename }")) }),
+    );
+    assert_eq!(
+        response["errors"][0]["message"],
+        json!("provided sha does not match query"),
+    );
+}
+
+#[test]
+fn unsupported_version_is_rejected() {
+    let schema = schema();
+
+    let response = execute(
+        &schema,
+        json!({
+            "extensions": {
+                "persistedQuery": { "version": 2, "sha256Hash": query_hash("{ hello }") },
+            },
+        }),
+    );
+    assert_eq!(
+        response["errors"][0]["message"],
+        json!("Unsupported persisted query version"),
+    );
+}
+
+#[test]
+fn persisted_queries_are_not_supported_without_store() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::new());
+
+    let response = execute(
+        &schema,
+        json!({ "query": "{ hello }", "extensions": persisted(&query_hash("{ hello }")) }),
+    );
+    assert_eq!(
+        response["errors"][0]["extensions"],
+        json!({ "code": "PERSISTED_QUERY_NOT_SUPPORTED" }),
+    );
+
+    assert_eq!(
+        execute(&schema, json!({ "query": "{ hello }" })),
+        json!({ "data": { "hello": "world" } }),
+    );
+}
@@ -0,0 +1,512 @@
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::{FieldResult, Variables},
+    relay::{offset_to_cursor, Connection, ConnectionArgs, Node, NodeValue, Nodes},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription, ID},
+    value::Value,
+    BoxFuture,
+};
+
+#[derive(Clone, GraphQLObjectInternal)]
+struct User {
+    name: String,
+}
+
+fn users() -> Vec<User> {
+    ["Alice", "Bob", "Carol", "Dave"]
+        .iter()
+        .map(|name| User {
+            name: (*name).to_owned(),
+        })
+        .collect()
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(node)]
+struct Ship {
+    id: ID,
+    name: String,
+}
+
+impl Node for Ship {
+    fn resolve_node(id: &str, _: &()) -> FieldResult<Option<Ship>> {
+        Ok(if id == "0" {
+            None
+        } else {
+            Some(Ship {
+                id: Ship::global_id(id),
+                name: format!("Ship {}", id),
+            })
+        })
+    }
+}
+
+struct Faction {
+    id: String,
+}
+
+#[crate::graphql_object_internal(node)]
+impl Faction {
+    fn id(&self) -> ID {
+        Faction::global_id(&self.id)
+    }
+
+    fn ships(&self) -> i32 {
+        3
+    }
+}
+
+impl Node for Faction {
+    fn resolve_node(id: &str, _: &()) -> FieldResult<Option<Faction>> {
+        Ok(Some(Faction { id: id.to_owned() }))
+    }
+}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(node)]
+struct Station {
+    id: ID,
+}
+
+impl Node for Station {
+    fn resolve_node_async<'a>(
+        id: &'a str,
+        _: &'a (),
+    ) -> BoxFuture<'a, FieldResult<Option<Station>>> {
+        Box::pin(async move {
+            let id = futures::future::ready(Station::global_id(id)).await;
+            Ok(Some(Station { id }))
+        })
+    }
+}
+
+fn nodes() -> Nodes<()> {
+    Nodes::new()
+        .node::<Ship>()
+        .node::<Faction>()
+        .node::<Station>()
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn node(id: ID) -> FieldResult<Option<NodeValue<()>>> {
+        nodes().resolve(&id, &())
+    }
+
+    fn nodes(ids: Vec<ID>) -> FieldResult<Vec<Option<NodeValue<()>>>> {
+        nodes().resolve_many(&ids, &())
+    }
+
+    async fn node_async(id: ID) -> FieldResult<Option<NodeValue<()>>> {
+        nodes().resolve_async(&id, &()).await
+    }
+
+    async fn nodes_async(ids: Vec<ID>) -> FieldResult<Vec<Option<NodeValue<()>>>> {
+        nodes().resolve_many_async(&ids, &()).await
+    }
+
+    fn users(
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        Connection::from_slice(
+            &users(),
+            &ConnectionArgs {
+                first,
+                after,
+                last,
+                before,
+            },
+        )
+    }
+
+    async fn users_async(
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        let args = ConnectionArgs {
+            first,
+            after,
+            ..ConnectionArgs::default()
+        };
+
+        Connection::from_source(&args, users().len(), |offset, limit| async move {
+            Ok(users().into_iter().skip(offset).take(limit).collect())
+        })
+        .await
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new()).with_nodes(&nodes())
+}
+
+fn page(field: &str, names: &[&str], page_info: (bool, bool, usize, usize)) -> Value {
+    let (has_previous_page, has_next_page, start, end) = page_info;
+
+    Value::object(
+        vec![(
+            field,
+            Value::object(
+                vec![
+                    (
+                        "edges",
+                        Value::list(
+                            names
+                                .iter()
+                                .enumerate()
+                                .map(|(i, name)| {
+                                    Value::object(
+                                        vec![
+                                            ("cursor", Value::scalar(offset_to_cursor(start + i))),
+                                            (
+                                                "node",
+                                                Value::object(
+                                                    vec![("name", Value::scalar(*name))]
+                                                        .into_iter()
+                                                        .collect(),
+                                                ),
+                                            ),
+                                        ]
+                                        .into_iter()
+                                        .collect(),
+                                    )
+                                })
+                                .collect(),
+                        ),
+                    ),
+                    (
+                        "pageInfo",
+                        Value::object(
+                            vec![
+                                ("hasPreviousPage", Value::scalar(has_previous_page)),
+                                ("hasNextPage", Value::scalar(has_next_page)),
+                                ("startCursor", Value::scalar(offset_to_cursor(start))),
+                                ("endCursor", Value::scalar(offset_to_cursor(end))),
+                            ]
+                            .into_iter()
+                            .collect(),
+                        ),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        )]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn query(field: &str, arguments: &str) -> String {
+    format!(
+        "{{ {}({}) {{ edges {{ cursor node {{ name }} }} \
+         pageInfo {{ hasPreviousPage hasNextPage startCursor endCursor }} }} }}",
+        field, arguments
+    )
+}
+
+#[test]
+fn names_types_after_node_type() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("type UserConnection {"));
+    assert!(sdl.contains("  edges: [UserEdge!]!\n"));
+    assert!(sdl.contains("  pageInfo: PageInfo!\n"));
+    assert!(sdl.contains("type UserEdge {"));
+    assert!(sdl.contains("  node: User!\n"));
+    assert!(sdl.contains("  startCursor: String\n"));
+    assert!(sdl.contains(
+        "  users(first: Int, after: String, last: Int, before: String): UserConnection!"
+    ));
+}
+
+#[test]
+fn paginates_forward() {
+    let arguments = format!("first: 2, after: \"{}\"", offset_to_cursor(0));
+
+    assert_eq!(
+        crate::execute_sync(
+            &query("users", &arguments),
+            None,
+            &schema(),
+            &Variables::new(),
+            &(),
+        ),
+        Ok((page("users", &["Bob", "Carol"], (true, true, 1, 2)), vec![]))
+    );
+}
+
+#[test]
+fn paginates_backward() {
+    let arguments = format!("last: 2, before: \"{}\"", offset_to_cursor(3));
+
+    assert_eq!(
+        crate::execute_sync(
+            &query("users", &arguments),
+            None,
+            &schema(),
+            &Variables::new(),
+            &(),
+        ),
+        Ok((page("users", &["Bob", "Carol"], (true, true, 1, 2)), vec![]))
+    );
+}
+
+#[tokio::test]
+async fn paginates_async_source() {
+    let arguments = format!("first: 5, after: \"{}\"", offset_to_cursor(1));
+
+    assert_eq!(
+        crate::execute(
+            &query("usersAsync", &arguments),
+            None,
+            &schema(),
+            &Variables::new(),
+            &(),
+        )
+        .await,
+        Ok((
+            page("usersAsync", &["Carol", "Dave"], (true, false, 2, 3)),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn reports_invalid_cursors() {
+    let (result, errs) = crate::execute_sync(
+        &query("users", "after: \"invalid\""),
+        None,
+        &schema(),
+        &Variables::new(),
+        &(),
+    )
+    .expect("Execution failed");
+
+    assert_eq!(result, Value::null());
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].error().message(), "Invalid cursor \"invalid\"");
+}
+
+#[test]
+fn node_types_implement_node_interface() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("interface Node {"));
+    assert!(sdl.contains("  id: ID!\n"));
+    assert!(sdl.contains("type Ship implements Node {"));
+    assert!(sdl.contains("type Faction implements Node {"));
+    assert!(sdl.contains("  node(id: ID!): Node\n"));
+    assert!(sdl.contains("  nodes(ids: [ID!]!): [Node]!\n"));
+}
+
+const NODES_QUERY: &str = r#"
+    query ($ids: [ID!]!) {
+        nodes(ids: $ids) {
+            __typename
+            id
+            ... on Ship { name }
+            ... on Faction { ships }
+        }
+    }
+"#;
+
+fn node_ids(ids: &[ID]) -> Variables {
+    let mut vars = Variables::new();
+    vars.insert(
+        "ids".to_owned(),
+        crate::InputValue::list(
+            ids.iter()
+                .map(|id| crate::InputValue::scalar(id.to_string()))
+                .collect(),
+        ),
+    );
+    vars
+}
+
+fn nodes_result() -> Value {
+    Value::object(
+        vec![(
+            "nodes",
+            Value::list(vec![
+                Value::object(
+                    vec![
+                        ("__typename", Value::scalar("Ship")),
+                        ("id", Value::scalar(Ship::global_id("1").to_string())),
+                        ("name", Value::scalar("Ship 1")),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                Value::object(
+                    vec![
+                        ("__typename", Value::scalar("Faction")),
+                        ("id", Value::scalar(Faction::global_id("2").to_string())),
+                        ("ships", Value::scalar(3)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                Value::null(),
+            ]),
+        )]
+        .into_iter()
+        .collect(),
+    )
+}
+
+#[test]
+fn refetches_nodes_by_global_id() {
+    assert_eq!(
+        crate::execute_sync(
+            NODES_QUERY,
+            None,
+            &schema(),
+            &node_ids(&[
+                Ship::global_id("1"),
+                Faction::global_id("2"),
+                Ship::global_id("0"),
+            ]),
+            &(),
+        ),
+        Ok((nodes_result(), vec![]))
+    );
+}
+
+#[tokio::test]
+async fn refetches_nodes_in_async_executor() {
+    assert_eq!(
+        crate::execute(
+            NODES_QUERY,
+            None,
+            &schema(),
+            &node_ids(&[
+                Ship::global_id("1"),
+                Faction::global_id("2"),
+                Ship::global_id("0"),
+            ]),
+            &(),
+        )
+        .await,
+        Ok((nodes_result(), vec![]))
+    );
+}
+
+#[tokio::test]
+async fn refetches_nodes_asynchronously() {
+    let station = |id: &str| {
+        Value::object(
+            vec![
+                ("__typename", Value::scalar("Station")),
+                ("id", Value::scalar(Station::global_id(id).to_string())),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    };
+
+    assert_eq!(
+        crate::execute(
+            r#"query ($ids: [ID!]!, $id: ID!) {
+                nodesAsync(ids: $ids) { __typename id }
+                nodeAsync(id: $id) { __typename id }
+            }"#,
+            None,
+            &schema(),
+            &{
+                let mut vars = node_ids(&[Station::global_id("s-1"), Station::global_id("s-2")]);
+                vars.insert(
+                    "id".to_owned(),
+                    crate::InputValue::scalar(Station::global_id("s-3").to_string()),
+                );
+                vars
+            },
+            &(),
+        )
+        .await,
+        Ok((
+            Value::object(
+                vec![
+                    (
+                        "nodesAsync",
+                        Value::list(vec![station("s-1"), station("s-2")]),
+                    ),
+                    ("nodeAsync", station("s-3")),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn reports_async_nodes_in_sync_resolvers() {
+    let (result, errs) = crate::execute_sync(
+        "query ($ids: [ID!]!) { nodes(ids: $ids) { id } }",
+        None,
+        &schema(),
+        &node_ids(&[Station::global_id("s-1")]),
+        &(),
+    )
+    .expect("Execution failed");
+
+    assert_eq!(result, Value::null());
+    assert_eq!(
+        errs[0].error().message(),
+        "Node \"Station\" can only be resolved asynchronously"
+    );
+}
+
+#[test]
+fn resolves_unknown_types_to_null() {
+    let mut vars = Variables::new();
+    vars.insert(
+        "id".to_owned(),
+        crate::InputValue::scalar(
+            crate::relay::GlobalId::new("Planet", "1")
+                .encode()
+                .to_string(),
+        ),
+    );
+
+    assert_eq!(
+        crate::execute_sync(
+            "query ($id: ID!) { node(id: $id) { id } }",
+            None,
+            &schema(),
+            &vars,
+            &(),
+        ),
+        Ok((
+            Value::object(vec![("node", Value::null())].into_iter().collect()),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn reports_invalid_global_ids() {
+    let (result, errs) = crate::execute_sync(
+        r#"{ node(id: "invalid") { id } }"#,
+        None,
+        &schema(),
+        &Variables::new(),
+        &(),
+    )
+    .expect("Execution failed");
+
+    assert_eq!(
+        result,
+        Value::object(vec![("node", Value::null())].into_iter().collect())
+    );
+    assert_eq!(errs[0].error().message(), "Invalid ID \"invalid\"");
+}
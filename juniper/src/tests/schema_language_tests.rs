@@ -0,0 +1,169 @@
+use juniper_codegen::{
+    GraphQLEnumInternal as GraphQLEnum, GraphQLInputObjectInternal as GraphQLInputObject,
+};
+
+use crate::{
+    schema::model::RootNode,
+    tests::{model::Database, schema::Query},
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+#[test]
+fn test_star_wars_schema_language() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    assert_eq!(
+        schema.as_sdl(),
+        r#""A character in the Star Wars Trilogy"
+interface Character {
+  "The id of the character"
+  id: String!
+  "The name of the character"
+  name: String
+  "The friends of the character"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+}
+
+"A mechanical creature in the Star Wars universe."
+type Droid implements Character {
+  "The id of the droid"
+  id: String!
+  "The name of the droid"
+  name: String
+  "The friends of the droid"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+  "The primary function of the droid"
+  primaryFunction: String
+}
+
+enum Episode {
+  NEW_HOPE
+  EMPIRE
+  JEDI
+}
+
+"A humanoid creature in the Star Wars universe."
+type Human implements Character {
+  "The id of the human"
+  id: String!
+  "The name of the human"
+  name: String
+  "The friends of the human"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+  "The home planet of the human"
+  homePlanet: String
+}
+
+"The root query object of the schema"
+type Query {
+  human(
+    "id of the human"
+    id: String!
+  ): Human
+  droid(
+    "id of the droid"
+    id: String!
+  ): Droid
+  hero(
+    """
+    If omitted, returns the hero of the whole saga. If provided, returns the hero of that particular episode
+    """
+    episode: Episode
+  ): Character
+}
+"#
+    );
+}
+
+#[derive(GraphQLEnum)]
+enum Color {
+    Red,
+    #[graphql(deprecated = "Use RED")]
+    Crimson,
+    #[graphql(deprecated)]
+    Scarlet,
+}
+
+#[derive(GraphQLInputObject)]
+/// A point on the plane.
+///
+/// Coordinates are "relative" to the origin.
+struct Point {
+    x: f64,
+    #[graphql(default = "0.0")]
+    y: f64,
+}
+
+struct Root;
+
+#[crate::graphql_object_internal(name = "Root")]
+impl Root {
+    #[graphql(arguments(color(default = Color::Red), label(default = "none".to_owned())))]
+    fn paint(color: Color, label: String, at: Option<Point>) -> i32 {
+        let _ = (color, label, at);
+        0
+    }
+
+    #[graphql(deprecated = "Use \"paint\" instead")]
+    fn draw() -> i32 {
+        0
+    }
+}
+
+struct Mutation;
+
+#[crate::graphql_object_internal]
+impl Mutation {
+    fn erase() -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_schema_language_with_custom_roots() {
+    let schema = RootNode::new(Root, Mutation, EmptySubscription::<()>::new());
+
+    assert_eq!(
+        schema.as_sdl(),
+        r#"schema {
+  query: Root
+  mutation: Mutation
+}
+
+enum Color {
+  RED
+  CRIMSON @deprecated(reason: "Use RED")
+  SCARLET @deprecated
+}
+
+type Mutation {
+  erase: Boolean!
+}
+
+"""
+A point on the plane.
+
+Coordinates are "relative" to the origin.
+"""
+input Point {
+  x: Float!
+  y: Float = 0
+}
+
+type Root {
+  paint(color: Color = RED, label: String = "none", at: Point): Int!
+  draw: Int! @deprecated(reason: "Use \"paint\" instead")
+}
+"#
+    );
+}
@@ -0,0 +1,392 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::InputValue,
+    executor::{FieldError, Variables},
+    schema::sdl::{SchemaBuildError, SchemaBuilder},
+    types::dynamic::{DynamicRootNode, DynamicValue},
+    value::{DefaultScalarValue, Value},
+};
+
+const SDL: &str = r#"
+"A character in a story"
+interface Character {
+    name: String!
+    friends: [Character!]!
+}
+
+type Human implements Character {
+    name: String!
+    friends: [Character!]!
+    homePlanet: String
+}
+
+type Droid implements Character {
+    name: String!
+    friends: [Character!]!
+    primaryFunction: String @deprecated(reason: "Use model")
+}
+
+enum Episode { NEW_HOPE EMPIRE JEDI }
+
+input Rename { id: ID!, name: String! }
+
+type Query {
+    hero(episode: Episode = NEW_HOPE): Character
+    characters: [Character!]!
+    database: String!
+}
+
+type Mutation {
+    rename(input: Rename!): Character
+}
+"#;
+
+struct Database {
+    names: HashMap<String, String>,
+}
+
+fn character(db: &Database, id: &str) -> DynamicValue {
+    let name = db.names[id].clone();
+    let kind = if id == "r2d2" { "Droid" } else { "Human" };
+    DynamicValue::from(Value::object(
+        vec![
+            ("id", Value::scalar(id.to_owned())),
+            ("name", Value::scalar(name)),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+    .typed(kind)
+}
+
+fn id_of(value: &DynamicValue) -> String {
+    value
+        .as_value()
+        .and_then(|v| v.as_object_value())
+        .and_then(|o| o.get_field_value("id"))
+        .and_then(|v| v.as_scalar_value::<String>())
+        .cloned()
+        .unwrap()
+}
+
+fn schema<'a>() -> DynamicRootNode<'a, Database> {
+    SchemaBuilder::from_sdl(SDL)
+        .unwrap()
+        .resolver("Query", "hero", |_, args, executor| {
+            let db: &Database = executor.context();
+            let episode = args.get::<InputValue>("episode").unwrap();
+            let id = match episode
+                .as_enum_value()
+                .or_else(|| episode.as_string_value())
+            {
+                Some("EMPIRE") => "luke",
+                _ => "r2d2",
+            };
+            Ok(character(db, id))
+        })
+        .resolver("Query", "characters", |_, _, executor| {
+            let db: &Database = executor.context();
+            Ok(DynamicValue::List(
+                vec!["luke", "r2d2"]
+                    .into_iter()
+                    .map(|id| character(db, id))
+                    .collect(),
+            ))
+        })
+        .resolver("Query", "database", |_, _, _| {
+            Ok(DynamicValue::from(Value::scalar("memory")))
+        })
+        .resolver("Character", "friends", |parent, _, executor| {
+            let db: &Database = executor.context();
+            let friend = if id_of(parent) == "luke" {
+                "r2d2"
+            } else {
+                "luke"
+            };
+            Ok(DynamicValue::List(vec![character(db, friend)]))
+        })
+        .resolver("Mutation", "rename", |_, args, executor| {
+            let input = args.get::<InputValue>("input").unwrap();
+            let input = input.to_object_value().unwrap();
+            let id = input["id"].as_string_value().unwrap();
+            if !executor.context().names.contains_key(id) {
+                return Err(FieldError::from(format!("Unknown character {}", id)));
+            }
+            let name = input["name"].as_string_value().unwrap();
+            let mut db = Database {
+                names: executor.context().names.clone(),
+            };
+            db.names.insert(id.to_owned(), name.to_owned());
+            Ok(character(&db, id))
+        })
+        .build()
+        .unwrap()
+}
+
+fn database() -> Database {
+    Database {
+        names: vec![("luke", "Luke Skywalker"), ("r2d2", "R2-D2")]
+            .into_iter()
+            .map(|(id, name)| (id.to_owned(), name.to_owned()))
+            .collect(),
+    }
+}
+
+fn run(query: &str) -> (Value, Vec<String>) {
+    let (result, errs) =
+        crate::execute_sync(query, None, &schema(), &Variables::new(), &database())
+            .expect("Execution failed");
+    (
+        result,
+        errs.iter()
+            .map(|e| e.error().message().to_owned())
+            .collect(),
+    )
+}
+
+#[test]
+fn resolves_registered_and_default_fields() {
+    let (result, errs) = run(
+        "{ hero { __typename name friends { name } ... on Droid { primaryFunction } } database }",
+    );
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(
+        result,
+        graphql_value!({
+            "hero": {
+                "__typename": "Droid",
+                "name": "R2-D2",
+                "friends": [{ "name": "Luke Skywalker" }],
+                "primaryFunction": None,
+            },
+            "database": "memory",
+        })
+    );
+}
+
+#[test]
+fn resolves_enum_arguments_and_lists() {
+    let (result, errs) =
+        run("{ hero(episode: EMPIRE) { name } characters { __typename ... on Human { name } } }");
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(
+        result,
+        graphql_value!({
+            "hero": { "name": "Luke Skywalker" },
+            "characters": [
+                { "__typename": "Human", "name": "Luke Skywalker" },
+                { "__typename": "Droid" },
+            ],
+        })
+    );
+}
+
+#[test]
+fn rejects_unknown_enum_literals() {
+    let schema = schema();
+    let res = crate::execute_sync(
+        "{ hero(episode: PHANTOM) { name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &database(),
+    );
+
+    match res {
+        Err(crate::GraphQLError::ValidationError(_)) => {}
+        res => panic!("Unexpected result {:?}", res),
+    }
+}
+
+#[test]
+fn executes_mutations() {
+    let (result, errs) =
+        run(r#"mutation { rename(input: { id: "luke", name: "Luke" }) { name } }"#);
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(result, graphql_value!({ "rename": { "name": "Luke" } }));
+
+    let (result, errs) =
+        run(r#"mutation { rename(input: { id: "leia", name: "Leia" }) { name } }"#);
+
+    assert_eq!(errs, vec!["Unknown character leia".to_owned()]);
+    assert_eq!(result, graphql_value!({ "rename": None }));
+}
+
+#[test]
+fn reports_missing_resolvers() {
+    let schema = SchemaBuilder::<()>::from_sdl("type Query { answer: Int }")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let (result, errs) =
+        crate::execute_sync("{ answer }", None, &schema, &Variables::new(), &()).unwrap();
+
+    assert_eq!(result, graphql_value!({ "answer": None }));
+    assert_eq!(
+        errs[0].error().message(),
+        r#"No resolver registered for field "Query.answer""#
+    );
+}
+
+#[tokio::test]
+async fn executes_asynchronously() {
+    let schema = schema();
+    let db = database();
+    let (result, errs) = crate::execute(
+        "{ characters { name friends { __typename } } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &db,
+    )
+    .await
+    .unwrap();
+
+    assert!(errs.is_empty());
+    assert_eq!(
+        result,
+        graphql_value!({
+            "characters": [
+                { "name": "Luke Skywalker", "friends": [{ "__typename": "Droid" }] },
+                { "name": "R2-D2", "friends": [{ "__typename": "Human" }] },
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn reports_untyped_abstract_values() {
+    let schema = SchemaBuilder::<()>::from_sdl(
+        "interface Named { name: String } type Pet implements Named { name: String } \
+         type Query { named: Named, pets: [Named] }",
+    )
+    .unwrap()
+    .resolver("Query", "named", |_, _, _| {
+        Ok(DynamicValue::from(Value::object(
+            vec![("name", Value::scalar("Rex"))].into_iter().collect(),
+        )))
+    })
+    .resolver("Query", "pets", |_, _, _| {
+        Ok(DynamicValue::List(vec![DynamicValue::from(Value::scalar(
+            "Rex",
+        ))
+        .typed("Query")]))
+    })
+    .build()
+    .unwrap();
+
+    let query = "{ named { __typename ... on Pet { name } } pets { __typename } }";
+    let expected_errs = vec![
+        r#"Unable to determine the concrete type of a "Named" value"#.to_owned(),
+        r#"Type "Query" is not a possible type of "Named""#.to_owned(),
+    ];
+    let messages = |errs: Vec<crate::ExecutionError<DefaultScalarValue>>| {
+        errs.iter()
+            .map(|e| e.error().message().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let (result, errs) = crate::execute_sync(query, None, &schema, &Variables::new(), &()).unwrap();
+    assert_eq!(result, graphql_value!({ "named": None, "pets": None }));
+    assert_eq!(messages(errs), expected_errs);
+
+    let (result, errs) = crate::execute(query, None, &schema, &Variables::new(), &())
+        .await
+        .unwrap();
+    assert_eq!(result, graphql_value!({ "named": None, "pets": None }));
+    assert_eq!(messages(errs), expected_errs);
+}
+
+#[test]
+fn prints_the_schema() {
+    let sdl = schema().as_sdl();
+
+    assert!(sdl.contains("\"A character in a story\"\ninterface Character {\n"));
+    assert!(sdl.contains("type Droid implements Character {\n"));
+    assert!(sdl.contains("  primaryFunction: String @deprecated(reason: \"Use model\")\n"));
+    assert!(sdl.contains("  hero(episode: Episode = NEW_HOPE): Character\n"));
+    assert!(sdl.contains("enum Episode {\n  NEW_HOPE\n  EMPIRE\n  JEDI\n}"));
+    assert!(sdl.contains("input Rename {\n  id: ID!\n  name: String!\n}"));
+}
+
+#[test]
+fn builds_one_of_input_objects() {
+    let schema = SchemaBuilder::<()>::from_sdl(
+        "input Filter @oneOf { id: ID, name: String } type Query { find(by: Filter!): String }",
+    )
+    .unwrap()
+    .resolver("Query", "find", |_, _, _| {
+        Ok(DynamicValue::from(Value::scalar("found".to_owned())))
+    })
+    .build()
+    .unwrap();
+
+    assert!(schema
+        .as_sdl()
+        .contains("input Filter @oneOf {\n  id: ID\n  name: String\n}"));
+
+    let run = |query| crate::execute_sync(query, None, &schema, &Variables::new(), &());
+
+    assert_eq!(
+        run(r#"{ __type(name: "Filter") { isOneOf } find(by: { id: "1" }) }"#),
+        Ok((
+            graphql_value!({ "__type": { "isOneOf": true }, "find": "found" }),
+            vec![]
+        ))
+    );
+    assert!(run(r#"{ find(by: { id: "1", name: "Luke" }) }"#).is_err());
+}
+
+#[test]
+fn reports_invalid_documents() {
+    let build = |sdl| {
+        SchemaBuilder::<(), DefaultScalarValue>::from_sdl(sdl)
+            .unwrap()
+            .build()
+            .map(|_| ())
+    };
+
+    assert_eq!(
+        build("type Mutation { a: Int }"),
+        Err(SchemaBuildError::MissingQueryType)
+    );
+    assert_eq!(
+        build("type Query { a: Int } type Query { b: Int }"),
+        Err(SchemaBuildError::DuplicateType("Query".to_owned()))
+    );
+    assert_eq!(
+        build("type Query { a: Missing }"),
+        Err(SchemaBuildError::UnknownType("Missing".to_owned()))
+    );
+    assert_eq!(
+        build("type Query { a(b: Query): Int }"),
+        Err(SchemaBuildError::InvalidTypeUsage {
+            type_name: "Query".to_owned(),
+            usage: r#"the type of "Query.a.b""#.to_owned(),
+        })
+    );
+    assert_eq!(
+        build("interface I { a: Int } type Query implements I { b: Int }"),
+        Err(SchemaBuildError::MissingInterfaceField {
+            type_name: "Query".to_owned(),
+            interface: "I".to_owned(),
+            field_name: "a".to_owned(),
+        })
+    );
+    assert_eq!(
+        SchemaBuilder::<()>::from_sdl("type Query { a: Int }")
+            .unwrap()
+            .resolver("Query", "b", |_, _, _| Ok(DynamicValue::null()))
+            .build()
+            .map(|_| ()),
+        Err(SchemaBuildError::UnknownField {
+            type_name: "Query".to_owned(),
+            field_name: "b".to_owned(),
+        })
+    );
+}
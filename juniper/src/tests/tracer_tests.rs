@@ -0,0 +1,267 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    ast::Operation,
+    executor::{
+        ExecutionError, ExecutionResult, Extensions, FieldError, TracedField, Tracer, Variables,
+    },
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Value},
+    GraphQLError,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn hero() -> Hero {
+        Hero
+    }
+
+    fn fail() -> Result<i32, FieldError> {
+        Err(FieldError::new("failed", Value::null()))
+    }
+}
+
+struct Hero;
+
+#[crate::graphql_object_internal]
+impl Hero {
+    fn name() -> &'static str {
+        "R2-D2"
+    }
+
+    async fn friends() -> Vec<Hero> {
+        vec![Hero]
+    }
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn record(&self, event: String) {
+        self.0.lock().unwrap().push(event);
+    }
+
+    fn events(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Tracer for Recorder {
+    fn parse_start(&self, _: &Extensions, _: &str) {
+        self.record("parse_start".into());
+    }
+
+    fn parse_end(&self, _: &Extensions, _: Duration, error: Option<&GraphQLError>) {
+        self.record(format!("parse_end {}", error.is_none()));
+    }
+
+    fn validation_start(&self, _: &Extensions) {
+        self.record("validation_start".into());
+    }
+
+    fn validation_end(&self, _: &Extensions, _: Duration, error: Option<&GraphQLError>) {
+        self.record(format!("validation_end {}", error.is_none()));
+    }
+
+    fn execution_start(&self, _: &Extensions, operation: &Operation<DefaultScalarValue>) {
+        let name = operation.name.as_ref().map(|n| n.item).unwrap_or("");
+        self.record(format!("execution_start {}", name));
+    }
+
+    fn execution_end(
+        &self,
+        _: &Extensions,
+        _: Duration,
+        errors: &[ExecutionError<DefaultScalarValue>],
+    ) {
+        self.record(format!("execution_end {}", errors.len()));
+    }
+
+    fn field_start(&self, _: &Extensions, field: &TracedField) {
+        self.record(format!(
+            "field_start {} {}.{}",
+            field.path.to_vec().join("."),
+            field.parent_type,
+            field.field_name,
+        ));
+    }
+
+    fn field_end(
+        &self,
+        _: &Extensions,
+        field: &TracedField,
+        _: Duration,
+        result: &ExecutionResult,
+    ) {
+        self.record(format!(
+            "field_end {}: {} {}",
+            field.path.to_vec().join("."),
+            field.return_type,
+            result.is_ok(),
+        ));
+    }
+}
+
+/// Reports the resolved fields in the response, the way Apollo tracing does
+struct ResolverTracing;
+
+impl Tracer for ResolverTracing {
+    fn field_end(
+        &self,
+        extensions: &Extensions,
+        field: &TracedField,
+        _: Duration,
+        _: &ExecutionResult,
+    ) {
+        let path = field.path.to_vec().join(".");
+        extensions.update("resolvers", |resolvers| {
+            let mut list = match resolvers {
+                Some(Value::List(list)) => list,
+                _ => vec![],
+            };
+            list.push(Value::scalar(path));
+            Value::list(list)
+        });
+    }
+}
+
+fn schema(
+    recorder: &Recorder,
+) -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+        .with_tracer(recorder.clone())
+}
+
+#[test]
+fn traces_every_phase_of_a_query() {
+    let recorder = Recorder::default();
+    let schema = schema(&recorder);
+
+    crate::execute_sync(
+        "query Q { hero { name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse_start",
+            "parse_end true",
+            "validation_start",
+            "validation_end true",
+            "execution_start Q",
+            "field_start hero Query.hero",
+            "field_start hero.name Hero.name",
+            "field_end hero.name: String! true",
+            "field_end hero: Hero! true",
+            "execution_end 0",
+        ],
+    );
+}
+
+#[tokio::test]
+async fn traces_fields_resolved_asynchronously() {
+    let recorder = Recorder::default();
+    let schema = schema(&recorder);
+
+    crate::execute(
+        "{ hero { friends { n: name } } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse_start",
+            "parse_end true",
+            "validation_start",
+            "validation_end true",
+            "execution_start ",
+            "field_start hero Query.hero",
+            "field_start hero.friends Hero.friends",
+            "field_start hero.friends.n Hero.name",
+            "field_end hero.friends.n: String! true",
+            "field_end hero.friends: [Hero!]! true",
+            "field_end hero: Hero! true",
+            "execution_end 0",
+        ],
+    );
+}
+
+#[test]
+fn traces_field_errors() {
+    let recorder = Recorder::default();
+    let schema = schema(&recorder);
+
+    let (_, errors) =
+        crate::execute_sync("{ fail }", None, &schema, &Variables::new(), &()).unwrap();
+    assert_eq!(errors.len(), 1);
+
+    let events = recorder.events();
+    assert!(events.contains(&"field_end fail: Int! false".to_owned()));
+    assert_eq!(events.last().unwrap(), "execution_end 1");
+}
+
+#[test]
+fn traces_failed_parsing_and_validation() {
+    let recorder = Recorder::default();
+    let schema = schema(&recorder);
+
+    assert!(crate::execute_sync("{ hero", None, &schema, &Variables::new(), &()).is_err());
+    assert!(crate::execute_sync("{ villain }", None, &schema, &Variables::new(), &()).is_err());
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse_start",
+            "parse_end false",
+            "parse_start",
+            "parse_end true",
+            "validation_start",
+            "validation_end false",
+        ],
+    );
+}
+
+#[test]
+fn tracers_write_response_extensions() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::new())
+        .with_tracer(ResolverTracing);
+    let extensions = Extensions::default();
+
+    crate::execute_sync_with_extensions(
+        "{ hero { name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+        &extensions,
+    )
+    .unwrap();
+
+    assert_eq!(
+        extensions.into_response(),
+        vec![(
+            "resolvers",
+            Value::list(vec![Value::scalar("hero.name"), Value::scalar("hero")]),
+        )]
+        .into_iter()
+        .collect(),
+    );
+}
@@ -1,13 +1,16 @@
 use crate::{
     ast::Selection,
-    executor::{ExecutionResult, Executor},
+    executor::{ExecutionResult, Executor, TracedField},
     parser::Spanning,
     value::{Object, ScalarValue, Value},
 };
 
 use crate::BoxFuture;
 
-use super::base::{is_excluded, merge_key_into, Arguments, GraphQLType};
+use super::{
+    base::{is_excluded, merge_key_into, Arguments, GraphQLType},
+    directives::AppliedDirectives,
+};
 
 /**
 This trait extends `GraphQLType` with asynchronous queries/mutations resolvers.
@@ -91,7 +94,7 @@ where
 
 // Wrapper function around resolve_selection_set_into_async_recursive.
 // This wrapper is necessary because async fns can not be recursive.
-fn resolve_selection_set_into_async<'a, 'e, T, CtxT, S>(
+pub(crate) fn resolve_selection_set_into_async<'a, 'e, T, CtxT, S>(
     instance: &'a T,
     info: &'a T::TypeInfo,
     selection_set: &'e [Selection<'e, S>],
@@ -134,7 +137,10 @@ where
     S: ScalarValue + Send + Sync,
     CtxT: Send + Sync,
 {
-    use futures::stream::{FuturesOrdered, StreamExt as _};
+    use futures::{
+        future,
+        stream::{FuturesOrdered, StreamExt as _},
+    };
 
     let mut object = Object::with_capacity(selection_set.len());
 
@@ -163,10 +169,12 @@ where
                 let response_name = f.alias.as_ref().unwrap_or(&f.name).item;
 
                 if f.name.item == "__typename" {
-                    object.add_field(
-                        response_name,
-                        Value::scalar(instance.concrete_type_name(executor.context(), info)),
-                    );
+                    // Keep the field in the order of the selection set.
+                    let typename = instance.concrete_type_name(executor.context(), info);
+                    async_values.push(Box::pin(future::ready(AsyncValue::Field(AsyncField {
+                        name: response_name.to_string(),
+                        value: Some(Value::scalar(typename)),
+                    }))));
                     continue;
                 }
 
@@ -196,16 +204,37 @@ where
                     &meta_field.arguments,
                 );
 
+                let directives = AppliedDirectives::new(&f.directives, executor);
+
                 let pos = *start_pos;
                 let is_non_null = meta_field.field_type.is_non_null();
+                let parent_type = meta_type.name().unwrap_or_default();
 
                 let response_name = response_name.to_string();
                 let field_future = async move {
                     // TODO: implement custom future type instead of
                     //       two-level boxing.
-                    let res = instance
-                        .resolve_field_async(info, f.name.item, &args, &sub_exec)
-                        .await;
+                    let traced = TracedField {
+                        path: sub_exec.field_path(),
+                        parent_type,
+                        field_name: f.name.item,
+                        return_type: &meta_field.field_type,
+                    };
+                    let started = sub_exec
+                        .tracers()
+                        .field_start(sub_exec.extensions(), &traced);
+
+                    let res = match directives.before_field(&sub_exec) {
+                        Ok(()) => instance
+                            .resolve_field_async(info, f.name.item, &args, &sub_exec)
+                            .await
+                            .and_then(|v| directives.after_field(&sub_exec, v)),
+                        Err(e) => Err(e),
+                    };
+
+                    sub_exec
+                        .tracers()
+                        .field_end(sub_exec.extensions(), &traced, started, &res);
 
                     let value = match res {
                         Ok(Value::Null) if is_non_null => None,
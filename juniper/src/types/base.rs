@@ -4,9 +4,10 @@ use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
 
 use crate::{
     ast::{Directive, FromInputValue, InputValue, Selection},
-    executor::{ExecutionResult, Executor, Registry, Variables},
+    executor::{ExecutionResult, Executor, Registry, TracedField, Variables},
     parser::Spanning,
     schema::meta::{Argument, MetaType},
+    types::directives::AppliedDirectives,
     value::{DefaultScalarValue, Object, ScalarValue, Value},
 };
 
@@ -405,21 +406,45 @@ where
                     f.selection_set.as_ref().map(|v| &v[..]),
                 );
 
-                let field_result = instance.resolve_field(
-                    info,
-                    f.name.item,
-                    &Arguments::new(
-                        f.arguments.as_ref().map(|m| {
-                            m.item
-                                .iter()
-                                .map(|&(ref k, ref v)| {
-                                    (k.item, v.item.clone().into_const(exec_vars))
-                                })
-                                .collect()
-                        }),
-                        &meta_field.arguments,
-                    ),
-                    &sub_exec,
+                let directives = AppliedDirectives::new(&f.directives, executor);
+
+                let traced = TracedField {
+                    path: sub_exec.field_path(),
+                    parent_type: meta_type.name().unwrap_or_default(),
+                    field_name: f.name.item,
+                    return_type: &meta_field.field_type,
+                };
+                let started = executor
+                    .tracers()
+                    .field_start(executor.extensions(), &traced);
+
+                let field_result = directives
+                    .before_field(&sub_exec)
+                    .and_then(|()| {
+                        instance.resolve_field(
+                            info,
+                            f.name.item,
+                            &Arguments::new(
+                                f.arguments.as_ref().map(|m| {
+                                    m.item
+                                        .iter()
+                                        .map(|&(ref k, ref v)| {
+                                            (k.item, v.item.clone().into_const(exec_vars))
+                                        })
+                                        .collect()
+                                }),
+                                &meta_field.arguments,
+                            ),
+                            &sub_exec,
+                        )
+                    })
+                    .and_then(|v| directives.after_field(&sub_exec, v));
+
+                executor.tracers().field_end(
+                    executor.extensions(),
+                    &traced,
+                    started,
+                    &field_result,
                 );
 
                 match field_result {
@@ -515,6 +540,13 @@ where
             ..
         } in directives
         {
+            let skip_if = match directive.name.item {
+                "skip" => true,
+                "include" => false,
+                // Other directives don't exclude anything.
+                _ => continue,
+            };
+
             let condition: bool = directive
                 .arguments
                 .iter()
@@ -523,9 +555,7 @@ where
                 .next()
                 .unwrap();
 
-            if (directive.name.item == "skip" && condition)
-                || (directive.name.item == "include" && !condition)
-            {
+            if condition == skip_if {
                 return true;
             }
         }
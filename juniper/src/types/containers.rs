@@ -177,7 +177,7 @@ where
     }
 }
 
-fn resolve_into_list<S, T, I>(
+pub(crate) fn resolve_into_list<S, T, I>(
     executor: &Executor<T::Context, S>,
     info: &T::TypeInfo,
     iter: I,
@@ -210,7 +210,7 @@ where
     Ok(Value::list(result))
 }
 
-async fn resolve_into_list_async<'a, S, T, I>(
+pub(crate) async fn resolve_into_list_async<'a, S, T, I>(
     executor: &'a Executor<'a, 'a, T::Context, S>,
     info: &'a T::TypeInfo,
     items: I,
@@ -0,0 +1,300 @@
+use std::{fmt, sync::Arc};
+
+use fnv::FnvHashMap;
+
+use crate::{
+    ast::Directive,
+    executor::{ExecutionResult, Executor, FieldResult, Registry},
+    parser::Spanning,
+    schema::{meta::Argument, model::DirectiveType},
+    types::base::Arguments,
+    value::{DefaultScalarValue, ScalarValue, Value},
+    Variables,
+};
+
+/// Runtime behavior of a custom executable directive
+///
+/// An executable directive is defined by its metadata, which is added to the
+/// schema, and two hooks that run around the resolution of every field the
+/// directive is attached to. Both hooks receive the directive's arguments,
+/// with variables and default values substituted, and the executor of the
+/// field, which gives access to the context.
+///
+/// Register directives with
+/// [`RootNode::with_directive`](struct.RootNode.html#method.with_directive).
+/// They apply in both the synchronous and the asynchronous executor. Below
+/// fields that are resolved with another context, the hooks still receive the
+/// context of the operation.
+///
+/// ```rust
+/// use juniper::{
+///     Arguments, DirectiveLocation, DirectiveType, ExecutableDirective, ExecutionResult,
+///     Executor, Registry, Value,
+/// };
+///
+/// struct Uppercase;
+///
+/// impl<CtxT> ExecutableDirective<CtxT> for Uppercase {
+///     fn meta<'r>(&self, _: &mut Registry<'r>) -> DirectiveType<'r, juniper::DefaultScalarValue> {
+///         DirectiveType::new("uppercase", &[DirectiveLocation::Field], &[])
+///     }
+///
+///     fn after_field(
+///         &self,
+///         _: &Arguments,
+///         _: &Executor<CtxT>,
+///         value: Value,
+///     ) -> ExecutionResult {
+///         Ok(match value.as_string_value() {
+///             Some(s) => Value::scalar(s.to_uppercase()),
+///             None => value,
+///         })
+///     }
+/// }
+/// ```
+pub trait ExecutableDirective<CtxT, S = DefaultScalarValue>: Send + Sync
+where
+    S: ScalarValue,
+{
+    /// The definition of the directive, which is added to the schema
+    ///
+    /// Runtime behavior only applies where the directive is attached to a
+    /// field, so the locations should usually be just
+    /// `DirectiveLocation::Field`.
+    fn meta<'r>(&self, registry: &mut Registry<'r, S>) -> DirectiveType<'r, S>;
+
+    /// Called before the field is resolved
+    ///
+    /// Returning an error prevents the field from being resolved, and the
+    /// error is reported like any other field error.
+    fn before_field(
+        &self,
+        _arguments: &Arguments<S>,
+        _executor: &Executor<CtxT, S>,
+    ) -> FieldResult<(), S> {
+        Ok(())
+    }
+
+    /// Called with the resolved value of the field, which is replaced by the
+    /// returned value
+    fn after_field(
+        &self,
+        _arguments: &Arguments<S>,
+        _executor: &Executor<CtxT, S>,
+        value: Value<S>,
+    ) -> ExecutionResult<S> {
+        Ok(value)
+    }
+}
+
+struct RegisteredDirective<'a, CtxT, S> {
+    arguments: Option<Vec<Argument<'a, S>>>,
+    directive: Box<dyn ExecutableDirective<CtxT, S> + 'a>,
+}
+
+type BindFn<CtxT, S> = for<'b> fn(BoundDirectives<'b, CtxT, S>) -> Arc<dyn FieldDirectives<S> + 'b>;
+
+pub(crate) struct ExecutableDirectives<'a, CtxT, S> {
+    directives: FnvHashMap<String, RegisteredDirective<'a, CtxT, S>>,
+    // Executors of other contexts keep the directives with their context type
+    // erased, which is only possible for contexts that are `Sync`.
+    bind: Option<BindFn<CtxT, S>>,
+}
+
+impl<'a, CtxT, S> Default for ExecutableDirectives<'a, CtxT, S> {
+    fn default() -> Self {
+        ExecutableDirectives {
+            directives: FnvHashMap::default(),
+            bind: None,
+        }
+    }
+}
+
+impl<'a, CtxT, S> fmt::Debug for ExecutableDirectives<'a, CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.directives.keys()).finish()
+    }
+}
+
+impl<'a, CtxT, S> ExecutableDirectives<'a, CtxT, S>
+where
+    S: ScalarValue,
+{
+    pub(crate) fn insert<D>(&mut self, meta: &DirectiveType<'a, S>, directive: D)
+    where
+        D: ExecutableDirective<CtxT, S> + 'a,
+        CtxT: Sync,
+        S: Send + Sync,
+    {
+        let arguments = if meta.arguments.is_empty() {
+            None
+        } else {
+            Some(meta.arguments.clone())
+        };
+
+        self.directives.insert(
+            meta.name.clone(),
+            RegisteredDirective {
+                arguments,
+                directive: Box::new(directive),
+            },
+        );
+        self.bind = Some(bind);
+    }
+
+    /// Bind the directives to the context of an operation, if there are any
+    pub(crate) fn bind<'b>(&'b self, context: &'b CtxT) -> Option<Arc<dyn FieldDirectives<S> + 'b>>
+    where
+        'a: 'b,
+    {
+        self.bind.map(|bind| {
+            bind(BoundDirectives {
+                directives: self,
+                context,
+            })
+        })
+    }
+
+    fn applied<'e>(
+        &'e self,
+        attached: &'e [Spanning<Directive<'e, S>>],
+        vars: &'e Variables<S>,
+    ) -> impl Iterator<Item = (&'e dyn ExecutableDirective<CtxT, S>, Arguments<'e, S>)> {
+        attached.iter().filter_map(move |Spanning { item: d, .. }| {
+            let registered = self.directives.get(d.name.item)?;
+            let arguments = Arguments::new(
+                d.arguments.as_ref().map(|args| {
+                    args.item
+                        .iter()
+                        .map(|(k, v)| (k.item, v.item.clone().into_const(vars)))
+                        .collect()
+                }),
+                &registered.arguments,
+            );
+            Some((&*registered.directive, arguments))
+        })
+    }
+}
+
+fn bind<'b, CtxT, S>(directives: BoundDirectives<'b, CtxT, S>) -> Arc<dyn FieldDirectives<S> + 'b>
+where
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    Arc::new(directives)
+}
+
+/// The executable directives of a schema, bound to the context of an operation
+pub(crate) struct BoundDirectives<'a, CtxT, S> {
+    directives: &'a ExecutableDirectives<'a, CtxT, S>,
+    context: &'a CtxT,
+}
+
+/// The executable directives of a schema, with the type of the context they
+/// were registered for erased
+///
+/// The hooks receive an executor with the context replaced by `()`, and run
+/// the directives with the context of the operation instead.
+pub(crate) trait FieldDirectives<S>: Send + Sync {
+    fn is_registered(&self, name: &str) -> bool;
+
+    fn before_field(
+        &self,
+        attached: &[Spanning<Directive<S>>],
+        executor: &Executor<(), S>,
+    ) -> FieldResult<(), S>;
+
+    fn after_field(
+        &self,
+        attached: &[Spanning<Directive<S>>],
+        executor: &Executor<(), S>,
+        value: Value<S>,
+    ) -> ExecutionResult<S>;
+}
+
+impl<'a, CtxT, S> FieldDirectives<S> for BoundDirectives<'a, CtxT, S>
+where
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn is_registered(&self, name: &str) -> bool {
+        self.directives.directives.contains_key(name)
+    }
+
+    fn before_field(
+        &self,
+        attached: &[Spanning<Directive<S>>],
+        executor: &Executor<(), S>,
+    ) -> FieldResult<(), S> {
+        let executor = executor.replaced_context(self.context);
+        for (directive, arguments) in self.directives.applied(attached, executor.variables()) {
+            directive.before_field(&arguments, &executor)?;
+        }
+        Ok(())
+    }
+
+    fn after_field(
+        &self,
+        attached: &[Spanning<Directive<S>>],
+        executor: &Executor<(), S>,
+        mut value: Value<S>,
+    ) -> ExecutionResult<S> {
+        let executor = executor.replaced_context(self.context);
+        for (directive, arguments) in self.directives.applied(attached, executor.variables()) {
+            value = directive.after_field(&arguments, &executor, value)?;
+        }
+        Ok(value)
+    }
+}
+
+/// The executable directives attached to a field
+pub(crate) struct AppliedDirectives<'e, S> {
+    registered: Option<&'e dyn FieldDirectives<S>>,
+    attached: &'e [Spanning<Directive<'e, S>>],
+}
+
+impl<'e, S> AppliedDirectives<'e, S>
+where
+    S: ScalarValue,
+{
+    pub(crate) fn new<'a, CtxT>(
+        attached: &'e Option<Vec<Spanning<Directive<'a, S>>>>,
+        executor: &'e Executor<'_, 'a, CtxT, S>,
+    ) -> Self
+    where
+        'a: 'e,
+    {
+        let attached = attached.as_ref().map(|d| &d[..]).unwrap_or_default();
+        let registered = executor.executable_directives().filter(|registered| {
+            attached
+                .iter()
+                .any(|d| registered.is_registered(d.item.name.item))
+        });
+        AppliedDirectives {
+            registered,
+            attached,
+        }
+    }
+
+    pub(crate) fn before_field<CtxT>(&self, executor: &Executor<CtxT, S>) -> FieldResult<(), S> {
+        match self.registered {
+            Some(registered) => {
+                registered.before_field(self.attached, &executor.replaced_context(&()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn after_field<CtxT>(
+        &self,
+        executor: &Executor<CtxT, S>,
+        value: Value<S>,
+    ) -> ExecutionResult<S> {
+        match self.registered {
+            Some(registered) => {
+                registered.after_field(self.attached, &executor.replaced_context(&()), value)
+            }
+            None => Ok(value),
+        }
+    }
+}
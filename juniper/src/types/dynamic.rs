@@ -0,0 +1,862 @@
+//! Types of schemas that are built at runtime from an SDL document
+
+use std::{any::Any, borrow::Cow, fmt, marker::PhantomData, sync::Arc};
+
+use fnv::FnvHashMap;
+
+use crate::{
+    ast::{
+        ConstDirective, FieldDefinition, FromInputValue, InputValue, InputValueDefinition,
+        Selection, Type, TypeDefinition, TypeDefinitionKind,
+    },
+    executor::{ExecutionResult, Executor, FieldError, FieldResult, Registry},
+    parser::{parse_scalar_literal, ScalarToken, Spanning},
+    schema::{
+        meta::{Argument, EnumMeta, EnumValue, Field, InputObjectMeta, MetaType, ScalarMeta},
+        model::RootNode,
+    },
+    types::{
+        async_await::{resolve_selection_set_into_async, GraphQLTypeAsync},
+        base::{resolve_selection_set_into, Arguments, GraphQLType},
+        containers::{resolve_into_list, resolve_into_list_async},
+        scalars::ID,
+    },
+    value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+/// Root node of a schema built from an SDL document
+pub type DynamicRootNode<'a, CtxT, S = DefaultScalarValue> =
+    RootNode<'a, Dynamic<CtxT, S>, Dynamic<CtxT, S>, Dynamic<CtxT, S>, S>;
+
+pub(crate) type FieldResolver<CtxT, S> = Box<
+    dyn Fn(&DynamicValue<S>, &Arguments<S>, &Executor<CtxT, S>) -> FieldResult<DynamicValue<S>, S>
+        + Send
+        + Sync,
+>;
+
+/// A value in a schema built from an SDL document
+///
+/// Field resolvers return these. Plain data is kept as a `Value`, and the
+/// fields of an object value are resolved from its entries unless a resolver
+/// was registered for them. Any other Rust data can be carried as an opaque
+/// value, which only registered resolvers know how to look into.
+#[derive(Clone)]
+pub enum DynamicValue<S = DefaultScalarValue> {
+    /// Plain GraphQL data
+    Value(Value<S>),
+    /// Arbitrary Rust data, see `DynamicValue::downcast_ref`
+    Opaque(Arc<dyn Any + Send + Sync>),
+    /// A list of values
+    List(Vec<DynamicValue<S>>),
+    /// A value tagged with the name of its concrete object type
+    ///
+    /// Values of interface or union fields need to be tagged, unless they are
+    /// objects with a `__typename` entry.
+    Typed(String, Box<DynamicValue<S>>),
+}
+
+impl<S> DynamicValue<S>
+where
+    S: ScalarValue,
+{
+    /// Construct a null value
+    pub fn null() -> Self {
+        DynamicValue::Value(Value::Null)
+    }
+
+    /// Wrap arbitrary Rust data
+    pub fn opaque<T>(value: T) -> Self
+    where
+        T: Any + Send + Sync,
+    {
+        DynamicValue::Opaque(Arc::new(value))
+    }
+
+    /// Tag the value with the name of its concrete object type
+    pub fn typed(self, type_name: &str) -> Self {
+        DynamicValue::Typed(type_name.to_owned(), Box::new(self))
+    }
+
+    /// Does this value represent null?
+    pub fn is_null(&self) -> bool {
+        match *self {
+            DynamicValue::Value(ref v) => v.is_null(),
+            DynamicValue::Typed(_, ref v) => v.is_null(),
+            _ => false,
+        }
+    }
+
+    /// View the underlying plain data, if present.
+    pub fn as_value(&self) -> Option<&Value<S>> {
+        match *self {
+            DynamicValue::Value(ref v) => Some(v),
+            DynamicValue::Typed(_, ref v) => v.as_value(),
+            _ => None,
+        }
+    }
+
+    /// View the underlying opaque data, if it is of type `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: Any,
+    {
+        match *self {
+            DynamicValue::Opaque(ref v) => v.downcast_ref(),
+            DynamicValue::Typed(_, ref v) => v.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    fn concrete_type_name(&self) -> Option<&str> {
+        match *self {
+            DynamicValue::Typed(ref name, _) => Some(name),
+            DynamicValue::Value(ref v) => v
+                .as_object_value()
+                .and_then(|o| o.get_field_value("__typename"))
+                .and_then(Value::as_scalar)
+                .and_then(ScalarValue::as_str),
+            _ => None,
+        }
+    }
+
+    fn field(&self, field_name: &str) -> Option<DynamicValue<S>> {
+        match *self {
+            DynamicValue::Value(Value::Object(ref o)) => Some(DynamicValue::Value(
+                o.get_field_value(field_name)
+                    .cloned()
+                    .unwrap_or(Value::Null),
+            )),
+            DynamicValue::Typed(_, ref v) => v.field(field_name),
+            _ => None,
+        }
+    }
+
+    fn list_items(&self) -> Option<Vec<DynamicValue<S>>> {
+        match *self {
+            DynamicValue::List(ref items) => Some(items.clone()),
+            DynamicValue::Value(Value::List(ref items)) => {
+                Some(items.iter().cloned().map(DynamicValue::Value).collect())
+            }
+            DynamicValue::Typed(_, ref v) => v.list_items(),
+            _ => None,
+        }
+    }
+}
+
+impl<S> From<Value<S>> for DynamicValue<S> {
+    fn from(value: Value<S>) -> Self {
+        DynamicValue::Value(value)
+    }
+}
+
+impl<S> fmt::Debug for DynamicValue<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DynamicValue::Value(ref v) => f.debug_tuple("Value").field(v).finish(),
+            DynamicValue::Opaque(_) => f.write_str("Opaque(..)"),
+            DynamicValue::List(ref l) => f.debug_tuple("List").field(l).finish(),
+            DynamicValue::Typed(ref n, ref v) => f.debug_tuple("Typed").field(n).field(v).finish(),
+        }
+    }
+}
+
+/// Definitions and resolvers shared by all types of a schema built from SDL
+pub(crate) struct DynamicSchema<CtxT, S> {
+    pub(crate) types: FnvHashMap<String, TypeDefinition<S>>,
+    pub(crate) query_type: String,
+    pub(crate) resolvers: FnvHashMap<String, FnvHashMap<String, FieldResolver<CtxT, S>>>,
+}
+
+impl<CtxT, S> DynamicSchema<CtxT, S>
+where
+    S: ScalarValue,
+{
+    fn field_type(&self, type_name: &str, field_name: &str) -> &Type<'static> {
+        let fields = match self.types.get(type_name).map(|t| &t.kind) {
+            Some(TypeDefinitionKind::Object { fields, .. })
+            | Some(TypeDefinitionKind::Interface { fields }) => fields,
+            _ => panic!("Type {} has no fields", type_name),
+        };
+
+        &fields
+            .iter()
+            .find(|f| f.name.item == field_name)
+            .unwrap_or_else(|| panic!("Field {} not found on type {}", field_name, type_name))
+            .field_type
+            .item
+    }
+
+    fn interfaces(&self, type_name: &str) -> &[Spanning<String>] {
+        match self.types.get(type_name).map(|t| &t.kind) {
+            Some(TypeDefinitionKind::Object { interfaces, .. }) => interfaces,
+            _ => &[],
+        }
+    }
+
+    fn is_possible_type(&self, abstract_type: &str, concrete_type: &str) -> bool {
+        match self.types.get(abstract_type).map(|t| &t.kind) {
+            Some(TypeDefinitionKind::Union { members }) => {
+                members.iter().any(|m| m.item == concrete_type)
+            }
+            Some(TypeDefinitionKind::Interface { .. }) => self
+                .interfaces(concrete_type)
+                .iter()
+                .any(|i| i.item == abstract_type),
+            _ => false,
+        }
+    }
+
+    fn implementors<'s>(&'s self, interface: &'s str) -> impl Iterator<Item = &'s str> + 's {
+        self.types
+            .keys()
+            .filter(move |t| self.is_possible_type(interface, t))
+            .map(String::as_str)
+    }
+
+    // Resolvers registered for interface fields are used by all implementors
+    // that don't have a resolver of their own.
+    fn field_resolver(&self, type_name: &str, field_name: &str) -> Option<&FieldResolver<CtxT, S>> {
+        let lookup = |t: &str| self.resolvers.get(t).and_then(|r| r.get(field_name));
+
+        lookup(type_name).or_else(|| {
+            self.interfaces(type_name)
+                .iter()
+                .filter_map(|i| lookup(&i.item))
+                .next()
+        })
+    }
+
+    fn resolve_field(
+        &self,
+        type_name: &str,
+        field_name: &str,
+        parent: &DynamicValue<S>,
+        arguments: &Arguments<S>,
+        executor: &Executor<CtxT, S>,
+    ) -> FieldResult<DynamicValue<S>, S> {
+        match self.field_resolver(type_name, field_name) {
+            Some(resolver) => resolver(parent, arguments, executor),
+            None => parent.field(field_name).ok_or_else(|| {
+                FieldError::from(format!(
+                    "No resolver registered for field \"{}.{}\"",
+                    type_name, field_name
+                ))
+            }),
+        }
+    }
+}
+
+/// Type info of the types of a schema built from an SDL document
+///
+/// Points at the GraphQL type a value is resolved as, and shares the
+/// definitions and resolvers of the whole schema.
+pub struct DynamicTypeInfo<CtxT, S = DefaultScalarValue> {
+    schema: Arc<DynamicSchema<CtxT, S>>,
+    ty: Type<'static>,
+}
+
+impl<CtxT, S> DynamicTypeInfo<CtxT, S> {
+    pub(crate) fn new(schema: Arc<DynamicSchema<CtxT, S>>, ty: Type<'static>) -> Self {
+        DynamicTypeInfo { schema, ty }
+    }
+
+    fn with_type(&self, ty: Type<'static>) -> Self {
+        DynamicTypeInfo {
+            schema: self.schema.clone(),
+            ty,
+        }
+    }
+
+    fn named(&self, name: &str) -> Self {
+        self.with_type(Type::NonNullNamed(Cow::Owned(name.to_owned())))
+    }
+
+    fn non_null(&self) -> Self {
+        self.with_type(match self.ty {
+            Type::Named(ref n) => Type::NonNullNamed(n.clone()),
+            Type::List(ref l) => Type::NonNullList(l.clone()),
+            ref t => t.clone(),
+        })
+    }
+
+    fn type_name(&self) -> &str {
+        match self.ty {
+            Type::NonNullNamed(ref n) => n,
+            ref t => panic!("Type {} is not a named type", t),
+        }
+    }
+
+    fn definition(&self) -> Option<&TypeDefinition<S>> {
+        self.schema.types.get(self.type_name())
+    }
+}
+
+impl<CtxT, S> Clone for DynamicTypeInfo<CtxT, S> {
+    fn clone(&self) -> Self {
+        self.with_type(self.ty.clone())
+    }
+}
+
+impl<CtxT, S> fmt::Debug for DynamicTypeInfo<CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DynamicTypeInfo")
+            .field("ty", &self.ty)
+            .finish()
+    }
+}
+
+/// The GraphQL type of all values of a schema built from an SDL document
+///
+/// Which GraphQL type a value is resolved as is decided by its
+/// `DynamicTypeInfo`.
+pub struct Dynamic<CtxT, S = DefaultScalarValue> {
+    value: DynamicValue<S>,
+    _context: PhantomData<fn() -> CtxT>,
+}
+
+impl<CtxT, S> Dynamic<CtxT, S> {
+    pub(crate) fn new(value: DynamicValue<S>) -> Self {
+        Dynamic {
+            value,
+            _context: PhantomData,
+        }
+    }
+
+    /// The value of the root types, which has no data, so that all their
+    /// fields need a resolver.
+    pub(crate) fn root() -> Self {
+        Dynamic::new(DynamicValue::Opaque(Arc::new(())))
+    }
+
+    fn concrete_type_name_in(&self, info: &DynamicTypeInfo<CtxT, S>) -> FieldResult<String, S>
+    where
+        S: ScalarValue,
+    {
+        let type_name = info.type_name();
+
+        match info.definition().map(|d| &d.kind) {
+            Some(TypeDefinitionKind::Interface { .. }) | Some(TypeDefinitionKind::Union { .. }) => {
+                match self.value.concrete_type_name() {
+                    Some(concrete) if info.schema.is_possible_type(type_name, concrete) => {
+                        Ok(concrete.to_owned())
+                    }
+                    Some(concrete) => Err(FieldError::from(format!(
+                        "Type \"{}\" is not a possible type of \"{}\"",
+                        concrete, type_name
+                    ))),
+                    None => Err(FieldError::from(format!(
+                        "Unable to determine the concrete type of a \"{}\" value",
+                        type_name
+                    ))),
+                }
+            }
+            _ => Ok(type_name.to_owned()),
+        }
+    }
+
+    // The values of interface and union fields are checked as soon as the
+    // field is resolved, so that an untyped or impossible value is reported
+    // as a field error instead of reaching `concrete_type_name`.
+    fn check_concrete_types(&self, info: &DynamicTypeInfo<CtxT, S>) -> FieldResult<(), S>
+    where
+        S: ScalarValue,
+    {
+        if self.value.is_null() {
+            return Ok(());
+        }
+
+        match info.ty {
+            Type::Named(_) | Type::List(_) => self.check_concrete_types(&info.non_null()),
+            Type::NonNullList(ref inner) => {
+                let item_info = info.with_type((**inner).clone());
+                self.list_items(info)?
+                    .iter()
+                    .try_for_each(|item| item.check_concrete_types(&item_info))
+            }
+            Type::NonNullNamed(_) => self.concrete_type_name_in(info).map(|_| ()),
+        }
+    }
+
+    fn leaf_value(&self, info: &DynamicTypeInfo<CtxT, S>) -> ExecutionResult<S>
+    where
+        S: ScalarValue,
+    {
+        self.value.as_value().cloned().ok_or_else(|| {
+            FieldError::from(format!(
+                "Expected a plain value for type \"{}\"",
+                info.type_name()
+            ))
+        })
+    }
+
+    fn list_items(&self, info: &DynamicTypeInfo<CtxT, S>) -> FieldResult<Vec<Self>, S>
+    where
+        S: ScalarValue,
+    {
+        self.value
+            .list_items()
+            .map(|items| items.into_iter().map(Dynamic::new).collect())
+            .ok_or_else(|| FieldError::from(format!("Expected a list for type \"{}\"", info.ty)))
+    }
+
+    fn possible_type_info(
+        &self,
+        info: &DynamicTypeInfo<CtxT, S>,
+        type_name: &str,
+    ) -> FieldResult<Option<DynamicTypeInfo<CtxT, S>>, S>
+    where
+        S: ScalarValue,
+    {
+        let concrete = self.concrete_type_name_in(info)?;
+
+        if concrete == type_name || info.schema.is_possible_type(type_name, &concrete) {
+            Ok(Some(info.named(&concrete)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<CtxT, S> fmt::Debug for Dynamic<CtxT, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Dynamic").field(&self.value).finish()
+    }
+}
+
+impl<S, CtxT> GraphQLType<S> for Dynamic<CtxT, S>
+where
+    S: ScalarValue,
+{
+    type Context = CtxT;
+    type TypeInfo = DynamicTypeInfo<CtxT, S>;
+
+    fn name(info: &Self::TypeInfo) -> Option<&str> {
+        match info.ty {
+            Type::NonNullNamed(ref n) => Some(n),
+            _ => None,
+        }
+    }
+
+    fn meta<'r>(info: &Self::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        match info.ty {
+            Type::Named(_) | Type::List(_) => registry
+                .build_nullable_type::<Self>(&info.non_null())
+                .into_meta(),
+            Type::NonNullList(ref inner) => registry
+                .build_list_type::<Self>(&info.with_type((**inner).clone()))
+                .into_meta(),
+            Type::NonNullNamed(_) => build_named_meta(info, registry),
+        }
+    }
+
+    fn resolve_field(
+        &self,
+        info: &Self::TypeInfo,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        let type_name = info.type_name();
+        let field_info = info.with_type(info.schema.field_type(type_name, field_name).clone());
+        let value = Dynamic::new(info.schema.resolve_field(
+            type_name,
+            field_name,
+            &self.value,
+            arguments,
+            executor,
+        )?);
+        value.check_concrete_types(&field_info)?;
+
+        executor.resolve(&field_info, &value)
+    }
+
+    fn resolve_into_type(
+        &self,
+        info: &Self::TypeInfo,
+        type_name: &str,
+        selection_set: Option<&[Selection<S>]>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        match self.possible_type_info(info, type_name)? {
+            Some(concrete_info) => self.resolve(&concrete_info, selection_set, executor),
+            None => Ok(Value::null()),
+        }
+    }
+
+    fn concrete_type_name(&self, _: &Self::Context, info: &Self::TypeInfo) -> String {
+        // Checked by `check_concrete_types` when the field was resolved.
+        self.concrete_type_name_in(info)
+            .unwrap_or_else(|_| info.type_name().to_owned())
+    }
+
+    fn resolve(
+        &self,
+        info: &Self::TypeInfo,
+        selection_set: Option<&[Selection<S>]>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        if self.value.is_null() {
+            return Ok(Value::null());
+        }
+
+        match info.ty {
+            Type::Named(_) | Type::List(_) => {
+                self.resolve(&info.non_null(), selection_set, executor)
+            }
+            Type::NonNullList(ref inner) => {
+                let items = self.list_items(info)?;
+                resolve_into_list(executor, &info.with_type((**inner).clone()), items.iter())
+            }
+            Type::NonNullNamed(_) => match info.definition().map(|d| &d.kind) {
+                Some(TypeDefinitionKind::Object { .. }) => {
+                    let selection_set = selection_set.expect("Object types need a selection set");
+                    let mut result = Object::with_capacity(selection_set.len());
+                    if resolve_selection_set_into(self, info, selection_set, executor, &mut result)
+                    {
+                        Ok(Value::Object(result))
+                    } else {
+                        Ok(Value::null())
+                    }
+                }
+                Some(TypeDefinitionKind::Interface { .. })
+                | Some(TypeDefinitionKind::Union { .. }) => {
+                    let concrete = self.concrete_type_name_in(info)?;
+                    self.resolve(&info.named(&concrete), selection_set, executor)
+                }
+                _ => self.leaf_value(info),
+            },
+        }
+    }
+}
+
+impl<S, CtxT> GraphQLTypeAsync<S> for Dynamic<CtxT, S>
+where
+    S: ScalarValue + Send + Sync,
+    CtxT: Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        let type_name = info.type_name();
+        let field_info = info.with_type(info.schema.field_type(type_name, field_name).clone());
+        let value =
+            info.schema
+                .resolve_field(type_name, field_name, &self.value, arguments, executor);
+
+        Box::pin(async move {
+            let value = Dynamic::new(value?);
+            value.check_concrete_types(&field_info)?;
+            executor.resolve_async(&field_info, &value).await
+        })
+    }
+
+    fn resolve_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        selection_set: Option<&'a [Selection<S>]>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        if self.value.is_null() {
+            return Box::pin(futures::future::ready(Ok(Value::null())));
+        }
+
+        match info.ty {
+            Type::Named(_) | Type::List(_) => Box::pin(async move {
+                self.resolve_async(&info.non_null(), selection_set, executor)
+                    .await
+            }),
+            Type::NonNullList(ref inner) => Box::pin(async move {
+                let items = self.list_items(info)?;
+                let item_info = info.with_type((**inner).clone());
+                resolve_into_list_async(executor, &item_info, items.iter()).await
+            }),
+            Type::NonNullNamed(_) => match info.definition().map(|d| &d.kind) {
+                Some(TypeDefinitionKind::Object { .. }) => {
+                    let selection_set = selection_set.expect("Object types need a selection set");
+                    Box::pin(async move {
+                        Ok(
+                            resolve_selection_set_into_async(self, info, selection_set, executor)
+                                .await,
+                        )
+                    })
+                }
+                Some(TypeDefinitionKind::Interface { .. })
+                | Some(TypeDefinitionKind::Union { .. }) => Box::pin(async move {
+                    let concrete_info = info.named(&self.concrete_type_name_in(info)?);
+                    self.resolve_async(&concrete_info, selection_set, executor)
+                        .await
+                }),
+                _ => Box::pin(futures::future::ready(self.leaf_value(info))),
+            },
+        }
+    }
+
+    fn resolve_into_type_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        type_name: &str,
+        selection_set: Option<&'a [Selection<'a, S>]>,
+        executor: &'a Executor<'a, 'a, Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        let concrete_info = self.possible_type_info(info, type_name);
+
+        Box::pin(async move {
+            match concrete_info? {
+                Some(concrete_info) => {
+                    self.resolve_async(&concrete_info, selection_set, executor)
+                        .await
+                }
+                None => Ok(Value::null()),
+            }
+        })
+    }
+}
+
+fn build_named_meta<'r, CtxT, S>(
+    info: &DynamicTypeInfo<CtxT, S>,
+    registry: &mut Registry<'r, S>,
+) -> MetaType<'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    let name = info.type_name();
+    let definition = match info.definition() {
+        Some(definition) => definition,
+        None => {
+            return match name {
+                "Boolean" => <bool as GraphQLType<S>>::meta(&(), registry),
+                "Float" => <f64 as GraphQLType<S>>::meta(&(), registry),
+                "ID" => <ID as GraphQLType<S>>::meta(&(), registry),
+                "Int" => <i32 as GraphQLType<S>>::meta(&(), registry),
+                "String" => <String as GraphQLType<S>>::meta(&(), registry),
+                // Missing mutation and subscription roots are represented by
+                // empty `_EmptyMutation` and `_EmptySubscription` objects.
+                _ => registry
+                    .build_object_type::<Dynamic<CtxT, S>>(info, &[])
+                    .into_meta(),
+            };
+        }
+    };
+    let description = definition.description.as_ref();
+
+    match definition.kind {
+        TypeDefinitionKind::Scalar => {
+            let meta = ScalarMeta::new::<AnyInput>(Cow::Owned(name.to_owned()));
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+        TypeDefinitionKind::Object {
+            ref interfaces,
+            ref fields,
+        } => {
+            // Register every type of the document along with the query type,
+            // so types that aren't reachable from the roots are part of the
+            // schema too.
+            if name == info.schema.query_type {
+                for type_name in info.schema.types.keys() {
+                    registry.get_type::<Dynamic<CtxT, S>>(&info.named(type_name));
+                }
+            }
+
+            let fields = fields
+                .iter()
+                .map(|f| build_field(info, f, registry))
+                .collect::<Vec<_>>();
+            let interfaces = interfaces
+                .iter()
+                .map(|i| registry.get_type::<Dynamic<CtxT, S>>(&info.named(&i.item)))
+                .collect::<Vec<_>>();
+
+            let meta = registry
+                .build_object_type::<Dynamic<CtxT, S>>(info, &fields)
+                .interfaces(&interfaces);
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+        TypeDefinitionKind::Interface { ref fields } => {
+            let fields = fields
+                .iter()
+                .map(|f| build_field(info, f, registry))
+                .collect::<Vec<_>>();
+
+            for implementor in info.schema.implementors(name) {
+                registry.get_type::<Dynamic<CtxT, S>>(&info.named(implementor));
+            }
+
+            let meta = registry.build_interface_type::<Dynamic<CtxT, S>>(info, &fields);
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+        TypeDefinitionKind::Union { ref members } => {
+            let members = members
+                .iter()
+                .map(|m| registry.get_type::<Dynamic<CtxT, S>>(&info.named(&m.item)))
+                .collect::<Vec<_>>();
+
+            let meta = registry.build_union_type::<Dynamic<CtxT, S>>(info, &members);
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+        TypeDefinitionKind::Enum { ref values } => {
+            let values = values
+                .iter()
+                .map(|v| {
+                    let value = EnumValue::new(&v.name.item);
+                    let value = match v.description {
+                        Some(ref d) => value.description(d),
+                        None => value,
+                    };
+                    match deprecation_reason(&v.directives) {
+                        Some(reason) => value.deprecated(reason),
+                        None => value,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let meta = EnumMeta::new::<AnyInput>(Cow::Owned(name.to_owned()), &values);
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+        TypeDefinitionKind::InputObject { ref fields } => {
+            let fields = fields
+                .iter()
+                .map(|f| build_argument(info, f, registry))
+                .collect::<Vec<_>>();
+
+            let meta = InputObjectMeta::new::<AnyInput>(Cow::Owned(name.to_owned()), &fields);
+            let meta = if definition
+                .directives
+                .iter()
+                .any(|d| d.item.name.item == "oneOf")
+            {
+                meta.one_of()
+            } else {
+                meta
+            };
+            match description {
+                Some(d) => meta.description(d),
+                None => meta,
+            }
+            .into_meta()
+        }
+    }
+}
+
+fn build_field<'r, CtxT, S>(
+    info: &DynamicTypeInfo<CtxT, S>,
+    definition: &FieldDefinition<S>,
+    registry: &mut Registry<'r, S>,
+) -> Field<'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    let field = registry.field::<Dynamic<CtxT, S>>(
+        &definition.name.item,
+        &info.with_type(definition.field_type.item.clone()),
+    );
+    let field = definition
+        .arguments
+        .iter()
+        .map(|a| build_argument(info, a, registry))
+        .fold(field, Field::argument);
+    let field = match definition.description {
+        Some(ref d) => field.description(d),
+        None => field,
+    };
+    match deprecation_reason(&definition.directives) {
+        Some(reason) => field.deprecated(reason),
+        None => field,
+    }
+}
+
+fn build_argument<'r, CtxT, S>(
+    info: &DynamicTypeInfo<CtxT, S>,
+    definition: &InputValueDefinition<S>,
+    registry: &mut Registry<'r, S>,
+) -> Argument<'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    let arg_type =
+        registry.get_type::<Dynamic<CtxT, S>>(&info.with_type(definition.value_type.item.clone()));
+    let argument = Argument::new(&definition.name.item, arg_type);
+    let argument = match definition.description {
+        Some(ref d) => argument.description(d),
+        None => argument,
+    };
+    match definition.default_value {
+        Some(ref v) => argument.default_value(v.item.clone()),
+        None => argument,
+    }
+}
+
+/// The reason given by a `@deprecated` directive, if there is one.
+///
+/// Returns `Some(None)` for a `@deprecated` directive without a reason.
+pub(crate) fn deprecation_reason<S>(
+    directives: &[Spanning<ConstDirective<S>>],
+) -> Option<Option<&str>>
+where
+    S: ScalarValue,
+{
+    directives
+        .iter()
+        .find(|d| d.item.name.item == "deprecated")
+        .map(|d| {
+            d.item
+                .arguments
+                .iter()
+                .find(|&&(ref name, _)| name.item == "reason")
+                .and_then(|&(_, ref value)| value.item.as_string_value())
+        })
+}
+
+/// Parsing of input values of types defined in SDL documents
+///
+/// Any input is accepted here: values are checked against the input types'
+/// definitions during validation, using the schema's metadata.
+struct AnyInput;
+
+impl<S> FromInputValue<S> for AnyInput {
+    fn from_input_value(_: &InputValue<S>) -> Option<AnyInput> {
+        Some(AnyInput)
+    }
+}
+
+impl<S> ParseScalarValue<S> for AnyInput
+where
+    S: ScalarValue,
+{
+    fn from_str(value: ScalarToken<'_>) -> ParseScalarResult<'_, S> {
+        parse_scalar_literal(value)
+    }
+}
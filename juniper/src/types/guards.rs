@@ -0,0 +1,108 @@
+use crate::{
+    executor::{ExecutionResult, Executor, FieldError, FieldResult},
+    types::base::Arguments,
+    value::{DefaultScalarValue, ScalarValue, Value},
+};
+
+/// A check that runs before a field is resolved, like an authorization rule
+///
+/// Attach a guard to a field with the `guard` attribute of `#[graphql_object]`,
+/// `#[graphql_subscription]` and `#[derive(GraphQLObject)]`. The attribute
+/// takes any expression implementing this trait. Functions and closures with a
+/// matching signature implement it, so a guard can be shared by many fields:
+///
+/// ```rust
+/// use juniper::{graphql_object, Arguments, FieldError, FieldResult, Value};
+///
+/// struct Context {
+///     is_admin: bool,
+/// }
+///
+/// impl juniper::Context for Context {}
+///
+/// fn admin_only(context: &Context, _: &Arguments) -> FieldResult<bool> {
+///     if context.is_admin {
+///         Ok(true)
+///     } else {
+///         Err(FieldError::new("Forbidden", Value::null()))
+///     }
+/// }
+///
+/// struct Query;
+///
+/// #[graphql_object(Context = Context)]
+/// impl Query {
+///     #[graphql(guard = admin_only)]
+///     fn secret() -> i32 {
+///         42
+///     }
+/// }
+/// ```
+///
+/// Objects deriving `GraphQLObject` are generic over the scalar value, unless
+/// one is given with the `scalar` attribute, so their guards need to be
+/// generic as well.
+///
+/// Hand-written implementations of `GraphQLType` can guard their fields with
+/// [`resolve_guarded`](fn.resolve_guarded.html).
+pub trait Guard<CtxT, S = DefaultScalarValue>
+where
+    S: ScalarValue,
+{
+    /// Whether the field may be resolved, given the context and the
+    /// arguments of the field
+    ///
+    /// If the check returns `Ok(false)`, the field resolves to null without
+    /// an error. If it returns an error, the error is reported like any other
+    /// field error.
+    fn check(&self, context: &CtxT, arguments: &Arguments<S>) -> FieldResult<bool, S>;
+}
+
+impl<F, CtxT, S> Guard<CtxT, S> for F
+where
+    F: Fn(&CtxT, &Arguments<S>) -> FieldResult<bool, S>,
+    S: ScalarValue,
+{
+    fn check(&self, context: &CtxT, arguments: &Arguments<S>) -> FieldResult<bool, S> {
+        self(context, arguments)
+    }
+}
+
+/// Resolve a field with `resolve` if `guard` allows it
+///
+/// Otherwise, the field resolves to null or to the error of the guard.
+pub fn resolve_guarded<G, F, CtxT, S>(
+    guard: &G,
+    arguments: &Arguments<S>,
+    executor: &Executor<CtxT, S>,
+    resolve: F,
+) -> ExecutionResult<S>
+where
+    G: Guard<CtxT, S> + ?Sized,
+    F: FnOnce() -> ExecutionResult<S>,
+    S: ScalarValue,
+{
+    match check_guard(guard, executor.context(), arguments) {
+        Ok(()) => resolve(),
+        Err(denied) => denied,
+    }
+}
+
+/// Run the check of a guard, returning the result of the field if the check
+/// doesn't pass
+#[doc(hidden)]
+pub fn check_guard<G, CtxT, S, T>(
+    guard: &G,
+    context: &CtxT,
+    arguments: &Arguments<S>,
+) -> Result<(), Result<Value<T>, FieldError<S>>>
+where
+    G: Guard<CtxT, S> + ?Sized,
+    S: ScalarValue,
+{
+    match guard.check(context, arguments) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Ok(Value::Null)),
+        Err(e) => Err(Err(e)),
+    }
+}
@@ -1,6 +1,9 @@
 pub mod async_await;
 pub mod base;
 pub mod containers;
+pub mod directives;
+pub mod dynamic;
+pub mod guards;
 pub mod marker;
 pub mod name;
 pub mod pointers;
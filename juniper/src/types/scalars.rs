@@ -59,6 +59,7 @@ where
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
         match value {
             ScalarToken::String(value) | ScalarToken::Int(value) => Ok(S::from(value.to_owned())),
+            ScalarToken::BlockString(value) => Ok(S::from(block_string_value(value))),
             _ => Err(ParseError::UnexpectedToken(Token::Scalar(value))),
         }
     }
@@ -127,12 +128,56 @@ where
                 }
             }
             Ok(ret.into())
+        } else if let ScalarToken::BlockString(value) = value {
+            Ok(block_string_value(value).into())
         } else {
             Err(ParseError::UnexpectedToken(Token::Scalar(value)))
         }
     }
 }
 
+/// The value of a block string, given its raw content
+///
+/// Escaped triple quotes are unescaped, the indentation common to all lines
+/// but the first is removed, and so are leading and trailing blank lines, as
+/// defined by the `BlockStringValue` algorithm of the specification.
+fn block_string_value(raw: &str) -> String {
+    fn indent(line: &str) -> usize {
+        line.len() - line.trim_start_matches(&[' ', '\t'][..]).len()
+    }
+    fn is_blank(line: &str) -> bool {
+        indent(line) == line.len()
+    }
+
+    let raw = raw.replace("\\\"\"\"", "\"\"\"");
+    let lines: Vec<&str> = raw
+        .split("\r\n")
+        .flat_map(|line| line.split(&['\n', '\r'][..]))
+        .collect();
+
+    let common_indent = lines[1..]
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| indent(line))
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => *line,
+            _ => line.get(common_indent..).unwrap_or(""),
+        })
+        .collect();
+
+    let first = lines.iter().position(|line| !is_blank(line));
+    let last = lines.iter().rposition(|line| !is_blank(line));
+    match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].join("\n"),
+        _ => String::new(),
+    }
+}
+
 fn parse_unicode_codepoint<'a, I>(char_iter: &mut I) -> Result<char, ParseError<'a>>
 where
     I: Iterator<Item = char>,
@@ -315,7 +360,9 @@ where
                 .parse()
                 .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
                 .map(|s: f64| s.into()),
-            ScalarToken::String(_) => Err(ParseError::UnexpectedToken(Token::Scalar(value))),
+            ScalarToken::String(_) | ScalarToken::BlockString(_) => {
+                Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+            }
         }
     }
 }
@@ -448,6 +495,32 @@ mod tests {
         assert_eq!(id.len(), 3);
     }
 
+    #[test]
+    fn parse_block_strings() {
+        fn parse_block_string(s: &str, expected: &str) {
+            let s = <String as ParseScalarValue<DefaultScalarValue>>::from_str(
+                ScalarToken::BlockString(s),
+            );
+            assert!(s.is_ok(), "A parsing error occurred: {:?}", s);
+            let s: Option<String> = s.unwrap().into();
+            assert!(s.is_some(), "No string returned");
+            assert_eq!(s.unwrap(), expected);
+        }
+
+        parse_block_string("simple", "simple");
+        parse_block_string(r#"not \n escaped"#, r#"not \n escaped"#);
+        parse_block_string(r#"escaped \""" quotes"#, r#"escaped """ quotes"#);
+        parse_block_string(
+            "\n    Hello,\n      World!\n\n    Yours,\n      GraphQL.\n  ",
+            "Hello,\n  World!\n\nYours,\n  GraphQL.",
+        );
+        parse_block_string(
+            "first line\n    indented\r\n    lines",
+            "first line\nindented\nlines",
+        );
+        parse_block_string("  \n\t\n", "");
+    }
+
     #[test]
     fn parse_strings() {
         fn parse_string(s: &str, expected: &str) {
@@ -39,6 +39,14 @@ where
                 return false;
             }
 
+            if let (&InputValue::Enum(ref name), &MetaType::Enum(EnumMeta { ref values, .. })) =
+                (arg_value, t)
+            {
+                if !values.iter().any(|v| &v.name == name) {
+                    return false;
+                }
+            }
+
             match *arg_value {
                 InputValue::Null | InputValue::Variable(_) => true,
                 ref v @ InputValue::Scalar(_) | ref v @ InputValue::Enum(_) => {
@@ -48,12 +56,25 @@ where
                         false
                     }
                 }
+                // Custom scalars can accept lists and objects, like the
+                // `_Any` scalar of Apollo Federation.
+                ref v @ InputValue::List(_) | ref v @ InputValue::Object(_) if t.is_leaf() => {
+                    t.input_value_parse_fn().map(|f| f(v)).unwrap_or(false)
+                }
                 InputValue::List(_) => false,
                 InputValue::Object(ref obj) => {
                     if let MetaType::InputObject(InputObjectMeta {
-                        ref input_fields, ..
+                        ref input_fields,
+                        is_one_of,
+                        ..
                     }) = *t
                     {
+                        // Exactly one field of a `@oneOf` input object is set,
+                        // and its value is not null.
+                        if is_one_of && (obj.len() != 1 || obj[0].1.item.is_null()) {
+                            return false;
+                        }
+
                         let mut remaining_required_fields = input_fields
                             .iter()
                             .filter_map(|f| {
@@ -16,8 +16,9 @@ pub struct RuleError {
     message: String,
 }
 
-#[doc(hidden)]
+/// The state of the validation of a document, passed to `Visitor`s
 pub struct ValidatorContext<'a, S: Debug + 'a> {
+    /// The schema the document is validated against
     pub schema: &'a SchemaType<'a, S>,
     errors: Vec<RuleError>,
     type_stack: Vec<Option<&'a MetaType<'a, S>>>,
@@ -92,11 +93,19 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         self.errors.append(&mut errors);
     }
 
-    #[doc(hidden)]
+    /// Report a validation error at the given positions of the document
     pub fn report_error(&mut self, message: &str, locations: &[SourcePosition]) {
         self.errors.push(RuleError::new(message, locations))
     }
 
+    /// Whether any validation error has been reported so far
+    ///
+    /// Custom rules run after the built-in ones, so they can use this to skip
+    /// documents that are already invalid.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
     #[doc(hidden)]
     pub fn into_errors(mut self) -> Vec<RuleError> {
         self.errors.sort();
@@ -160,12 +169,12 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         res
     }
 
-    #[doc(hidden)]
+    /// The type of the field or fragment being visited, if it exists
     pub fn current_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The type literal of the field being visited, if it exists
     pub fn current_type_literal(&self) -> Option<&Type<'a>> {
         match self.type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -173,12 +182,13 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// The type that contains the field being visited, if it exists
     pub fn parent_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.parent_type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The type literal of the argument or input value being visited, if it
+    /// exists
     pub fn current_input_type_literal(&self) -> Option<&Type<'a>> {
         match self.input_type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -186,7 +196,7 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// Is a fragment with the given name defined in the document?
     pub fn is_known_fragment(&self, name: &str) -> bool {
         self.fragment_names.contains(name)
     }
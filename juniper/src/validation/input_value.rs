@@ -180,8 +180,8 @@ fn unify_scalar<'a, S>(
 where
     S: fmt::Debug,
 {
-    let mut errors: Vec<RuleError> = vec![];
-
+    // Lists and objects are only accepted by custom scalars that can parse
+    // them, like the `_Any` scalar of Apollo Federation.
     if !(meta.try_parse_fn)(value) {
         return vec![unification_error(
             var_name,
@@ -191,22 +191,7 @@ where
         )];
     }
 
-    match *value {
-        InputValue::List(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found list"#, meta.name),
-        )),
-        InputValue::Object(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found object"#, meta.name),
-        )),
-        _ => (),
-    }
-    errors
+    vec![]
 }
 
 fn unify_enum<'a, S>(
@@ -308,6 +293,15 @@ where
                 "Unknown field",
             ));
         }
+
+        if meta.is_one_of && (obj.len() != 1 || obj.values().any(|v| v.is_null())) {
+            errors.push(unification_error(
+                var_name,
+                var_pos,
+                path,
+                &format!(r#"Expected exactly one non-null field of "{}""#, meta.name),
+            ));
+        }
     } else {
         errors.push(unification_error(
             var_name,
@@ -10,12 +10,14 @@ mod visitor;
 #[cfg(test)]
 pub(crate) mod test_harness;
 
-pub(crate) use self::rules::visit_all_rules;
+pub(crate) use self::rules::{
+    visit_all_rules, visit_custom_rules, visit_limit_rules, CustomRules, OperationLimits,
+};
 pub use self::{
     context::{RuleError, ValidatorContext},
     input_value::validate_input_values,
     multi_visitor::MultiVisitorNil,
-    traits::Visitor,
+    traits::{ValidationRule, Visitor},
     visitor::visit,
 };
 
@@ -1076,6 +1076,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn one_of_object_with_one_field() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { stringField: "foo" })
+              }
+            }
+        "#,
+        );
+    }
+
+    #[test]
+    fn one_of_object_with_two_fields() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { intField: 4, stringField: "foo" })
+              }
+            }
+        "#,
+            &[RuleError::new(
+                &error_message("oneOfArg", "OneOfInput"),
+                &[SourcePosition::new(87, 3, 40)],
+            )],
+        );
+    }
+
+    #[test]
+    fn one_of_object_with_null_field() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { intField: null })
+              }
+            }
+        "#,
+            &[RuleError::new(
+                &error_message("oneOfArg", "OneOfInput"),
+                &[SourcePosition::new(87, 3, 40)],
+            )],
+        );
+    }
+
+    #[test]
+    fn one_of_object_without_fields() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: {})
+              }
+            }
+        "#,
+            &[RuleError::new(
+                &error_message("oneOfArg", "OneOfInput"),
+                &[SourcePosition::new(87, 3, 40)],
+            )],
+        );
+    }
+
     #[test]
     fn directive_with_valid_types() {
         expect_passes_rule::<_, _, DefaultScalarValue>(
@@ -0,0 +1,179 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    ast::{Definition, Document, Fragment, Operation, Selection},
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct MaxAliases<'a, S: Debug + 'a> {
+    max_aliases: Option<usize>,
+    named_fragments: HashMap<&'a str, &'a Fragment<'a, S>>,
+    fragment_aliases: HashMap<&'a str, usize>,
+}
+
+pub fn factory<'a, S: Debug>(max_aliases: Option<usize>) -> MaxAliases<'a, S> {
+    MaxAliases {
+        max_aliases,
+        named_fragments: HashMap::new(),
+        fragment_aliases: HashMap::new(),
+    }
+}
+
+impl<'a, S: Debug> MaxAliases<'a, S> {
+    // Aliases in fragments are counted once for every time the fragment is
+    // spread.
+    fn selection_set_aliases(&mut self, selection_set: &'a [Selection<S>]) -> usize {
+        selection_set
+            .iter()
+            .map(|selection| match *selection {
+                Selection::Field(Spanning { ref item, .. }) => {
+                    let alias = if item.alias.is_some() { 1 } else { 0 };
+                    alias
+                        + item
+                            .selection_set
+                            .as_ref()
+                            .map(|s| self.selection_set_aliases(s))
+                            .unwrap_or(0)
+                }
+                Selection::InlineFragment(Spanning { ref item, .. }) => {
+                    self.selection_set_aliases(&item.selection_set)
+                }
+                Selection::FragmentSpread(Spanning { ref item, .. }) => {
+                    self.fragment_aliases(item.name.item)
+                }
+            })
+            .fold(0, usize::saturating_add)
+    }
+
+    fn fragment_aliases(&mut self, name: &'a str) -> usize {
+        if let Some(aliases) = self.fragment_aliases.get(name) {
+            return *aliases;
+        }
+
+        // Guards against cycles, which are reported by `NoFragmentCycles`.
+        self.fragment_aliases.insert(name, 0);
+
+        let aliases = match self.named_fragments.get(name) {
+            Some(fragment) => self.selection_set_aliases(&fragment.selection_set),
+            None => 0,
+        };
+        self.fragment_aliases.insert(name, aliases);
+
+        aliases
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for MaxAliases<'a, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, defs: &'a Document<S>) {
+        for def in defs {
+            if let Definition::Fragment(Spanning { ref item, .. }) = *def {
+                self.named_fragments.insert(item.name.item, item);
+            }
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let max_aliases = match self.max_aliases {
+            Some(max_aliases) => max_aliases,
+            None => return,
+        };
+
+        let aliases = self.selection_set_aliases(&op.item.selection_set);
+        if aliases > max_aliases {
+            ctx.report_error(&error_message(aliases, max_aliases), &[op.start]);
+        }
+    }
+}
+
+fn error_message(aliases: usize, max_aliases: usize) -> String {
+    format!(
+        "Operation has {} aliases, which exceeds the maximum of {}",
+        aliases, max_aliases
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn within_max_aliases() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            first: dog {
+              name
+            }
+            second: dog {
+              dogName: name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn exceeds_max_aliases() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          {
+            first: dog {
+              name
+            }
+            second: dog {
+              dogName: name
+            }
+            third: dog {
+              name
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 2),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn counts_aliases_in_each_fragment_spread() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          query Foo {
+            dog {
+              ...names
+            }
+            cat: dog {
+              ...names
+            }
+          }
+
+          fragment names on Dog {
+            dogName: name
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+}
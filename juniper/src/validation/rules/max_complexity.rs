@@ -0,0 +1,253 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    ast::{Definition, Document, Fragment, Operation, OperationType, Selection},
+    parser::Spanning,
+    schema::{meta::MetaType, model::SchemaType},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct MaxComplexity<'a, S: Debug + 'a> {
+    max_complexity: Option<usize>,
+    named_fragments: HashMap<&'a str, &'a Fragment<'a, S>>,
+    fragment_complexities: HashMap<&'a str, usize>,
+}
+
+pub fn factory<'a, S: Debug>(max_complexity: Option<usize>) -> MaxComplexity<'a, S> {
+    MaxComplexity {
+        max_complexity,
+        named_fragments: HashMap::new(),
+        fragment_complexities: HashMap::new(),
+    }
+}
+
+impl<'a, S> MaxComplexity<'a, S>
+where
+    S: ScalarValue,
+{
+    // The complexity of a field is its own cost, plus the complexity of its
+    // selection set times its list size. `__typename` is free, while fields of
+    // the introspection system count like any other. Fragments on different
+    // types are all counted.
+    fn selection_set_complexity(
+        &mut self,
+        schema: &'a SchemaType<'a, S>,
+        parent_type: Option<&'a MetaType<'a, S>>,
+        selection_set: &'a [Selection<S>],
+    ) -> usize {
+        selection_set
+            .iter()
+            .map(|selection| match *selection {
+                Selection::Field(Spanning { ref item, .. }) if item.name.item == "__typename" => 0,
+                Selection::Field(Spanning { ref item, .. }) => {
+                    let field = match parent_type.and_then(|t| t.field_by_name(item.name.item)) {
+                        Some(field) => field,
+                        None => return 0,
+                    };
+                    let children = match item.selection_set {
+                        Some(ref selection_set) => {
+                            let field_type =
+                                schema.concrete_type_by_name(field.field_type.innermost_name());
+                            self.selection_set_complexity(schema, field_type, selection_set)
+                        }
+                        None => 0,
+                    };
+
+                    field
+                        .complexity
+                        .saturating_add(field.list_size.saturating_mul(children))
+                }
+                Selection::InlineFragment(Spanning { ref item, .. }) => {
+                    let fragment_type = match item.type_condition {
+                        Some(ref name) => schema.concrete_type_by_name(name.item),
+                        None => parent_type,
+                    };
+                    self.selection_set_complexity(schema, fragment_type, &item.selection_set)
+                }
+                Selection::FragmentSpread(Spanning { ref item, .. }) => {
+                    self.fragment_complexity(schema, item.name.item)
+                }
+            })
+            .fold(0, usize::saturating_add)
+    }
+
+    fn fragment_complexity(&mut self, schema: &'a SchemaType<'a, S>, name: &'a str) -> usize {
+        if let Some(complexity) = self.fragment_complexities.get(name) {
+            return *complexity;
+        }
+
+        // Guards against cycles, which are reported by `NoFragmentCycles`.
+        self.fragment_complexities.insert(name, 0);
+
+        let complexity = match self.named_fragments.get(name) {
+            Some(fragment) => {
+                let fragment_type = schema.concrete_type_by_name(fragment.type_condition.item);
+                self.selection_set_complexity(schema, fragment_type, &fragment.selection_set)
+            }
+            None => 0,
+        };
+        self.fragment_complexities.insert(name, complexity);
+
+        complexity
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for MaxComplexity<'a, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, defs: &'a Document<S>) {
+        for def in defs {
+            if let Definition::Fragment(Spanning { ref item, .. }) = *def {
+                self.named_fragments.insert(item.name.item, item);
+            }
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let max_complexity = match self.max_complexity {
+            Some(max_complexity) => max_complexity,
+            None => return,
+        };
+
+        let schema = ctx.schema;
+        let root_type = match op.item.operation_type {
+            OperationType::Query => Some(schema.concrete_query_type()),
+            OperationType::Mutation => schema.concrete_mutation_type(),
+            OperationType::Subscription => schema.concrete_subscription_type(),
+        };
+
+        let complexity = self.selection_set_complexity(schema, root_type, &op.item.selection_set);
+        if complexity > max_complexity {
+            ctx.report_error(&error_message(complexity, max_complexity), &[op.start]);
+        }
+    }
+}
+
+fn error_message(complexity: usize, max_complexity: usize) -> String {
+    format!(
+        "Operation has a complexity of {}, which exceeds the maximum complexity of {}",
+        complexity, max_complexity
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn within_max_complexity() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            dog {
+              name
+              ...barks
+            }
+          }
+
+          fragment barks on Dog {
+            barks
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn typename_is_free() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(1)),
+            r#"
+          {
+            __typename
+            dog {
+              __typename
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn counts_introspection_fields() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            __schema {
+              types {
+                fields {
+                  type {
+                    ofType {
+                      name
+                    }
+                  }
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(6, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn exceeds_max_complexity() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            dog {
+              name
+              barks
+            }
+            cat {
+              name
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(5, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn multiplies_by_list_size() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(20)),
+            r#"
+          query Foo {
+            human {
+              relatives {
+                ... on Human {
+                  name
+                  iq
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(22, 20),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+}
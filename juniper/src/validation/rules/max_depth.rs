@@ -0,0 +1,260 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    ast::{Definition, Document, Fragment, Operation, Selection},
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct MaxDepth<'a, S: Debug + 'a> {
+    max_depth: Option<usize>,
+    named_fragments: HashMap<&'a str, &'a Fragment<'a, S>>,
+    fragment_depths: HashMap<&'a str, usize>,
+}
+
+pub fn factory<'a, S: Debug>(max_depth: Option<usize>) -> MaxDepth<'a, S> {
+    MaxDepth {
+        max_depth,
+        named_fragments: HashMap::new(),
+        fragment_depths: HashMap::new(),
+    }
+}
+
+impl<'a, S: Debug> MaxDepth<'a, S> {
+    // `__typename` is a leaf that any selection set may contain, so it doesn't
+    // count. Selections of `__schema` and `__type` count like any other.
+    fn selection_set_depth(&mut self, selection_set: &'a [Selection<S>]) -> usize {
+        selection_set
+            .iter()
+            .map(|selection| match *selection {
+                Selection::Field(Spanning { ref item, .. }) if item.name.item == "__typename" => 0,
+                Selection::Field(Spanning { ref item, .. }) => {
+                    1 + item
+                        .selection_set
+                        .as_ref()
+                        .map(|s| self.selection_set_depth(s))
+                        .unwrap_or(0)
+                }
+                Selection::InlineFragment(Spanning { ref item, .. }) => {
+                    self.selection_set_depth(&item.selection_set)
+                }
+                Selection::FragmentSpread(Spanning { ref item, .. }) => {
+                    self.fragment_depth(item.name.item)
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn fragment_depth(&mut self, name: &'a str) -> usize {
+        if let Some(depth) = self.fragment_depths.get(name) {
+            return *depth;
+        }
+
+        // Guards against cycles, which are reported by `NoFragmentCycles`.
+        self.fragment_depths.insert(name, 0);
+
+        let depth = match self.named_fragments.get(name) {
+            Some(fragment) => self.selection_set_depth(&fragment.selection_set),
+            None => 0,
+        };
+        self.fragment_depths.insert(name, depth);
+
+        depth
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for MaxDepth<'a, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, defs: &'a Document<S>) {
+        for def in defs {
+            if let Definition::Fragment(Spanning { ref item, .. }) = *def {
+                self.named_fragments.insert(item.name.item, item);
+            }
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let max_depth = match self.max_depth {
+            Some(max_depth) => max_depth,
+            None => return,
+        };
+
+        let depth = self.selection_set_depth(&op.item.selection_set);
+        if depth > max_depth {
+            ctx.report_error(&error_message(depth, max_depth), &[op.start]);
+        }
+    }
+}
+
+fn error_message(depth: usize, max_depth: usize) -> String {
+    format!(
+        "Operation has a depth of {}, which exceeds the maximum depth of {}",
+        depth, max_depth
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn within_max_depth() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              relatives {
+                name
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn no_max_depth() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(None),
+            r#"
+          {
+            human {
+              relatives {
+                relatives {
+                  relatives {
+                    name
+                  }
+                }
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn typename_does_not_count() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(1)),
+            r#"
+          {
+            __typename
+            dog {
+              __typename
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn exceeds_max_depth_through_introspection() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            __schema {
+              types {
+                fields {
+                  type {
+                    ofType {
+                      name
+                    }
+                  }
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(6, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn exceeds_max_depth() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              relatives {
+                relatives {
+                  name
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn exceeds_max_depth_through_fragments() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          query Foo {
+            human {
+              ...relatives
+            }
+          }
+
+          fragment relatives on Human {
+            relatives {
+              ... on Human {
+                relatives {
+                  name
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fragment_cycles_terminate() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              ...relatives
+            }
+          }
+
+          fragment relatives on Human {
+            relatives {
+              ...relatives
+            }
+          }
+        "#,
+        );
+    }
+}
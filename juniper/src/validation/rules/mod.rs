@@ -7,6 +7,9 @@ mod known_directives;
 mod known_fragment_names;
 mod known_type_names;
 mod lone_anonymous_operation;
+mod max_aliases;
+mod max_complexity;
+mod max_depth;
 mod no_fragment_cycles;
 mod no_undefined_variables;
 mod no_unused_fragments;
@@ -25,10 +28,10 @@ mod variables_in_allowed_position;
 
 use crate::{
     ast::Document,
-    validation::{visit, MultiVisitorNil, ValidatorContext},
+    validation::{visit, MultiVisitorNil, ValidationRule, ValidatorContext},
     value::ScalarValue,
 };
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 
 pub(crate) fn visit_all_rules<'a, S: Debug>(ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>)
 where
@@ -62,3 +65,59 @@ where
 
     visit(&mut mv, ctx, doc)
 }
+
+/// Limits on the size of operations, which are checked by optional rules
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct OperationLimits {
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_aliases: Option<usize>,
+    pub(crate) max_complexity: Option<usize>,
+}
+
+pub(crate) fn visit_limit_rules<'a, S: Debug>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+    limits: &OperationLimits,
+) where
+    S: ScalarValue,
+{
+    if limits.max_depth.is_none() && limits.max_aliases.is_none() && limits.max_complexity.is_none()
+    {
+        return;
+    }
+
+    let mut mv = MultiVisitorNil
+        .with(self::max_aliases::factory(limits.max_aliases))
+        .with(self::max_complexity::factory(limits.max_complexity))
+        .with(self::max_depth::factory(limits.max_depth));
+
+    visit(&mut mv, ctx, doc)
+}
+
+/// Validation rules added to a schema, which run after the built-in ones
+pub(crate) struct CustomRules<S>(pub(crate) Vec<Box<dyn ValidationRule<S>>>);
+
+impl<S> Default for CustomRules<S> {
+    fn default() -> Self {
+        CustomRules(Vec::new())
+    }
+}
+
+impl<S> Debug for CustomRules<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CustomRules({} rules)", self.0.len())
+    }
+}
+
+pub(crate) fn visit_custom_rules<'a, S: Debug>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+    rules: &CustomRules<S>,
+) where
+    S: ScalarValue,
+{
+    for rule in &rules.0 {
+        let mut visitor = rule.visitor();
+        visit(&mut *visitor, ctx, doc);
+    }
+}
@@ -384,6 +384,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn non_null_int_into_one_of_field() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Query($intArg: Int!) {
+            complicatedArgs {
+              oneOfArgField(oneOfArg: { intField: $intArg })
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn int_into_one_of_field() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Query($intArg: Int) {
+            complicatedArgs {
+              oneOfArgField(oneOfArg: { intField: $intArg })
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message("intArg", "Int", "Int!"),
+                &[
+                    SourcePosition::new(23, 1, 22),
+                    SourcePosition::new(119, 3, 50),
+                ],
+            )],
+        );
+    }
+
     #[test]
     fn int_into_non_null_int() {
         expect_fails_rule::<_, _, DefaultScalarValue>(
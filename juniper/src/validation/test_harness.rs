@@ -67,6 +67,13 @@ struct ComplexInput {
     string_list_field: Option<Vec<Option<String>>>,
 }
 
+#[allow(dead_code)]
+#[derive(Debug)]
+enum OneOfInput {
+    Int(i32),
+    String(String),
+}
+
 impl<S> GraphQLType<S> for Being
 where
     S: ScalarValue,
@@ -361,7 +368,9 @@ where
                 .field::<Option<String>>("name", i)
                 .argument(registry.arg::<Option<bool>>("surname", i)),
             registry.field::<Option<Vec<Option<Pet>>>>("pets", i),
-            registry.field::<Option<Vec<Human>>>("relatives", i),
+            registry
+                .field::<Option<Vec<Human>>>("relatives", i)
+                .list_size(10),
             registry.field::<Option<i32>>("iq", i),
         ];
         registry
@@ -501,6 +510,51 @@ where
     }
 }
 
+impl<S> GraphQLType<S> for OneOfInput
+where
+    S: ScalarValue,
+{
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("OneOfInput")
+    }
+
+    fn meta<'r>(i: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = &[
+            registry.arg::<Option<i32>>("intField", i),
+            registry.arg::<Option<String>>("stringField", i),
+        ];
+
+        registry
+            .build_input_object_type::<Self>(i, fields)
+            .one_of()
+            .into_meta()
+    }
+}
+
+impl<S> FromInputValue<S> for OneOfInput
+where
+    S: ScalarValue,
+{
+    fn from_input_value<'a>(v: &InputValue<S>) -> Option<OneOfInput> {
+        let obj = v.to_object_value()?;
+        if obj.len() != 1 {
+            return None;
+        }
+
+        match obj.get_index(0) {
+            Some((&"intField", v)) => v.convert().map(OneOfInput::Int),
+            Some((&"stringField", v)) => v.convert().map(OneOfInput::String),
+            _ => None,
+        }
+    }
+}
+
 impl<S> GraphQLType<S> for ComplicatedArgs
 where
     S: ScalarValue,
@@ -544,6 +598,9 @@ where
             registry
                 .field::<Option<String>>("complexArgField", i)
                 .argument(registry.arg::<Option<ComplexInput>>("complexArg", i)),
+            registry
+                .field::<Option<String>>("oneOfArgField", i)
+                .argument(registry.arg::<Option<OneOfInput>>("oneOfArg", i)),
             registry
                 .field::<Option<String>>("multipleReqs", i)
                 .argument(registry.arg::<i32>("req1", i))
@@ -8,7 +8,39 @@ use crate::{
     value::ScalarValue,
 };
 
-#[doc(hidden)]
+/// A pass over a query document, e.g. to check it against a validation rule
+///
+/// The methods are called in document order: `enter_*` when a node of the
+/// document is reached, and `exit_*` after all its children were visited. The
+/// `ValidatorContext` tracks the types of the visited nodes and collects the
+/// errors reported with `ValidatorContext::report_error`. Unimplemented
+/// methods do nothing.
+///
+/// A visitor that is also `Clone` and doesn't borrow from the document is a
+/// `ValidationRule` that can be added to a schema with
+/// `RootNode::with_validation_rule`.
+///
+/// ```rust
+/// # use juniper::{parser::Spanning, Operation, ScalarValue, ValidatorContext, Visitor};
+/// #[derive(Clone)]
+/// struct NoAnonymousOperations;
+///
+/// impl<'a, S> Visitor<'a, S> for NoAnonymousOperations
+/// where
+///     S: ScalarValue,
+/// {
+///     fn enter_operation_definition(
+///         &mut self,
+///         ctx: &mut ValidatorContext<'a, S>,
+///         op: &'a Spanning<Operation<S>>,
+///     ) {
+///         if op.item.name.is_none() {
+///             ctx.report_error("Operations must be named", &[op.start]);
+///         }
+///     }
+/// }
+/// ```
+#[allow(missing_docs)]
 pub trait Visitor<'a, S>
 where
     S: ScalarValue,
@@ -154,3 +186,31 @@ where
     ) {
     }
 }
+
+/// A validation rule that is checked on every document executed against a
+/// schema, after the built-in rules
+///
+/// Rules create a fresh `Visitor` for every document. Visitors that can be
+/// cloned and don't borrow from the document are rules themselves.
+pub trait ValidationRule<S>: Send + Sync
+where
+    S: ScalarValue,
+{
+    /// Create the visitor that checks a single document
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a;
+}
+
+impl<S, V> ValidationRule<S> for V
+where
+    S: ScalarValue,
+    V: for<'a> Visitor<'a, S> + Clone + Send + Sync + 'static,
+{
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a,
+    {
+        Box::new(self.clone())
+    }
+}
@@ -6,19 +6,16 @@ use crate::{
         InlineFragment, InputValue, Operation, OperationType, Selection, Type, VariableDefinitions,
     },
     parser::Spanning,
-    schema::meta::Argument,
-    validation::{multi_visitor::MultiVisitorCons, ValidatorContext, Visitor},
+    schema::meta::{Argument, InputObjectMeta, MetaType},
+    validation::{ValidatorContext, Visitor},
     value::ScalarValue,
 };
 
 #[doc(hidden)]
-pub fn visit<'a, A, B, S>(
-    v: &mut MultiVisitorCons<A, B>,
-    ctx: &mut ValidatorContext<'a, S>,
-    d: &'a Document<S>,
-) where
+pub fn visit<'a, S, V>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, d: &'a Document<S>)
+where
     S: ScalarValue,
-    MultiVisitorCons<A, B>: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     v.enter_document(ctx, d);
     visit_definitions(v, ctx, d);
@@ -28,7 +25,7 @@ pub fn visit<'a, A, B, S>(
 fn visit_definitions<'a, S, V>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, d: &'a [Definition<S>])
 where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     for def in d {
         let def_type = match *def {
@@ -85,7 +82,7 @@ where
 fn enter_definition<'a, S, V>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, def: &'a Definition<S>)
 where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     match *def {
         Definition::Operation(ref op) => v.enter_operation_definition(ctx, op),
@@ -96,7 +93,7 @@ where
 fn exit_definition<'a, S, V>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, def: &'a Definition<S>)
 where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     match *def {
         Definition::Operation(ref op) => v.exit_operation_definition(ctx, op),
@@ -107,7 +104,7 @@ where
 fn visit_definition<'a, S, V>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, def: &'a Definition<S>)
 where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     match *def {
         Definition::Operation(ref op) => {
@@ -128,7 +125,7 @@ fn visit_variable_definitions<'a, S, V>(
     defs: &'a Option<Spanning<VariableDefinitions<S>>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     if let Some(ref defs) = *defs {
         for def in defs.item.iter() {
@@ -153,7 +150,7 @@ fn visit_directives<'a, S, V>(
     directives: &'a Option<Vec<Spanning<Directive<S>>>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     if let Some(ref directives) = *directives {
         for directive in directives {
@@ -176,7 +173,7 @@ fn visit_arguments<'a, S, V>(
     arguments: &'a Option<Spanning<Arguments<S>>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     if let Some(ref arguments) = *arguments {
         for argument in arguments.item.iter() {
@@ -201,7 +198,7 @@ fn visit_selection_set<'a, S, V>(
     selection_set: &'a [Selection<S>],
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     ctx.with_pushed_parent_type(|ctx| {
         v.enter_selection_set(ctx, selection_set);
@@ -220,7 +217,7 @@ fn visit_selection<'a, S, V>(
     selection: &'a Selection<S>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     match *selection {
         Selection::Field(ref field) => visit_field(v, ctx, field),
@@ -235,7 +232,7 @@ fn visit_field<'a, S, V>(
     field: &'a Spanning<Field<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     let meta_field = ctx
         .parent_type()
@@ -264,7 +261,7 @@ fn visit_fragment_spread<'a, S, V>(
     spread: &'a Spanning<FragmentSpread<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     v.enter_fragment_spread(ctx, spread);
 
@@ -279,7 +276,7 @@ fn visit_inline_fragment<'a, S, V>(
     fragment: &'a Spanning<InlineFragment<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     let mut visit_fn = move |ctx: &mut ValidatorContext<'a, S>| {
         v.enter_inline_fragment(ctx, fragment);
@@ -309,25 +306,38 @@ fn visit_input_value<'a, S, V>(
     input_value: &'a Spanning<InputValue<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     enter_input_value(v, ctx, input_value);
 
     match input_value.item {
         InputValue::Object(ref fields) => {
             for field in fields {
-                let inner_type = ctx
-                    .current_input_type_literal()
-                    .and_then(|t| match *t {
-                        Type::NonNullNamed(ref name) | Type::Named(ref name) => {
-                            ctx.schema.concrete_type_by_name(name)
-                        }
-                        _ => None,
-                    })
+                let object_type = ctx.current_input_type_literal().and_then(|t| match *t {
+                    Type::NonNullNamed(ref name) | Type::Named(ref name) => {
+                        ctx.schema.concrete_type_by_name(name)
+                    }
+                    _ => None,
+                });
+                let mut inner_type = object_type
                     .and_then(|ct| ct.input_field_by_name(&field.0.item))
-                    .map(|f| &f.arg_type);
+                    .map(|f| f.arg_type.clone());
+
+                // The fields of `@oneOf` input objects are nullable, but the
+                // value of the field that is set must not be null, so variables
+                // used there must be of a non-null type.
+                if let Some(&MetaType::InputObject(InputObjectMeta {
+                    is_one_of: true, ..
+                })) = object_type
+                {
+                    inner_type = inner_type.map(|t| match t {
+                        Type::Named(name) => Type::NonNullNamed(name),
+                        Type::List(inner) => Type::NonNullList(inner),
+                        t => t,
+                    });
+                }
 
-                ctx.with_pushed_input_type(inner_type, |ctx| {
+                ctx.with_pushed_input_type(inner_type.as_ref(), |ctx| {
                     v.enter_object_field(ctx, field);
                     visit_input_value(v, ctx, &field.1);
                     v.exit_object_field(ctx, field);
@@ -360,7 +370,7 @@ fn enter_input_value<'a, S, V>(
     input_value: &'a Spanning<InputValue<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     use crate::InputValue::*;
 
@@ -383,7 +393,7 @@ fn exit_input_value<'a, S, V>(
     input_value: &'a Spanning<InputValue<S>>,
 ) where
     S: ScalarValue,
-    V: Visitor<'a, S>,
+    V: Visitor<'a, S> + ?Sized,
 {
     use crate::InputValue::*;
 
@@ -41,6 +41,8 @@ Check the LICENSE file for details.
 #![doc(html_root_url = "https://docs.rs/juniper_actix/0.1.0")]
 
 // use futures::{FutureExt as _};
+use std::convert::TryFrom;
+
 use actix_web::{
     error::{ErrorBadRequest, ErrorMethodNotAllowed, ErrorUnsupportedMediaType},
     http::{header::CONTENT_TYPE, Method},
@@ -48,7 +50,7 @@ use actix_web::{
 };
 use juniper::{
     http::{
-        graphiql::graphiql_source, playground::playground_source, GraphQLBatchRequest,
+        graphiql::graphiql_source, multipart, playground::playground_source, GraphQLBatchRequest,
         GraphQLRequest,
     },
     ScalarValue,
@@ -58,27 +60,37 @@ use serde::Deserialize;
 #[derive(Deserialize, Clone, PartialEq, Debug)]
 #[serde(deny_unknown_fields)]
 struct GetGraphQLRequest {
+    // Requests referring to a persisted query may leave out the query.
+    #[serde(default)]
     query: String,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
     variables: Option<String>,
+    extensions: Option<String>,
 }
 
-impl<S> From<GetGraphQLRequest> for GraphQLRequest<S>
+impl<S> TryFrom<GetGraphQLRequest> for GraphQLRequest<S>
 where
     S: ScalarValue,
 {
-    fn from(get_req: GetGraphQLRequest) -> Self {
+    type Error = serde_json::Error;
+
+    fn try_from(get_req: GetGraphQLRequest) -> Result<Self, Self::Error> {
         let GetGraphQLRequest {
             query,
             operation_name,
             variables,
+            extensions,
         } = get_req;
         let variables = match variables {
-            Some(variables) => Some(serde_json::from_str(&variables).unwrap()),
+            Some(variables) => Some(serde_json::from_str(&variables)?),
+            None => None,
+        };
+        let extensions = match extensions {
+            Some(extensions) => Some(serde_json::from_str(&extensions)?),
             None => None,
         };
-        Self::new(query, operation_name, variables)
+        Ok(Self::new(query, operation_name, variables).with_extensions(extensions))
     }
 }
 
@@ -124,7 +136,7 @@ where
     Subscription::TypeInfo: Send + Sync,
 {
     let get_req = web::Query::<GetGraphQLRequest>::from_query(req.query_string())?;
-    let req = GraphQLRequest::from(get_req.into_inner());
+    let req = GraphQLRequest::try_from(get_req.into_inner()).map_err(ErrorBadRequest)?;
     let gql_response = req.execute(schema, context).await;
     let body_response = serde_json::to_string(&gql_response)?;
     let response = match gql_response.is_ok() {
@@ -139,6 +151,10 @@ where
 }
 
 /// Actix GraphQL Handler for POST requests
+///
+/// Besides JSON, accepts `multipart/form-data` requests carrying files, see
+/// [`juniper::http::multipart`](../juniper/http/multipart/index.html). Their
+/// size is limited by the `web::PayloadConfig` of the application.
 pub async fn post_graphql_handler<Query, Mutation, Subscription, Context, S>(
     schema: &juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context: &Context,
@@ -168,8 +184,13 @@ where
                 Err(err) => Err(ErrorBadRequest(err)),
             }
         }
+        Some(content_type) if multipart::is_multipart(content_type) => {
+            let body = web::Bytes::from_request(&req, &mut payload.into_inner()).await?;
+            multipart::parse(content_type, &body).map_err(ErrorBadRequest)
+        }
         _ => Err(ErrorUnsupportedMediaType(
-            "GraphQL requests should have content type `application/json` or `application/graphql`",
+            "GraphQL requests should have content type `application/json`, `application/graphql` \
+             or `multipart/form-data`",
         )),
     }?;
     let gql_batch_response = req.execute(schema, context).await;
@@ -217,6 +238,181 @@ pub async fn playground_handler(
         .body(html))
 }
 
+/// Serving subscriptions over websockets, with the `graphql-transport-ws`
+/// protocol implemented by [`juniper_subscriptions::ws`]
+///
+/// [`juniper_subscriptions::ws`]: juniper_subscriptions::ws
+#[cfg(feature = "subscriptions")]
+pub mod subscriptions {
+    use std::sync::Arc;
+
+    use actix::{fut, Actor, ActorContext as _, AsyncContext as _, StreamHandler};
+    use actix_web::{http::header, web, Error, HttpRequest, HttpResponse};
+    use actix_web_actors::ws;
+    use futures::{
+        channel::mpsc,
+        future::{self, LocalBoxFuture},
+        Future, FutureExt as _,
+    };
+    use juniper::ScalarValue;
+    use juniper_subscriptions::{ws::OutgoingMessage, Coordinator};
+
+    pub use juniper_subscriptions::ws::{ConnectionConfig, Protocol};
+
+    /// Actix Web handler upgrading a request to a websocket serving
+    /// subscriptions over the `graphql-transport-ws` protocol
+    pub async fn graphql_transport_ws_handler<Query, Mutation, Subscription, Context, S>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Context,
+        req: HttpRequest,
+        payload: web::Payload,
+        config: ConnectionConfig,
+    ) -> Result<HttpResponse, Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let init = move |_| future::ready(Ok(context));
+        graphql_transport_ws_handler_with_init(coordinator, init, req, payload, config).await
+    }
+
+    /// Actix Web handler upgrading a request to a websocket serving
+    /// subscriptions over the `graphql-transport-ws` protocol, building the
+    /// context of the connection from the payload of its `connection_init`
+    /// message
+    ///
+    /// See [`juniper_subscriptions::ws::serve_with_init`] for how `init` is
+    /// used.
+    ///
+    /// [`juniper_subscriptions::ws::serve_with_init`]: juniper_subscriptions::ws::serve_with_init
+    pub async fn graphql_transport_ws_handler_with_init<
+        Query,
+        Mutation,
+        Subscription,
+        Context,
+        S,
+        Init,
+        Fut,
+    >(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        init: Init,
+        req: HttpRequest,
+        payload: web::Payload,
+        config: ConnectionConfig,
+    ) -> Result<HttpResponse, Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        Init: FnOnce(serde_json::Value) -> Fut + 'static,
+        Fut: Future<Output = Result<Context, String>> + 'static,
+    {
+        let mut response = ws::handshake(&req)?;
+        let protocol_offered = req
+            .headers()
+            .get(header::SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|v| v.to_str().ok())
+            .map_or(false, |v| Protocol::GraphQLTransportWs.is_offered(v));
+        if protocol_offered {
+            response.header(
+                header::SEC_WEBSOCKET_PROTOCOL,
+                Protocol::GraphQLTransportWs.name(),
+            );
+        }
+
+        let (incoming_tx, incoming) = mpsc::unbounded();
+        let (outgoing, outgoing_rx) = mpsc::unbounded();
+        let connection = juniper_subscriptions::ws::serve_with_init(
+            incoming,
+            outgoing,
+            coordinator,
+            init,
+            config,
+        )
+        .map(|_| ())
+        .boxed_local();
+        let actor = SubscriptionActor {
+            incoming: incoming_tx,
+            outgoing: Some(outgoing_rx),
+            connection: Some(connection),
+        };
+
+        Ok(response.streaming(ws::WebsocketContext::create(actor, payload)))
+    }
+
+    /// Relays the messages of a websocket to and from the connection future
+    /// of `juniper_subscriptions`, which runs on the context of the actor
+    struct SubscriptionActor {
+        incoming: mpsc::UnboundedSender<String>,
+        outgoing: Option<mpsc::UnboundedReceiver<OutgoingMessage>>,
+        connection: Option<LocalBoxFuture<'static, ()>>,
+    }
+
+    impl Actor for SubscriptionActor {
+        type Context = ws::WebsocketContext<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            if let Some(outgoing) = self.outgoing.take() {
+                ctx.add_stream(outgoing);
+            }
+            if let Some(connection) = self.connection.take() {
+                ctx.spawn(fut::wrap_future(connection));
+            }
+        }
+    }
+
+    impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubscriptionActor {
+        fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+            match msg {
+                Ok(ws::Message::Text(text)) => {
+                    let _ = self.incoming.unbounded_send(text);
+                }
+                Ok(ws::Message::Binary(data)) => {
+                    let text = String::from_utf8(data.to_vec()).unwrap_or_default();
+                    let _ = self.incoming.unbounded_send(text);
+                }
+                Ok(ws::Message::Ping(data)) => ctx.pong(&data),
+                Ok(ws::Message::Close(reason)) => {
+                    ctx.close(reason);
+                    ctx.stop();
+                }
+                Ok(_) => {}
+                Err(_) => ctx.stop(),
+            }
+        }
+    }
+
+    /// Once the connection is done, the outgoing stream ends and the actor
+    /// is stopped.
+    impl StreamHandler<OutgoingMessage> for SubscriptionActor {
+        fn handle(&mut self, msg: OutgoingMessage, ctx: &mut Self::Context) {
+            match msg {
+                OutgoingMessage::Text(text) => ctx.text(text),
+                OutgoingMessage::Close(code, reason) => {
+                    ctx.close(Some(ws::CloseReason {
+                        code: code.into(),
+                        description: Some(reason),
+                    }));
+                    ctx.stop();
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,6 +594,26 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn graphql_get_rejects_malformed_extensions() {
+        let schema: Schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/?query=%7B%20hero%20%7B%20name%20%7D%20%7D&extensions=%7B")
+            .to_request();
+
+        let mut app =
+            test::init_service(App::new().data(schema).route("/", web::get().to(index))).await;
+
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
     #[actix_rt::test]
     async fn batch_request_works() {
         use juniper::{
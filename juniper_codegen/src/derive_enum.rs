@@ -86,12 +86,24 @@ pub fn impl_enum(
                 );
             }
 
+            if let Some(guard) = field_attrs.guard {
+                error.unsupported_attribute_within(guard.span_ident(), UnsupportedAttribute::Guard);
+            }
+
             Some(util::GraphQLTypeDefinitionField {
                 name,
                 _type,
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: field_attrs
+                    .directives
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                complexity: None,
+                list_size: None,
+                guard: None,
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
@@ -118,6 +130,22 @@ pub fn impl_enum(
         });
     }
 
+    attrs.implementors.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Implementors)
+    });
+
+    if let Some(ref value_enum) = attrs.value_enum {
+        error.unsupported_attribute(value_enum.span_ident(), UnsupportedAttribute::Enum);
+    }
+
+    if let Some(ref one_of) = attrs.one_of {
+        error.unsupported_attribute(one_of.span_ident(), UnsupportedAttribute::OneOf);
+    }
+
+    if let Some(ref node) = attrs.node {
+        error.unsupported_attribute(node.span_ident(), UnsupportedAttribute::Node);
+    }
+
     if let Some(scalar) = attrs.scalar {
         error.unsupported_attribute(scalar.span_ident(), UnsupportedAttribute::Scalar);
     }
@@ -138,10 +166,16 @@ pub fn impl_enum(
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: None,
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs
+            .directives
+            .into_iter()
+            .map(SpanContainer::into_inner)
+            .collect(),
         fields,
         // NOTICE: only unit variants allow -> no generics possible
         generics: syn::Generics::default(),
         interfaces: None,
+        node: false,
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
@@ -13,21 +13,75 @@ pub fn impl_input_object(
     error: GraphQLScope,
 ) -> syn::Result<TokenStream> {
     let ast_span = ast.span();
+
+    // Parse attributes.
+    let attrs = util::ObjectAttributes::from_attrs(&ast.attrs)?;
+    let is_one_of = attrs.one_of.is_some();
+
+    // The input fields are the fields of structs, or the variants of `one_of`
+    // enums, each holding the value of its field.
     let fields = match ast.data {
         Data::Struct(data) => match data.fields {
-            Fields::Named(named) => named.named,
+            Fields::Named(named) => {
+                if let Some(ref one_of) = attrs.one_of {
+                    error.custom(
+                        one_of.span_ident(),
+                        "can only be `one_of` when it is an enum",
+                    );
+                }
+
+                named
+                    .named
+                    .into_iter()
+                    .map(|field| {
+                        let span = field.span();
+                        let ident = field.ident.unwrap();
+                        let default_name = util::to_camel_case(&ident.unraw().to_string());
+                        (field.attrs, ident, field.ty, default_name, span)
+                    })
+                    .collect::<Vec<_>>()
+            }
             _ => {
                 return Err(
                     error.custom_error(ast_span, "all fields must be named, e.g., `test: String`")
                 )
             }
         },
+        Data::Enum(data) => {
+            if !is_one_of {
+                return Err(error.custom_error(
+                    ast_span,
+                    "can only be used on enums with `#[graphql(one_of)]`",
+                ));
+            }
+
+            data.variants
+                .into_iter()
+                .filter_map(|variant| {
+                    let span = variant.span();
+                    let ty = match variant.fields {
+                        Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+                            unnamed.unnamed[0].ty.clone()
+                        }
+                        _ => {
+                            error.custom(
+                                span,
+                                "expects all variants to hold a single value, e.g., `ById(ID)`",
+                            );
+                            return None;
+                        }
+                    };
+                    let default_name = util::to_camel_case(
+                        &util::to_upper_snake_case(&variant.ident.unraw().to_string())
+                            .to_lowercase(),
+                    );
+                    Some((variant.attrs, variant.ident, ty, default_name, span))
+                })
+                .collect()
+        }
         _ => return Err(error.custom_error(ast_span, "can only be used on structs with fields")),
     };
 
-    // Parse attributes.
-    let attrs = util::ObjectAttributes::from_attrs(&ast.attrs)?;
-
     // Parse attributes.
     let ident = &ast.ident;
     let name = attrs
@@ -38,10 +92,9 @@ pub fn impl_input_object(
 
     let fields = fields
         .into_iter()
-        .filter_map(|field| {
-            let span = field.span();
+        .filter_map(|(field_attrs, field_ident, field_ty, default_name, span)| {
             let field_attrs = match util::FieldAttributes::from_attrs(
-                &field.attrs,
+                &field_attrs,
                 util::FieldAttributeParseMode::Object,
             ) {
                 Ok(attrs) => attrs,
@@ -51,10 +104,9 @@ pub fn impl_input_object(
                 }
             };
 
-            let field_ident = field.ident.as_ref().unwrap();
             let name = match field_attrs.name {
                 Some(ref name) => name.to_string(),
-                None => crate::util::to_camel_case(&field_ident.unraw().to_string()),
+                None => default_name,
             };
 
             if let Some(span) = field_attrs.skip {
@@ -68,6 +120,10 @@ pub fn impl_input_object(
                 )
             }
 
+            if let Some(guard) = field_attrs.guard {
+                error.unsupported_attribute_within(guard.span_ident(), UnsupportedAttribute::Guard)
+            }
+
             if name.starts_with("__") {
                 error.no_double_underscore(if let Some(name) = field_attrs.name {
                     name.span_ident()
@@ -76,6 +132,14 @@ pub fn impl_input_object(
                 });
             }
 
+            // The fields of `one_of` input objects are optional.
+            if let (true, Some(ref default)) = (is_one_of, &field_attrs.default) {
+                error.unsupported_attribute_within(
+                    default.span_ident(),
+                    UnsupportedAttribute::Default,
+                )
+            }
+
             let resolver_code = quote!(#field_ident);
 
             let default = field_attrs
@@ -87,10 +151,18 @@ pub fn impl_input_object(
 
             Some(util::GraphQLTypeDefinitionField {
                 name,
-                _type: field.ty,
+                _type: field_ty,
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: None,
+                directives: field_attrs
+                    .directives
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                complexity: None,
+                list_size: None,
+                guard: None,
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
@@ -117,6 +189,18 @@ pub fn impl_input_object(
         });
     }
 
+    attrs.implementors.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Implementors)
+    });
+
+    if let Some(ref value_enum) = attrs.value_enum {
+        error.unsupported_attribute(value_enum.span_ident(), UnsupportedAttribute::Enum);
+    }
+
+    if let Some(ref node) = attrs.node {
+        error.unsupported_attribute(node.span_ident(), UnsupportedAttribute::Node);
+    }
+
     if let Some(duplicates) =
         crate::util::duplicate::Duplicate::find_by_key(&fields, |field| field.name.as_str())
     {
@@ -139,14 +223,24 @@ pub fn impl_input_object(
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: attrs.scalar.map(SpanContainer::into_inner),
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs
+            .directives
+            .into_iter()
+            .map(SpanContainer::into_inner)
+            .collect(),
         fields,
         generics: ast.generics,
         interfaces: None,
+        node: false,
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
     };
 
     let juniper_crate_name = if is_internal { "crate" } else { "juniper" };
-    Ok(definition.into_input_object_tokens(juniper_crate_name))
+    if is_one_of {
+        Ok(definition.into_one_of_input_object_tokens(juniper_crate_name))
+    } else {
+        Ok(definition.into_input_object_tokens(juniper_crate_name))
+    }
 }
@@ -81,6 +81,14 @@ pub fn build_derive_object(
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: field_attrs
+                    .directives
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                complexity: field_attrs.complexity.map(SpanContainer::into_inner),
+                list_size: field_attrs.list_size.map(SpanContainer::into_inner),
+                guard: field_attrs.guard.map(SpanContainer::into_inner),
                 resolver_code,
                 default: None,
                 is_type_inferred: true,
@@ -99,6 +107,18 @@ pub fn build_derive_object(
         });
     }
 
+    attrs.implementors.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Implementors)
+    });
+
+    if let Some(ref value_enum) = attrs.value_enum {
+        error.unsupported_attribute(value_enum.span_ident(), UnsupportedAttribute::Enum);
+    }
+
+    if let Some(ref one_of) = attrs.one_of {
+        error.unsupported_attribute(one_of.span_ident(), UnsupportedAttribute::OneOf);
+    }
+
     if let Some(duplicates) =
         crate::util::duplicate::Duplicate::find_by_key(&fields, |field| field.name.as_str())
     {
@@ -126,9 +146,15 @@ pub fn build_derive_object(
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: attrs.scalar.map(SpanContainer::into_inner),
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs
+            .directives
+            .into_iter()
+            .map(SpanContainer::into_inner)
+            .collect(),
         fields,
         generics: ast.generics,
         interfaces: None,
+        node: attrs.node.is_some(),
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
@@ -92,6 +92,13 @@ pub fn build_derive_union(
                 );
             }
 
+            if let Some(directive) = field_attrs.directives.first() {
+                error.unsupported_attribute_within(
+                    directive.span_ident(),
+                    UnsupportedAttribute::Directive,
+                );
+            }
+
             if let Some(default) = field_attrs.default {
                 error.unsupported_attribute_within(
                     default.span_ident(),
@@ -113,6 +120,10 @@ pub fn build_derive_union(
                 args: Vec::new(),
                 description: None,
                 deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: Vec::new(),
+                complexity: None,
+                list_size: None,
+                guard: None,
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
@@ -131,6 +142,26 @@ pub fn build_derive_union(
         });
     }
 
+    attrs.implementors.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Implementors)
+    });
+
+    if let Some(ref value_enum) = attrs.value_enum {
+        error.unsupported_attribute(value_enum.span_ident(), UnsupportedAttribute::Enum);
+    }
+
+    if let Some(ref one_of) = attrs.one_of {
+        error.unsupported_attribute(one_of.span_ident(), UnsupportedAttribute::OneOf);
+    }
+
+    if let Some(ref node) = attrs.node {
+        error.unsupported_attribute(node.span_ident(), UnsupportedAttribute::Node);
+    }
+
+    if let Some(directive) = attrs.directives.first() {
+        error.unsupported_attribute(directive.span_ident(), UnsupportedAttribute::Directive);
+    }
+
     if fields.is_empty() {
         error.not_empty(ast_span);
     }
@@ -169,9 +200,11 @@ pub fn build_derive_union(
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: attrs.scalar.map(SpanContainer::into_inner),
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: Vec::new(),
         fields,
         generics: ast.generics,
         interfaces: None,
+        node: false,
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
@@ -0,0 +1,732 @@
+use crate::{
+    result::{GraphQLScope, UnsupportedAttribute},
+    util::{self, span_container::SpanContainer},
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, parse_quote, spanned::Spanned};
+
+/// Generate code for the juniper::graphql_interface macro.
+pub fn impl_interface(
+    is_internal: bool,
+    attrs: TokenStream,
+    body: TokenStream,
+    error: GraphQLScope,
+) -> syn::Result<TokenStream> {
+    let attrs = syn::parse2::<util::ObjectAttributes>(attrs)?;
+    let mut _trait = syn::parse2::<syn::ItemTrait>(body)?;
+
+    let trait_ident = _trait.ident.clone();
+    let name = attrs
+        .name
+        .clone()
+        .map(SpanContainer::into_inner)
+        .unwrap_or_else(|| trait_ident.unraw().to_string());
+    let crate_name = util::juniper_path(is_internal);
+
+    if !_trait.generics.params.is_empty() {
+        error.custom(
+            _trait.generics.span(),
+            "can not be declared by a generic trait",
+        );
+    }
+
+    attrs
+        .interfaces
+        .iter()
+        .for_each(|elm| error.unsupported_attribute(elm.span(), UnsupportedAttribute::Interface));
+
+    if let Some(ref node) = attrs.node {
+        error.unsupported_attribute(node.span_ident(), UnsupportedAttribute::Node);
+    }
+
+    if let Some(ref one_of) = attrs.one_of {
+        error.unsupported_attribute(one_of.span_ident(), UnsupportedAttribute::OneOf);
+    }
+
+    attrs.directives.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Directive)
+    });
+
+    if attrs.implementors.is_empty() {
+        error.custom(
+            trait_ident.span(),
+            "expects its implementors to be listed: `for = [...]`",
+        );
+    }
+
+    // The variants of the enum of implementors are named after their types.
+    let variant_idents = if attrs.value_enum.is_some() {
+        attrs
+            .implementors
+            .iter()
+            .filter_map(|ty| {
+                let ident = util::name_of_type(ty.inner());
+                if ident.is_none() {
+                    error.custom(
+                        ty.span(),
+                        "expects the implementors in its enum to be named types",
+                    );
+                }
+                ident
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let fields = _trait
+        .items
+        .iter_mut()
+        .filter_map(|item| match item {
+            syn::TraitItem::Method(method) => Some(method),
+            _ => None,
+        })
+        .filter_map(|method| {
+            let span = method.span();
+            let field_attrs = match util::FieldAttributes::from_attrs(
+                &method.attrs,
+                util::FieldAttributeParseMode::Impl,
+            ) {
+                Ok(attrs) => attrs,
+                Err(err) => {
+                    proc_macro_error::emit_error!(err);
+                    return None;
+                }
+            };
+            // The attributes are only known to this macro, not to the trait.
+            method.attrs.retain(|attr| !attr.path.is_ident("graphql"));
+
+            // Skipped methods are regular trait methods, not fields.
+            if field_attrs.skip.is_some() {
+                return None;
+            }
+
+            let _type = match method.sig.output {
+                syn::ReturnType::Type(_, ref t) => elide_lifetimes(t),
+                syn::ReturnType::Default => {
+                    error.custom(method.sig.span(), "return value required");
+                    return None;
+                }
+            };
+
+            if let Some(asyncness) = method.sig.asyncness {
+                error.custom(
+                    asyncness.span(),
+                    "fields can not be async: they are resolved by the fields of the implementors, \
+                     which may be async",
+                );
+                return None;
+            }
+
+            let parse_signature = util::parse_impl::parse_signature(
+                &method.sig,
+                attrs.context.as_ref(),
+                false,
+                |captured, arg_ident, _| {
+                    let arg_name = arg_ident.unraw().to_string();
+                    let final_name = field_attrs
+                        .argument(&arg_name)
+                        .and_then(|attrs| attrs.rename.clone().map(|ident| ident.value()))
+                        .unwrap_or_else(|| util::to_camel_case(&arg_name));
+
+                    if final_name.starts_with("__") {
+                        error.no_double_underscore(arg_ident.span());
+                    }
+
+                    let field_type = util::GraphQLTypeDefinitionFieldArg {
+                        description: field_attrs
+                            .argument(&arg_name)
+                            .and_then(|arg| arg.description.as_ref().map(|d| d.value())),
+                        default: field_attrs
+                            .argument(&arg_name)
+                            .and_then(|arg| arg.default.clone()),
+                        directives: field_attrs
+                            .argument(&arg_name)
+                            .map(|arg| arg.directives.clone())
+                            .unwrap_or_default(),
+                        _type: captured.ty.clone(),
+                        name: final_name,
+                    };
+                    Ok((quote!(), field_type))
+                },
+            );
+
+            let args = match parse_signature {
+                Ok((_, args)) => args,
+                Err(err) => {
+                    proc_macro_error::emit_error!(err);
+                    return None;
+                }
+            };
+
+            let ident = &method.sig.ident;
+            let name = field_attrs
+                .name
+                .clone()
+                .map(SpanContainer::into_inner)
+                .unwrap_or_else(|| util::to_camel_case(&ident.unraw().to_string()));
+
+            if name.starts_with("__") {
+                error.no_double_underscore(if let Some(name) = field_attrs.name {
+                    name.span_ident()
+                } else {
+                    ident.span()
+                });
+            }
+
+            if let Some(default) = field_attrs.default {
+                error.unsupported_attribute_within(
+                    default.span_ident(),
+                    UnsupportedAttribute::Default,
+                );
+            }
+
+            if let Some(guard) = field_attrs.guard {
+                error.unsupported_attribute_within(guard.span_ident(), UnsupportedAttribute::Guard);
+            }
+
+            Some(util::GraphQLTypeDefinitionField {
+                name,
+                _type,
+                args,
+                description: field_attrs.description.map(SpanContainer::into_inner),
+                deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: field_attrs
+                    .directives
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                complexity: field_attrs.complexity.map(SpanContainer::into_inner),
+                list_size: field_attrs.list_size.map(SpanContainer::into_inner),
+                guard: None,
+                resolver_code: quote!(),
+                is_type_inferred: false,
+                is_async: false,
+                default: None,
+                span,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Early abort after checking all fields
+    proc_macro_error::abort_if_dirty();
+
+    if let Some(duplicates) =
+        crate::util::duplicate::Duplicate::find_by_key(&fields, |field| field.name.as_str())
+    {
+        error.duplicate(duplicates.iter());
+    }
+
+    if name.starts_with("__") && !is_internal {
+        error.no_double_underscore(if let Some(name) = attrs.name.as_ref() {
+            name.span_ident()
+        } else {
+            trait_ident.span()
+        });
+    }
+
+    if fields.is_empty() {
+        error.not_empty(_trait.span());
+    }
+
+    // Early abort after GraphQL properties
+    proc_macro_error::abort_if_dirty();
+
+    let scalar = attrs
+        .scalar
+        .as_ref()
+        .map(|s| quote!( #s ))
+        .unwrap_or_else(|| quote!( #crate_name::DefaultScalarValue ));
+    let context = attrs
+        .context
+        .as_ref()
+        .map(|c| quote!( #c ))
+        .unwrap_or_else(|| quote!(()));
+    let description = attrs
+        .description
+        .clone()
+        .or_else(|| util::get_doc_comment(&_trait.attrs))
+        .map(|description| {
+            let description = description.into_inner();
+            quote!( .description(#description) )
+        });
+
+    let implementors = attrs
+        .implementors
+        .iter()
+        .map(SpanContainer::inner)
+        .collect::<Vec<_>>();
+    let field_definitions = fields.iter().map(|field| field.meta_tokens(&crate_name));
+
+    // Values of the interface are downcast to the implementors, which resolve
+    // the fields of the interface with their own fields.
+    let supertrait: syn::TypeParamBound = parse_quote!( #crate_name::AsAny );
+    _trait.supertraits.push(supertrait);
+
+    // Only the implementors listed in `for = [...]` can implement the trait,
+    // so that downcasting a value of the interface never fails.
+    let sealed_mod = quote::format_ident!("__juniper_{}", trait_ident.unraw());
+    let sealed_doc = format!(
+        "Implemented by the types listed in `for = [...]` of the `{}` interface.",
+        name
+    );
+    let sealed: syn::TypeParamBound = parse_quote!( #sealed_mod::Implementor );
+    _trait.supertraits.push(sealed);
+    if _trait.colon_token.is_none() {
+        _trait.colon_token = Some(Default::default());
+    }
+
+    let object_marks = implementors
+        .iter()
+        .map(|ty| quote!( <#ty as #crate_name::marker::GraphQLObjectType<#scalar>>::mark(); ))
+        .collect::<Vec<_>>();
+
+    let meta_types = implementors
+        .iter()
+        .map(|ty| quote!( let _ = registry.get_type::<#ty>(info); ));
+
+    let meta_impl = quote! {
+        fn name(_: &Self::TypeInfo) -> Option<&str> {
+            Some(#name)
+        }
+
+        fn meta<'r>(
+            info: &Self::TypeInfo,
+            registry: &mut #crate_name::Registry<'r, #scalar>
+        ) -> #crate_name::meta::MetaType<'r, #scalar>
+            where #scalar: 'r,
+        {
+            // Ensure all implementors are registered
+            #( #meta_types )*
+
+            let fields = vec![
+                #( #field_definitions ),*
+            ];
+            registry.build_interface_type::<Self>(info, &fields)
+                #description
+                .into_meta()
+        }
+    };
+
+    let mark_impl = quote! {
+        fn mark() {
+            #( #object_marks )*
+        }
+    };
+
+    let as_any = quote!( #crate_name::AsAny::as_any(&**self) );
+
+    let unhandled = format!("Concrete type not handled by the implementors of {}", name);
+    let unhandled = quote!( Err(#crate_name::FieldError::from(#unhandled)) );
+    let unhandled_async = quote! {{
+        use futures::future;
+        future::FutureExt::boxed(future::ready(#unhandled))
+    }};
+
+    let resolve_field = implementors.iter().map(|ty| {
+        quote! {
+            if let Some(value) = value.downcast_ref::<#ty>() {
+                return <#ty as #crate_name::GraphQLType<#scalar>>::resolve_field(
+                    value, &(), field, args, executor,
+                );
+            }
+        }
+    });
+
+    let concrete_type_name = implementors.iter().map(|ty| {
+        quote! {
+            if value.is::<#ty>() {
+                return <#ty as #crate_name::GraphQLType<#scalar>>::name(&()).unwrap().to_string();
+            }
+        }
+    });
+
+    let resolve_into_type = implementors.iter().map(|ty| {
+        quote! {
+            if type_name == (<#ty as #crate_name::GraphQLType<#scalar>>::name(&())).unwrap() {
+                return executor.resolve(&(), &value.downcast_ref::<#ty>());
+            }
+        }
+    });
+
+    let resolve_field_async = implementors.iter().map(|ty| {
+        quote! {
+            if let Some(value) = value.downcast_ref::<#ty>() {
+                return <#ty as #crate_name::GraphQLTypeAsync<#scalar>>::resolve_field_async(
+                    value, &(), field, args, executor,
+                );
+            }
+        }
+    });
+
+    let resolve_into_type_async = implementors.iter().map(|ty| {
+        quote! {
+            if type_name == (<#ty as #crate_name::GraphQLType<#scalar>>::name(&())).unwrap() {
+                let value = value.downcast_ref::<#ty>();
+                return Box::pin(async move { executor.resolve_async(&(), &value).await });
+            }
+        }
+    });
+
+    let type_impl = quote! {
+        #meta_impl
+
+        #[allow(unused_variables)]
+        fn resolve_field(
+            &self,
+            _info: &Self::TypeInfo,
+            field: &str,
+            args: &#crate_name::Arguments<#scalar>,
+            executor: &#crate_name::Executor<Self::Context, #scalar>,
+        ) -> #crate_name::ExecutionResult<#scalar> {
+            let value = #as_any;
+
+            #( #resolve_field )*
+
+            #unhandled
+        }
+
+        fn concrete_type_name(&self, _: &Self::Context, _: &Self::TypeInfo) -> String {
+            let value = #as_any;
+
+            #( #concrete_type_name )*
+
+            // Other types can not implement the sealed `Implementor` trait.
+            unreachable!("Concrete type not handled by the implementors of {}", #name);
+        }
+
+        fn resolve_into_type(
+            &self,
+            _info: &Self::TypeInfo,
+            type_name: &str,
+            _: Option<&[#crate_name::Selection<#scalar>]>,
+            executor: &#crate_name::Executor<Self::Context, #scalar>,
+        ) -> #crate_name::ExecutionResult<#scalar> {
+            let value = #as_any;
+
+            #( #resolve_into_type )*
+
+            #unhandled
+        }
+    };
+
+    let async_type_impl = quote! {
+        #[allow(unused_variables)]
+        fn resolve_field_async<'b>(
+            &'b self,
+            _info: &'b Self::TypeInfo,
+            field: &'b str,
+            args: &'b #crate_name::Arguments<#scalar>,
+            executor: &'b #crate_name::Executor<Self::Context, #scalar>,
+        ) -> #crate_name::BoxFuture<'b, #crate_name::ExecutionResult<#scalar>> {
+            let value = #as_any;
+
+            #( #resolve_field_async )*
+
+            #unhandled_async
+        }
+
+        fn resolve_into_type_async<'b>(
+            &'b self,
+            _info: &'b Self::TypeInfo,
+            type_name: &str,
+            _: Option<&'b [#crate_name::Selection<'b, #scalar>]>,
+            executor: &'b #crate_name::Executor<'b, 'b, Self::Context, #scalar>,
+        ) -> #crate_name::BoxFuture<'b, #crate_name::ExecutionResult<#scalar>> {
+            let value = #as_any;
+
+            #( #resolve_into_type_async )*
+
+            #unhandled_async
+        }
+    };
+
+    // Interfaces are used through references and boxes of trait objects.
+    let impls = [
+        (
+            quote!( <'a, 'obj> ),
+            quote!( &'a (dyn #trait_ident + 'obj) ),
+        ),
+        (quote!( <'obj> ), quote!( Box<dyn #trait_ident + 'obj> )),
+    ]
+    .iter()
+    .map(|(generics, ty)| {
+        let async_impl = if attrs.no_async.is_none() {
+            Some(quote! {
+                impl #generics #crate_name::GraphQLTypeAsync<#scalar> for #ty
+                    where #scalar: Send + Sync,
+                {
+                    #async_type_impl
+                }
+            })
+        } else {
+            None
+        };
+
+        quote! {
+            impl #generics #crate_name::marker::IsOutputType<#scalar> for #ty {
+                #mark_impl
+            }
+
+            impl #generics #crate_name::GraphQLType<#scalar> for #ty {
+                type Context = #context;
+                type TypeInfo = ();
+
+                #type_impl
+            }
+
+            #async_impl
+        }
+    })
+    .collect::<Vec<_>>();
+
+    // Optionally, interfaces are also used through an enum of their
+    // implementors, which is matched instead of downcast.
+    let enum_impl = attrs.value_enum.as_ref().map(|enum_ident| {
+        let vis = &_trait.vis;
+        let doc = format!("Values of the `{}` GraphQL interface.", name);
+        let variants = implementors
+            .iter()
+            .zip(variant_idents.iter())
+            .map(|(ty, variant)| quote!( #variant(#ty) ))
+            .collect::<Vec<_>>();
+
+        let from_impls = implementors.iter().zip(variant_idents.iter()).map(|(ty, variant)| {
+            quote! {
+                impl From<#ty> for #enum_ident {
+                    fn from(value: #ty) -> Self {
+                        #enum_ident::#variant(value)
+                    }
+                }
+            }
+        });
+
+        let arms = |f: &dyn Fn(&syn::Type) -> TokenStream| {
+            implementors
+                .iter()
+                .zip(variant_idents.iter())
+                .map(|(ty, variant)| {
+                    let body = f(ty);
+                    quote!( #enum_ident::#variant(ref value) => { #body } )
+                })
+                .collect::<Vec<_>>()
+        };
+        // Fragments on the other implementors resolve to null.
+        let type_arms = |resolved: TokenStream, unresolved: TokenStream| {
+            implementors
+                .iter()
+                .zip(variant_idents.iter())
+                .map(|(ty, variant)| {
+                    quote! {
+                        if type_name == (<#ty as #crate_name::GraphQLType<#scalar>>::name(&())).unwrap() {
+                            return match *self {
+                                #enum_ident::#variant(ref value) => #resolved,
+                                _ => #unresolved,
+                            };
+                        }
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let resolve_field = arms(&|ty| {
+            quote! {
+                <#ty as #crate_name::GraphQLType<#scalar>>::resolve_field(
+                    value, &(), field, args, executor,
+                )
+            }
+        });
+        let concrete_type_name = arms(&|ty| {
+            quote!( <#ty as #crate_name::GraphQLType<#scalar>>::name(&()).unwrap().to_string() )
+        });
+        let resolve_into_type = type_arms(
+            quote!(executor.resolve(&(), value)),
+            quote!(Ok(#crate_name::Value::null())),
+        );
+        let resolve_field_async = arms(&|ty| {
+            quote! {
+                <#ty as #crate_name::GraphQLTypeAsync<#scalar>>::resolve_field_async(
+                    value, &(), field, args, executor,
+                )
+            }
+        });
+        let resolve_into_type_async = type_arms(
+            quote!(Box::pin(async move { executor.resolve_async(&(), value).await })),
+            quote!(Box::pin(async { Ok(#crate_name::Value::null()) })),
+        );
+
+        let async_impl = if attrs.no_async.is_none() {
+            Some(quote! {
+                impl #crate_name::GraphQLTypeAsync<#scalar> for #enum_ident
+                    where #scalar: Send + Sync,
+                {
+                    #[allow(unused_variables)]
+                    fn resolve_field_async<'b>(
+                        &'b self,
+                        _info: &'b Self::TypeInfo,
+                        field: &'b str,
+                        args: &'b #crate_name::Arguments<#scalar>,
+                        executor: &'b #crate_name::Executor<Self::Context, #scalar>,
+                    ) -> #crate_name::BoxFuture<'b, #crate_name::ExecutionResult<#scalar>> {
+                        match *self {
+                            #( #resolve_field_async )*
+                        }
+                    }
+
+                    #[allow(unreachable_patterns)]
+                    fn resolve_into_type_async<'b>(
+                        &'b self,
+                        _info: &'b Self::TypeInfo,
+                        type_name: &str,
+                        _: Option<&'b [#crate_name::Selection<'b, #scalar>]>,
+                        executor: &'b #crate_name::Executor<'b, 'b, Self::Context, #scalar>,
+                    ) -> #crate_name::BoxFuture<'b, #crate_name::ExecutionResult<#scalar>> {
+                        #( #resolve_into_type_async )*
+
+                        #unhandled_async
+                    }
+                }
+            })
+        } else {
+            None
+        };
+
+        quote! {
+            #[doc = #doc]
+            #vis enum #enum_ident {
+                #( #variants ),*
+            }
+
+            #( #from_impls )*
+
+            impl #crate_name::marker::IsOutputType<#scalar> for #enum_ident {
+                #mark_impl
+            }
+
+            impl #crate_name::GraphQLType<#scalar> for #enum_ident {
+                type Context = #context;
+                type TypeInfo = ();
+
+                #meta_impl
+
+                #[allow(unused_variables)]
+                fn resolve_field(
+                    &self,
+                    _info: &Self::TypeInfo,
+                    field: &str,
+                    args: &#crate_name::Arguments<#scalar>,
+                    executor: &#crate_name::Executor<Self::Context, #scalar>,
+                ) -> #crate_name::ExecutionResult<#scalar> {
+                    match *self {
+                        #( #resolve_field )*
+                    }
+                }
+
+                fn concrete_type_name(&self, _: &Self::Context, _: &Self::TypeInfo) -> String {
+                    match *self {
+                        #( #concrete_type_name )*
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn resolve_into_type(
+                    &self,
+                    _info: &Self::TypeInfo,
+                    type_name: &str,
+                    _: Option<&[#crate_name::Selection<#scalar>]>,
+                    executor: &#crate_name::Executor<Self::Context, #scalar>,
+                ) -> #crate_name::ExecutionResult<#scalar> {
+                    #( #resolve_into_type )*
+
+                    #unhandled
+                }
+            }
+
+            #async_impl
+        }
+    });
+
+    let sealed_impls = implementors
+        .iter()
+        .map(|ty| quote!( impl #sealed_mod::Implementor for #ty {} ));
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #sealed_mod {
+            #[doc = #sealed_doc]
+            pub trait Implementor {}
+        }
+
+        #( #sealed_impls )*
+
+        #_trait
+
+        #( #impls )*
+
+        #enum_impl
+    })
+}
+
+/// Replace the lifetimes of a field type by `'_`, as the type is only named
+/// in the `meta` of the interface, where the lifetimes of the trait method
+/// are not declared.
+fn elide_lifetimes(ty: &syn::Type) -> syn::Type {
+    fn elide_path(path: &mut syn::Path) {
+        for segment in path.segments.iter_mut() {
+            match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref mut generic) => {
+                    for arg in generic.args.iter_mut() {
+                        match arg {
+                            syn::GenericArgument::Lifetime(lifetime) => {
+                                *lifetime = parse_quote!('_);
+                            }
+                            syn::GenericArgument::Type(ty) => *ty = elide_lifetimes(ty),
+                            _ => {}
+                        }
+                    }
+                }
+                syn::PathArguments::Parenthesized(ref mut generic) => {
+                    for ty in generic.inputs.iter_mut() {
+                        *ty = elide_lifetimes(ty);
+                    }
+                    if let syn::ReturnType::Type(_, ref mut ty) = generic.output {
+                        **ty = elide_lifetimes(ty);
+                    }
+                }
+                syn::PathArguments::None => {}
+            }
+        }
+    }
+
+    let mut ty = ty.clone();
+    match ty {
+        syn::Type::Reference(ref mut reference) => {
+            if reference.lifetime.is_some() {
+                reference.lifetime = Some(parse_quote!('_));
+            }
+            *reference.elem = elide_lifetimes(&reference.elem);
+        }
+        syn::Type::Path(ref mut path) => elide_path(&mut path.path),
+        syn::Type::Slice(ref mut slice) => *slice.elem = elide_lifetimes(&slice.elem),
+        syn::Type::Array(ref mut array) => *array.elem = elide_lifetimes(&array.elem),
+        syn::Type::Paren(ref mut paren) => *paren.elem = elide_lifetimes(&paren.elem),
+        syn::Type::Group(ref mut group) => *group.elem = elide_lifetimes(&group.elem),
+        syn::Type::Tuple(ref mut tuple) => {
+            for elem in tuple.elems.iter_mut() {
+                *elem = elide_lifetimes(elem);
+            }
+        }
+        syn::Type::TraitObject(ref mut object) => {
+            for bound in object.bounds.iter_mut() {
+                match bound {
+                    syn::TypeParamBound::Lifetime(lifetime) => *lifetime = parse_quote!('_),
+                    syn::TypeParamBound::Trait(bound) => elide_path(&mut bound.path),
+                }
+            }
+        }
+        _ => {}
+    }
+    ty
+}
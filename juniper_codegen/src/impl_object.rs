@@ -125,6 +125,10 @@ fn create(
                         default: attrs
                             .argument(&arg_name)
                             .and_then(|arg| arg.default.clone()),
+                        directives: attrs
+                            .argument(&arg_name)
+                            .map(|arg| arg.directives.clone())
+                            .unwrap_or_default(),
                         _type: ty.clone(),
                         name: final_name,
                     };
@@ -173,6 +177,14 @@ fn create(
                 args,
                 description: attrs.description.map(SpanContainer::into_inner),
                 deprecation: attrs.deprecation.map(SpanContainer::into_inner),
+                directives: attrs
+                    .directives
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                complexity: attrs.complexity.map(SpanContainer::into_inner),
+                list_size: attrs.list_size.map(SpanContainer::into_inner),
+                guard: attrs.guard.map(SpanContainer::into_inner),
                 resolver_code,
                 is_type_inferred: false,
                 is_async,
@@ -202,6 +214,18 @@ fn create(
         error.not_empty(body_span);
     }
 
+    _impl.attrs.implementors.iter().for_each(|elm| {
+        error.unsupported_attribute(elm.span_ident(), UnsupportedAttribute::Implementors)
+    });
+
+    if let Some(ref value_enum) = _impl.attrs.value_enum {
+        error.unsupported_attribute(value_enum.span_ident(), UnsupportedAttribute::Enum);
+    }
+
+    if let Some(ref one_of) = _impl.attrs.one_of {
+        error.unsupported_attribute(one_of.span_ident(), UnsupportedAttribute::OneOf);
+    }
+
     // Early abort after GraphQL properties
     proc_macro_error::abort_if_dirty();
 
@@ -211,6 +235,12 @@ fn create(
         scalar: _impl.attrs.scalar.map(SpanContainer::into_inner),
         context: _impl.attrs.context.map(SpanContainer::into_inner),
         description: _impl.description,
+        directives: _impl
+            .attrs
+            .directives
+            .into_iter()
+            .map(SpanContainer::into_inner)
+            .collect(),
         fields,
         generics: _impl.generics.clone(),
         interfaces: if !_impl.attrs.interfaces.is_empty() {
@@ -225,6 +255,7 @@ fn create(
         } else {
             None
         },
+        node: _impl.attrs.node.is_some(),
         include_type_generics: false,
         generic_scalar: false,
         no_async: _impl.attrs.no_async.is_some(),
@@ -17,6 +17,7 @@ mod derive_input_object;
 mod derive_object;
 mod derive_scalar_value;
 mod derive_union;
+mod impl_interface;
 mod impl_object;
 mod impl_scalar;
 mod impl_union;
@@ -338,6 +339,25 @@ impl InternalQuery {
     )]
     fn deprecated_field_simple() -> bool { true }
 
+    // The cost of resolving a field, and the expected number of items of a
+    // list field, can be set for `RootNode::with_max_complexity`.
+    #[graphql(complexity = 5, list_size = 20)]
+    fn expensive_list() -> Vec<bool> { Vec::new() }
+
+    // Type system directives can be attached to fields, arguments and the
+    // type itself. They show up in the schema's metadata and printed SDL.
+    #[graphql(
+        directive(cost(weight = 10), internal),
+        arguments(id(directive(length(max = 32)))),
+    )]
+    fn annotated(id: String) -> String { id }
+
+    // A guard runs before the field is resolved, and can reject it with an
+    // error, or with null by returning `Ok(false)`. Any expression
+    // implementing `juniper::Guard`, like a function, can be used.
+    #[graphql(guard = |_: &(), _: &juniper::Arguments| Ok(true))]
+    fn guarded() -> Option<bool> { Some(true) }
+
 
     // Customizing field arguments is a little awkward right now.
     // This will improve once [RFC 2564](https://github.com/rust-lang/rust/issues/60406)
@@ -554,6 +574,109 @@ pub fn graphql_subscription_internal(args: TokenStream, input: TokenStream) -> T
     ))
 }
 
+/// Expose a trait as a GraphQL interface.
+///
+/// The methods of the trait declare the fields of the interface, following
+/// the syntax of `#[graphql_object]`: arguments, context and executor
+/// parameters, doc comments and `#[graphql(...)]` attributes work the same.
+/// The object types implementing the interface are listed with `for = [...]`;
+/// they are registered in the schema along with the interface.
+///
+/// Values of the interface are trait objects, either `&dyn Trait` or
+/// `Box<dyn Trait>`. They are resolved by the fields of the implementing
+/// objects, which may be async, so the methods of the trait only serve as
+/// declarations. As trait objects are downcast to their concrete type, the
+/// implementors must be `'static`, `Send` and `Sync`.
+///
+/// ```
+/// /// A character in the Star Wars Trilogy
+/// #[juniper::graphql_interface(for = [Human, Droid])]
+/// trait Character {
+///     /// The id of the character
+///     fn id(&self) -> &str;
+/// }
+///
+/// struct Human {
+///     id: String,
+///     home_planet: String,
+/// }
+///
+/// impl Character for Human {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+/// }
+///
+/// #[juniper::graphql_object(interfaces = [&dyn Character])]
+/// impl Human {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+///
+///     fn home_planet(&self) -> &str {
+///         &self.home_planet
+///     }
+/// }
+///
+/// struct Droid {
+///     id: String,
+/// }
+///
+/// impl Character for Droid {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+/// }
+///
+/// #[juniper::graphql_object(interfaces = [&dyn Character])]
+/// impl Droid {
+///     async fn id(&self) -> &str {
+///         &self.id
+///     }
+/// }
+///
+/// struct Query;
+///
+/// #[juniper::graphql_object]
+/// impl Query {
+///     fn hero(&self) -> Box<dyn Character> {
+///         Box::new(Droid { id: "2001".to_owned() })
+///     }
+/// }
+/// ```
+///
+/// With `enum = CharacterValue`, an enum of the implementors is generated as
+/// well, with a variant named after each of them and `From` conversions. It
+/// is another value of the interface, resolved by matching its variants.
+///
+/// The interface accepts the `name`, `description`, `Context` and `Scalar`
+/// attributes of objects, as well as `noasync` when its implementors are not
+/// async. Methods marked `#[graphql(skip)]` are not fields.
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn graphql_interface(attrs: TokenStream, body: TokenStream) -> TokenStream {
+    let attrs = proc_macro2::TokenStream::from(attrs);
+    let body = proc_macro2::TokenStream::from(body);
+    let gen = impl_interface::impl_interface(false, attrs, body, GraphQLScope::ImplInterface);
+    match gen {
+        Ok(gen) => gen.into(),
+        Err(err) => proc_macro_error::abort!(err),
+    }
+}
+
+#[proc_macro_error]
+#[proc_macro_attribute]
+#[doc(hidden)]
+pub fn graphql_interface_internal(attrs: TokenStream, body: TokenStream) -> TokenStream {
+    let attrs = proc_macro2::TokenStream::from(attrs);
+    let body = proc_macro2::TokenStream::from(body);
+    let gen = impl_interface::impl_interface(true, attrs, body, GraphQLScope::ImplInterface);
+    match gen {
+        Ok(gen) => gen.into(),
+        Err(err) => proc_macro_error::abort!(err),
+    }
+}
+
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn graphql_union(attrs: TokenStream, body: TokenStream) -> TokenStream {
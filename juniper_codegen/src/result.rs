@@ -17,6 +17,7 @@ pub enum GraphQLScope {
     ImplUnion,
     ImplScalar,
     ImplObject,
+    ImplInterface,
 }
 
 impl GraphQLScope {
@@ -25,6 +26,7 @@ impl GraphQLScope {
             GraphQLScope::DeriveObject | GraphQLScope::ImplObject => "#sec-Objects",
             GraphQLScope::DeriveInputObject => "#sec-Input-Objects",
             GraphQLScope::DeriveUnion | GraphQLScope::ImplUnion => "#sec-Unions",
+            GraphQLScope::ImplInterface => "#sec-Interfaces",
             GraphQLScope::DeriveEnum => "#sec-Enums",
             GraphQLScope::DeriveScalar | GraphQLScope::ImplScalar => "#sec-Scalars",
         }
@@ -37,6 +39,7 @@ impl fmt::Display for GraphQLScope {
             GraphQLScope::DeriveObject | GraphQLScope::ImplObject => "object",
             GraphQLScope::DeriveInputObject => "input object",
             GraphQLScope::DeriveUnion | GraphQLScope::ImplUnion => "union",
+            GraphQLScope::ImplInterface => "interface",
             GraphQLScope::DeriveEnum => "enum",
             GraphQLScope::DeriveScalar | GraphQLScope::ImplScalar => "scalar",
         };
@@ -50,10 +53,16 @@ impl fmt::Display for GraphQLScope {
 pub enum UnsupportedAttribute {
     Skip,
     Interface,
+    Implementors,
+    Enum,
     Scalar,
     Description,
     Deprecation,
     Default,
+    Directive,
+    Node,
+    OneOf,
+    Guard,
 }
 
 impl GraphQLScope {
@@ -10,8 +10,8 @@ use quote::quote;
 use span_container::SpanContainer;
 use std::collections::HashMap;
 use syn::{
-    parse, parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Lit, Meta, MetaList,
-    MetaNameValue, NestedMeta, Token,
+    ext::IdentExt, parse, parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Lit,
+    Meta, MetaList, MetaNameValue, NestedMeta, Token,
 };
 
 pub fn juniper_path(is_internal: bool) -> syn::Path {
@@ -79,6 +79,133 @@ pub struct DeprecationAttr {
     pub reason: Option<String>,
 }
 
+/// A schema directive, e.g. `key(fields = "id")` in
+/// `#[graphql(directive(key(fields = "id")))]`.
+#[derive(Clone, Debug)]
+pub struct DirectiveAttr {
+    pub name: String,
+    pub arguments: Vec<(String, DirectiveValue)>,
+}
+
+/// The value of a schema directive argument: a literal, an enum value or a
+/// list of values.
+#[derive(Clone, Debug)]
+pub enum DirectiveValue {
+    Lit(syn::Lit),
+    Enum(String),
+    List(Vec<DirectiveValue>),
+}
+
+impl parse::Parse for DirectiveAttr {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        let ident = input.call(syn::Ident::parse_any)?;
+        let name = ident.unraw().to_string();
+        if !is_valid_name(&name) {
+            return Err(syn::Error::new(
+                ident.span(),
+                "name consists of not allowed characters. (must match /^[_a-zA-Z][_a-zA-Z0-9]*$/)",
+            ));
+        }
+
+        let mut arguments = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            while !content.is_empty() {
+                let arg_name = content.call(syn::Ident::parse_any)?;
+                content.parse::<Token![=]>()?;
+                arguments.push((arg_name.unraw().to_string(), content.parse()?));
+
+                // Discard trailing comma.
+                content.parse::<Token![,]>().ok();
+            }
+        }
+
+        Ok(DirectiveAttr { name, arguments })
+    }
+}
+
+impl parse::Parse for DirectiveValue {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        if input.peek(syn::token::Bracket) {
+            let content;
+            syn::bracketed!(content in input);
+            let items = Punctuated::<DirectiveValue, Token![,]>::parse_terminated(&content)?;
+            Ok(DirectiveValue::List(items.into_iter().collect()))
+        } else if input.peek(syn::Lit) {
+            match input.parse()? {
+                lit @ Lit::Str(_)
+                | lit @ Lit::Int(_)
+                | lit @ Lit::Float(_)
+                | lit @ Lit::Bool(_) => Ok(DirectiveValue::Lit(lit)),
+                lit => Err(syn::Error::new(
+                    lit.span(),
+                    "only string, integer, float and boolean literals are supported",
+                )),
+            }
+        } else {
+            let ident = input.call(syn::Ident::parse_any)?;
+            Ok(DirectiveValue::Enum(ident.unraw().to_string()))
+        }
+    }
+}
+
+impl DirectiveAttr {
+    /// Generates the `SchemaDirective` of this directive.
+    pub fn to_tokens(&self, juniper_crate_name: &syn::Path) -> TokenStream {
+        let name = &self.name;
+        let arguments = self.arguments.iter().map(|(arg_name, value)| {
+            let value = value.to_tokens(juniper_crate_name);
+            quote!( .argument(#arg_name, #value) )
+        });
+
+        quote! {
+            #juniper_crate_name::meta::SchemaDirective::new(#name)
+                #( #arguments )*
+        }
+    }
+}
+
+impl DirectiveValue {
+    fn to_tokens(&self, juniper_crate_name: &syn::Path) -> TokenStream {
+        match self {
+            DirectiveValue::Lit(lit) => quote!( #juniper_crate_name::InputValue::scalar(#lit) ),
+            DirectiveValue::Enum(name) => {
+                quote!( #juniper_crate_name::InputValue::enum_value(#name) )
+            }
+            DirectiveValue::List(items) => {
+                let items = items.iter().map(|item| item.to_tokens(juniper_crate_name));
+                quote!( #juniper_crate_name::InputValue::list(vec![ #( #items ),* ]) )
+            }
+        }
+    }
+}
+
+/// Generates `.directive(..)` calls for the builder of a meta type.
+fn directive_calls(directives: &[DirectiveAttr], juniper_crate_name: &syn::Path) -> TokenStream {
+    let directives = directives
+        .iter()
+        .map(|directive| directive.to_tokens(juniper_crate_name));
+    quote!( #( .directive(#directives) )* )
+}
+
+/// Generates the check of a field's guard, returning `deny(denied)` from the
+/// resolver if the guard rejects the field.
+fn guard_check(
+    guard: Option<&syn::Expr>,
+    args: TokenStream,
+    deny: impl FnOnce(TokenStream) -> TokenStream,
+    juniper_crate_name: &syn::Path,
+) -> Option<TokenStream> {
+    let guard = guard?;
+    let denied = deny(quote!(denied));
+    Some(quote!(
+        if let Err(denied) = #juniper_crate_name::check_guard(&(#guard), executor.context(), #args) {
+            return #denied;
+        }
+    ))
+}
+
 pub fn find_graphql_attr(attrs: &[Attribute]) -> Option<&Attribute> {
     attrs
         .iter()
@@ -278,6 +405,11 @@ pub struct ObjectAttributes {
     pub context: Option<SpanContainer<syn::Type>>,
     pub scalar: Option<SpanContainer<syn::Type>>,
     pub interfaces: Vec<SpanContainer<syn::Type>>,
+    pub implementors: Vec<SpanContainer<syn::Type>>,
+    pub value_enum: Option<SpanContainer<syn::Ident>>,
+    pub directives: Vec<SpanContainer<DirectiveAttr>>,
+    pub node: Option<SpanContainer<()>>,
+    pub one_of: Option<SpanContainer<()>>,
     pub no_async: Option<SpanContainer<()>>,
 }
 
@@ -289,11 +421,17 @@ impl syn::parse::Parse for ObjectAttributes {
             context: None,
             scalar: None,
             interfaces: Vec::new(),
+            implementors: Vec::new(),
+            value_enum: None,
+            directives: Vec::new(),
+            node: None,
+            one_of: None,
             no_async: None,
         };
 
         while !input.is_empty() {
-            let ident: syn::Ident = input.parse()?;
+            // `for` and `enum` are keywords.
+            let ident = input.call(syn::Ident::parse_any)?;
             match ident.to_string().as_str() {
                 "name" => {
                     input.parse::<syn::Token![=]>()?;
@@ -344,6 +482,41 @@ impl syn::parse::Parse for ObjectAttributes {
                         })
                         .collect();
                 }
+                "for" => {
+                    input.parse::<syn::Token![=]>()?;
+                    let content;
+                    syn::bracketed!(content in input);
+                    output.implementors =
+                        syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated(
+                            &content,
+                        )?
+                        .into_iter()
+                        .map(|implementor| {
+                            SpanContainer::new(ident.span(), Some(implementor.span()), implementor)
+                        })
+                        .collect();
+                }
+                "enum" => {
+                    input.parse::<syn::Token![=]>()?;
+                    let val = input.parse::<syn::Ident>()?;
+                    output.value_enum =
+                        Some(SpanContainer::new(ident.span(), Some(val.span()), val));
+                }
+                "directive" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    output.directives.extend(
+                        Punctuated::<DirectiveAttr, Token![,]>::parse_terminated(&content)?
+                            .into_iter()
+                            .map(|directive| SpanContainer::new(ident.span(), None, directive)),
+                    );
+                }
+                "node" => {
+                    output.node = Some(SpanContainer::new(ident.span(), None, ()));
+                }
+                "one_of" => {
+                    output.one_of = Some(SpanContainer::new(ident.span(), None, ()));
+                }
                 // FIXME: make this unneccessary.
                 "noasync" => {
                     output.no_async = Some(SpanContainer::new(ident.span(), None, ()));
@@ -387,6 +560,7 @@ pub struct FieldAttributeArgument {
     pub rename: Option<SpanContainer<syn::LitStr>>,
     pub default: Option<syn::Expr>,
     pub description: Option<syn::LitStr>,
+    pub directives: Vec<DirectiveAttr>,
 }
 
 impl parse::Parse for FieldAttributeArgument {
@@ -398,12 +572,25 @@ impl parse::Parse for FieldAttributeArgument {
             rename: None,
             default: None,
             description: None,
+            directives: Vec::new(),
         };
 
         let content;
         syn::parenthesized!(content in input);
         while !content.is_empty() {
             let name = content.parse::<syn::Ident>()?;
+
+            if name == "directive" {
+                let directives;
+                syn::parenthesized!(directives in content);
+                arg.directives
+                    .extend(Punctuated::<DirectiveAttr, Token![,]>::parse_terminated(
+                        &directives,
+                    )?);
+                content.parse::<Token![,]>().ok();
+                continue;
+            }
+
             content.parse::<Token![=]>()?;
 
             match name.to_string().as_str() {
@@ -441,6 +628,10 @@ enum FieldAttribute {
     Skip(SpanContainer<syn::Ident>),
     Arguments(HashMap<String, FieldAttributeArgument>),
     Default(SpanContainer<Option<syn::Expr>>),
+    Complexity(SpanContainer<syn::LitInt>),
+    ListSize(SpanContainer<syn::LitInt>),
+    Guard(SpanContainer<syn::Expr>),
+    Directives(Vec<SpanContainer<DirectiveAttr>>),
 }
 
 impl parse::Parse for FieldAttribute {
@@ -515,6 +706,45 @@ impl parse::Parse for FieldAttribute {
 
                 Ok(FieldAttribute::Default(default_expr))
             }
+            "complexity" => {
+                input.parse::<Token![=]>()?;
+                let lit = input.parse::<syn::LitInt>()?;
+                Ok(FieldAttribute::Complexity(SpanContainer::new(
+                    ident.span(),
+                    Some(lit.span()),
+                    lit,
+                )))
+            }
+            "list_size" => {
+                input.parse::<Token![=]>()?;
+                let lit = input.parse::<syn::LitInt>()?;
+                Ok(FieldAttribute::ListSize(SpanContainer::new(
+                    ident.span(),
+                    Some(lit.span()),
+                    lit,
+                )))
+            }
+            "guard" => {
+                input.parse::<Token![=]>()?;
+                let expr = input.parse::<syn::Expr>()?;
+                Ok(FieldAttribute::Guard(SpanContainer::new(
+                    ident.span(),
+                    Some(expr.span()),
+                    expr,
+                )))
+            }
+            "directive" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let directives =
+                    Punctuated::<DirectiveAttr, Token![,]>::parse_terminated(&content)?;
+                Ok(FieldAttribute::Directives(
+                    directives
+                        .into_iter()
+                        .map(|directive| SpanContainer::new(ident.span(), None, directive))
+                        .collect(),
+                ))
+            }
             _ => Err(syn::Error::new(ident.span(), "unknown attribute")),
         }
     }
@@ -525,12 +755,19 @@ pub struct FieldAttributes {
     pub name: Option<SpanContainer<String>>,
     pub description: Option<SpanContainer<String>>,
     pub deprecation: Option<SpanContainer<DeprecationAttr>>,
-    // Only relevant for GraphQLObject derive.
+    // Only relevant for GraphQLObject derive and interfaces.
     pub skip: Option<SpanContainer<syn::Ident>>,
     /// Only relevant for object macro.
     pub arguments: HashMap<String, FieldAttributeArgument>,
     /// Only relevant for object input objects.
     pub default: Option<SpanContainer<Option<syn::Expr>>>,
+    /// Only relevant for objects.
+    pub complexity: Option<SpanContainer<syn::LitInt>>,
+    /// Only relevant for objects.
+    pub list_size: Option<SpanContainer<syn::LitInt>>,
+    /// Only relevant for objects.
+    pub guard: Option<SpanContainer<syn::Expr>>,
+    pub directives: Vec<SpanContainer<DirectiveAttr>>,
 }
 
 impl parse::Parse for FieldAttributes {
@@ -544,6 +781,10 @@ impl parse::Parse for FieldAttributes {
             skip: None,
             arguments: Default::default(),
             default: None,
+            complexity: None,
+            list_size: None,
+            guard: None,
+            directives: Vec::new(),
         };
 
         for item in items {
@@ -566,6 +807,18 @@ impl parse::Parse for FieldAttributes {
                 FieldAttribute::Default(expr) => {
                     output.default = Some(expr);
                 }
+                FieldAttribute::Complexity(lit) => {
+                    output.complexity = Some(lit);
+                }
+                FieldAttribute::ListSize(lit) => {
+                    output.list_size = Some(lit);
+                }
+                FieldAttribute::Guard(expr) => {
+                    output.guard = Some(expr);
+                }
+                FieldAttribute::Directives(directives) => {
+                    output.directives.extend(directives);
+                }
             }
         }
 
@@ -614,6 +867,7 @@ pub struct GraphQLTypeDefinitionFieldArg {
     pub description: Option<String>,
     pub default: Option<syn::Expr>,
     pub _type: Box<syn::Type>,
+    pub directives: Vec<DirectiveAttr>,
 }
 
 #[derive(Debug)]
@@ -622,6 +876,10 @@ pub struct GraphQLTypeDefinitionField {
     pub _type: syn::Type,
     pub description: Option<String>,
     pub deprecation: Option<DeprecationAttr>,
+    pub complexity: Option<syn::LitInt>,
+    pub list_size: Option<syn::LitInt>,
+    pub guard: Option<syn::Expr>,
+    pub directives: Vec<DirectiveAttr>,
     pub args: Vec<GraphQLTypeDefinitionFieldArg>,
     pub resolver_code: TokenStream,
     pub is_type_inferred: bool,
@@ -630,6 +888,84 @@ pub struct GraphQLTypeDefinitionField {
     pub span: Span,
 }
 
+impl GraphQLTypeDefinitionField {
+    /// Render the registration of this field, as part of the `meta` of the
+    /// type declaring it.
+    pub fn meta_tokens(&self, juniper_crate_name: &syn::Path) -> TokenStream {
+        let args = self.args.iter().map(|arg| {
+            let arg_type = &arg._type;
+            let arg_name = &arg.name;
+
+            let description = match arg.description.as_ref() {
+                Some(value) => quote!( .description( #value ) ),
+                None => quote!(),
+            };
+
+            let directives = directive_calls(&arg.directives, juniper_crate_name);
+
+            // Code.
+            match arg.default.as_ref() {
+                Some(value) => quote!(
+                    .argument(
+                        registry.arg_with_default::<#arg_type>(#arg_name, &#value, info)
+                            #description
+                            #directives
+                    )
+                ),
+                None => quote!(
+                    .argument(
+                        registry.arg::<#arg_type>(#arg_name, info)
+                            #description
+                            #directives
+                    )
+                ),
+            }
+        });
+
+        let description = match self.description.as_ref() {
+            Some(description) => quote!( .description(#description) ),
+            None => quote!(),
+        };
+
+        let deprecation = match self.deprecation.as_ref() {
+            Some(deprecation) => {
+                if let Some(reason) = deprecation.reason.as_ref() {
+                    quote!( .deprecated(Some(#reason)) )
+                } else {
+                    quote!( .deprecated(None) )
+                }
+            }
+            None => quote!(),
+        };
+
+        let complexity = self
+            .complexity
+            .as_ref()
+            .map(|complexity| quote!( .complexity(#complexity) ));
+
+        let list_size = self
+            .list_size
+            .as_ref()
+            .map(|list_size| quote!( .list_size(#list_size) ));
+
+        let directives = directive_calls(&self.directives, juniper_crate_name);
+
+        let field_name = &self.name;
+
+        let _type = &self._type;
+        quote! {
+            registry
+                .field_convert::<#_type, _, Self::Context>(#field_name, info)
+                #(#args)*
+                #description
+                #deprecation
+                #complexity
+                #list_size
+                #directives
+        }
+    }
+}
+
 impl syn::spanned::Spanned for GraphQLTypeDefinitionField {
     fn span(&self) -> Span {
         self.span
@@ -655,6 +991,9 @@ pub struct GraphQLTypeDefiniton {
     pub fields: Vec<GraphQLTypeDefinitionField>,
     pub generics: syn::Generics,
     pub interfaces: Option<Vec<syn::Type>>,
+    pub directives: Vec<DirectiveAttr>,
+    // Whether the type implements the Relay `Node` interface.
+    pub node: bool,
     // Due to syn parsing differences,
     // when parsing an impl the type generics are included in the type
     // directly, but in syn::DeriveInput, the type generics are
@@ -688,60 +1027,10 @@ impl GraphQLTypeDefiniton {
             .map(|ctx| quote!( #ctx ))
             .unwrap_or_else(|| quote!(()));
 
-        let field_definitions = self.fields.iter().map(|field| {
-            let args = field.args.iter().map(|arg| {
-                let arg_type = &arg._type;
-                let arg_name = &arg.name;
-
-                let description = match arg.description.as_ref() {
-                    Some(value) => quote!( .description( #value ) ),
-                    None => quote!(),
-                };
-
-                // Code.
-                match arg.default.as_ref() {
-                    Some(value) => quote!(
-                        .argument(
-                            registry.arg_with_default::<#arg_type>(#arg_name, &#value, info)
-                                #description
-                        )
-                    ),
-                    None => quote!(
-                        .argument(
-                            registry.arg::<#arg_type>(#arg_name, info)
-                                #description
-                        )
-                    ),
-                }
-            });
-
-            let description = match field.description.as_ref() {
-                Some(description) => quote!( .description(#description) ),
-                None => quote!(),
-            };
-
-            let deprecation = match field.deprecation.as_ref() {
-                Some(deprecation) => {
-                    if let Some(reason) = deprecation.reason.as_ref() {
-                        quote!( .deprecated(Some(#reason)) )
-                    } else {
-                        quote!( .deprecated(None) )
-                    }
-                }
-                None => quote!(),
-            };
-
-            let field_name = &field.name;
-
-            let _type = &field._type;
-            quote! {
-                registry
-                    .field_convert::<#_type, _, Self::Context>(#field_name, info)
-                    #(#args)*
-                    #description
-                    #deprecation
-            }
-        });
+        let field_definitions = self
+            .fields
+            .iter()
+            .map(|field| field.meta_tokens(&juniper_crate_name));
 
         let scalar = self
             .scalar
@@ -777,8 +1066,15 @@ impl GraphQLTypeDefiniton {
                     let _type = &field._type;
                     quote!(: #_type)
                 };
+                let guard = guard_check(
+                    field.guard.as_ref(),
+                    quote!(args),
+                    |denied| denied,
+                    &juniper_crate_name,
+                );
                 quote!(
                     #name => {
+                        #guard
                         let res #_type = (|| { #code })();
                         #juniper_crate_name::IntoResolvable::into(
                             res,
@@ -800,13 +1096,25 @@ impl GraphQLTypeDefiniton {
             .as_ref()
             .map(|description| quote!( .description(#description) ));
 
-        let interfaces = self.interfaces.as_ref().map(|items| {
-            quote!(
+        let node_interface = if self.node {
+            Some(quote!(#juniper_crate_name::relay::NodeValue<#context, #scalar>))
+        } else {
+            None
+        };
+        let interfaces = if self.interfaces.is_some() || node_interface.is_some() {
+            let items = self.interfaces.iter().flatten();
+            let node_interface = node_interface.iter();
+            Some(quote!(
                 .interfaces(&[
                     #( registry.get_type::< #items >(&()) ,)*
+                    #( registry.get_type::< #node_interface >(&()) ,)*
                 ])
-            )
-        });
+            ))
+        } else {
+            None
+        };
+
+        let directives = directive_calls(&self.directives, &juniper_crate_name);
 
         // Preserve the original type_generics before modification,
         // since alteration makes them invalid if self.generic_scalar
@@ -847,9 +1155,16 @@ impl GraphQLTypeDefiniton {
                 };
 
                 if field.is_async {
+                    let guard = guard_check(
+                        field.guard.as_ref(),
+                        quote!(args),
+                        |denied| denied,
+                        &juniper_crate_name,
+                    );
                     quote!(
                         #name => {
                             let f = async move {
+                                #guard
                                 let res #_type = async move { #code }.await;
 
                                 let inner_res = #juniper_crate_name::IntoResolvable::into(
@@ -899,8 +1214,15 @@ impl GraphQLTypeDefiniton {
                         )
                     };
 
+                    let guard = guard_check(
+                        field.guard.as_ref(),
+                        quote!(args),
+                        |denied| quote!(future::FutureExt::boxed(future::ready(#denied))),
+                        &juniper_crate_name,
+                    );
                     quote!(
                         #name => {
+                            #guard
                             let res #_type = (||{ #code })();
                             let res2 = #juniper_crate_name::IntoResolvable::into(
                                 res,
@@ -997,7 +1319,8 @@ impl GraphQLTypeDefiniton {
                     ];
                     let meta = registry.build_object_type::<#ty>( info, &fields )
                         #description
-                        #interfaces;
+                        #interfaces
+                        #directives;
                     meta.into_meta()
                 }
 
@@ -1068,17 +1391,21 @@ impl GraphQLTypeDefiniton {
                     None => quote!(),
                 };
 
+                let directives = directive_calls(&arg.directives, &juniper_crate_name);
+
                 match arg.default.as_ref() {
                     Some(value) => quote!(
                         .argument(
                             registry.arg_with_default::<#arg_type>(#arg_name, &#value, info)
                                 #description
+                                #directives
                         )
                     ),
                     None => quote!(
                         .argument(
                             registry.arg::<#arg_type>(#arg_name, info)
                                 #description
+                                #directives
                         )
                     ),
                 }
@@ -1100,6 +1427,18 @@ impl GraphQLTypeDefiniton {
                 None => quote!(),
             };
 
+            let complexity = field
+                .complexity
+                .as_ref()
+                .map(|complexity| quote!( .complexity(#complexity) ));
+
+            let list_size = field
+                .list_size
+                .as_ref()
+                .map(|list_size| quote!( .list_size(#list_size) ));
+
+            let directives = directive_calls(&field.directives, &juniper_crate_name);
+
             let field_name = &field.name;
 
             let type_name = &field._type;
@@ -1118,6 +1457,9 @@ impl GraphQLTypeDefiniton {
                     #(#args)*
                     #description
                     #deprecation
+                    #complexity
+                    #list_size
+                    #directives
             }
         });
 
@@ -1134,6 +1476,8 @@ impl GraphQLTypeDefiniton {
             )
         });
 
+        let directives = directive_calls(&self.directives, &juniper_crate_name);
+
         // Preserve the original type_generics before modification,
         // since alteration makes them invalid if self.generic_scalar
         // is specified.
@@ -1175,9 +1519,16 @@ impl GraphQLTypeDefiniton {
                     let _type_name = &field._type;
                     _type = quote!(: #_type_name);
                 };
+                let guard = guard_check(
+                    field.guard.as_ref(),
+                    quote!(&args),
+                    |denied| denied,
+                    &juniper_crate_name,
+                );
                 quote!(
                     #name => {
                         futures::FutureExt::boxed(async move {
+                            #guard
                             let res #_type = { #code };
                             let res = #juniper_crate_name::IntoFieldResult::<_, #scalar>::into_result(res)?;
                             let executor= executor.as_owned_executor();
@@ -1232,7 +1583,8 @@ impl GraphQLTypeDefiniton {
                         ];
                         let meta = registry.build_object_type::<#ty>( info, &fields )
                             #description
-                            #interfaces;
+                            #interfaces
+                            #directives;
                         meta.into_meta()
                     }
 
@@ -1571,6 +1923,8 @@ impl GraphQLTypeDefiniton {
             .as_ref()
             .map(|description| quote!( .description(#description) ));
 
+        let directives = directive_calls(&self.directives, &juniper_crate_name);
+
         let values = self.fields.iter().map(|variant| {
             let variant_name = &variant.name;
 
@@ -1589,11 +1943,17 @@ impl GraphQLTypeDefiniton {
                 })
                 .unwrap_or_else(|| quote!(#juniper_crate_name::meta::DeprecationStatus::Current));
 
+            let directives = variant
+                .directives
+                .iter()
+                .map(|directive| directive.to_tokens(&juniper_crate_name));
+
             quote!(
                 #juniper_crate_name::meta::EnumValue {
                     name: #variant_name.to_string(),
                     description: #descr,
                     deprecation_status: #depr,
+                    directives: vec![ #( #directives ),* ],
                 },
             )
         });
@@ -1694,6 +2054,7 @@ impl GraphQLTypeDefiniton {
                         #( #values )*
                     ])
                     #description
+                    #directives
                     .into_meta()
                 }
 
@@ -1804,11 +2165,14 @@ impl GraphQLTypeDefiniton {
                     }
                 };
 
+                let directives = directive_calls(&field.directives, &juniper_crate_name);
+
                 quote!(
                     {
                         #create_meta_field
                         #description
                         #deprecation
+                        #directives
                     },
                 )
             })
@@ -1861,6 +2225,8 @@ impl GraphQLTypeDefiniton {
             .as_ref()
             .map(|description| quote!( .description(#description) ));
 
+        let directives = directive_calls(&self.directives, &juniper_crate_name);
+
         // Preserve the original type_generics before modification,
         // since alteration makes them invalid if self.generic_scalar
         // is specified.
@@ -1938,6 +2304,7 @@ impl GraphQLTypeDefiniton {
                     ];
                     registry.build_input_object_type::<#ty>(&(), fields)
                     #description
+                    #directives
                     .into_meta()
                 }
             }
@@ -1976,6 +2343,197 @@ impl GraphQLTypeDefiniton {
 
         body
     }
+
+    /// The input object of an enum whose variants are its fields, exactly one
+    /// of them being set.
+    pub fn into_one_of_input_object_tokens(self, juniper_crate_name: &str) -> TokenStream {
+        let juniper_crate_name = syn::parse_str::<syn::Path>(juniper_crate_name).unwrap();
+
+        let name = &self.name;
+        let ty = &self._type;
+        let context = self
+            .context
+            .as_ref()
+            .map(|ctx| quote!( #ctx ))
+            .unwrap_or_else(|| quote!(()));
+
+        let scalar = self
+            .scalar
+            .as_ref()
+            .map(|s| quote!( #s ))
+            .unwrap_or_else(|| {
+                if self.generic_scalar {
+                    quote!(__S)
+                } else {
+                    quote!(#juniper_crate_name::DefaultScalarValue)
+                }
+            });
+
+        let meta_fields = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_ty = &field._type;
+                let field_name = &field.name;
+
+                let description = field
+                    .description
+                    .as_ref()
+                    .map(|description| quote!( .description(#description) ));
+
+                let directives = directive_calls(&field.directives, &juniper_crate_name);
+
+                quote!(
+                    {
+                        registry.arg::<Option<#field_ty>>(#field_name, &())
+                        #description
+                        #directives
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let from_inputs = self
+            .fields
+            .iter()
+            .map(|field| {
+                let variant = &field.resolver_code;
+                let field_name = &field.name;
+
+                quote!(
+                    Some((&#field_name, value)) => {
+                        #juniper_crate_name::FromInputValue::from_input_value(value)
+                            .map(#ty::#variant)
+                    }
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let to_inputs = self
+            .fields
+            .iter()
+            .map(|field| {
+                let variant = &field.resolver_code;
+                let field_name = &field.name;
+
+                quote!(
+                    #ty::#variant(ref value) => (
+                        #field_name,
+                        #juniper_crate_name::ToInputValue::to_input_value(value),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let description = self
+            .description
+            .as_ref()
+            .map(|description| quote!( .description(#description) ));
+
+        let directives = directive_calls(&self.directives, &juniper_crate_name);
+
+        let (_, type_generics, _) = self.generics.split_for_impl();
+
+        let mut generics = self.generics.clone();
+
+        if self.scalar.is_none() && self.generic_scalar {
+            generics.params.push(parse_quote!(__S));
+
+            let where_clause = generics.where_clause.get_or_insert(parse_quote!(where));
+            where_clause
+                .predicates
+                .push(parse_quote!(__S: #juniper_crate_name::ScalarValue));
+        }
+
+        let type_generics_tokens = if self.include_type_generics {
+            Some(type_generics)
+        } else {
+            None
+        };
+
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let mut where_async = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+
+        where_async
+            .predicates
+            .push(parse_quote!( #scalar: Send + Sync ));
+        where_async.predicates.push(parse_quote!(Self: Send + Sync));
+
+        let async_type = quote!(
+            impl#impl_generics #juniper_crate_name::GraphQLTypeAsync<#scalar> for #ty #type_generics_tokens
+                #where_async
+            {}
+        );
+
+        let mut body = quote!(
+            impl#impl_generics #juniper_crate_name::marker::IsInputType<#scalar> for #ty #type_generics_tokens
+                #where_clause {
+                    fn mark() {}
+                }
+
+            impl#impl_generics #juniper_crate_name::GraphQLType<#scalar> for #ty #type_generics_tokens
+                #where_clause
+            {
+                type Context = #context;
+                type TypeInfo = ();
+
+                fn name(_: &()) -> Option<&'static str> {
+                    Some(#name)
+                }
+
+                fn meta<'r>(
+                    _: &(),
+                    registry: &mut #juniper_crate_name::Registry<'r, #scalar>
+                ) -> #juniper_crate_name::meta::MetaType<'r, #scalar>
+                where #scalar: 'r
+                {
+                    let fields = &[
+                        #( #meta_fields )*
+                    ];
+                    registry.build_input_object_type::<#ty>(&(), fields)
+                    .one_of()
+                    #description
+                    #directives
+                    .into_meta()
+                }
+            }
+
+            impl#impl_generics #juniper_crate_name::FromInputValue<#scalar> for #ty #type_generics_tokens
+                #where_clause
+            {
+                fn from_input_value(value: &#juniper_crate_name::InputValue<#scalar>) -> Option<Self>
+                {
+                    let obj = value.to_object_value()?;
+                    if obj.len() != 1 {
+                        return None;
+                    }
+
+                    match obj.get_index(0) {
+                        #( #from_inputs )*
+                        _ => None,
+                    }
+                }
+            }
+
+            impl#impl_generics #juniper_crate_name::ToInputValue<#scalar> for #ty #type_generics_tokens
+                #where_clause
+            {
+                fn to_input_value(&self) -> #juniper_crate_name::InputValue<#scalar> {
+                    let (name, value) = match *self {
+                        #( #to_inputs )*
+                    };
+                    #juniper_crate_name::InputValue::object(vec![(name, value)].into_iter().collect())
+                }
+            }
+        );
+
+        if !self.no_async {
+            body.extend(async_type);
+        }
+
+        body
+    }
 }
 
 #[cfg(test)]
@@ -63,96 +63,12 @@ impl ImplBlock {
         is_self_optional: bool,
         f: F,
     ) -> syn::Result<(Vec<TokenStream>, Vec<util::GraphQLTypeDefinitionFieldArg>)> {
-        let mut arguments = method.sig.inputs.iter().peekable();
-
-        // Verify `&self` argument.
-        match arguments.peek() {
-            Some(syn::FnArg::Receiver(rec)) => {
-                let _consume = arguments.next();
-                if rec.reference.is_none() || rec.mutability.is_some() {
-                    return Err(syn::Error::new(
-                        rec.span(),
-                        "invalid argument: did you mean `&self`?",
-                    ));
-                }
-            }
-            _ => {
-                if !is_self_optional {
-                    return Err(syn::Error::new(
-                        method.sig.span(),
-                        "expected a `&self` argument",
-                    ));
-                }
-            }
-        }
-
-        let mut resolve_parts = Vec::new();
-        let mut additional_arguments = Vec::new();
-
-        for arg in arguments {
-            match arg {
-                syn::FnArg::Receiver(_) => {
-                    if !is_self_optional {
-                        return Err(syn::Error::new(
-                            method.sig.ident.span(),
-                            "self receiver must be the first argument",
-                        ));
-                    }
-                }
-                syn::FnArg::Typed(captured) => {
-                    let (arg_ident, is_mut) = match &*captured.pat {
-                        syn::Pat::Ident(ref pat_ident) => {
-                            (&pat_ident.ident, pat_ident.mutability.is_some())
-                        }
-                        _ => {
-                            return Err(syn::Error::new(
-                                captured.pat.span(),
-                                "expected identifier for function argument",
-                            ));
-                        }
-                    };
-                    let context_type = self.attrs.context.as_ref();
-
-                    // Check for executor arguments.
-                    if util::type_is_identifier_ref(&captured.ty, "Executor") {
-                        resolve_parts.push(quote!(let #arg_ident = executor;));
-                    }
-                    // Make sure executor is specified as a reference.
-                    else if util::type_is_identifier(&captured.ty, "Executor") {
-                        return Err(syn::Error::new(
-                            captured.ty.span(),
-                            "to access the Executor, you need to specify the type as a reference.\nDid you mean &Executor?"
-                        ));
-                    }
-                    // Check for context arg.
-                    else if context_type
-                        .clone()
-                        .map(|ctx| util::type_is_ref_of(&captured.ty, ctx))
-                        .unwrap_or(false)
-                    {
-                        resolve_parts.push(quote!( let #arg_ident = executor.context(); ));
-                    }
-                    // Make sure the user does not specify the Context
-                    //  without a reference. (&Context)
-                    else if context_type
-                        .clone()
-                        .map(|ctx| ctx.inner() == &*captured.ty)
-                        .unwrap_or(false)
-                    {
-                        return Err(syn::Error::new(
-                            captured.ty.span(),
-                            format!("to access the context, you need to specify the type as a reference.\nDid you mean &{}?", quote!(captured.ty)),
-                        ));
-                    } else {
-                        let (tokens, ty) = f(captured, arg_ident, is_mut)?;
-                        resolve_parts.push(tokens);
-                        additional_arguments.push(ty);
-                    }
-                }
-            }
-        }
-
-        Ok((resolve_parts, additional_arguments))
+        parse_signature(
+            &method.sig,
+            self.attrs.context.as_ref(),
+            is_self_optional,
+            f,
+        )
     }
 
     pub fn parse(attr_tokens: TokenStream, body: TokenStream) -> syn::Result<ImplBlock> {
@@ -215,3 +131,99 @@ impl ImplBlock {
         })
     }
 }
+
+/// Parse the signature of a `fn <NAME>() -> <TYPE>` method declaration,
+/// found in objects and interfaces.
+pub fn parse_signature<
+    F: Fn(&PatType, &Ident, bool) -> syn::Result<(TokenStream, util::GraphQLTypeDefinitionFieldArg)>,
+>(
+    sig: &syn::Signature,
+    context_type: Option<&SpanContainer<syn::Type>>,
+    is_self_optional: bool,
+    f: F,
+) -> syn::Result<(Vec<TokenStream>, Vec<util::GraphQLTypeDefinitionFieldArg>)> {
+    let mut arguments = sig.inputs.iter().peekable();
+
+    // Verify `&self` argument.
+    match arguments.peek() {
+        Some(syn::FnArg::Receiver(rec)) => {
+            let _consume = arguments.next();
+            if rec.reference.is_none() || rec.mutability.is_some() {
+                return Err(syn::Error::new(
+                    rec.span(),
+                    "invalid argument: did you mean `&self`?",
+                ));
+            }
+        }
+        _ => {
+            if !is_self_optional {
+                return Err(syn::Error::new(sig.span(), "expected a `&self` argument"));
+            }
+        }
+    }
+
+    let mut resolve_parts = Vec::new();
+    let mut additional_arguments = Vec::new();
+
+    for arg in arguments {
+        match arg {
+            syn::FnArg::Receiver(_) => {
+                if !is_self_optional {
+                    return Err(syn::Error::new(
+                        sig.ident.span(),
+                        "self receiver must be the first argument",
+                    ));
+                }
+            }
+            syn::FnArg::Typed(captured) => {
+                let (arg_ident, is_mut) = match &*captured.pat {
+                    syn::Pat::Ident(ref pat_ident) => {
+                        (&pat_ident.ident, pat_ident.mutability.is_some())
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            captured.pat.span(),
+                            "expected identifier for function argument",
+                        ));
+                    }
+                };
+
+                // Check for executor arguments.
+                if util::type_is_identifier_ref(&captured.ty, "Executor") {
+                    resolve_parts.push(quote!(let #arg_ident = executor;));
+                }
+                // Make sure executor is specified as a reference.
+                else if util::type_is_identifier(&captured.ty, "Executor") {
+                    return Err(syn::Error::new(
+                        captured.ty.span(),
+                        "to access the Executor, you need to specify the type as a reference.\nDid you mean &Executor?"
+                    ));
+                }
+                // Check for context arg.
+                else if context_type
+                    .map(|ctx| util::type_is_ref_of(&captured.ty, ctx))
+                    .unwrap_or(false)
+                {
+                    resolve_parts.push(quote!( let #arg_ident = executor.context(); ));
+                }
+                // Make sure the user does not specify the Context
+                //  without a reference. (&Context)
+                else if context_type
+                    .map(|ctx| ctx.inner() == &*captured.ty)
+                    .unwrap_or(false)
+                {
+                    return Err(syn::Error::new(
+                        captured.ty.span(),
+                        format!("to access the context, you need to specify the type as a reference.\nDid you mean &{}?", quote!(captured.ty)),
+                    ));
+                } else {
+                    let (tokens, ty) = f(captured, arg_ident, is_mut)?;
+                    resolve_parts.push(tokens);
+                    additional_arguments.push(ty);
+                }
+            }
+        }
+    }
+
+    Ok((resolve_parts, additional_arguments))
+}
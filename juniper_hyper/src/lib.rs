@@ -8,7 +8,10 @@ use hyper::{
     Body, Method, Request, Response, StatusCode,
 };
 use juniper::{
-    http::{GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest},
+    http::{
+        multipart::{self, MultipartError},
+        GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest,
+    },
     GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, InputValue, RootNode, ScalarValue,
 };
 use serde_json::error::Error as SerdeError;
@@ -40,7 +43,7 @@ where
             }
         }
         Method::POST => {
-            let gql_req = parse_post_req(request.into_body()).await;
+            let gql_req = parse_post_req(request).await;
 
             match gql_req {
                 Ok(gql_req) => Ok(execute_request(root_node, context, gql_req).await),
@@ -76,7 +79,7 @@ where
             }
         }
         Method::POST => {
-            let gql_req = parse_post_req(request.into_body()).await;
+            let gql_req = parse_post_req(request).await;
 
             match gql_req {
                 Ok(gql_req) => Ok(execute_request_async(root_node, context, gql_req).await),
@@ -101,12 +104,22 @@ fn parse_get_req<S: ScalarValue>(
 }
 
 async fn parse_post_req<S: ScalarValue>(
-    body: Body,
+    req: Request<Body>,
 ) -> Result<GraphQLBatchRequest<S>, GraphQLRequestError> {
-    let chunk = hyper::body::to_bytes(body)
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned());
+
+    let chunk = hyper::body::to_bytes(req.into_body())
         .await
         .map_err(GraphQLRequestError::BodyHyper)?;
 
+    if let Some(content_type) = content_type.filter(|c| multipart::is_multipart(c)) {
+        return multipart::parse(&content_type, &chunk).map_err(GraphQLRequestError::Multipart);
+    }
+
     let input = String::from_utf8(chunk.iter().cloned().collect())
         .map_err(GraphQLRequestError::BodyUtf8)?;
 
@@ -215,6 +228,7 @@ where
     let mut query = None;
     let operation_name = None;
     let mut variables = None;
+    let mut extensions = None;
     for (key, value) in form_urlencoded::parse(input.as_bytes()).into_owned() {
         match key.as_ref() {
             "query" => {
@@ -239,15 +253,31 @@ where
                     Err(e) => return Err(e),
                 }
             }
+            "extensions" => {
+                if extensions.is_some() {
+                    return Err(invalid_err("extensions"));
+                }
+                match serde_json::from_str::<InputValue<S>>(&value)
+                    .map_err(GraphQLRequestError::Extensions)
+                {
+                    Ok(parsed_extensions) => extensions = Some(parsed_extensions),
+                    Err(e) => return Err(e),
+                }
+            }
             _ => continue,
         }
     }
-    match query {
-        Some(query) => Ok(JuniperGraphQLRequest::new(query, operation_name, variables)),
-        None => Err(GraphQLRequestError::Invalid(
-            "'query' parameter is missing".to_string(),
-        )),
-    }
+    // Requests referring to a persisted query may leave out the query.
+    let query = match (query, &extensions) {
+        (Some(query), _) => query,
+        (None, Some(_)) => String::new(),
+        (None, None) => {
+            return Err(GraphQLRequestError::Invalid(
+                "'query' parameter is missing".to_string(),
+            ))
+        }
+    };
+    Ok(JuniperGraphQLRequest::new(query, operation_name, variables).with_extensions(extensions))
 }
 
 fn invalid_err(parameter_name: &str) -> GraphQLRequestError {
@@ -278,6 +308,8 @@ enum GraphQLRequestError {
     BodyUtf8(FromUtf8Error),
     BodyJSONError(SerdeError),
     Variables(SerdeError),
+    Extensions(SerdeError),
+    Multipart(MultipartError),
     Invalid(String),
 }
 
@@ -288,6 +320,8 @@ impl fmt::Display for GraphQLRequestError {
             GraphQLRequestError::BodyUtf8(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::BodyJSONError(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::Variables(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Extensions(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Multipart(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::Invalid(ref err) => fmt::Display::fmt(err, &mut f),
         }
     }
@@ -300,11 +334,221 @@ impl Error for GraphQLRequestError {
             GraphQLRequestError::BodyUtf8(ref err) => Some(err),
             GraphQLRequestError::BodyJSONError(ref err) => Some(err),
             GraphQLRequestError::Variables(ref err) => Some(err),
+            GraphQLRequestError::Extensions(ref err) => Some(err),
+            GraphQLRequestError::Multipart(ref err) => Some(err),
             GraphQLRequestError::Invalid(_) => None,
         }
     }
 }
 
+/// Serving subscriptions over websockets
+#[cfg(feature = "subscriptions")]
+pub mod subscriptions {
+    use std::sync::Arc;
+
+    use futures::{future, Future, SinkExt as _, StreamExt as _};
+    use hyper::{
+        header::{self, HeaderValue},
+        Body, Method, Request, Response, StatusCode,
+    };
+    use juniper::{GraphQLSubscriptionType, GraphQLTypeAsync, ScalarValue};
+    use juniper_subscriptions::{
+        ws::{self, OutgoingMessage},
+        Coordinator,
+    };
+    use sha1::{Digest as _, Sha1};
+    use tokio_tungstenite::{
+        tungstenite::{
+            self,
+            protocol::{frame::coding::CloseCode, CloseFrame, Role},
+            Message,
+        },
+        WebSocketStream,
+    };
+
+    pub use juniper_subscriptions::ws::{ConnectionConfig, Protocol};
+
+    /// Upgrade `request` to a websocket serving subscriptions over the
+    /// `graphql-transport-ws` protocol
+    ///
+    /// The connection is served by a task spawned on the `tokio` runtime once
+    /// the upgrade completes. Requests that aren't valid websocket upgrades,
+    /// or don't offer the `graphql-transport-ws` subprotocol, get a
+    /// `400 Bad Request` response, and those asking for another websocket
+    /// version a `426 Upgrade Required` one.
+    pub async fn graphql_transport_ws<CtxT, QueryT, MutationT, SubscriptionT, S>(
+        coordinator: Arc<Coordinator<'static, QueryT, MutationT, SubscriptionT, CtxT, S>>,
+        context: CtxT,
+        request: Request<Body>,
+        config: ConnectionConfig,
+    ) -> Result<Response<Body>, hyper::Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        SubscriptionT::TypeInfo: Send + Sync,
+    {
+        let init = move |_| future::ready(Ok(context));
+        graphql_transport_ws_with_init(coordinator, init, request, config).await
+    }
+
+    /// Upgrade `request` to a websocket serving subscriptions over the
+    /// `graphql-transport-ws` protocol, building the context of the connection
+    /// from the payload of its `connection_init` message
+    ///
+    /// See [`juniper_subscriptions::ws::serve_with_init`] for how `init` is
+    /// used.
+    ///
+    /// [`juniper_subscriptions::ws::serve_with_init`]: juniper_subscriptions::ws::serve_with_init
+    pub async fn graphql_transport_ws_with_init<
+        CtxT,
+        QueryT,
+        MutationT,
+        SubscriptionT,
+        S,
+        Init,
+        Fut,
+    >(
+        coordinator: Arc<Coordinator<'static, QueryT, MutationT, SubscriptionT, CtxT, S>>,
+        init: Init,
+        request: Request<Body>,
+        config: ConnectionConfig,
+    ) -> Result<Response<Body>, hyper::Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        SubscriptionT::TypeInfo: Send + Sync,
+        Init: FnOnce(serde_json::Value) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<CtxT, String>> + Send + 'static,
+    {
+        let accept = match check_upgrade(&request) {
+            Ok(key) => accept_key(key.as_bytes()),
+            Err(resp) => return Ok(resp),
+        };
+
+        tokio::spawn(async move {
+            if let Ok(upgraded) = request.into_body().on_upgrade().await {
+                let websocket =
+                    WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+                let _ = serve(websocket, coordinator, init, config).await;
+            }
+        });
+
+        let mut resp = super::new_response(StatusCode::SWITCHING_PROTOCOLS);
+        let headers = resp.headers_mut();
+        headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+        headers.insert(
+            header::SEC_WEBSOCKET_ACCEPT,
+            HeaderValue::from_str(&accept).expect("base64 is a valid header value"),
+        );
+        headers.insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(Protocol::GraphQLTransportWs.name()),
+        );
+        Ok(resp)
+    }
+
+    /// Check that `request` is a websocket upgrade offering the
+    /// `graphql-transport-ws` subprotocol, returning its `Sec-WebSocket-Key`
+    /// or the response rejecting it
+    fn check_upgrade(request: &Request<Body>) -> Result<&HeaderValue, Response<Body>> {
+        let headers = request.headers();
+        let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        let has_token = |name, token: &str| {
+            header_str(name).map_or(false, |v| {
+                v.split(',').any(|t| t.trim().eq_ignore_ascii_case(token))
+            })
+        };
+
+        if request.method() != Method::GET
+            || !has_token(header::CONNECTION, "upgrade")
+            || !has_token(header::UPGRADE, "websocket")
+        {
+            return Err(super::new_response(StatusCode::BAD_REQUEST));
+        }
+        if header_str(header::SEC_WEBSOCKET_VERSION) != Some("13") {
+            let mut resp = super::new_response(StatusCode::UPGRADE_REQUIRED);
+            resp.headers_mut().insert(
+                header::SEC_WEBSOCKET_VERSION,
+                HeaderValue::from_static("13"),
+            );
+            return Err(resp);
+        }
+        if !header_str(header::SEC_WEBSOCKET_PROTOCOL)
+            .map_or(false, |v| Protocol::GraphQLTransportWs.is_offered(v))
+        {
+            return Err(super::new_response(StatusCode::BAD_REQUEST));
+        }
+
+        headers
+            .get(header::SEC_WEBSOCKET_KEY)
+            .ok_or_else(|| super::new_response(StatusCode::BAD_REQUEST))
+    }
+
+    async fn serve<CtxT, QueryT, MutationT, SubscriptionT, S, Init, Fut>(
+        websocket: WebSocketStream<hyper::upgrade::Upgraded>,
+        coordinator: Arc<Coordinator<'static, QueryT, MutationT, SubscriptionT, CtxT, S>>,
+        init: Init,
+        config: ConnectionConfig,
+    ) -> Result<(), tungstenite::Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        SubscriptionT::TypeInfo: Send + Sync,
+        Init: FnOnce(serde_json::Value) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<CtxT, String>> + Send + 'static,
+    {
+        let (sink, stream) = websocket.split();
+
+        // Pings are answered by `tungstenite` itself.
+        let incoming = stream
+            .take_while(|msg| future::ready(matches!(msg, Ok(msg) if !msg.is_close())))
+            .filter_map(|msg| {
+                future::ready(match msg.expect("errors end the stream") {
+                    Message::Text(text) => Some(text),
+                    Message::Binary(data) => Some(String::from_utf8(data).unwrap_or_default()),
+                    Message::Ping(_) | Message::Pong(_) | Message::Close(_) => None,
+                })
+            });
+        let outgoing = sink.with(|msg: OutgoingMessage| {
+            future::ready(Ok::<_, tungstenite::Error>(match msg {
+                OutgoingMessage::Text(text) => Message::Text(text),
+                OutgoingMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                    code: CloseCode::from(code),
+                    reason: reason.into(),
+                })),
+            }))
+        });
+
+        ws::serve_with_init(incoming, outgoing, coordinator, init, config).await
+    }
+
+    /// The `Sec-WebSocket-Accept` header value answering a `Sec-WebSocket-Key`
+    pub(crate) fn accept_key(key: &[u8]) -> String {
+        const GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+        let mut sha1 = Sha1::default();
+        sha1.update(key);
+        sha1.update(GUID);
+        base64::encode(sha1.finalize())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hyper::{
@@ -418,4 +662,128 @@ mod tests {
             eprintln!("server error: {}", e);
         }
     }
+
+    #[cfg(feature = "subscriptions")]
+    #[test]
+    fn websocket_accept_key() {
+        // The example handshake of RFC 6455
+        assert_eq!(
+            super::subscriptions::accept_key(b"dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=",
+        );
+    }
+
+    #[cfg(feature = "subscriptions")]
+    async fn upgrade(request: hyper::Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        super::subscriptions::graphql_transport_ws(
+            Arc::new(juniper_subscriptions::Coordinator::new(schema)),
+            Database::new(),
+            request,
+            super::subscriptions::ConnectionConfig::new(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "subscriptions")]
+    fn upgrade_request() -> hyper::Request<Body> {
+        hyper::Request::get("/subscriptions")
+            .header("connection", "keep-alive, Upgrade")
+            .header("upgrade", "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .header("sec-websocket-protocol", "graphql-transport-ws")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[cfg(feature = "subscriptions")]
+    #[tokio::test]
+    async fn rejects_invalid_websocket_upgrades() {
+        use hyper::header;
+
+        let mut post = upgrade_request();
+        *post.method_mut() = Method::POST;
+        assert_eq!(
+            upgrade(post).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let mut no_connection = upgrade_request();
+        no_connection.headers_mut().remove(header::CONNECTION);
+        assert_eq!(
+            upgrade(no_connection).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let mut no_key = upgrade_request();
+        no_key.headers_mut().remove(header::SEC_WEBSOCKET_KEY);
+        assert_eq!(
+            upgrade(no_key).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let mut old_version = upgrade_request();
+        old_version
+            .headers_mut()
+            .insert(header::SEC_WEBSOCKET_VERSION, "8".parse().unwrap());
+        let response = upgrade(old_version).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UPGRADE_REQUIRED);
+        assert_eq!(response.headers()[header::SEC_WEBSOCKET_VERSION], "13");
+
+        let mut legacy_protocol = upgrade_request();
+        legacy_protocol.headers_mut().insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            "graphql-ws".parse().unwrap(),
+        );
+        assert_eq!(
+            upgrade(legacy_protocol).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[cfg(feature = "subscriptions")]
+    #[tokio::test]
+    async fn serves_upgraded_websocket_connections() {
+        use futures::{SinkExt as _, StreamExt as _};
+        use tokio_tungstenite::tungstenite::{client::IntoClientRequest as _, Message};
+
+        let new_service = make_service_fn(|_| async { Ok::<_, hyper::Error>(service_fn(upgrade)) });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(new_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let mut request = format!("ws://{}/subscriptions", addr)
+            .into_client_request()
+            .unwrap();
+        request.headers_mut().insert(
+            "sec-websocket-protocol",
+            "graphql-transport-ws".parse().unwrap(),
+        );
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut websocket, response) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .expect("handshake");
+        assert_eq!(
+            response.headers()["sec-websocket-protocol"],
+            "graphql-transport-ws"
+        );
+
+        websocket
+            .send(Message::Text(r#"{"type":"connection_init"}"#.to_owned()))
+            .await
+            .unwrap();
+        let ack = match websocket.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str::<serde_json::Value>(&text),
+            other => panic!("expected a text message, got {:?}", other),
+        };
+        assert_eq!(
+            ack.unwrap(),
+            serde_json::json!({ "type": "connection_ack" })
+        );
+    }
 }
@@ -122,8 +122,9 @@ use std::{error::Error, fmt, io::Read};
 use serde_json::error::Error as SerdeError;
 
 use juniper::{
-    http, http::GraphQLBatchRequest, DefaultScalarValue, GraphQLType, InputValue, RootNode,
-    ScalarValue,
+    http,
+    http::GraphQLBatchRequest,
+    DefaultScalarValue, GraphQLType, InputValue, RootNode, ScalarValue,
 };
 
 /// Handler that executes `GraphQL` queries in the given schema
@@ -227,21 +228,39 @@ where
         }
     }
 
+    /// Support automatic persisted queries, keeping them in `store`
+    ///
+    /// See [`juniper::http::persisted_queries`](../juniper/http/persisted_queries/index.html).
+    #[cfg(feature = "persisted-queries")]
+    pub fn with_persisted_queries<T>(mut self, store: T) -> Self
+    where
+        T: juniper::http::persisted_queries::PersistedQueryStore + 'static,
+    {
+        self.root_node = self.root_node.with_persisted_queries(store);
+        self
+    }
+
     fn handle_get(&self, req: &mut Request) -> IronResult<GraphQLBatchRequest<S>> {
         let url_query_string = req
             .get_mut::<UrlEncodedQuery>()
             .map_err(GraphQLIronError::Url)?;
 
-        let input_query = parse_url_param(url_query_string.remove("query"))?
-            .ok_or_else(|| GraphQLIronError::InvalidData("No query provided"))?;
+        let input_query = parse_url_param(url_query_string.remove("query"))?;
         let operation_name = parse_url_param(url_query_string.remove("operationName"))?;
         let variables = parse_variable_param(url_query_string.remove("variables"))?;
+        let extensions = parse_variable_param(url_query_string.remove("extensions"))?;
 
-        Ok(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            input_query,
-            operation_name,
-            variables,
-        )))
+        // Requests referring to a persisted query may leave out the query.
+        let input_query = match (input_query, &extensions) {
+            (Some(query), _) => query,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err(GraphQLIronError::InvalidData("No query provided").into()),
+        };
+
+        Ok(GraphQLBatchRequest::Single(
+            http::GraphQLRequest::new(input_query, operation_name, variables)
+                .with_extensions(extensions),
+        ))
     }
 
     fn handle_post(&self, req: &mut Request) -> IronResult<GraphQLBatchRequest<S>> {
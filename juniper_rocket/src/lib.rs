@@ -191,6 +191,7 @@ where
         let mut query = None;
         let mut operation_name = None;
         let mut variables = None;
+        let mut extensions = None;
 
         for form_item in form_items {
             let (key, value) = form_item.key_value();
@@ -234,6 +235,21 @@ where
                         );
                     }
                 }
+                "extensions" => {
+                    if extensions.is_some() {
+                        return Err("Extensions parameter must not occur more than once".to_owned());
+                    } else {
+                        let decoded;
+                        match value.url_decode() {
+                            Ok(v) => decoded = v,
+                            Err(e) => return Err(e.to_string()),
+                        }
+                        extensions = Some(
+                            serde_json::from_str::<InputValue<_>>(&decoded)
+                                .map_err(|err| err.to_string())?,
+                        );
+                    }
+                }
                 _ => {
                     if strict {
                         return Err(format!("Prohibited extra field '{}'", key));
@@ -242,13 +258,16 @@ where
             }
         }
 
-        if let Some(query) = query {
-            Ok(GraphQLRequest(GraphQLBatchRequest::Single(
-                http::GraphQLRequest::new(query, operation_name, variables),
-            )))
-        } else {
-            Err("Query parameter missing".to_owned())
-        }
+        // Requests referring to a persisted query may leave out the query.
+        let query = match (query, &extensions) {
+            (Some(query), _) => query,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err("Query parameter missing".to_owned()),
+        };
+
+        Ok(GraphQLRequest(GraphQLBatchRequest::Single(
+            http::GraphQLRequest::new(query, operation_name, variables).with_extensions(extensions),
+        )))
     }
 }
 
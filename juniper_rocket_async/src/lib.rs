@@ -61,7 +61,8 @@ use juniper::{
 ///
 /// See the `http` module for more information. This type can be constructed
 /// automatically from both GET and POST routes by implementing the `FromForm`
-/// and `FromData` traits.
+/// and `FromData` traits. POST routes accept both JSON and `multipart/form-data`
+/// bodies, the latter carrying uploaded files.
 #[derive(Debug, PartialEq)]
 pub struct GraphQLRequest<S = DefaultScalarValue>(GraphQLBatchRequest<S>)
 where
@@ -212,6 +213,7 @@ where
         let mut query = None;
         let mut operation_name = None;
         let mut variables = None;
+        let mut extensions = None;
 
         for form_item in form_items {
             let (key, value) = form_item.key_value();
@@ -255,6 +257,21 @@ where
                         );
                     }
                 }
+                "extensions" => {
+                    if extensions.is_some() {
+                        return Err("Extensions parameter must not occur more than once".to_owned());
+                    } else {
+                        let decoded;
+                        match value.url_decode() {
+                            Ok(v) => decoded = v,
+                            Err(e) => return Err(e.to_string()),
+                        }
+                        extensions = Some(
+                            serde_json::from_str::<InputValue<_>>(&decoded)
+                                .map_err(|err| err.to_string())?,
+                        );
+                    }
+                }
                 _ => {
                     if strict {
                         return Err(format!("Prohibited extra field '{}'", key).to_owned());
@@ -263,13 +280,16 @@ where
             }
         }
 
-        if let Some(query) = query {
-            Ok(GraphQLRequest(GraphQLBatchRequest::Single(
-                http::GraphQLRequest::new(query, operation_name, variables),
-            )))
-        } else {
-            Err("Query parameter missing".to_owned())
-        }
+        // Requests referring to a persisted query may leave out the query.
+        let query = match (query, &extensions) {
+            (Some(query), _) => query,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err("Query parameter missing".to_owned()),
+        };
+
+        Ok(GraphQLRequest(GraphQLBatchRequest::Single(
+            http::GraphQLRequest::new(query, operation_name, variables).with_extensions(extensions),
+        )))
     }
 }
 
@@ -288,6 +308,9 @@ where
 
 const BODY_LIMIT: u64 = 1024 * 100;
 
+/// The limit of `multipart/form-data` bodies, which carry uploaded files
+const MULTIPART_BODY_LIMIT: u64 = 1024 * 1024 * 10;
+
 impl<S> FromDataSimple for GraphQLRequest<S>
 where
     S: ScalarValue + Send + Sync,
@@ -297,6 +320,27 @@ where
     fn from_data(request: &Request, data: Data) -> FromDataFuture<'static, Self, Self::Error> {
         use tokio::io::AsyncReadExt as _;
 
+        if request.content_type().map_or(false, |ct| ct.is_form_data()) {
+            let content_type = request
+                .headers()
+                .get_one("Content-Type")
+                .unwrap_or_default()
+                .to_owned();
+
+            return Box::pin(async move {
+                let mut body = Vec::new();
+                let mut reader = data.open().take(MULTIPART_BODY_LIMIT);
+                if let Err(e) = reader.read_to_end(&mut body).await {
+                    return Failure((Status::InternalServerError, format!("{:?}", e)));
+                }
+
+                match http::multipart::parse(&content_type, &body) {
+                    Ok(value) => Success(GraphQLRequest(value)),
+                    Err(failure) => Failure((Status::BadRequest, format!("{}", failure))),
+                }
+            });
+        }
+
         if !request.content_type().map_or(false, |ct| ct.is_json()) {
             return Box::pin(async move { Forward(data) });
         }
@@ -11,6 +11,8 @@
 #![deny(warnings)]
 #![doc(html_root_url = "https://docs.rs/juniper_subscriptions/0.14.2")]
 
+pub mod ws;
+
 use std::{iter::FromIterator, pin::Pin};
 
 use futures::{task::Poll, Stream};
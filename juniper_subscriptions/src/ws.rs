@@ -0,0 +1,1156 @@
+//! Transport independent implementation of the `graphql-transport-ws`
+//! websocket protocol
+//!
+//! [`serve`] runs a whole connection, given the text messages received from
+//! the client as a [`Stream`] and a [`Sink`] for the messages to send back.
+//! Server integrations only have to convert their websocket messages from and
+//! to those, which keeps the protocol the same on every server.
+//!
+//! [`serve`]: fn.serve.html
+//! [`Stream`]: futures::Stream
+//! [`Sink`]: futures::Sink
+
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context as TaskContext, Poll, Waker},
+    time::Duration,
+};
+
+use futures::{
+    future::{self, AbortHandle, Abortable, BoxFuture, Either, Shared},
+    stream::{self, FuturesUnordered},
+    Future, FutureExt as _, Sink, Stream, StreamExt as _,
+};
+use juniper::{
+    http::GraphQLRequest, GraphQLSubscriptionType, GraphQLTypeAsync, ScalarValue,
+    SubscriptionCoordinator as _,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::Coordinator;
+
+/// The websocket sub-protocols spoken by the subscription handlers of the
+/// server integrations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// The legacy protocol of `subscriptions-transport-ws`, named `graphql-ws`
+    GraphQLWs,
+    /// The `graphql-transport-ws` protocol of the `graphql-ws` library,
+    /// served by [`serve`](fn.serve.html)
+    GraphQLTransportWs,
+}
+
+impl Protocol {
+    /// The name of the protocol, as sent in the `Sec-WebSocket-Protocol`
+    /// header
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::GraphQLWs => "graphql-ws",
+            Protocol::GraphQLTransportWs => "graphql-transport-ws",
+        }
+    }
+
+    /// Choose the first supported protocol from the comma separated list of
+    /// a `Sec-WebSocket-Protocol` header
+    pub fn negotiate(header: &str) -> Option<Self> {
+        header
+            .split(',')
+            .map(str::trim)
+            .find_map(|name| match name {
+                "graphql-ws" => Some(Protocol::GraphQLWs),
+                "graphql-transport-ws" => Some(Protocol::GraphQLTransportWs),
+                _ => None,
+            })
+    }
+
+    /// Whether the comma separated list of a `Sec-WebSocket-Protocol` header
+    /// contains this protocol
+    pub fn is_offered(self, header: &str) -> bool {
+        header.split(',').any(|name| name.trim() == self.name())
+    }
+}
+
+/// Configuration of subscription connections over websockets
+#[derive(Clone, Debug)]
+pub struct ConnectionConfig {
+    connection_init_timeout: Duration,
+    max_operations: Option<usize>,
+    keep_alive_interval: Option<Duration>,
+    outgoing_buffer: Option<(usize, OverflowPolicy)>,
+    shutdown: Shutdown,
+}
+
+impl ConnectionConfig {
+    /// Construct the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Close connections that don't send `connection_init` within `timeout`,
+    /// 3 seconds by default
+    pub fn with_connection_init_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_init_timeout = timeout;
+        self
+    }
+
+    /// Allow at most `max` operations to run at the same time on a
+    /// connection, without any limit by default
+    ///
+    /// Operations started beyond the limit fail with an `error` message,
+    /// leaving the connection and its running operations untouched.
+    pub fn with_max_operations(mut self, max: usize) -> Self {
+        self.max_operations = Some(max);
+        self
+    }
+
+    /// Send a keep-alive message every `interval`, so that idle connections
+    /// aren't dropped by proxies, which isn't done by default
+    ///
+    /// The keep-alive message is a `ping` in the `graphql-transport-ws`
+    /// protocol, and a `ka` in the legacy `graphql-ws` one.
+    pub fn with_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Buffer at most `capacity` messages waiting to be sent to a client,
+    /// applying `policy` when a slow client lets the buffer fill up
+    ///
+    /// The buffer is unbounded by default.
+    pub fn with_outgoing_buffer(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.outgoing_buffer = Some((capacity, policy));
+        self
+    }
+
+    /// Shut connections down once `signal` resolves
+    ///
+    /// Every running operation is sent a `complete` message, and the
+    /// connection is then closed with the `1001` close code. The same signal
+    /// is shared by all the connections using this configuration.
+    pub fn with_shutdown<F>(mut self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown = Shutdown(Some(signal.boxed().shared()));
+        self
+    }
+
+    /// Whether a connection running `running` operations may start another
+    /// one
+    pub fn allows_another_operation(&self, running: usize) -> bool {
+        match self.max_operations {
+            Some(max) => running < max,
+            None => true,
+        }
+    }
+
+    /// The interval of keep-alive messages, if they are enabled
+    pub fn keep_alive_interval(&self) -> Option<Duration> {
+        self.keep_alive_interval
+    }
+
+    /// A future resolving once connections have to shut down
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            connection_init_timeout: Duration::from_secs(3),
+            max_operations: None,
+            keep_alive_interval: None,
+            outgoing_buffer: None,
+            shutdown: Shutdown(None),
+        }
+    }
+}
+
+/// What to do with a message sent to a client whose outgoing buffer is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest message of the buffer to make room for the new one
+    DropOldest,
+    /// Drop the buffered messages and close the connection with the `1008`
+    /// close code
+    Close,
+}
+
+/// Resolves once connections have to shut down, as configured by
+/// [`ConnectionConfig::with_shutdown`], and never if no signal was configured
+///
+/// [`ConnectionConfig::with_shutdown`]: struct.ConnectionConfig.html#method.with_shutdown
+#[derive(Clone)]
+pub struct Shutdown(Option<Shared<BoxFuture<'static, ()>>>);
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        match &mut self.0 {
+            Some(signal) => signal.poll_unpin(cx),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Shutdown")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// A message to send to the client
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutgoingMessage {
+    /// A text message
+    Text(String),
+    /// Close the connection with the given code and reason
+    Close(u16, String),
+}
+
+/// The messages waiting to be sent to a client, bounded as configured by
+/// [`ConnectionConfig::with_outgoing_buffer`]
+///
+/// Clones share the same buffer, which is read as a [`Stream`] by a single
+/// consumer. The stream ends after a `Close` message, or once
+/// [`finish`](#method.finish) has been called and the buffered messages have
+/// been read.
+///
+/// [`ConnectionConfig::with_outgoing_buffer`]: struct.ConnectionConfig.html#method.with_outgoing_buffer
+/// [`Stream`]: futures::Stream
+#[derive(Clone)]
+pub struct Outbox {
+    state: Arc<Mutex<OutboxState>>,
+}
+
+struct OutboxState {
+    messages: VecDeque<OutgoingMessage>,
+    limit: Option<(usize, OverflowPolicy)>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl Outbox {
+    /// Construct an empty outbox for a connection using `config`
+    pub fn new(config: &ConnectionConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(OutboxState {
+                messages: VecDeque::new(),
+                limit: config.outgoing_buffer,
+                finished: false,
+                waker: None,
+            })),
+        }
+    }
+
+    /// Buffer `message`, returning whether it was accepted
+    ///
+    /// Messages are refused once the outbox is finished, which is also the
+    /// case when it overflows with the [`OverflowPolicy::Close`] policy.
+    ///
+    /// [`OverflowPolicy::Close`]: enum.OverflowPolicy.html#variant.Close
+    pub fn push(&self, message: OutgoingMessage) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.finished {
+            return false;
+        }
+        let (message, accepted) = match state.limit {
+            Some((capacity, policy)) if state.messages.len() >= capacity => match policy {
+                OverflowPolicy::DropOldest => {
+                    state.messages.pop_front();
+                    (message, true)
+                }
+                OverflowPolicy::Close => {
+                    state.messages.clear();
+                    let reason = "Too many pending messages".to_owned();
+                    (OutgoingMessage::Close(1008, reason), false)
+                }
+            },
+            _ => (message, true),
+        };
+        if let OutgoingMessage::Close(..) = message {
+            state.finished = true;
+        }
+        state.messages.push_back(message);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        accepted
+    }
+
+    /// Refuse any further message, ending the stream once the buffered ones
+    /// have been read
+    pub fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.finished = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether messages are refused
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().finished
+    }
+}
+
+impl Stream for Outbox {
+    type Item = OutgoingMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(message) = state.messages.pop_front() {
+            Poll::Ready(Some(message))
+        } else if state.finished {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for Outbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Outbox")
+            .field("messages", &state.messages)
+            .field("finished", &state.finished)
+            .finish()
+    }
+}
+
+/// Serve subscriptions over the `graphql-transport-ws` protocol
+///
+/// `incoming` yields the text messages received from the client, and should
+/// end when the client closes the connection. Integrations should pass
+/// binary messages on as empty strings, so that they are rejected as invalid
+/// messages, and answer websocket pings themselves. Messages to send back are
+/// written to `outgoing`, which is closed once the connection is done.
+///
+/// Any number of operations can run at the same time, each identified by the
+/// `id` of its `subscribe` message. Operations are executed by the returned
+/// future, so no task is spawned. Protocol violations close the connection
+/// with the close code defined by the protocol:
+///  - `4400` for invalid messages
+///  - `4401` for `subscribe` messages before `connection_init`
+///  - `4403` if the connection is rejected by
+///    [`serve_with_init`](fn.serve_with_init.html)
+///  - `4408` if `connection_init` isn't received in time
+///  - `4409` for `subscribe` messages reusing the id of a running operation
+///  - `4429` for repeated `connection_init` messages
+///
+/// The connection is also closed with `1001` when the
+/// [shutdown signal](struct.ConnectionConfig.html#method.with_shutdown) of
+/// `config` resolves, and with `1008` when the outgoing buffer of a slow
+/// client overflows with the [`OverflowPolicy::Close`] policy.
+///
+/// The timeout of `connection_init` and keep-alive messages rely on the timer
+/// of the `tokio` runtime.
+///
+/// [`OverflowPolicy::Close`]: enum.OverflowPolicy.html#variant.Close
+pub async fn serve<Query, Mutation, Subscription, Context, S, In, Out>(
+    incoming: In,
+    outgoing: Out,
+    coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+    context: Context,
+    config: ConnectionConfig,
+) -> Result<(), Out::Error>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    In: Stream<Item = String>,
+    Out: Sink<OutgoingMessage>,
+{
+    let init = move |_| future::ready(Ok(context));
+    serve_with_init(incoming, outgoing, coordinator, init, config).await
+}
+
+/// Serve subscriptions over the `graphql-transport-ws` protocol, building the
+/// context of the connection from the payload of its `connection_init`
+/// message
+///
+/// `init` receives the payload, or null if there is none. It either returns
+/// the context of the operations of the connection, or rejects the connection
+/// with a message that is sent as the reason of the `4403` close code.
+/// Browsers can't set headers on websocket upgrade requests, so the payload is
+/// where clients pass their credentials.
+///
+/// The connection is served like [`serve`](fn.serve.html) does otherwise.
+pub async fn serve_with_init<Query, Mutation, Subscription, Context, S, In, Out, Init, Fut>(
+    incoming: In,
+    outgoing: Out,
+    coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+    init: Init,
+    config: ConnectionConfig,
+) -> Result<(), Out::Error>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    In: Stream<Item = String>,
+    Out: Sink<OutgoingMessage>,
+    Init: FnOnce(serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<Context, String>>,
+{
+    let outbox = Outbox::new(&config);
+    let forward = outbox.clone().map(Ok).forward(outgoing);
+    let connection = run_connection(incoming, outbox, coordinator, init, config);
+
+    let (result, ()) = future::join(forward, connection).await;
+    result
+}
+
+enum Event {
+    Message(String),
+    Closed,
+    InitTimeout,
+    KeepAlive,
+    Shutdown,
+}
+
+async fn run_connection<Query, Mutation, Subscription, Context, S, In, Init, Fut>(
+    incoming: In,
+    outbox: Outbox,
+    coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+    init: Init,
+    config: ConnectionConfig,
+) where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    In: Stream<Item = String>,
+    Init: FnOnce(serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<Context, String>>,
+{
+    let keep_alive = match config.keep_alive_interval {
+        Some(interval) => {
+            let start = tokio::time::Instant::now() + interval;
+            let ticks = tokio::time::interval_at(start, interval);
+            stream::unfold(ticks, |mut ticks| async {
+                ticks.tick().await;
+                Some((Event::KeepAlive, ticks))
+            })
+            .left_stream()
+        }
+        None => stream::pending().right_stream(),
+    };
+    let events = stream::select(
+        stream::select(
+            incoming
+                .map(Event::Message)
+                .chain(stream::once(future::ready(Event::Closed))),
+            stream::once(tokio::time::delay_for(config.connection_init_timeout))
+                .map(|()| Event::InitTimeout),
+        ),
+        stream::select(
+            keep_alive,
+            stream::once(config.shutdown()).map(|()| Event::Shutdown),
+        ),
+    );
+    futures::pin_mut!(events);
+
+    // The context is built once the connection is initialised.
+    let mut init = Some(init);
+    let mut context = None;
+    let mut handles: HashMap<String, AbortHandle> = HashMap::new();
+    let mut operations = FuturesUnordered::new();
+
+    // The outbox is finished when it overflows.
+    while !outbox.is_finished() {
+        let event = if operations.is_empty() {
+            events.next().await
+        } else {
+            match future::select(events.next(), operations.next()).await {
+                Either::Left((event, _)) => event,
+                Either::Right((finished, _)) => {
+                    // Aborted operations were removed when they were completed
+                    // by the client.
+                    if let Some(Ok(id)) = finished {
+                        handles.remove(&id);
+                    }
+                    continue;
+                }
+            }
+        };
+
+        let text = match event {
+            Some(Event::Message(text)) => text,
+            Some(Event::InitTimeout) => {
+                if context.is_some() {
+                    continue;
+                }
+                close(&outbox, 4408, "Connection initialisation timeout".into());
+                break;
+            }
+            Some(Event::KeepAlive) => {
+                let ping: ServerMessage<()> = ServerMessage::Ping { payload: None };
+                send(&outbox, &ping);
+                continue;
+            }
+            Some(Event::Shutdown) => {
+                for (id, handle) in handles.drain() {
+                    handle.abort();
+                    let complete: ServerMessage<()> = ServerMessage::Complete { id: &id };
+                    send(&outbox, &complete);
+                }
+                close(&outbox, 1001, "Server shutting down".into());
+                break;
+            }
+            Some(Event::Closed) | None => break,
+        };
+
+        let message = match serde_json::from_str::<ClientMessage<S>>(&text) {
+            Ok(message) => message,
+            Err(e) => {
+                close(&outbox, 4400, format!("Invalid message: {}", e));
+                break;
+            }
+        };
+
+        match message {
+            ClientMessage::ConnectionInit { payload } => {
+                let init = match init.take() {
+                    Some(init) => init,
+                    None => {
+                        close(&outbox, 4429, "Too many initialisation requests".into());
+                        break;
+                    }
+                };
+                match init(payload).await {
+                    Ok(initialised) => {
+                        context = Some(Arc::new(initialised));
+                        send(&outbox, &ServerMessage::<()>::ConnectionAck);
+                    }
+                    Err(reason) => {
+                        close(&outbox, 4403, reason);
+                        break;
+                    }
+                }
+            }
+            ClientMessage::Ping { payload } => {
+                let pong: ServerMessage<()> = ServerMessage::Pong { payload };
+                send(&outbox, &pong);
+            }
+            ClientMessage::Pong { .. } => {}
+            ClientMessage::Subscribe { id, payload } => {
+                let context = match &context {
+                    Some(context) => Arc::clone(context),
+                    None => {
+                        close(&outbox, 4401, "Unauthorized".into());
+                        break;
+                    }
+                };
+                if !handles.contains_key(&id) && !config.allows_another_operation(handles.len()) {
+                    send(
+                        &outbox,
+                        &ServerMessage::Error {
+                            id: &id,
+                            payload: [ErrorMessage {
+                                message: "Too many operations",
+                            }],
+                        },
+                    );
+                    continue;
+                }
+                let registration = match handles.entry(id.clone()) {
+                    Entry::Occupied(_) => {
+                        close(
+                            &outbox,
+                            4409,
+                            format!("Subscriber for {} already exists", id),
+                        );
+                        break;
+                    }
+                    Entry::Vacant(entry) => {
+                        let (handle, registration) = AbortHandle::new_pair();
+                        entry.insert(handle);
+                        registration
+                    }
+                };
+                operations.push(Abortable::new(
+                    run_operation(
+                        id,
+                        payload,
+                        Arc::clone(&coordinator),
+                        context,
+                        outbox.clone(),
+                    ),
+                    registration,
+                ));
+            }
+            ClientMessage::Complete { id } => {
+                if let Some(handle) = handles.remove(&id) {
+                    handle.abort();
+                }
+            }
+        }
+    }
+
+    outbox.finish();
+}
+
+/// Execute a single operation, sending its results as `next` messages until
+/// it completes or the outbox refuses them, and return its id
+async fn run_operation<Query, Mutation, Subscription, Context, S>(
+    id: String,
+    request: GraphQLRequest<S>,
+    coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+    context: Arc<Context>,
+    outbox: Outbox,
+) -> String
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    match coordinator.subscribe(&request, &*context).await {
+        Ok(mut responses) => {
+            while let Some(response) = responses.next().await {
+                let next = ServerMessage::Next {
+                    id: &id,
+                    payload: response,
+                };
+                if !send(&outbox, &next) {
+                    return id;
+                }
+            }
+            let complete: ServerMessage<()> = ServerMessage::Complete { id: &id };
+            send(&outbox, &complete);
+        }
+        Err(error) => {
+            send(
+                &outbox,
+                &ServerMessage::Error {
+                    id: &id,
+                    payload: error,
+                },
+            );
+        }
+    }
+    id
+}
+
+fn send<P>(outbox: &Outbox, message: &ServerMessage<P>) -> bool
+where
+    P: Serialize,
+{
+    let text = serde_json::to_string(message)
+        .unwrap_or_else(|e| format!(r#"{{"type":"error","payload":"{}"}}"#, e));
+    outbox.push(OutgoingMessage::Text(text))
+}
+
+fn close(outbox: &Outbox, code: u16, reason: String) {
+    outbox.push(OutgoingMessage::Close(code, reason));
+}
+
+/// The payload of an `error` message that isn't a `GraphQLError`
+#[derive(Serialize)]
+struct ErrorMessage<'a> {
+    message: &'a str,
+}
+
+/// A message sent by the client
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    bound = "GraphQLRequest<S>: Deserialize<'de>"
+)]
+enum ClientMessage<S>
+where
+    S: ScalarValue,
+{
+    ConnectionInit {
+        #[serde(default)]
+        payload: serde_json::Value,
+    },
+    Ping {
+        #[serde(default)]
+        payload: Option<serde_json::Value>,
+    },
+    Pong {},
+    Subscribe {
+        id: String,
+        payload: GraphQLRequest<S>,
+    },
+    Complete {
+        id: String,
+    },
+}
+
+/// A message sent by the server
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a, P> {
+    ConnectionAck,
+    Ping {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
+    },
+    Pong {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
+    },
+    Next {
+        id: &'a str,
+        payload: P,
+    },
+    Error {
+        id: &'a str,
+        payload: P,
+    },
+    Complete {
+        id: &'a str,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use futures::{channel::mpsc, future, stream, FutureExt as _, Stream, StreamExt as _};
+    use juniper::{DefaultScalarValue, EmptyMutation, FieldError, RootNode};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Session {
+        user: Option<String>,
+    }
+
+    impl juniper::Context for Session {}
+
+    struct Query;
+
+    #[juniper::graphql_object(Context = Session)]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    type Numbers = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+    struct Subscription;
+
+    #[juniper::graphql_subscription(Context = Session)]
+    impl Subscription {
+        async fn numbers() -> Numbers {
+            Box::pin(stream::iter(vec![Ok(1), Ok(2)]))
+        }
+
+        async fn endless() -> Numbers {
+            Box::pin(stream::once(future::ready(Ok(1))).chain(stream::pending()))
+        }
+
+        async fn whoami(session: &Session) -> Pin<Box<dyn Stream<Item = Option<String>> + Send>> {
+            Box::pin(stream::once(future::ready(session.user.clone())))
+        }
+    }
+
+    type Client = (
+        mpsc::UnboundedSender<String>,
+        mpsc::UnboundedReceiver<OutgoingMessage>,
+    );
+
+    fn connect(config: ConnectionConfig) -> Client {
+        connect_with_init(config, |_| future::ready(Ok(Session::default())))
+    }
+
+    fn connect_with_init<Init, Fut>(config: ConnectionConfig, init: Init) -> Client
+    where
+        Init: FnOnce(Value) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Session, String>> + Send + 'static,
+    {
+        let schema = RootNode::new(Query, EmptyMutation::<Session>::new(), Subscription);
+        let coordinator = Arc::new(Coordinator::new(schema));
+        let (client_tx, incoming) = mpsc::unbounded();
+        let (outgoing, client_rx) = mpsc::unbounded();
+        tokio::spawn(serve_with_init(
+            incoming,
+            outgoing,
+            coordinator,
+            init,
+            config,
+        ));
+        (client_tx, client_rx)
+    }
+
+    fn authenticate(payload: Value) -> future::Ready<Result<Session, String>> {
+        future::ready(match payload["token"].as_str() {
+            Some("secret") => Ok(Session {
+                user: Some("alice".into()),
+            }),
+            _ => Err("Invalid token".into()),
+        })
+    }
+
+    fn send(client: &Client, message: Value) {
+        client.0.unbounded_send(message.to_string()).unwrap();
+    }
+
+    async fn receive(client: &mut Client) -> Value {
+        match client.1.next().await {
+            Some(OutgoingMessage::Text(text)) => serde_json::from_str(&text).unwrap(),
+            other => panic!("expected a text message, got {:?}", other),
+        }
+    }
+
+    async fn receive_close(client: &mut Client) -> u16 {
+        match client.1.next().await {
+            Some(OutgoingMessage::Close(code, _)) => code,
+            other => panic!("expected a close message, got {:?}", other),
+        }
+    }
+
+    async fn init(client: &mut Client) {
+        send(client, json!({ "type": "connection_init" }));
+        assert_eq!(receive(client).await, json!({ "type": "connection_ack" }));
+    }
+
+    #[test]
+    fn negotiates_first_supported_protocol() {
+        assert_eq!(
+            Protocol::negotiate("graphql-transport-ws"),
+            Some(Protocol::GraphQLTransportWs),
+        );
+        assert_eq!(
+            Protocol::negotiate("mqtt, graphql-ws, graphql-transport-ws"),
+            Some(Protocol::GraphQLWs),
+        );
+        assert_eq!(Protocol::negotiate("mqtt"), None);
+
+        assert!(Protocol::GraphQLTransportWs.is_offered("graphql-ws, graphql-transport-ws"));
+        assert!(!Protocol::GraphQLTransportWs.is_offered("graphql-ws"));
+    }
+
+    #[test]
+    fn parses_client_messages() {
+        let message: ClientMessage<DefaultScalarValue> = serde_json::from_value(json!({
+            "type": "subscribe",
+            "id": "1",
+            "payload": { "query": "subscription { numbers }", "variables": { "x": 1 } },
+        }))
+        .unwrap();
+        match message {
+            ClientMessage::Subscribe { id, .. } => assert_eq!(id, "1"),
+            _ => panic!("expected a subscribe message"),
+        }
+
+        assert!(serde_json::from_value::<ClientMessage<DefaultScalarValue>>(
+            json!({ "type": "start", "id": "1" })
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn streams_results_until_complete() {
+        let mut client = connect(ConnectionConfig::new());
+        init(&mut client).await;
+
+        send(
+            &client,
+            json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { numbers }" },
+            }),
+        );
+        for n in 1..=2 {
+            assert_eq!(
+                receive(&mut client).await,
+                json!({ "type": "next", "id": "1", "payload": { "data": { "numbers": n } } }),
+            );
+        }
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "complete", "id": "1" }),
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_operations() {
+        let mut client = connect(ConnectionConfig::new());
+        init(&mut client).await;
+
+        send(
+            &client,
+            json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { unknown }" },
+            }),
+        );
+        let message = receive(&mut client).await;
+        assert_eq!(message["type"], "error");
+        assert_eq!(message["id"], "1");
+        assert!(message["payload"].is_array());
+    }
+
+    #[tokio::test]
+    async fn stops_operations_completed_by_the_client() {
+        let mut client = connect(ConnectionConfig::new());
+        init(&mut client).await;
+
+        let subscribe = json!({
+            "type": "subscribe",
+            "id": "1",
+            "payload": { "query": "subscription { endless }" },
+        });
+        send(&client, subscribe.clone());
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "next", "id": "1", "payload": { "data": { "endless": 1 } } }),
+        );
+
+        send(&client, json!({ "type": "complete", "id": "1" }));
+        // The id can be reused once the operation is stopped.
+        send(&client, subscribe);
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "next", "id": "1", "payload": { "data": { "endless": 1 } } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_running_operations() {
+        let mut client = connect(ConnectionConfig::new().with_max_operations(2));
+        init(&mut client).await;
+
+        let subscribe = |id: &str| {
+            json!({
+                "type": "subscribe",
+                "id": id,
+                "payload": { "query": "subscription { endless }" },
+            })
+        };
+        for id in &["1", "2"] {
+            send(&client, subscribe(id));
+            assert_eq!(
+                receive(&mut client).await,
+                json!({ "type": "next", "id": id, "payload": { "data": { "endless": 1 } } }),
+            );
+        }
+        send(&client, subscribe("3"));
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "error", "id": "3", "payload": [{ "message": "Too many operations" }] }),
+        );
+
+        // Stopping an operation makes room for another one.
+        send(&client, json!({ "type": "complete", "id": "1" }));
+        send(&client, subscribe("3"));
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "next", "id": "3", "payload": { "data": { "endless": 1 } } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn answers_pings() {
+        let mut client = connect(ConnectionConfig::new());
+
+        send(&client, json!({ "type": "ping", "payload": { "n": 1 } }));
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "pong", "payload": { "n": 1 } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn closes_on_protocol_violations() {
+        let mut client = connect(ConnectionConfig::new());
+        client.0.unbounded_send("{".into()).unwrap();
+        assert_eq!(receive_close(&mut client).await, 4400);
+        assert!(client.1.next().await.is_none());
+
+        let mut client = connect(ConnectionConfig::new());
+        send(
+            &client,
+            json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { numbers }" },
+            }),
+        );
+        assert_eq!(receive_close(&mut client).await, 4401);
+
+        let mut client = connect(ConnectionConfig::new());
+        init(&mut client).await;
+        send(&client, json!({ "type": "connection_init" }));
+        assert_eq!(receive_close(&mut client).await, 4429);
+
+        let mut client = connect(ConnectionConfig::new());
+        init(&mut client).await;
+        let subscribe = json!({
+            "type": "subscribe",
+            "id": "1",
+            "payload": { "query": "subscription { endless }" },
+        });
+        send(&client, subscribe.clone());
+        receive(&mut client).await;
+        send(&client, subscribe);
+        assert_eq!(receive_close(&mut client).await, 4409);
+    }
+
+    #[tokio::test]
+    async fn builds_context_from_init_payload() {
+        let mut client = connect_with_init(ConnectionConfig::new(), authenticate);
+        send(
+            &client,
+            json!({ "type": "connection_init", "payload": { "token": "secret" } }),
+        );
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "connection_ack" }),
+        );
+
+        send(
+            &client,
+            json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { whoami }" },
+            }),
+        );
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "next", "id": "1", "payload": { "data": { "whoami": "alice" } } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_connections_refused_by_init() {
+        let mut client = connect_with_init(ConnectionConfig::new(), authenticate);
+        send(
+            &client,
+            json!({ "type": "connection_init", "payload": { "token": "guess" } }),
+        );
+        match client.1.next().await {
+            Some(OutgoingMessage::Close(code, reason)) => {
+                assert_eq!(code, 4403);
+                assert_eq!(reason, "Invalid token");
+            }
+            other => panic!("expected a close message, got {:?}", other),
+        }
+
+        let mut client = connect_with_init(ConnectionConfig::new(), authenticate);
+        send(&client, json!({ "type": "connection_init" }));
+        assert_eq!(receive_close(&mut client).await, 4403);
+    }
+
+    #[tokio::test]
+    async fn closes_without_connection_init() {
+        let config =
+            ConnectionConfig::new().with_connection_init_timeout(Duration::from_millis(10));
+        let mut client = connect(config);
+        assert_eq!(receive_close(&mut client).await, 4408);
+    }
+
+    #[tokio::test]
+    async fn sends_keep_alive_pings() {
+        let config = ConnectionConfig::new().with_keep_alive_interval(Duration::from_millis(10));
+        let mut client = connect(config);
+        init(&mut client).await;
+
+        for _ in 0..2 {
+            assert_eq!(receive(&mut client).await, json!({ "type": "ping" }));
+        }
+    }
+
+    #[tokio::test]
+    async fn completes_operations_on_shutdown() {
+        let (shutdown, signal) = futures::channel::oneshot::channel::<()>();
+        let config = ConnectionConfig::new().with_shutdown(signal.map(|_| ()));
+        let mut client = connect(config);
+        init(&mut client).await;
+
+        send(
+            &client,
+            json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { endless }" },
+            }),
+        );
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "next", "id": "1", "payload": { "data": { "endless": 1 } } }),
+        );
+
+        shutdown.send(()).unwrap();
+        assert_eq!(
+            receive(&mut client).await,
+            json!({ "type": "complete", "id": "1" }),
+        );
+        assert_eq!(receive_close(&mut client).await, 1001);
+        assert_eq!(client.1.next().await, None);
+    }
+
+    fn text(text: &str) -> OutgoingMessage {
+        OutgoingMessage::Text(text.into())
+    }
+
+    #[tokio::test]
+    async fn outbox_drops_oldest_messages() {
+        let config = ConnectionConfig::new().with_outgoing_buffer(2, OverflowPolicy::DropOldest);
+        let outbox = Outbox::new(&config);
+        for message in &["1", "2", "3"] {
+            assert!(outbox.push(text(message)));
+        }
+        outbox.finish();
+        assert!(!outbox.push(text("4")));
+
+        let messages = outbox.collect::<Vec<_>>().await;
+        assert_eq!(messages, vec![text("2"), text("3")]);
+    }
+
+    #[tokio::test]
+    async fn outbox_closes_on_overflow() {
+        let config = ConnectionConfig::new().with_outgoing_buffer(2, OverflowPolicy::Close);
+        let outbox = Outbox::new(&config);
+        assert!(outbox.push(text("1")));
+        assert!(outbox.push(text("2")));
+        assert!(!outbox.push(text("3")));
+        assert!(outbox.is_finished());
+
+        let messages = outbox.collect::<Vec<_>>().await;
+        assert_eq!(
+            messages,
+            vec![OutgoingMessage::Close(
+                1008,
+                "Too many pending messages".into()
+            )],
+        );
+    }
+}
@@ -43,7 +43,13 @@ Check the LICENSE file for details.
 use std::{pin::Pin, sync::Arc};
 
 use futures::{Future, FutureExt as _, TryFutureExt};
-use juniper::{http::GraphQLBatchRequest, ScalarValue};
+use juniper::{
+    http::{
+        multipart::{self, MultipartOptions},
+        GraphQLBatchRequest,
+    },
+    ScalarValue,
+};
 use tokio::task;
 use warp::{filters::BoxedFilter, Filter};
 
@@ -111,6 +117,34 @@ pub fn make_graphql_filter<Query, Mutation, Subscription, Context, S>(
     schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context_extractor: BoxedFilter<(Context,)>,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    make_graphql_filter_with_multipart_options(
+        schema,
+        context_extractor,
+        MultipartOptions::default(),
+    )
+}
+
+/// Make a filter for graphql queries/mutations, parsing `multipart/form-data`
+/// requests within the limits of `multipart_options`
+///
+/// Multipart bodies larger than allowed are rejected with
+/// `warp::reject::PayloadTooLarge` before being read, and those without a
+/// `Content-Length` with `warp::reject::LengthRequired`.
+pub fn make_graphql_filter_with_multipart_options<Query, Mutation, Subscription, Context, S>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    multipart_options: MultipartOptions,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
     S: ScalarValue + Send + Sync + 'static,
     Context: Send + Sync + 'static,
@@ -140,7 +174,7 @@ where
 
     let post_filter = warp::post()
         .and(context_extractor.clone())
-        .and(warp::body::json())
+        .and(post_request(multipart_options))
         .and_then(handle_post_request);
 
     let handle_get_request =
@@ -152,14 +186,26 @@ where
                     None => None,
                     Some(vs) => serde_json::from_str(&vs)?,
                 };
+                let extensions = match request.remove("extensions") {
+                    None => None,
+                    Some(es) => serde_json::from_str(&es)?,
+                };
+
+                // Requests referring to a persisted query may leave out the query.
+                let query = match (request.remove("query"), &extensions) {
+                    (Some(query), _) => query,
+                    (None, Some(_)) => String::new(),
+                    (None, None) => {
+                        failure::bail!("Missing GraphQL query string in query parameters")
+                    }
+                };
 
                 let graphql_request = juniper::http::GraphQLRequest::new(
-                    request.remove("query").ok_or_else(|| {
-                        failure::format_err!("Missing GraphQL query string in query parameters")
-                    })?,
+                    query,
                     request.get("operation_name").map(|s| s.to_owned()),
                     variables,
-                );
+                )
+                .with_extensions(extensions);
 
                 let response = graphql_request.execute(&schema, &context).await;
 
@@ -181,6 +227,27 @@ pub fn make_graphql_filter_sync<Query, Mutation, Subscription, Context, S>(
     schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context_extractor: BoxedFilter<(Context,)>,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+{
+    make_graphql_filter_sync_with_multipart_options(
+        schema,
+        context_extractor,
+        MultipartOptions::default(),
+    )
+}
+
+/// Make a synchronous filter for graphql endpoint, parsing
+/// `multipart/form-data` requests within the limits of `multipart_options`
+pub fn make_graphql_filter_sync_with_multipart_options<Query, Mutation, Subscription, Context, S>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    multipart_options: MultipartOptions,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
     S: ScalarValue + Send + Sync + 'static,
     Context: Send + Sync + 'static,
@@ -211,7 +278,7 @@ where
 
     let post_filter = warp::post()
         .and(context_extractor.clone())
-        .and(warp::body::json())
+        .and(post_request(multipart_options))
         .and_then(handle_post_request);
 
     let handle_get_request = move |context: Context,
@@ -226,14 +293,26 @@ where
                         None => None,
                         Some(vs) => serde_json::from_str(&vs)?,
                     };
+                    let extensions = match request.remove("extensions") {
+                        None => None,
+                        Some(es) => serde_json::from_str(&es)?,
+                    };
+
+                    // Requests referring to a persisted query may leave out the query.
+                    let query = match (request.remove("query"), &extensions) {
+                        (Some(query), _) => query,
+                        (None, Some(_)) => String::new(),
+                        (None, None) => {
+                            failure::bail!("Missing GraphQL query string in query parameters")
+                        }
+                    };
 
                     let graphql_request = juniper::http::GraphQLRequest::new(
-                        request.remove("query").ok_or_else(|| {
-                            failure::format_err!("Missing GraphQL query string in query parameters")
-                        })?,
+                        query,
                         request.get("operation_name").map(|s| s.to_owned()),
                         variables,
-                    );
+                    )
+                    .with_extensions(extensions);
 
                     let response = graphql_request.execute_sync(&schema, &context);
                     Ok((serde_json::to_vec(&response)?, response.is_ok()))
@@ -268,6 +347,55 @@ impl std::fmt::Debug for JoinError {
     }
 }
 
+/// Error raised when a multipart request cannot be parsed
+///
+/// Wrapper type is needed as inner type does not implement `warp::reject::Reject`
+pub struct MultipartError(multipart::MultipartError);
+
+impl warp::reject::Reject for MultipartError {}
+
+impl std::fmt::Debug for MultipartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MultipartError({:?})", self.0)
+    }
+}
+
+/// Extract the request from the body of a POST, sent either as JSON or as a
+/// `multipart/form-data` request carrying files
+fn post_request<S>(options: MultipartOptions) -> BoxedFilter<(GraphQLBatchRequest<S>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let multipart_request = warp::header::<String>("content-type")
+        .and_then(|content_type: String| async move {
+            if multipart::is_multipart(&content_type) {
+                Ok(content_type)
+            } else {
+                Err(warp::reject())
+            }
+        })
+        .and(warp::body::content_length_limit(options.max_body_size()))
+        .and(warp::body::bytes())
+        .and_then(move |content_type: String, body: bytes::Bytes| async move {
+            multipart::parse_with_options(&content_type, &body, &options)
+                .map_err(|e| warp::reject::custom(MultipartError(e)))
+        });
+
+    // Multipart requests are left to the filter above, so that its rejections
+    // aren't hidden by the unsupported media type of this one.
+    let json_request = warp::header::optional::<String>("content-type")
+        .and_then(|content_type: Option<String>| async move {
+            match content_type {
+                Some(content_type) if multipart::is_multipart(&content_type) => Err(warp::reject()),
+                _ => Ok(()),
+            }
+        })
+        .untuple_one()
+        .and(warp::body::json());
+
+    multipart_request.or(json_request).unify().boxed()
+}
+
 fn build_response(
     response: Result<(Vec<u8>, bool), failure::Error>,
 ) -> warp::http::Response<Vec<u8>> {
@@ -374,26 +502,41 @@ fn playground_response(
 pub mod subscriptions {
     use std::{
         collections::HashMap,
-        sync::{
-            atomic::{AtomicBool, Ordering},
-            Arc,
-        },
+        sync::{Arc, Mutex},
+        time::Duration,
     };
 
-    use futures::{channel::mpsc, Future, StreamExt as _};
+    use futures::{
+        future::{self, AbortHandle, Abortable, BoxFuture, Either},
+        Future, FutureExt as _, SinkExt as _, StreamExt as _,
+    };
     use juniper::{http::GraphQLRequest, InputValue, ScalarValue, SubscriptionCoordinator as _};
-    use juniper_subscriptions::Coordinator;
+    use juniper_subscriptions::{
+        ws::{self, Outbox, OutgoingMessage},
+        Coordinator,
+    };
     use serde::{Deserialize, Serialize};
-    use warp::ws::Message;
-
-    /// Listen to incoming messages and do one of the following:
-    ///  - execute subscription and return values from stream
-    ///  - stop stream and close ws connection
-    #[allow(dead_code)]
+    use warp::{filters::BoxedFilter, ws::Message, Filter};
+
+    pub use juniper_subscriptions::ws::{ConnectionConfig, OverflowPolicy, Protocol};
+
+    /// Serve subscriptions over the legacy `graphql-ws` protocol of
+    /// `subscriptions-transport-ws`
+    ///
+    /// Each `start` message runs an operation identified by its `id`, until
+    /// the operation completes or a `stop` message with the same `id` is
+    /// received, and any number of operations can run at the same time.
+    /// Starting an operation while another one with the same `id` is running,
+    /// or beyond the limit of `config`, fails with an `error` message for that
+    /// `id` only.
+    ///
+    /// Operations can only be started once the connection has been
+    /// acknowledged in reply to its `connection_init` message.
     pub fn graphql_subscriptions<Query, Mutation, Subscription, Context, S>(
         websocket: warp::ws::WebSocket,
         coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
         context: Context,
+        config: ConnectionConfig,
     ) -> impl Future<Output = Result<(), failure::Error>> + Send
     where
         S: ScalarValue + Send + Sync + 'static,
@@ -406,160 +549,490 @@ pub mod subscriptions {
             juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
         Subscription::TypeInfo: Send + Sync,
     {
-        let (sink_tx, sink_rx) = websocket.split();
-        let (ws_tx, ws_rx) = mpsc::unbounded();
-        tokio::task::spawn(
-            ws_rx
-                .take_while(|v: &Option<_>| futures::future::ready(v.is_some()))
-                .map(|x| x.unwrap())
-                .forward(sink_tx),
-        );
+        let init = move |_| future::ready(Ok(context));
+        graphql_subscriptions_with_init(websocket, coordinator, init, config)
+    }
 
-        let context = Arc::new(context);
-        let running = Arc::new(AtomicBool::new(false));
-        let got_close_signal = Arc::new(AtomicBool::new(false));
+    /// Serve subscriptions over the legacy `graphql-ws` protocol, building the
+    /// context of the connection from the payload of its `connection_init`
+    /// message
+    ///
+    /// `init` receives the payload, or null if there is none, and returns
+    /// either the context of the connection or the message of a
+    /// `connection_error`, after which the connection is closed.
+    pub fn graphql_subscriptions_with_init<Query, Mutation, Subscription, Context, S, Init, Fut>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        init: Init,
+        config: ConnectionConfig,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        Init: FnOnce(serde_json::Value) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Context, String>> + Send + 'static,
+    {
+        let (sink_tx, mut sink_rx) = websocket.split();
+        let outbox = Outbox::new(&config);
+        tokio::task::spawn(outbox.clone().map(warp_message).map(Ok).forward(sink_tx));
 
-        sink_rx.fold(Ok(()), move |_, msg| {
-            let coordinator = coordinator.clone();
-            let context = context.clone();
-            let running = running.clone();
-            let got_close_signal = got_close_signal.clone();
-            let ws_tx = ws_tx.clone();
+        let operations: Arc<Mutex<HashMap<String, AbortHandle>>> = Default::default();
 
-            async move {
-                let msg = match msg {
-                    Ok(m) => m,
-                    Err(e) => {
-                        got_close_signal.store(true, Ordering::Relaxed);
-                        return Err(failure::format_err!("Websocket error: {}", e));
+        async move {
+            let mut init = Some(init);
+            let mut context = None;
+            let mut shutdown = config.shutdown();
+
+            let result = loop {
+                // The outbox is finished when it overflows.
+                if outbox.is_finished() {
+                    break Ok(());
+                }
+
+                let next = match future::select(sink_rx.next(), &mut shutdown).await {
+                    Either::Left((next, _)) => next,
+                    Either::Right(((), _)) => {
+                        for (id, handle) in operations.lock().unwrap().drain() {
+                            handle.abort();
+                            send_complete(&outbox, &id);
+                        }
+                        outbox.push(OutgoingMessage::Close(1001, "Server shutting down".into()));
+                        break Ok(());
                     }
                 };
-
+                let msg = match next {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(e)) => break Err(failure::format_err!("Websocket error: {}", e)),
+                    None => break Ok(()),
+                };
                 if msg.is_close() {
-                    return Ok(());
+                    break Ok(());
+                }
+                if msg.is_ping() || msg.is_pong() {
+                    continue;
                 }
 
-                let msg = msg
+                let request = msg
                     .to_str()
-                    .map_err(|_| failure::format_err!("Non-text messages are not accepted"))?;
-                let request: WsPayload<S> = serde_json::from_str(msg)
-                    .map_err(|e| failure::format_err!("Invalid WsPayload: {}", e))?;
+                    .map_err(|_| failure::format_err!("Non-text messages are not accepted"))
+                    .and_then(|msg| {
+                        serde_json::from_str::<WsPayload>(msg)
+                            .map_err(|e| failure::format_err!("Invalid WsPayload: {}", e))
+                    });
+                let request = match request {
+                    Ok(request) => request,
+                    Err(e) => break Err(e),
+                };
+                let request_id = request.id.unwrap_or_else(|| "1".to_owned());
 
                 match request.type_name.as_str() {
-                    "connection_init" => {}
-                    "start" => {
-                        {
-                            let closed = got_close_signal.load(Ordering::Relaxed);
-                            if closed {
-                                return Ok(());
+                    "connection_init" => {
+                        let init = match init.take() {
+                            Some(init) => init,
+                            None => continue,
+                        };
+                        match init(request.payload.unwrap_or_default()).await {
+                            Ok(initialised) => {
+                                context = Some(Arc::new(initialised));
+                                send(&outbox, serde_json::json!({ "type": "connection_ack" }));
+                                if let Some(interval) = config.keep_alive_interval() {
+                                    tokio::task::spawn(keep_alive(outbox.clone(), interval));
+                                }
                             }
-
-                            if running.load(Ordering::Relaxed) {
-                                return Ok(());
+                            Err(reason) => {
+                                let error = serde_json::json!({
+                                    "type": "connection_error",
+                                    "payload": { "message": reason },
+                                });
+                                send(&outbox, error);
+                                break Ok(());
                             }
-                            running.store(true, Ordering::Relaxed);
                         }
-
-                        let ws_tx = ws_tx.clone();
-
-                        if let Some(ref payload) = request.payload {
-                            if payload.query.is_none() {
-                                return Err(failure::format_err!("Query not found"));
+                    }
+                    "start" => {
+                        let context = match &context {
+                            Some(context) => Arc::clone(context),
+                            None => {
+                                send_error(&outbox, &request_id, "Connection not initialised");
+                                continue;
                             }
-                        } else {
-                            return Err(failure::format_err!("Payload not found"));
-                        }
-
-                        tokio::task::spawn(async move {
-                            let payload = request.payload.unwrap();
-
-                            let request_id = request.id.unwrap_or("1".to_owned());
-
-                            let graphql_request = GraphQLRequest::<S>::new(
-                                payload.query.unwrap(),
-                                None,
-                                payload.variables,
-                            );
-
-                            let values_stream =
-                                match coordinator.subscribe(&graphql_request, &context).await {
-                                    Ok(s) => s,
-                                    Err(err) => {
-                                        let _ =
-                                            ws_tx.unbounded_send(Some(Ok(Message::text(format!(
-                                                r#"{{"type":"error","id":"{}","payload":{}}}"#,
-                                                request_id,
-                                                serde_json::ser::to_string(&err).unwrap_or(
-                                                    "Error deserializing GraphQLError".to_owned()
-                                                )
-                                            )))));
-
-                                        let close_message = format!(
-                                            r#"{{"type":"complete","id":"{}","payload":null}}"#,
-                                            request_id
-                                        );
-                                        let _ = ws_tx
-                                            .unbounded_send(Some(Ok(Message::text(close_message))));
-                                        // close channel
-                                        let _ = ws_tx.unbounded_send(None);
-                                        return;
-                                    }
-                                };
-
-                            values_stream
-                                .take_while(move |response| {
-                                    let request_id = request_id.clone();
-                                    let closed = got_close_signal.load(Ordering::Relaxed);
-                                    if !closed {
-                                        let mut response_text = serde_json::to_string(&response)
-                                            .unwrap_or("Error deserializing response".to_owned());
-
-                                        response_text = format!(
-                                            r#"{{"type":"data","id":"{}","payload":{} }}"#,
-                                            request_id, response_text
-                                        );
-
-                                        let _ = ws_tx
-                                            .unbounded_send(Some(Ok(Message::text(response_text))));
-                                    }
-
-                                    async move { !closed }
-                                })
-                                .for_each(|_| async {})
-                                .await;
-                        });
+                        };
+                        let payload = match request.payload {
+                            Some(payload) => payload,
+                            None => break Err(failure::format_err!("Payload not found")),
+                        };
+                        let payload = match serde_json::from_value::<GraphQLPayload<S>>(payload) {
+                            Ok(payload) => payload,
+                            Err(e) => break Err(failure::format_err!("Invalid payload: {}", e)),
+                        };
+                        let query = match payload.query {
+                            Some(query) => query,
+                            None => break Err(failure::format_err!("Query not found")),
+                        };
+
+                        let registration = {
+                            let mut operations = operations.lock().unwrap();
+                            if operations.contains_key(&request_id) {
+                                send_error(
+                                    &outbox,
+                                    &request_id,
+                                    &format!("Subscriber for {} already exists", request_id),
+                                );
+                                continue;
+                            }
+                            if !config.allows_another_operation(operations.len()) {
+                                send_error(&outbox, &request_id, "Too many operations");
+                                continue;
+                            }
+                            let (handle, registration) = AbortHandle::new_pair();
+                            operations.insert(request_id.clone(), handle);
+                            registration
+                        };
+
+                        let graphql_request =
+                            GraphQLRequest::<S>::new(query, None, payload.variables);
+                        tokio::task::spawn(Abortable::new(
+                            run_operation(
+                                request_id,
+                                graphql_request,
+                                Arc::clone(&coordinator),
+                                context,
+                                outbox.clone(),
+                                Arc::clone(&operations),
+                            ),
+                            registration,
+                        ));
                     }
                     "stop" => {
-                        got_close_signal.store(true, Ordering::Relaxed);
+                        let stopped = operations.lock().unwrap().remove(&request_id);
+                        if let Some(handle) = stopped {
+                            handle.abort();
+                            send_complete(&outbox, &request_id);
+                        }
+                    }
+                    "connection_terminate" => break Ok(()),
+                    _ => {}
+                }
+            };
 
-                        let request_id = request.id.unwrap_or("1".to_owned());
-                        let close_message = format!(
-                            r#"{{"type":"complete","id":"{}","payload":null}}"#,
-                            request_id
-                        );
-                        let _ = ws_tx.unbounded_send(Some(Ok(Message::text(close_message))));
+            for (_, handle) in operations.lock().unwrap().drain() {
+                handle.abort();
+            }
+            outbox.finish();
+            result
+        }
+    }
 
-                        // close channel
-                        let _ = ws_tx.unbounded_send(None);
+    /// Execute a single operation of a `graphql-ws` connection, sending its
+    /// results as `data` messages until it completes or the outbox refuses
+    /// them
+    async fn run_operation<Query, Mutation, Subscription, Context, S>(
+        request_id: String,
+        graphql_request: GraphQLRequest<S>,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Arc<Context>,
+        outbox: Outbox,
+        operations: Arc<Mutex<HashMap<String, AbortHandle>>>,
+    ) where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        match coordinator.subscribe(&graphql_request, &*context).await {
+            Ok(mut values_stream) => {
+                while let Some(response) = values_stream.next().await {
+                    let response_text = serde_json::to_string(&response)
+                        .unwrap_or("Error deserializing response".to_owned());
+                    let data = OutgoingMessage::Text(format!(
+                        r#"{{"type":"data","id":"{}","payload":{} }}"#,
+                        request_id, response_text
+                    ));
+                    if !outbox.push(data) {
+                        break;
                     }
-                    _ => {}
                 }
+            }
+            Err(err) => {
+                outbox.push(OutgoingMessage::Text(format!(
+                    r#"{{"type":"error","id":"{}","payload":{}}}"#,
+                    request_id,
+                    serde_json::ser::to_string(&err)
+                        .unwrap_or("Error deserializing GraphQLError".to_owned())
+                )));
+            }
+        }
+
+        operations.lock().unwrap().remove(&request_id);
+        send_complete(&outbox, &request_id);
+    }
 
-                Ok(())
+    /// Send a `ka` message every `interval`, starting right away, until the
+    /// connection is done
+    async fn keep_alive(outbox: Outbox, interval: Duration) {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            ticks.tick().await;
+            if !send(&outbox, serde_json::json!({ "type": "ka" })) {
+                break;
             }
-        })
+        }
     }
 
-    #[derive(Deserialize)]
-    #[serde(bound = "GraphQLPayload<S>: Deserialize<'de>")]
-    struct WsPayload<S>
+    fn send(outbox: &Outbox, message: serde_json::Value) -> bool {
+        outbox.push(OutgoingMessage::Text(message.to_string()))
+    }
+
+    fn send_error(outbox: &Outbox, request_id: &str, message: &str) {
+        let error = serde_json::json!({
+            "type": "error",
+            "id": request_id,
+            "payload": [{ "message": message }],
+        });
+        send(outbox, error);
+    }
+
+    fn send_complete(outbox: &Outbox, request_id: &str) {
+        let complete = serde_json::json!({
+            "type": "complete",
+            "id": request_id,
+            "payload": null,
+        });
+        send(outbox, complete);
+    }
+
+    fn warp_message(message: OutgoingMessage) -> Message {
+        match message {
+            OutgoingMessage::Text(text) => Message::text(text),
+            OutgoingMessage::Close(code, reason) => Message::close_with(code, reason),
+        }
+    }
+
+    /// Make a filter serving subscriptions over websockets
+    ///
+    /// The protocol is negotiated through the `Sec-WebSocket-Protocol` header of
+    /// the upgrade request, and the chosen one is sent back in the same header.
+    /// Clients that don't ask for a supported protocol are served the legacy
+    /// `graphql-ws` one.
+    ///
+    /// The `context_extractor` argument should be a filter that provides the
+    /// GraphQL context required by the schema, and `config` applies to the
+    /// connections of both protocols.
+    pub fn make_ws_filter<Query, Mutation, Subscription, Context, S>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context_extractor: BoxedFilter<(Context,)>,
+        config: ConnectionConfig,
+    ) -> BoxedFilter<(Box<dyn warp::Reply>,)>
     where
         S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let init_extractor = context_extractor
+            .map(|context| {
+                Box::new(move |_: serde_json::Value| future::ready(Ok(context)).boxed())
+                    as InitFn<Context>
+            })
+            .boxed();
+        ws_filter(coordinator, init_extractor, config)
+    }
+
+    /// Make a filter serving subscriptions over websockets, building the
+    /// context of each connection from the payload of its `connection_init`
+    /// message
+    ///
+    /// Browsers can't set headers on websocket upgrade requests, so this is
+    /// how their connections are usually authenticated. `init` is called once
+    /// per connection, and either returns its context or rejects it with a
+    /// message, as documented by
+    /// [`graphql_subscriptions_with_init`](fn.graphql_subscriptions_with_init.html)
+    /// and [`graphql_transport_ws_with_init`](fn.graphql_transport_ws_with_init.html).
+    pub fn make_ws_filter_with_init<Query, Mutation, Subscription, Context, S, Init, Fut>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        init: Init,
+        config: ConnectionConfig,
+    ) -> BoxedFilter<(Box<dyn warp::Reply>,)>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        Init: Fn(serde_json::Value) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<Context, String>> + Send + 'static,
+    {
+        let init_extractor = warp::any()
+            .map(move || {
+                let init = init.clone();
+                Box::new(move |payload: serde_json::Value| init(payload).boxed()) as InitFn<Context>
+            })
+            .boxed();
+        ws_filter(coordinator, init_extractor, config)
+    }
+
+    /// Builds the context of a connection from its `connection_init` payload
+    type InitFn<Context> =
+        Box<dyn FnOnce(serde_json::Value) -> BoxFuture<'static, Result<Context, String>> + Send>;
+
+    fn ws_filter<Query, Mutation, Subscription, Context, S>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        init_extractor: BoxedFilter<(InitFn<Context>,)>,
+        config: ConnectionConfig,
+    ) -> BoxedFilter<(Box<dyn warp::Reply>,)>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        warp::ws()
+            .and(warp::header::optional::<String>("sec-websocket-protocol"))
+            .and(init_extractor)
+            .map(
+                move |ws: warp::ws::Ws, protocols: Option<String>, init: InitFn<Context>| {
+                    let coordinator = Arc::clone(&coordinator);
+                    let config = config.clone();
+                    let protocol = protocols.as_deref().and_then(Protocol::negotiate);
+
+                    let reply = ws.on_upgrade(move |websocket| {
+                        let handler = match protocol.unwrap_or(Protocol::GraphQLWs) {
+                            Protocol::GraphQLWs => graphql_subscriptions_with_init(
+                                websocket,
+                                coordinator,
+                                init,
+                                config,
+                            )
+                            .boxed(),
+                            Protocol::GraphQLTransportWs => {
+                                graphql_transport_ws_with_init(websocket, coordinator, init, config)
+                                    .boxed()
+                            }
+                        };
+                        // Errors of the `graphql-transport-ws` protocol are
+                        // reported to the client through close codes.
+                        handler.map(|_| ())
+                    });
+
+                    match protocol {
+                        Some(protocol) => Box::new(warp::reply::with_header(
+                            reply,
+                            "sec-websocket-protocol",
+                            protocol.name(),
+                        )) as Box<dyn warp::Reply>,
+                        None => Box::new(reply),
+                    }
+                },
+            )
+            .boxed()
+    }
+
+    /// Serve subscriptions over the `graphql-transport-ws` protocol
+    ///
+    /// The protocol itself is implemented by
+    /// [`juniper_subscriptions::ws::serve`], which documents its behaviour
+    /// and close codes.
+    ///
+    /// [`juniper_subscriptions::ws::serve`]: juniper_subscriptions::ws::serve
+    pub fn graphql_transport_ws<Query, Mutation, Subscription, Context, S>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Context,
+        config: ConnectionConfig,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let init = move |_| future::ready(Ok(context));
+        graphql_transport_ws_with_init(websocket, coordinator, init, config)
+    }
+
+    /// Serve subscriptions over the `graphql-transport-ws` protocol, building
+    /// the context of the connection from the payload of its
+    /// `connection_init` message
+    ///
+    /// See [`juniper_subscriptions::ws::serve_with_init`] for how `init` is
+    /// used.
+    ///
+    /// [`juniper_subscriptions::ws::serve_with_init`]: juniper_subscriptions::ws::serve_with_init
+    pub fn graphql_transport_ws_with_init<Query, Mutation, Subscription, Context, S, Init, Fut>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        init: Init,
+        config: ConnectionConfig,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        Init: FnOnce(serde_json::Value) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Context, String>> + Send + 'static,
     {
+        let (sink, stream) = websocket.split();
+
+        let incoming = stream
+            .take_while(|msg| future::ready(matches!(msg, Ok(msg) if !msg.is_close())))
+            .filter_map(|msg| {
+                let msg = msg.expect("errors end the stream");
+                future::ready(if msg.is_ping() || msg.is_pong() {
+                    None
+                } else {
+                    Some(msg.to_str().map(str::to_owned).unwrap_or_default())
+                })
+            });
+        let outgoing = sink
+            .with(|msg: OutgoingMessage| future::ready(Ok::<_, warp::Error>(warp_message(msg))));
+
+        ws::serve_with_init(incoming, outgoing, coordinator, init, config)
+            .map(|r| r.map_err(|e| failure::format_err!("Websocket error: {}", e)))
+    }
+
+    #[derive(Deserialize)]
+    struct WsPayload {
         id: Option<String>,
         #[serde(rename(deserialize = "type"))]
         type_name: String,
-        payload: Option<GraphQLPayload<S>>,
+        payload: Option<serde_json::Value>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -580,11 +1053,196 @@ pub mod subscriptions {
         data: String,
         variables: String,
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::pin::Pin;
+
+        use futures::{stream, Stream};
+        use juniper::{DefaultScalarValue, EmptyMutation, FieldError, RootNode};
+        use serde_json::{json, Value};
+        use warp::test::WsClient;
+
+        use super::*;
+
+        #[derive(Clone, Default)]
+        struct Session {
+            user: Option<String>,
+        }
+
+        impl juniper::Context for Session {}
+
+        struct Query;
+
+        #[juniper::graphql_object(Context = Session)]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        type Numbers = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+        struct Subscription;
+
+        #[juniper::graphql_subscription(Context = Session)]
+        impl Subscription {
+            async fn numbers() -> Numbers {
+                Box::pin(stream::iter(vec![Ok(1), Ok(2)]))
+            }
+
+            async fn endless() -> Numbers {
+                Box::pin(stream::once(future::ready(Ok(1))).chain(stream::pending()))
+            }
+
+            async fn whoami(
+                session: &Session,
+            ) -> Pin<Box<dyn Stream<Item = Option<String>> + Send>> {
+                Box::pin(stream::once(future::ready(session.user.clone())))
+            }
+        }
+
+        type TestCoordinator = Coordinator<
+            'static,
+            Query,
+            EmptyMutation<Session>,
+            Subscription,
+            Session,
+            DefaultScalarValue,
+        >;
+
+        fn coordinator() -> Arc<TestCoordinator> {
+            let schema = RootNode::new(Query, EmptyMutation::new(), Subscription);
+            Arc::new(Coordinator::new(schema))
+        }
+
+        fn authenticate(payload: Value) -> future::Ready<Result<Session, String>> {
+            future::ready(match payload["token"].as_str() {
+                Some("secret") => Ok(Session {
+                    user: Some("alice".into()),
+                }),
+                _ => Err("Invalid token".into()),
+            })
+        }
+
+        async fn connect(filter: BoxedFilter<(Box<dyn warp::Reply>,)>, payload: Value) -> WsClient {
+            let mut client = warp::test::ws().handshake(filter).await.expect("handshake");
+            send(
+                &mut client,
+                json!({ "type": "connection_init", "payload": payload }),
+            )
+            .await;
+            client
+        }
+
+        async fn send(client: &mut WsClient, message: Value) {
+            client.send_text(message.to_string()).await;
+        }
+
+        async fn recv(client: &mut WsClient) -> Value {
+            let message = client.recv().await.expect("message");
+            serde_json::from_str(message.to_str().expect("text message")).expect("JSON message")
+        }
+
+        fn start(id: &str, query: &str) -> Value {
+            json!({ "type": "start", "id": id, "payload": { "query": query } })
+        }
+
+        fn data(id: &str, data: Value) -> Value {
+            json!({ "type": "data", "id": id, "payload": { "data": data } })
+        }
+
+        fn complete(id: &str) -> Value {
+            json!({ "type": "complete", "id": id, "payload": null })
+        }
+
+        #[tokio::test]
+        async fn runs_concurrent_operations_on_one_connection() {
+            let filter = make_ws_filter(
+                coordinator(),
+                warp::any().map(Session::default).boxed(),
+                ConnectionConfig::new(),
+            );
+            let mut client = connect(filter, Value::Null).await;
+            assert_eq!(recv(&mut client).await, json!({ "type": "connection_ack" }));
+
+            send(&mut client, start("1", "subscription { endless }")).await;
+            assert_eq!(recv(&mut client).await, data("1", json!({ "endless": 1 })));
+            send(&mut client, start("2", "subscription { numbers }")).await;
+            assert_eq!(recv(&mut client).await, data("2", json!({ "numbers": 1 })));
+            assert_eq!(recv(&mut client).await, data("2", json!({ "numbers": 2 })));
+            assert_eq!(recv(&mut client).await, complete("2"));
+
+            // The first operation kept running after the second completed.
+            send(&mut client, json!({ "type": "stop", "id": "1" })).await;
+            assert_eq!(recv(&mut client).await, complete("1"));
+        }
+
+        #[tokio::test]
+        async fn rejects_operations_past_the_limit() {
+            let filter = make_ws_filter(
+                coordinator(),
+                warp::any().map(Session::default).boxed(),
+                ConnectionConfig::new().with_max_operations(1),
+            );
+            let mut client = connect(filter, Value::Null).await;
+            assert_eq!(recv(&mut client).await, json!({ "type": "connection_ack" }));
+
+            send(&mut client, start("1", "subscription { endless }")).await;
+            assert_eq!(recv(&mut client).await, data("1", json!({ "endless": 1 })));
+            send(&mut client, start("2", "subscription { endless }")).await;
+            assert_eq!(
+                recv(&mut client).await,
+                json!({
+                    "type": "error",
+                    "id": "2",
+                    "payload": [{ "message": "Too many operations" }],
+                }),
+            );
+
+            // Stopping the first operation makes room for another one.
+            send(&mut client, json!({ "type": "stop", "id": "1" })).await;
+            assert_eq!(recv(&mut client).await, complete("1"));
+            send(&mut client, start("3", "subscription { numbers }")).await;
+            assert_eq!(recv(&mut client).await, data("3", json!({ "numbers": 1 })));
+        }
+
+        #[tokio::test]
+        async fn builds_the_context_from_the_init_payload() {
+            let filter =
+                make_ws_filter_with_init(coordinator(), authenticate, ConnectionConfig::new());
+            let mut client = connect(filter, json!({ "token": "secret" })).await;
+            assert_eq!(recv(&mut client).await, json!({ "type": "connection_ack" }));
+
+            send(&mut client, start("1", "subscription { whoami }")).await;
+            assert_eq!(
+                recv(&mut client).await,
+                data("1", json!({ "whoami": "alice" }))
+            );
+            assert_eq!(recv(&mut client).await, complete("1"));
+        }
+
+        #[tokio::test]
+        async fn rejects_connections_with_an_invalid_init_payload() {
+            let filter =
+                make_ws_filter_with_init(coordinator(), authenticate, ConnectionConfig::new());
+            let mut client = connect(filter, json!({ "token": "wrong" })).await;
+
+            assert_eq!(
+                recv(&mut client).await,
+                json!({
+                    "type": "connection_error",
+                    "payload": { "message": "Invalid token" },
+                }),
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use juniper::{http::multipart::Upload, Executor, FieldResult};
     use warp::{http, test::request};
 
     #[test]
@@ -771,6 +1429,79 @@ mod tests {
         );
     }
 
+    struct UploadQuery;
+
+    #[juniper::graphql_object]
+    impl UploadQuery {
+        fn size(executor: &Executor, file: Upload) -> FieldResult<i32> {
+            Ok(file.content(executor)?.len() as i32)
+        }
+    }
+
+    fn upload_filter(options: MultipartOptions) -> BoxedFilter<(http::Response<Vec<u8>>,)> {
+        use juniper::{EmptyMutation, EmptySubscription, RootNode};
+
+        let schema = RootNode::new(
+            UploadQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        warp::path("graphql")
+            .and(make_graphql_filter_with_multipart_options(
+                schema,
+                warp::any().map(|| ()).boxed(),
+                options,
+            ))
+            .boxed()
+    }
+
+    const UPLOAD_BODY: &str = "--xyz\r\n\
+        Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+        {\"query\": \"query ($file: Upload!) { size(file: $file) }\", \"variables\": {\"file\": null}}\r\n\
+        --xyz\r\n\
+        Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+        {\"0\": [\"variables.file\"]}\r\n\
+        --xyz\r\n\
+        Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        Alpha\r\n\
+        --xyz--\r\n";
+
+    #[tokio::test]
+    async fn graphql_handler_works_multipart_post() {
+        let filter = upload_filter(MultipartOptions::new());
+
+        let response = request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "multipart/form-data; boundary=xyz")
+            .body(UPLOAD_BODY)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"data":{"size":5}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn multipart_post_rejects_oversized_body() {
+        let filter =
+            upload_filter(MultipartOptions::new().with_max_body_size(UPLOAD_BODY.len() as u64 - 1));
+
+        let response = request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "multipart/form-data; boundary=xyz")
+            .body(UPLOAD_BODY)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
     #[test]
     fn batch_request_deserialization_can_fail() {
         let json = r#"blah"#;